@@ -0,0 +1,142 @@
+//! Generates the algorithm registries as phf maps and their help text as consts,
+//! so nothing is built at startup however many sub commands there are
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Name, help and the value in the map
+type Entry = (&'static str, &'static str, String);
+
+fn main() {
+	let hash_algorithms = [
+		("md5", "MD5"),
+		("sha1", "SHA-1"),
+		("sha2_224", "SHA-2 224"),
+		("sha2_256", "SHA-2 256"),
+		("sha2_384", "SHA-2 384"),
+		("sha2_512", "SHA-2 512"),
+		("sha2_512_224", "SHA-2 512 truncate 224"),
+		("sha2_512_256", "SHA-2 512 truncate 256"),
+		("sha3_224", "SHA-3 224"),
+		("sha3_256", "SHA-3 256"),
+		("sha3_384", "SHA-3 384"),
+		("sha3_512", "SHA-3 512"),
+		("sha3_k_224", "SHA-3 keccak 224"),
+		("sha3_k_256", "SHA-3 keccak 256"),
+		("sha3_k_384", "SHA-3 keccak 384"),
+		("sha3_k_512", "SHA-3 keccak 512"),
+		("ripemd_160", "RIPEMD-160"),
+		("crc_32", "CRC32"),
+		("blake2b_160", "Blake2b 160"),
+		("blake2b_256", "Blake2b 256"),
+		("blake2b_384", "Blake2b 384"),
+		("blake2b_512", "Blake2b 512"),
+		("sm3", "Chinese National Standard SM3"),
+	]
+	.iter()
+	.map(|&(name, help)| {
+		let f = if name.starts_with("blake2b") {
+			"WithKey"
+		} else {
+			"Normal"
+		};
+		(
+			name,
+			help,
+			format!("Algorithm {{ f: AlgorithmF::{}({}) }}", f, name),
+		)
+	})
+	.collect::<Vec<_>>();
+
+	let curves = [
+		("secp256k1", "Secp256k1"),
+		("p256", "NIST P-256"),
+		("p384", "NIST P-384"),
+		("sm2", "Chinese National Standard SM2"),
+	]
+	.iter()
+	.map(|&(name, help)| {
+		let value = format!(
+			"Curve {{ gk_f: {0}::ec_gk_{0}, sign_f: {0}::ec_sign_{0}, verify_f: {0}::ec_verify_{0}, pk_f: {0}::ec_pk_{0} }}",
+			name
+		);
+		(name, help, value)
+	})
+	.collect::<Vec<_>>();
+
+	let signature_forms = vec![
+		("der", "ASN1 DER", "SignatureFormEnum::Der".to_string()),
+		("fixed", "Fixed", "SignatureFormEnum::Fixed".to_string()),
+	];
+
+	let out_dir = env::var("OUT_DIR").expect("qed");
+	write(
+		&Path::new(&out_dir).join("hash_algorithms.rs"),
+		&[registry(
+			"ALGORITHM",
+			"Algorithm",
+			"Hash algorithm\n",
+			"",
+			&hash_algorithms,
+		)],
+	);
+	write(
+		&Path::new(&out_dir).join("ecdsa_registries.rs"),
+		&[
+			registry("CURVE", "Curve", "Curve\n", "\n", &curves),
+			registry(
+				"SIGNATURE_FORM",
+				"SignatureFormEnum",
+				"Signature form\n",
+				"\n",
+				&signature_forms,
+			),
+		],
+	);
+
+	println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// `<PREFIX>S` as an ordered phf map, `<PREFIX>_NAMES` and `<PREFIX>_HELP`
+fn registry(
+	prefix: &str,
+	value_type: &str,
+	title: &str,
+	suffix: &str,
+	entries: &[Entry],
+) -> String {
+	let mut map = phf_codegen::OrderedMap::new();
+	for (name, _, value) in entries {
+		map.entry(*name, value);
+	}
+
+	let names = entries
+		.iter()
+		.map(|(name, _, _)| format!("{:?}", name))
+		.collect::<Vec<_>>()
+		.join(", ");
+
+	let help = entries
+		.iter()
+		.map(|(name, help, _)| format!("{}: {}", name, help))
+		.collect::<Vec<_>>()
+		.join("\n");
+	let help = format!("{}{}{}", title, help, suffix);
+
+	format!(
+		"static {0}S: phf::OrderedMap<&'static str, {1}> = {2};\n\
+		 const {0}_NAMES: [&str; {3}] = [{4}];\n\
+		 const {0}_HELP: &str = {5:?};\n",
+		prefix,
+		value_type,
+		map.build(),
+		entries.len(),
+		names,
+		help
+	)
+}
+
+fn write(path: &Path, registries: &[String]) {
+	fs::write(path, registries.concat()).expect("qed");
+}
@@ -1,14 +1,21 @@
 use crate::modules::ModuleManager;
 use clap::App;
 
-pub fn build_app<'a, 'b>() -> (App<'a, 'b>, ModuleManager<'a, 'b>) {
+/// The app of the sub command to run if given, aliases and plugins are only included
+/// if it isn't built in (e.g. none for the help), so plugins aren't discovered on every run
+pub fn build_app<'a, 'b>(name: Option<&str>) -> (App<'a, 'b>, ModuleManager<'a, 'b>) {
+	let module_manager = ModuleManager::new();
+	let extensions = !name.is_some_and(|name| module_manager.is_builtin(name));
+	let subcommands = module_manager.apps(extensions);
+
+	// built once at startup, the help of the app lives as long as the process
+	let template: &'static str = Box::leak(help_template(&module_manager, &subcommands).into());
+
 	let mut app = App::new(env!("CARGO_PKG_NAME"))
 		.version(env!("CARGO_PKG_VERSION"))
 		.author(env!("CARGO_PKG_AUTHORS"))
-		.about(env!("CARGO_PKG_DESCRIPTION"));
-
-	let module_manager = ModuleManager::new();
-	let subcommands = module_manager.apps();
+		.about(env!("CARGO_PKG_DESCRIPTION"))
+		.template(template);
 
 	for subcommand in subcommands {
 		app = app.subcommand(subcommand);
@@ -16,3 +23,44 @@ pub fn build_app<'a, 'b>() -> (App<'a, 'b>, ModuleManager<'a, 'b>) {
 
 	(app, module_manager)
 }
+
+/// Sub commands grouped by category with the first line of their descriptions
+fn help_template(module_manager: &ModuleManager, subcommands: &[App]) -> String {
+	let about = |name: &str| {
+		let mut about = vec![];
+		if let Some(app) = subcommands.iter().find(|x| x.get_name() == name) {
+			app.clone()
+				.template("{about}")
+				.write_help(&mut about)
+				.unwrap_or(());
+		}
+		String::from_utf8_lossy(&about)
+			.lines()
+			.next()
+			.unwrap_or_default()
+			.to_string()
+	};
+
+	let categories = module_manager.categories(subcommands);
+	let width = categories
+		.iter()
+		.flat_map(|(_, names)| names.iter().map(String::len))
+		.max()
+		.unwrap_or_default();
+
+	let mut template =
+		"{bin} {version}\n{author}\n{about}\n\nUSAGE:\n    {usage}\n\nFLAGS:\n{flags}\n"
+			.to_string();
+	for (category, names) in categories {
+		template.push_str(&format!("\n{}:\n", category.name().to_uppercase()));
+		for name in names {
+			template.push_str(&format!(
+				"    {:width$}    {}\n",
+				name,
+				about(&name),
+				width = width
+			));
+		}
+	}
+	template
+}
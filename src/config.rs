@@ -0,0 +1,145 @@
+use crate::error::Error;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use toml::Value;
+
+lazy_static! {
+	pub static ref CONFIG: Config = Config::load().unwrap_or_else(|e| {
+		eprintln!("{}", e);
+		std::process::exit(1);
+	});
+}
+
+/// User config in `$XDG_CONFIG_HOME/dtool/config.toml` (default `~/.config/dtool/config.toml`)
+///
+/// ```toml
+/// # default values of options (by long name) for all sub commands having them
+/// [default]
+/// color = "always"
+///
+/// # default values of options for the sub command
+/// [hash]
+/// algo = "sha2_256"
+///
+/// # aliases, shown as sub commands
+/// [alias]
+/// keccak = "hash -a sha3_k_256"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+	default: HashMap<String, String>,
+	commands: HashMap<String, HashMap<String, String>>,
+	pub aliases: Vec<(String, String)>,
+}
+
+impl Config {
+	fn load() -> Result<Self, Error> {
+		let path = match path() {
+			Some(path) if path.exists() => path,
+			_ => return Ok(Self::default()),
+		};
+
+		let content = fs::read_to_string(&path)
+			.map_err(|e| Error::invalid("config", format!("{}: {}", path.display(), e)))?;
+
+		Self::parse(&content)
+			.map_err(|e| Error::invalid("config", format!("{}: {}", path.display(), e)))
+	}
+
+	pub fn parse(content: &str) -> Result<Self, Error> {
+		let value = content.parse::<Value>().map_err(Error::failed)?;
+
+		let mut config = Self::default();
+		for (name, section) in value.as_table().ok_or(Error::failed("Expect table"))? {
+			let section = section
+				.as_table()
+				.ok_or_else(|| Error::failed(format!("Expect table: {}", name)))?;
+			let options = section
+				.iter()
+				.map(|(k, v)| {
+					let v = match v {
+						Value::String(v) => v.to_string(),
+						Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => v.to_string(),
+						_ => {
+							return Err(Error::failed(format!(
+								"Expect string or number: {}.{}",
+								name, k
+							)))
+						}
+					};
+					Ok((k.to_string(), v))
+				})
+				.collect::<Result<HashMap<_, _>, Error>>()?;
+			match name.as_str() {
+				"default" => config.default = options,
+				// in the order of the config
+				"alias" => {
+					config.aliases = section
+						.keys()
+						.filter_map(|k| options.get(k).map(|v| (k.to_string(), v.to_string())))
+						.collect()
+				}
+				_ => {
+					config.commands.insert(name.to_string(), options);
+				}
+			}
+		}
+
+		Ok(config)
+	}
+
+	/// Default value of the option of the sub command
+	pub fn option(&self, command: &str, long: &str) -> Option<&str> {
+		self.commands
+			.get(command)
+			.and_then(|x| x.get(long))
+			.or_else(|| self.default.get(long))
+			.map(String::as_str)
+	}
+}
+
+/// `$XDG_CONFIG_HOME/dtool` (default `~/.config/dtool`)
+pub fn dir() -> Option<PathBuf> {
+	env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+		.map(|x| x.join("dtool"))
+}
+
+fn path() -> Option<PathBuf> {
+	dir().map(|x| x.join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let config = Config::parse(
+			"[default]\ncolor = \"always\"\nwidth = 40\n[hash]\nalgo = \"sha2_256\"\n[diff]\ncolor = \"never\"",
+		)
+		.unwrap();
+		assert_eq!(config.option("hash", "algo"), Some("sha2_256"));
+		assert_eq!(config.option("filter", "color"), Some("always"));
+		assert_eq!(config.option("diff", "color"), Some("never"));
+		assert_eq!(config.option("wrap", "width"), Some("40"));
+		assert_eq!(config.option("hash", "key"), None);
+
+		let config =
+			Config::parse("[alias]\nkeccak = \"hash -a sha3_k_256\"\nb = \"s2h\"").unwrap();
+		assert_eq!(
+			config.aliases,
+			vec![
+				("keccak".to_string(), "hash -a sha3_k_256".to_string()),
+				("b".to_string(), "s2h".to_string())
+			]
+		);
+
+		assert!(Config::parse("algo = \"md5\"").is_err());
+		assert!(Config::parse("[hash]\nalgo = [\"md5\"]").is_err());
+	}
+}
@@ -0,0 +1,176 @@
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Errors of sub commands, rendered as `Invalid <what>: <detail>` like the messages before
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+	/// The input can not be read as expected (e.g. not UTF-8, wrong length)
+	InvalidInput(String),
+	/// Not hex, e.g. the input or a key
+	InvalidHex,
+	/// An arg or a part of the input, e.g. `Invalid key`, `Invalid JSON: ...`
+	Invalid { what: String, detail: String },
+	/// With the suggestion, e.g. `, did you mean sha2_256?`
+	UnsupportedAlgorithm {
+		algorithm: String,
+		suggestion: String,
+	},
+	/// Reading or writing a file, `-` for stdin or stdout
+	Io { path: String, detail: String },
+	/// A validation failed, e.g. hash --verify, with the actual result
+	Mismatched(String),
+	/// The conversion failed, e.g. `Convert failed`
+	Failed(String),
+}
+
+impl Error {
+	/// `Invalid input`, with the detail if not empty
+	pub fn input(detail: impl fmt::Display) -> Self {
+		Error::InvalidInput(detail.to_string())
+	}
+
+	/// `Invalid <what>`, with the detail if not empty, e.g. `Error::invalid("key", "expect 32 bytes")`
+	pub fn invalid(what: &str, detail: impl fmt::Display) -> Self {
+		Error::Invalid {
+			what: what.to_string(),
+			detail: detail.to_string(),
+		}
+	}
+
+	/// The suggestion is from `base::did_you_mean`, e.g. `, did you mean sha2_256?`
+	pub fn algorithm(algorithm: &str, suggestion: impl Into<String>) -> Self {
+		Error::UnsupportedAlgorithm {
+			algorithm: algorithm.to_string(),
+			suggestion: suggestion.into(),
+		}
+	}
+
+	/// A failure without an invalid part, e.g. `Convert failed`
+	pub fn failed(message: impl fmt::Display) -> Self {
+		Error::Failed(message.to_string())
+	}
+
+	pub fn io(path: &str, e: impl fmt::Display) -> Self {
+		Error::Io {
+			path: path.to_string(),
+			detail: e.to_string(),
+		}
+	}
+
+	/// Snake case name of the variant, e.g. `invalid_input`
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Error::InvalidInput(_) => "invalid_input",
+			Error::InvalidHex => "invalid_hex",
+			Error::Invalid { .. } => "invalid",
+			Error::UnsupportedAlgorithm { .. } => "unsupported_algorithm",
+			Error::Io { .. } => "io",
+			Error::Mismatched(_) => "mismatched",
+			Error::Failed(_) => "failed",
+		}
+	}
+
+	/// `{"kind": ..., "message": ...}` with the context, e.g. `"what"` of `invalid`
+	pub fn to_json(&self) -> Value {
+		let mut value = json!({
+			"kind": self.kind(),
+			"message": self.to_string(),
+		});
+		let context = match self {
+			Error::Invalid { what, .. } => json!({ "what": what }),
+			Error::UnsupportedAlgorithm { algorithm, .. } => json!({ "algorithm": algorithm }),
+			Error::Io { path, .. } => json!({ "path": path }),
+			Error::Mismatched(actual) => json!({ "actual": actual }),
+			_ => json!({}),
+		};
+		if let (Some(value), Value::Object(context)) = (value.as_object_mut(), context) {
+			value.extend(context);
+		}
+		value
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let (what, detail) = match self {
+			Error::InvalidInput(detail) => ("input", detail.as_str()),
+			Error::InvalidHex => ("hex", ""),
+			Error::Invalid { what, detail } => (what.as_str(), detail.as_str()),
+			Error::UnsupportedAlgorithm {
+				algorithm,
+				suggestion,
+			} => return write!(f, "Invalid algorithm: {}{}", algorithm, suggestion),
+			Error::Io { path, detail } if detail.is_empty() => {
+				return write!(f, "Invalid file: {}", path)
+			}
+			Error::Io { path, detail } => return write!(f, "Invalid file: {}: {}", path, detail),
+			Error::Mismatched(actual) => return write!(f, "Mismatched: {}", actual),
+			Error::Failed(message) => return write!(f, "{}", message),
+		};
+		if detail.is_empty() {
+			write!(f, "Invalid {}", what)
+		} else {
+			write!(f, "Invalid {}: {}", what, detail)
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for String {
+	fn from(e: Error) -> Self {
+		e.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_error() {
+		let errors = [
+			(Error::input(""), "Invalid input", "invalid_input"),
+			(
+				Error::input("not UTF-8 at byte 1"),
+				"Invalid input: not UTF-8 at byte 1",
+				"invalid_input",
+			),
+			(Error::InvalidHex, "Invalid hex", "invalid_hex"),
+			(
+				Error::invalid("key", "expect 32 bytes"),
+				"Invalid key: expect 32 bytes",
+				"invalid",
+			),
+			(
+				Error::algorithm("sha2256", ", did you mean sha2_256?"),
+				"Invalid algorithm: sha2256, did you mean sha2_256?",
+				"unsupported_algorithm",
+			),
+			(
+				Error::io("a.txt", "No such file"),
+				"Invalid file: a.txt: No such file",
+				"io",
+			),
+			(
+				Error::Mismatched("0x00".to_string()),
+				"Mismatched: 0x00",
+				"mismatched",
+			),
+			(Error::failed("Convert failed"), "Convert failed", "failed"),
+		];
+		for (e, message, kind) in errors.iter() {
+			assert_eq!(e.kind(), *kind);
+			assert_eq!(e.to_string(), *message);
+		}
+
+		assert_eq!(
+			Error::algorithm("sha2256", ", did you mean sha2_256?").to_json(),
+			json!({
+				"kind": "unsupported_algorithm",
+				"message": "Invalid algorithm: sha2256, did you mean sha2_256?",
+				"algorithm": "sha2256",
+			})
+		);
+	}
+}
@@ -0,0 +1,381 @@
+//! dtool as a library, every conversion is a function of the input and the options of its module,
+//! named after the sub command, e.g. `hex::h2s` for `dtool h2s`,
+//! the output is the result lines joined by `\n` unless the sub command writes raw bytes (e.g. `h2b`)
+//!
+//! ```
+//! use dtool::{hash, hex};
+//!
+//! let output = hex::s2h(b"abc", &hex::Options).unwrap();
+//! assert_eq!(output, b"0x616263".to_vec());
+//!
+//! let options = hash::Options {
+//!     algorithm: "md5".to_string(),
+//!     ..Default::default()
+//! };
+//! let output = hash::hash(b"0x616263", &options).unwrap();
+//! assert_eq!(output, b"0x900150983cd24fb0d6963f7d28e17f72".to_vec());
+//!
+//! let output = hex::h2b(b"0x616263", &hex::Options).unwrap();
+//! assert_eq!(output, b"abc".to_vec());
+//!
+//! let e = hex::h2s(b"0xzz", &hex::Options).unwrap_err();
+//! assert_eq!(e.kind(), "failed");
+//! ```
+
+mod app;
+mod config;
+mod error;
+mod modules;
+
+use clap::ErrorKind;
+pub use error::Error;
+use std::env;
+use std::process;
+
+/// Hex / UTF-8 string / binary / byte array conversion
+pub mod hex {
+	pub use crate::modules::hex::{a2h, b2h, h2a, h2b, h2s, s2h, Options};
+}
+
+/// Number 10/2/8/16 base conversion
+pub mod number_system {
+	pub use crate::modules::number_system::{ns, Options};
+}
+
+/// Hex / base58 conversion
+pub mod base58 {
+	pub use crate::modules::base58::{b582h, b58c2h, h2b58, h2b58c, Options};
+}
+
+/// Hex / base64 conversion
+pub mod base64 {
+	pub use crate::modules::base64::{b642h, h2b64, Options};
+}
+
+/// URL encode / decode, query string / JSON conversion, URL parse / build
+#[cfg(feature = "web")]
+pub mod url {
+	pub use crate::modules::url::{
+		j2qs, qs2j, ud, ue, url_build, url_parse, ArrayFormat, Format, Options,
+	};
+}
+
+/// Number codec
+#[cfg(feature = "blockchain")]
+pub mod number_codec {
+	pub use crate::modules::number_codec::{nd, ne, NumberType, Options};
+}
+
+/// Hash (MD5, SHA-1, SHA-2, SHA-3, RIPEMD, CRC, Blake2b, SM3)
+pub mod hash {
+	pub use crate::modules::hash::{hash, Options};
+}
+
+/// UTF-8 string / unicode conversion
+pub mod unicode {
+	pub use crate::modules::unicode::{s2u, u2s, Format, Options};
+}
+
+/// HTML entity encode / decode, HTML to text / markdown
+#[cfg(feature = "web")]
+pub mod html {
+	pub use crate::modules::html::{hd, he, html_text, Options};
+}
+
+/// QR code encode / decode
+#[cfg(feature = "qr")]
+pub mod qr {
+	pub use crate::modules::qr::{qr_dec, qr_enc, Format, Options};
+	pub use qrcode::EcLevel;
+}
+
+/// Regex match, replace, explain, filter
+pub mod re {
+	pub use crate::modules::re::{filter, re, re_explain, re_match, re_replace, Options};
+}
+
+/// Pbkdf2
+#[cfg(feature = "crypto")]
+pub mod pbkdf2 {
+	pub use crate::modules::pbkdf2::{pbkdf2, Algorithm, Options};
+}
+
+/// Case conversion (upper, lower, title, camel, pascal, snake, shouty snake, kebab, sarcasm)
+pub mod case {
+	pub use crate::modules::case::{case, CaseType, Options};
+}
+
+/// AES encrypt / decrypt
+#[cfg(feature = "crypto")]
+pub mod aes {
+	pub use crate::modules::aes::{aes_dec, aes_enc, Mode, Options};
+}
+
+/// SM4 encrypt / decrypt
+#[cfg(feature = "crypto")]
+pub mod sm4 {
+	pub use crate::modules::sm4::{sm4_dec, sm4_enc, Mode, Options};
+}
+
+/// ECDSA (Secp256k1, NIST P-256, NIST P-384, SM2)
+#[cfg(feature = "crypto")]
+pub mod ecdsa {
+	pub use crate::modules::ecdsa::{ec_gk, ec_pk, ec_sign, ec_verify, Options, SignatureFormEnum};
+}
+
+/// EdDSA (Ed25519)
+#[cfg(feature = "crypto")]
+pub mod eddsa {
+	pub use crate::modules::eddsa::{
+		ed_gk, ed_pk, ed_sign, ed_sk, ed_verify, AltSecretKey, Options,
+	};
+}
+
+/// sr25519 signature
+#[cfg(feature = "blockchain")]
+pub mod srdsa {
+	pub use crate::modules::srdsa::{
+		sr_gk, sr_pk, sr_sign, sr_sk, sr_verify, AltSecretKey, Options,
+	};
+}
+
+/// JSON (format, minify, sort keys, canonicalize, JSON5, JSONPath query, diff, flatten, check)
+#[cfg(feature = "structured-data")]
+pub mod json {
+	pub use crate::modules::json::{
+		json_check, json_diff, json_flatten, json_fmt, json_get, json_unflatten, Options,
+	};
+}
+
+/// JSON / YAML conversion, YAML split / merge
+#[cfg(feature = "structured-data")]
+pub mod yaml {
+	pub use crate::modules::yaml::{j2y, y2j, yaml_merge, yaml_split, Options};
+}
+
+/// JSON / TOML conversion, TOML format / check
+#[cfg(feature = "structured-data")]
+pub mod toml {
+	pub use crate::modules::toml::{j2t, t2j, toml_check, toml_fmt, Options};
+}
+
+/// JSON / CSV conversion, CSV table
+#[cfg(feature = "structured-data")]
+pub mod csv {
+	pub use crate::modules::csv::{c2j, csv_table, j2c, Options};
+}
+
+/// JSON / MessagePack conversion
+#[cfg(feature = "structured-data")]
+pub mod msgpack {
+	pub use crate::modules::msgpack::{j2m, m2j, Options};
+}
+
+/// JSON / CBOR conversion, CBOR diagnostic notation
+#[cfg(feature = "structured-data")]
+pub mod cbor {
+	pub use crate::modules::cbor::{cbor2j, cbor_diag, j2cbor, Options};
+}
+
+/// BSON to JSON (MongoDB extended JSON)
+#[cfg(feature = "structured-data")]
+pub mod bson {
+	pub use crate::modules::bson::{bson2j, Options};
+}
+
+/// XML format, JSON / XML conversion
+#[cfg(feature = "structured-data")]
+pub mod xml {
+	pub use crate::modules::xml::{j2x, x2j, xml_fmt, Options};
+}
+
+/// INI / properties conversion (to JSON, TOML)
+#[cfg(feature = "structured-data")]
+pub mod ini {
+	pub use crate::modules::ini::{ini2j, ini2t, j2ini, j2prop, prop2j, t2ini, Options};
+}
+
+/// .env to JSON, JSON to .env, .env merge
+#[cfg(feature = "structured-data")]
+pub mod dotenv {
+	pub use crate::modules::dotenv::{env2j, env_merge, j2env, Options};
+}
+
+/// NDJSON (JSON Lines) split, join, filter
+#[cfg(feature = "structured-data")]
+pub mod ndjson {
+	pub use crate::modules::ndjson::{ndjson_filter, ndjson_join, ndjson_split, Options};
+}
+
+/// Protobuf to JSON, JSON to protobuf (with .proto)
+#[cfg(feature = "structured-data")]
+pub mod protobuf {
+	pub use crate::modules::protobuf::{j2pb, pb2j, Options};
+}
+
+/// MIME type detection
+#[cfg(feature = "filetype")]
+pub mod mime {
+	pub use crate::modules::mime::{mime, Options};
+}
+
+/// File type identification
+#[cfg(feature = "filetype")]
+pub mod filetype {
+	pub use crate::modules::filetype::{filetype, Options};
+}
+
+/// Text diff
+pub mod diff {
+	pub use crate::modules::diff::{diff, Options};
+}
+
+/// Lines sort, unique, count
+pub mod lines {
+	pub use crate::modules::lines::{lines_count, lines_sort, lines_uniq, Mode, Options};
+}
+
+/// Slugify
+pub mod slug {
+	pub use crate::modules::slug::{slug, Options};
+}
+
+/// Text statistics
+pub mod count {
+	pub use crate::modules::count::{count, CountType, Options};
+}
+
+/// String similarity (Levenshtein, Damerau-Levenshtein, Jaro, Jaro-Winkler)
+pub mod similarity {
+	pub use crate::modules::similarity::{similarity, Options, SimilarityType};
+}
+
+/// Line ending detection / conversion
+pub mod eol {
+	pub use crate::modules::eol::{eol, LineEnding, Options};
+}
+
+/// Whitespace clean, tab / space conversion
+pub mod whitespace {
+	pub use crate::modules::whitespace::{ws_clean, ws_expand, ws_unexpand, Options};
+}
+
+/// Text wrap, truncate
+pub mod wrap {
+	pub use crate::modules::wrap::{truncate, wrap, Options};
+}
+
+/// String reverse, repeat, pad, substring
+pub mod string {
+	pub use crate::modules::string::{str_pad, str_repeat, str_reverse, str_sub, Options, Side};
+}
+
+/// ASCII art banner
+pub mod banner {
+	pub use crate::modules::banner::{banner, Font, Options};
+}
+
+/// Markdown to HTML
+#[cfg(feature = "web")]
+pub mod markdown {
+	pub use crate::modules::markdown::{md, Options};
+}
+
+/// HTTP header explain
+#[cfg(feature = "web")]
+pub mod http_header {
+	pub use crate::modules::http_header::{http_explain, Options};
+}
+
+/// User-Agent parse
+#[cfg(feature = "web")]
+pub mod user_agent {
+	pub use crate::modules::user_agent::{ua_parse, Format, Options};
+}
+
+/// Email address validation
+#[cfg(feature = "web")]
+pub mod email {
+	pub use crate::modules::email::{email, Mx, Options};
+}
+
+/// UUID generate / parse
+#[cfg(feature = "id")]
+pub mod uuid {
+	pub use crate::modules::uuid::{uuid_gen, uuid_parse, Format, Options, Version};
+}
+
+/// ULID generate / parse, ULID / UUID convert
+#[cfg(feature = "id")]
+pub mod ulid {
+	pub use crate::modules::ulid::{ulid2uuid, ulid_gen, ulid_parse, uuid2ulid, Options};
+}
+
+/// NanoID generate
+pub mod nanoid {
+	pub use crate::modules::nanoid::{nanoid, Options};
+}
+
+/// Snowflake ID decode
+pub mod snowflake {
+	pub use crate::modules::snowflake::{snowflake, Options};
+}
+
+/// Semantic version validate / compare / match
+pub mod semver {
+	pub use crate::modules::semver::{semver, Options};
+}
+
+/// Compress / decompress (gzip, zlib, deflate, zstd, brotli, lz4)
+#[cfg(feature = "compression")]
+pub mod compress {
+	pub use crate::modules::compress::{compress, decompress, Algorithm, Options};
+}
+
+/// Archive list / extract (tar, tar.gz, zip)
+#[cfg(feature = "compression")]
+pub mod archive {
+	pub use crate::modules::archive::{archive_extract, archive_list, Options};
+}
+
+/// Lorem ipsum generator
+pub mod lorem {
+	pub use crate::modules::lorem::{lorem, Options, Unit};
+}
+
+/// Timestamp / date conversion
+pub mod time {
+	pub use crate::modules::time::{
+		d2d, d2ts, d2w, e2ts, now, r2ts, tdiff, ts2d, ts2e, ts2r, w2d, DiffUnit, Epoch, Format,
+		NowFormat, Options, Zone,
+	};
+}
+
+/// The command line entry
+#[doc(hidden)]
+pub fn cli() {
+	let args = env::args().collect::<Vec<_>>();
+	let (app, module_manager) = app::build_app(args.get(1).map(String::as_str));
+
+	let mut app_clone = app.clone();
+
+	// usage errors exit with 2, failed sub commands with 1
+	let matches = module_manager
+		.get_matches(&app, &args, 1)
+		.unwrap_or_else(|e| match e.kind {
+			ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => e.exit(),
+			_ => {
+				eprintln!("{}", e.message);
+				process::exit(2);
+			}
+		});
+
+	let (name, matches) = matches.subcommand();
+
+	if let Some(matches) = matches {
+		module_manager.run(name, matches);
+	} else {
+		app_clone.print_help().unwrap_or(());
+		println!();
+	}
+}
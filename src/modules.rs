@@ -1,27 +1,132 @@
-use clap::{App, ArgMatches};
+use crate::config::{Config, CONFIG};
+use crate::error::Error;
+use chrono::Local;
+use clap::{App, ArgMatches, ErrorKind, Result as ClapResult};
 use linked_hash_map::LinkedHashMap;
-use std::iter;
-mod aes;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(feature = "crypto")]
+pub mod aes;
+mod alias;
+#[cfg(feature = "compression")]
+pub mod archive;
+pub mod banner;
 mod base;
-mod base58;
-mod base64;
-mod case;
+pub mod base58;
+pub mod base64;
+mod bench;
+#[cfg(feature = "structured-data")]
+pub mod bson;
+pub mod case;
+#[cfg(feature = "structured-data")]
+pub mod cbor;
 mod completion;
-mod ecdsa;
-mod eddsa;
-mod hash;
-mod hex;
-mod html;
-mod number_codec;
-mod number_system;
-mod pbkdf2;
-mod re;
-mod sm4;
-mod srdsa;
-mod time;
-mod unicode;
-mod url;
+#[cfg(feature = "compression")]
+pub mod compress;
+pub mod count;
+#[cfg(feature = "structured-data")]
+pub mod csv;
+pub mod diff;
+#[cfg(feature = "web")]
+mod dns;
+#[cfg(feature = "structured-data")]
+pub mod dotenv;
+#[cfg(feature = "crypto")]
+pub mod ecdsa;
+#[cfg(feature = "crypto")]
+pub mod eddsa;
+#[cfg(feature = "web")]
+pub mod email;
+pub mod eol;
+#[cfg(feature = "filetype")]
+pub mod filetype;
+pub mod hash;
+pub mod hex;
+#[cfg(feature = "web")]
+pub mod html;
+#[cfg(feature = "web")]
+mod http;
+#[cfg(feature = "web")]
+pub mod http_header;
+#[cfg(feature = "structured-data")]
+pub mod ini;
+#[cfg(feature = "web")]
+mod ipinfo;
+#[cfg(feature = "structured-data")]
+pub mod json;
+pub mod lines;
+pub mod lorem;
+#[cfg(feature = "web")]
+pub mod markdown;
+#[cfg(feature = "filetype")]
+pub mod mime;
+#[cfg(feature = "structured-data")]
+pub mod msgpack;
+pub mod nanoid;
+#[cfg(feature = "structured-data")]
+pub mod ndjson;
+#[cfg(feature = "blockchain")]
+pub mod number_codec;
+pub mod number_system;
+#[cfg(feature = "crypto")]
+pub mod pbkdf2;
+mod pipe;
+mod plugin;
+#[cfg(feature = "structured-data")]
+pub mod protobuf;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod re;
+pub mod semver;
+#[cfg(feature = "web")]
+mod serve;
+pub mod similarity;
+pub mod slug;
+#[cfg(feature = "crypto")]
+pub mod sm4;
+pub mod snowflake;
+#[cfg(feature = "blockchain")]
+pub mod srdsa;
+mod stream;
+pub mod string;
+pub mod time;
+#[cfg(feature = "web")]
+mod tls;
+#[cfg(feature = "structured-data")]
+pub mod toml;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "id")]
+pub mod ulid;
+pub mod unicode;
+#[cfg(feature = "web")]
+mod update;
+#[cfg(feature = "web")]
+pub mod url;
 mod usage;
+#[cfg(feature = "web")]
+pub mod user_agent;
+#[cfg(feature = "id")]
+pub mod uuid;
+#[cfg(feature = "plugins")]
+mod wasm;
+pub mod whitespace;
+pub mod wrap;
+#[cfg(feature = "web")]
+mod ws;
+#[cfg(feature = "structured-data")]
+pub mod xml;
+#[cfg(feature = "structured-data")]
+pub mod yaml;
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Clone)]
 pub struct Module<'a, 'b> {
@@ -33,78 +138,750 @@ pub struct Module<'a, 'b> {
 #[derive(Clone)]
 pub struct Command<'a, 'b> {
 	pub app: App<'a, 'b>,
-	pub f: fn(&ArgMatches<'a>) -> Result<Vec<String>, String>,
+	pub f: fn(&ArgMatches<'a>) -> Result<Vec<String>, Error>,
+	/// Optional, used instead of f for large input from stdin or a file
+	pub stream: Option<StreamF<'a>>,
 }
 
+pub type StreamF<'a> = fn(&ArgMatches<'a>) -> Result<Box<dyn stream::Stream>, Error>;
+
 #[derive(Clone)]
 pub struct Case {
 	pub desc: String,
 	pub input: Vec<String>,
 	pub output: Vec<String>,
 	pub is_example: bool,
+	/// Run by the tests of the module
+	#[cfg_attr(not(test), allow(dead_code))]
 	pub is_test: bool,
 	pub since: String,
 }
 
+/// Groups of sub commands in `dtool --help`, in order
+#[derive(Clone, Copy, PartialEq)]
+pub enum Category {
+	Encoding,
+	Hash,
+	Crypto,
+	Time,
+	Data,
+	Text,
+	Network,
+	/// Unique IDs, e.g. UUID
+	Id,
+	/// Sub commands running other sub commands, e.g. pipe and serve
+	Tool,
+	/// Aliases and plugins from the user config
+	Plugin,
+}
+
+impl Category {
+	pub const ALL: [Category; 10] = [
+		Category::Encoding,
+		Category::Hash,
+		Category::Crypto,
+		Category::Time,
+		Category::Data,
+		Category::Text,
+		Category::Network,
+		Category::Id,
+		Category::Tool,
+		Category::Plugin,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Category::Encoding => "encoding",
+			Category::Hash => "hash",
+			Category::Crypto => "crypto",
+			Category::Time => "time",
+			Category::Data => "data",
+			Category::Text => "text",
+			Category::Network => "network",
+			Category::Id => "id",
+			Category::Tool => "tool",
+			Category::Plugin => "plugin",
+		}
+	}
+}
+
 pub struct ModuleManager<'a, 'b> {
 	modules: Vec<Module<'a, 'b>>,
 	commands: LinkedHashMap<String, Command<'a, 'b>>,
+	categories: HashMap<String, Category>,
+	/// Option values of sub commands not given in the args
+	config: Option<&'static Config>,
 }
 
 impl<'a, 'b> ModuleManager<'a, 'b> {
+	/// With the option values from the user config
 	pub fn new() -> Self {
+		Self {
+			config: Some(&CONFIG),
+			..Self::new_without_config()
+		}
+	}
+
+	pub fn new_without_config() -> Self {
 		let mut mm = Self {
 			modules: Vec::new(),
 			commands: LinkedHashMap::new(),
+			categories: HashMap::new(),
+			config: None,
 		};
-		mm.register(hex::module());
-		mm.register(time::module());
-		mm.register(number_system::module());
-		mm.register(base58::module());
-		mm.register(base64::module());
-		mm.register(url::module());
-		mm.register(number_codec::module());
-		mm.register(hash::module());
-		mm.register(unicode::module());
-		mm.register(html::module());
-		mm.register(re::module());
-		mm.register(pbkdf2::module());
-		mm.register(case::module());
-		mm.register(aes::module());
-		mm.register(ecdsa::module());
-		mm.register(sm4::module());
-		mm.register(eddsa::module());
-		mm.register(srdsa::module());
+		mm.register(Category::Encoding, hex::module());
+		mm.register(Category::Time, time::module());
+		mm.register(Category::Encoding, number_system::module());
+		mm.register(Category::Encoding, base58::module());
+		mm.register(Category::Encoding, base64::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Encoding, url::module());
+		#[cfg(feature = "blockchain")]
+		mm.register(Category::Encoding, number_codec::module());
+		mm.register(Category::Hash, hash::module());
+		mm.register(Category::Encoding, unicode::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Encoding, html::module());
+		#[cfg(feature = "qr")]
+		mm.register(Category::Encoding, qr::module());
+		mm.register(Category::Text, re::module());
+		#[cfg(feature = "crypto")]
+		mm.register(Category::Crypto, pbkdf2::module());
+		mm.register(Category::Text, case::module());
+		#[cfg(feature = "crypto")]
+		mm.register(Category::Crypto, aes::module());
+		#[cfg(feature = "crypto")]
+		mm.register(Category::Crypto, ecdsa::module());
+		#[cfg(feature = "crypto")]
+		mm.register(Category::Crypto, sm4::module());
+		#[cfg(feature = "crypto")]
+		mm.register(Category::Crypto, eddsa::module());
+		#[cfg(feature = "blockchain")]
+		mm.register(Category::Crypto, srdsa::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, json::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, yaml::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, toml::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, csv::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, msgpack::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, cbor::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, bson::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, xml::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, ini::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, dotenv::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, ndjson::module());
+		#[cfg(feature = "structured-data")]
+		mm.register(Category::Data, protobuf::module());
+		#[cfg(feature = "filetype")]
+		mm.register(Category::Data, mime::module());
+		#[cfg(feature = "filetype")]
+		mm.register(Category::Data, filetype::module());
+		mm.register(Category::Text, diff::module());
+		mm.register(Category::Text, lines::module());
+		mm.register(Category::Text, slug::module());
+		mm.register(Category::Text, lorem::module());
+		mm.register(Category::Text, count::module());
+		mm.register(Category::Text, similarity::module());
+		mm.register(Category::Text, eol::module());
+		mm.register(Category::Text, whitespace::module());
+		mm.register(Category::Text, wrap::module());
+		mm.register(Category::Text, string::module());
+		mm.register(Category::Text, banner::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Text, markdown::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, dns::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, ipinfo::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, http::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, http_header::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, tls::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, ws::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, user_agent::module());
+		#[cfg(feature = "web")]
+		mm.register(Category::Network, email::module());
+		#[cfg(feature = "id")]
+		mm.register(Category::Id, uuid::module());
+		#[cfg(feature = "id")]
+		mm.register(Category::Id, ulid::module());
+		mm.register(Category::Id, nanoid::module());
+		mm.register(Category::Id, snowflake::module());
+		mm.register(Category::Text, semver::module());
+		#[cfg(feature = "compression")]
+		mm.register(Category::Encoding, compress::module());
+		#[cfg(feature = "compression")]
+		mm.register(Category::Encoding, archive::module());
 		mm
 	}
 
-	pub fn apps(&self) -> Vec<App<'a, 'b>> {
-		self.commands
+	/// Whether the name is a sub command, not an alias or a plugin
+	pub fn is_builtin(&self, name: &str) -> bool {
+		self.commands.contains_key(name) || tool_apps().iter().any(|x| x.get_name() == name)
+	}
+
+	/// Sub commands, with aliases and plugins if `extensions`
+	/// (plugins are discovered on PATH and in the plugin dir, only when needed)
+	pub fn apps(&self, extensions: bool) -> Vec<App<'a, 'b>> {
+		let mut apps = self
+			.commands
 			.iter()
 			.map(|(_, command)| command.app.to_owned())
-			.chain(iter::once(usage::app()))
-			.chain(iter::once(completion::app()))
-			.collect()
+			.chain(tool_apps())
+			.collect::<Vec<_>>();
+		if !extensions {
+			return apps;
+		}
+
+		// aliases and plugins can't override sub commands,
+		// aliases take precedence over plugins, and plugins over WASM plugins
+		let mut extensions = alias::apps();
+		extensions.extend(plugin::apps());
+		#[cfg(feature = "plugins")]
+		extensions.extend(wasm::apps());
+		for app in extensions {
+			if !apps.iter().any(|x| x.get_name() == app.get_name()) {
+				apps.push(app);
+			}
+		}
+
+		apps
 	}
 
 	pub fn run(&self, name: &str, matches: &ArgMatches<'a>) {
+		let start = Instant::now();
+		let mut failed = false;
 		let result = match name {
 			"usage" => usage::run(matches, &self.modules),
 			"completion" => completion::run(matches),
-			_ => (self.commands.get(name).expect("subcommand must exist").f)(matches),
+			"pipe" => pipe::run(matches, self),
+			"bench" => bench::run(matches, self),
+			#[cfg(feature = "web")]
+			"serve" => serve::run(matches, self),
+			#[cfg(feature = "tui")]
+			"tui" => tui::run(matches, self),
+			#[cfg(feature = "web")]
+			"update" => update::run(matches),
+			_ if !self.commands.contains_key(name) => self.run_extension(name, matches),
+			_ if matches.is_present("EACH_LINE") => {
+				verbose_args(name, matches, "each line");
+				self.run_each_line(name, &mut failed)
+			}
+			_ if matches.is_present("WATCH") => {
+				verbose_args(name, matches, "watch");
+				self.run_watch(name, matches)
+			}
+			_ => self.run_input(name, matches, &mut failed),
 		};
 
+		let result = result.and_then(|result| base::output(name, matches, result));
+
+		if matches.is_present("TIMING") {
+			eprintln!("{}", base::timing(start.elapsed()));
+		}
+
 		match result {
-			Ok(result) => result.iter().for_each(|x| println!("{}", x)),
-			Err(e) => eprintln!("{}", e),
+			Ok(()) if !failed => (),
+			Ok(()) => std::process::exit(1),
+			Err(e) => {
+				eprintln!("{}", base::error(matches, &e));
+				std::process::exit(1);
+			}
+		}
+	}
+
+	/// Runs the command with INPUT, INPUT_FILE (multiple files in parallel) or stdin
+	fn run_input(
+		&self,
+		name: &str,
+		matches: &ArgMatches<'a>,
+		failed: &mut bool,
+	) -> Result<Vec<String>, Error> {
+		if multiple_files(matches) {
+			verbose_args(name, matches, "files in parallel");
+			return self.run_files(name, matches, failed);
+		}
+		if matches.occurrences_of("INPUT") > 1 {
+			verbose_args(name, matches, "each INPUT");
+			return self.run_each_input(name, matches, failed);
+		}
+		let command = self.commands.get(name).expect("subcommand must exist");
+		match command.stream {
+			Some(stream) if stream::streamable(matches) => {
+				verbose_args(name, matches, "stream");
+				stream(matches)
+					.and_then(|x| stream::run(x, matches))
+					.map(|_| vec![])
+			}
+			_ => {
+				verbose_args(name, matches, "whole input");
+				(command.f)(matches)
+			}
+		}
+	}
+
+	/// Runs the command whenever a file of INPUT_FILE changes (polled), until killed,
+	/// a timestamped separator is written to stderr before every run
+	fn run_watch(&self, name: &str, matches: &ArgMatches<'a>) -> Result<Vec<String>, Error> {
+		let files = input_files(matches)?;
+
+		let mut last = None;
+		loop {
+			let modified = files
+				.iter()
+				.map(|x| fs::metadata(x).and_then(|x| x.modified()).ok())
+				.collect::<Vec<_>>();
+			if last.as_ref() != Some(&modified) {
+				last = Some(modified);
+				eprintln!("--- {} ---", Local::now().format("%Y-%m-%d %H:%M:%S"));
+				let mut failed = false;
+				let result = self
+					.run_input(name, matches, &mut failed)
+					.and_then(|result| base::output(name, matches, result));
+				if let Err(e) = result {
+					eprintln!("{}", base::error(matches, &e));
+				}
+			}
+			thread::sleep(WATCH_INTERVAL);
+		}
+	}
+
+	/// Runs the command with every line of stdin as INPUT,
+	/// a failed line is reported to stderr and left blank in the output
+	fn run_each_line(&self, name: &str, failed: &mut bool) -> Result<Vec<String>, Error> {
+		let command = self.commands.get(name).expect("subcommand must exist");
+
+		// the other args are passed to every run
+		let args = env::args()
+			.skip_while(|x| x != name)
+			.filter(|x| x != "--each-line")
+			.collect::<Vec<_>>();
+
+		let mut result = vec![];
+		for (i, line) in io::stdin().lock().lines().enumerate() {
+			let line = line.map_err(|_| Error::input(""))?;
+			let args = args
+				.iter()
+				.cloned()
+				.chain(vec!["--".to_string(), line])
+				.collect::<Vec<_>>();
+			let run = self
+				.get_matches(&command.app, &args, 0)
+				.map_err(|e| Error::failed(e.message))
+				.and_then(|matches| (command.f)(&matches));
+			match run {
+				Ok(x) => result.extend(x),
+				Err(e) => {
+					eprintln!("line {}: {}", i + 1, e);
+					*failed = true;
+					result.push("".to_string());
+				}
+			}
+		}
+
+		Ok(result)
+	}
+
+	/// Runs the command with every INPUT value, one result per value in order,
+	/// a failed value is reported to stderr and left blank in the output
+	fn run_each_input(
+		&self,
+		name: &str,
+		matches: &ArgMatches<'a>,
+		failed: &mut bool,
+	) -> Result<Vec<String>, Error> {
+		let f = self.commands.get(name).expect("subcommand must exist").f;
+
+		let mut result = vec![];
+		for (i, input) in matches.values_of("INPUT").into_iter().flatten().enumerate() {
+			match base::with_input(input.to_string(), || f(matches)) {
+				Ok(x) => result.extend(x),
+				Err(e) => {
+					eprintln!("input {}: {}", i + 1, e);
+					*failed = true;
+					result.push("".to_string());
+				}
+			}
+		}
+
+		Ok(result)
+	}
+
+	/// Runs the alias or plugin of the name,
+	/// aliases take precedence over plugins, and plugins over WASM plugins
+	fn run_extension(&self, name: &str, matches: &ArgMatches<'a>) -> Result<Vec<String>, Error> {
+		if alias::get(name).is_some() {
+			return alias::run(name, matches, self);
+		}
+		if plugin::contains(name) {
+			return plugin::run(name, matches);
+		}
+		#[cfg(feature = "plugins")]
+		if wasm::contains(name) {
+			return wasm::run(name, matches);
+		}
+		Err(Error::invalid("sub command", name))
+	}
+
+	/// Runs the command with every file of INPUT_FILE (globs expanded) in parallel
+	/// (with the parallel feature), the results are `file: result` lines in the order of the files,
+	/// a failed file is reported to stderr
+	fn run_files(
+		&self,
+		name: &str,
+		matches: &ArgMatches<'a>,
+		failed: &mut bool,
+	) -> Result<Vec<String>, Error> {
+		let f = self.commands.get(name).expect("subcommand must exist").f;
+
+		let files = input_files(matches)?;
+
+		#[cfg(feature = "parallel")]
+		let iter = files.par_iter();
+		#[cfg(not(feature = "parallel"))]
+		let iter = files.iter();
+
+		let runs = iter
+			.map(|file| {
+				let input = fs::read(file).map_err(|e| Error::io(file, e))?;
+				let (result, output) = base::with_io(input, || f(matches));
+				let mut result = result?;
+				// raw output written by the command
+				if result.is_empty() && !output.is_empty() {
+					result = String::from_utf8_lossy(&output)
+						.lines()
+						.map(String::from)
+						.collect();
+				}
+				Ok(result)
+			})
+			.collect::<Vec<Result<Vec<String>, Error>>>();
+
+		let mut result = vec![];
+		for (file, run) in files.iter().zip(runs) {
+			match run {
+				Ok(x) => result.extend(x.into_iter().map(|x| format!("{}: {}", file, x))),
+				Err(e) => {
+					eprintln!("{}: {}", file, e);
+					*failed = true;
+				}
+			}
 		}
+
+		Ok(result)
 	}
 
-	fn register(&mut self, module: Module<'a, 'b>) {
+	/// Parses the args, with the sub command name at `at` (0 in process, 1 on the command line),
+	/// options of the sub command not given are filled from the user config
+	pub fn get_matches(
+		&self,
+		app: &App<'a, 'b>,
+		args: &[String],
+		at: usize,
+	) -> ClapResult<ArgMatches<'a>> {
+		let config = match (self.config, args.get(at).and_then(|x| self.commands.get(x))) {
+			(Some(config), Some(command)) => config_args(config, &command.app, &args[at + 1..]),
+			_ => vec![],
+		};
+		get_matches_with_config(app, args, at, config)
+	}
+
+	/// Sorted names of the apps in every category, empty categories are skipped
+	pub fn categories(&self, apps: &[App]) -> Vec<(Category, Vec<String>)> {
+		let tools = tool_apps()
+			.iter()
+			.map(|x| x.get_name().to_string())
+			.collect::<Vec<_>>();
+
+		Category::ALL
+			.iter()
+			.map(|&category| {
+				let mut names = apps
+					.iter()
+					.map(|x| x.get_name().to_string())
+					.filter(|name| {
+						let actual = match self.categories.get(name) {
+							Some(&category) => category,
+							None if tools.contains(name) => Category::Tool,
+							None => Category::Plugin,
+						};
+						actual == category
+					})
+					.collect::<Vec<_>>();
+				names.sort();
+				(category, names)
+			})
+			.filter(|(_, names)| !names.is_empty())
+			.collect()
+	}
+
+	fn register(&mut self, category: Category, module: Module<'a, 'b>) {
 		self.modules.push(module.clone());
 		for command in module.commands {
-			self.commands
-				.insert(command.app.get_name().to_string(), command);
+			let name = command.app.get_name().to_string();
+			self.categories.insert(name.clone(), category);
+			self.commands.insert(name, command);
+		}
+	}
+}
+
+/// Sub commands running other sub commands or dtool itself
+fn tool_apps<'a, 'b>() -> Vec<App<'a, 'b>> {
+	vec![
+		usage::app(),
+		completion::app(),
+		pipe::app(),
+		bench::app(),
+		#[cfg(feature = "web")]
+		serve::app(),
+		#[cfg(feature = "tui")]
+		tui::app(),
+		#[cfg(feature = "web")]
+		update::app(),
+	]
+}
+
+/// Logs the sub command, the args given (e.g. algorithm parameters) and how the input is processed
+fn verbose_args(name: &str, matches: &ArgMatches, mode: &str) {
+	base::verbose(matches, 1, || args_message(name, matches, mode));
+}
+
+/// Values of SECRET_ARGS are redacted
+fn args_message(name: &str, matches: &ArgMatches, mode: &str) -> String {
+	let mut args = matches
+		.args
+		.iter()
+		.filter(|(key, _)| !["INPUT", "INPUTS", "VERBOSE"].contains(key))
+		.map(|(key, arg)| {
+			let values = arg
+				.vals
+				.iter()
+				.map(|x| x.to_string_lossy())
+				.collect::<Vec<_>>();
+			if values.is_empty() {
+				key.to_string()
+			} else if SECRET_ARGS.contains(key) {
+				format!("{}=<redacted>", key)
+			} else {
+				format!("{}={}", key, values.join(","))
+			}
+		})
+		.collect::<Vec<_>>();
+	args.sort();
+	format!(
+		"sub command: {}, args: [{}], mode: {}",
+		name,
+		args.join(" "),
+		mode
+	)
+}
+
+/// `--long=value` of the options of the sub command from the config, unless given in the args
+fn config_args(config: &Config, app: &App, args: &[String]) -> Vec<String> {
+	let args = args.iter().take_while(|x| *x != "--").collect::<Vec<_>>();
+	let given = |short: Option<char>, long: &str| {
+		args.iter().any(|x| {
+			*x == &format!("--{}", long)
+				|| x.starts_with(&format!("--{}=", long))
+				|| short.is_some_and(|short| {
+					!x.starts_with("--") && x.starts_with(&format!("-{}", short))
+				})
+		})
+	};
+	base::options(app)
+		.into_iter()
+		.filter(|(short, long)| !given(*short, long))
+		.filter_map(|(_, long)| {
+			config
+				.option(app.get_name(), &long)
+				.map(|value| format!("--{}={}", long, value))
+		})
+		.collect()
+}
+
+/// Parses with the config args inserted after the sub command name at `at`,
+/// a config arg conflicting with the args given is dropped (e.g. `format = "json"` with `--raw`)
+fn get_matches_with_config<'a>(
+	app: &App<'a, '_>,
+	args: &[String],
+	at: usize,
+	mut config: Vec<String>,
+) -> ClapResult<ArgMatches<'a>> {
+	let (head, tail) = args.split_at((at + 1).min(args.len()));
+	let parse = |config: &[String]| {
+		app.clone()
+			.get_matches_from_safe(head.iter().chain(config).chain(tail))
+	};
+	loop {
+		match parse(&config) {
+			Err(e) if e.kind == ErrorKind::ArgumentConflict => {
+				let conflicting = (0..config.len()).find(|&i| {
+					let mut config = config.clone();
+					config.remove(i);
+					parse(&config).map_or_else(|x| x.message != e.message, |_| true)
+				});
+				match conflicting {
+					Some(i) => {
+						config.remove(i);
+					}
+					None => return Err(e),
+				}
+			}
+			result => return result,
 		}
 	}
 }
+
+/// Secret options, also read from DTOOL_KEY (kept out of the shell history and process listings),
+/// redacted in the verbose output, never taken from DTOOL_KEY or the config by serve
+pub(crate) const SECRET_ARGS: [&str; 3] = ["KEY", "SECRET_KEY", "MINI_SECRET_KEY"];
+
+/// More than one INPUT_FILE, or globs
+fn multiple_files(matches: &ArgMatches) -> bool {
+	match matches.values_of("INPUT_FILE") {
+		Some(files) => {
+			let files = files.collect::<Vec<_>>();
+			files.len() > 1 || files.iter().any(|x| is_glob(x))
+		}
+		None => false,
+	}
+}
+
+/// INPUT_FILE with globs expanded
+fn input_files(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let mut result = vec![];
+	for file in matches.values_of("INPUT_FILE").into_iter().flatten() {
+		if !is_glob(file) {
+			result.push(file.to_string());
+			continue;
+		}
+		let paths = glob::glob(file)
+			.map_err(|e| Error::io(file, e))?
+			.filter_map(Result::ok)
+			.filter(|x| x.is_file())
+			.map(|x| x.to_string_lossy().to_string())
+			.collect::<Vec<_>>();
+		if paths.is_empty() {
+			return Err(Error::io(file, "no match"));
+		}
+		result.extend(paths);
+	}
+	Ok(result)
+}
+
+fn is_glob(file: &str) -> bool {
+	file.contains(['*', '?', '['])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{Arg, SubCommand};
+
+	#[test]
+	fn test_args_message() {
+		let app = SubCommand::with_name("hmac")
+			.arg(
+				Arg::with_name("KEY")
+					.long("key")
+					.short("k")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("MINI_SECRET_KEY")
+					.long("mini-secret-key")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("ALGORITHM")
+					.long("algorithm")
+					.takes_value(true),
+			);
+		let matches = app.get_matches_from(vec![
+			"hmac",
+			"-k",
+			"0x1234",
+			"--mini-secret-key",
+			"0x5678",
+			"--algorithm",
+			"sha256",
+		]);
+		let message = args_message("hmac", &matches, "whole input");
+		assert_eq!(
+			message,
+			"sub command: hmac, args: [ALGORITHM=sha256 KEY=<redacted> MINI_SECRET_KEY=<redacted>], mode: whole input"
+		);
+	}
+
+	#[test]
+	fn test_config_args() {
+		let config = Config::parse("[default]\nformat = \"json\"\n[hash]\nalgo = \"md5\"").unwrap();
+		let mm = ModuleManager::new_without_config();
+		let app = &mm.commands["hash"].app;
+		let args = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+		assert_eq!(
+			config_args(&config, app, &args(&["abc"])),
+			vec!["--algo=md5", "--format=json"]
+		);
+		assert_eq!(
+			config_args(&config, app, &args(&["-a", "sha2_256", "abc"])),
+			vec!["--format=json"]
+		);
+		assert_eq!(
+			config_args(
+				&config,
+				app,
+				&args(&["--algo=sha2_256", "--format", "plain"])
+			),
+			Vec::<String>::new()
+		);
+		// INPUT after --
+		assert_eq!(
+			config_args(&config, app, &args(&["--", "-a"])),
+			vec!["--algo=md5", "--format=json"]
+		);
+
+		// the format from the config conflicts with --raw
+		let config = config_args(&config, app, &args(&["--raw", "0x61"]));
+		let matches =
+			get_matches_with_config(app, &args(&["hash", "--raw", "0x61"]), 0, config).unwrap();
+		assert_eq!(matches.value_of("ALGORITHM"), Some("md5"));
+		assert!(!matches.is_present("OUTPUT_FORMAT"));
+	}
+
+	#[test]
+	fn test_common_args() {
+		let mm = ModuleManager::new_without_config();
+		for (name, command) in &mm.commands {
+			for (short, long) in base::options(&command.app) {
+				match short {
+					Some('f') => assert_eq!(long, "file", "{}", name),
+					Some('o') => assert_eq!(long, "output", "{}", name),
+					_ => (),
+				}
+			}
+		}
+
+		let matches = mm.commands["ts2d"]
+			.app
+			.clone()
+			.get_matches_from(vec!["ts2d", "-f", "a.txt", "-F", "unix"]);
+		assert_eq!(matches.value_of("INPUT_FILE"), Some("a.txt"));
+		assert_eq!(matches.value_of("FORMAT"), Some("unix"));
+	}
+}
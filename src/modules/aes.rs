@@ -1,7 +1,7 @@
-use self::Mode::{CBC, CTR, ECB};
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 use crypto::aes::{cbc_decryptor, cbc_encryptor, ctr, ecb_decryptor, ecb_encryptor, KeySize};
 use crypto::blockmodes::PkcsPadding;
 use crypto::buffer::{RefReadBuffer, RefWriteBuffer, WriteBuffer};
@@ -18,14 +18,10 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("aes_enc")
+			app: base::sub_command("aes_enc")
 				.about("AES encrypt")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Plain (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Plain (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("MODE")
 						.long("mode")
@@ -41,6 +37,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("k")
 						.help("Key (Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				)
 				.arg(
@@ -51,17 +49,14 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				),
-			f: aes_enc,
+			f: |matches| base::convert(matches, aes_enc),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("aes_dec")
+			app: base::sub_command("aes_dec")
 				.about("AES decrypt")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Cipher (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Cipher (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("MODE")
 						.long("mode")
@@ -77,6 +72,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("k")
 						.help("Key (Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				)
 				.arg(
@@ -87,147 +84,177 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				),
-			f: aes_dec,
+			f: |matches| base::convert(matches, aes_dec),
+			stream: None,
 		},
 	]
 }
 
-enum Mode {
-	ECB,
-	CBC { iv: Vec<u8> },
-	CTR { iv: Vec<u8> },
+/// Options of `aes_enc` and `aes_dec`, the key is 128, 192 or 256 bits
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub key: Vec<u8>,
+	pub mode: Mode,
 }
 
-fn aes_enc(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let (key_size, key, mode, input) = get_common_arg(matches)?;
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+	Ecb,
+	Cbc { iv: Vec<u8> },
+	Ctr { iv: Vec<u8> },
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let key = matches.value_of("KEY").ok_or(Error::invalid("key", ""))?;
+		let key: Vec<u8> = key
+			.parse::<Hex>()
+			.map_err(|_| Error::invalid("key", ""))?
+			.into();
+
+		let get_iv = || -> Result<Vec<u8>, Error> {
+			let iv = matches.value_of("IV").ok_or(Error::invalid("IV", ""))?;
+			let iv: Vec<u8> = iv
+				.parse::<Hex>()
+				.map_err(|_| Error::invalid("IV", ""))?
+				.into();
+			Ok(iv)
+		};
+
+		let mode = matches.value_of("MODE").ok_or(Error::invalid("mode", ""))?;
+		let mode = match mode {
+			"ecb" => Mode::Ecb,
+			"cbc" => Mode::Cbc { iv: get_iv()? },
+			"ctr" => Mode::Ctr { iv: get_iv()? },
+			_ => unreachable!(),
+		};
+
+		Ok(Options { key, mode })
+	}
+}
+
+/// AES encrypt, the input is the plain (hex)
+pub fn aes_enc(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let (key_size, input) = get_common_arg(input, options)?;
+	let key = &options.key;
 
 	// cipher
-	let result = match mode {
-		ECB => aes_enc_ecb(key_size, &key, &input),
-		CBC { iv } => aes_enc_cbc(key_size, &key, &input, &iv),
-		CTR { iv } => aes_enc_ctr(key_size, &key, &input, &iv),
+	let result = match &options.mode {
+		Mode::Ecb => aes_enc_ecb(key_size, key, &input),
+		Mode::Cbc { iv } => aes_enc_cbc(key_size, key, &input, iv),
+		Mode::Ctr { iv } => aes_enc_ctr(key_size, key, &input, iv),
 	}?;
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn aes_dec(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let (key_size, key, mode, input) = get_common_arg(matches)?;
+/// AES decrypt, the input is the cipher (hex)
+pub fn aes_dec(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let (key_size, input) = get_common_arg(input, options)?;
+	let key = &options.key;
 
 	// plain
-	let result = match mode {
-		ECB => aes_dec_ecb(key_size, &key, &input),
-		CBC { iv } => aes_dec_cbc(key_size, &key, &input, &iv),
-		CTR { iv } => aes_dec_ctr(key_size, &key, &input, &iv),
+	let result = match &options.mode {
+		Mode::Ecb => aes_dec_ecb(key_size, key, &input),
+		Mode::Cbc { iv } => aes_dec_cbc(key_size, key, &input, iv),
+		Mode::Ctr { iv } => aes_dec_ctr(key_size, key, &input, iv),
 	}?;
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn get_common_arg(matches: &ArgMatches) -> Result<(KeySize, Vec<u8>, Mode, Vec<u8>), String> {
-	let input = base::input_string(matches)?;
-
-	// key and key_size
-	let key = matches.value_of("KEY").ok_or("Invalid key".to_string())?;
-	let key: Vec<u8> = key.parse::<Hex>().map_err(|_| "Invalid key")?.into();
-	let key_size = match key.len() {
+fn get_common_arg(input: &[u8], options: &Options) -> Result<(KeySize, Vec<u8>), Error> {
+	let key_size = match options.key.len() {
 		16 => KeySize::KeySize128,
 		24 => KeySize::KeySize192,
 		32 => KeySize::KeySize256,
-		_ => return Err("Invalid key size (should be 128/192/256)".to_string()),
+		_ => return Err(Error::invalid("key size (should be 128/192/256)", "")),
 	};
 
-	let get_iv = || -> Result<Vec<u8>, String> {
-		let iv = matches.value_of("IV").ok_or("Invalid IV".to_string())?;
-		let iv: Vec<u8> = iv.parse::<Hex>().map_err(|_| "Invalid IV")?.into();
-		Ok(iv)
-	};
-
-	// mode
-	let mode = matches.value_of("MODE").ok_or("Invalid mode".to_string())?;
-	let mode = match mode {
-		"ecb" => ECB,
-		"cbc" => CBC { iv: get_iv()? },
-		"ctr" => CTR { iv: get_iv()? },
-		_ => unreachable!(),
-	};
+	if let Mode::Cbc { iv } | Mode::Ctr { iv } = &options.mode {
+		if iv.len() != 16 {
+			return Err(Error::invalid("IV size (should be 128)", ""));
+		}
+	}
 
-	// input
-	let input = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
+	let input = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::input(""))?
+		.into();
 
-	Ok((key_size, key, mode, input))
+	Ok((key_size, input))
 }
 
-fn aes_enc_ecb(key_size: KeySize, key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_enc_ecb(key_size: KeySize, key: &[u8], input: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = ecb_encryptor(key_size, key, PkcsPadding);
 	let cipher_len = cipher_length(input.len());
 	let mut result = vec![0u8; cipher_len];
 	a.encrypt(
-		&mut RefReadBuffer::new(&input),
+		&mut RefReadBuffer::new(input),
 		&mut RefWriteBuffer::new(&mut result),
 		true,
 	)
-	.map_err(|_| "Enc failed")?;
+	.map_err(|_| Error::failed("Enc failed"))?;
 	Ok(result)
 }
 
-fn aes_enc_cbc(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_enc_cbc(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = cbc_encryptor(key_size, key, iv, PkcsPadding);
 	let cipher_len = cipher_length(input.len());
 	let mut result = vec![0u8; cipher_len];
 	a.encrypt(
-		&mut RefReadBuffer::new(&input),
+		&mut RefReadBuffer::new(input),
 		&mut RefWriteBuffer::new(&mut result),
 		true,
 	)
-	.map_err(|_| "Enc failed")?;
+	.map_err(|_| Error::failed("Enc failed"))?;
 	Ok(result)
 }
 
-fn aes_enc_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_enc_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = ctr(key_size, key, iv);
 	let mut result = vec![0u8; input.len()];
 	a.encrypt(
-		&mut RefReadBuffer::new(&input),
+		&mut RefReadBuffer::new(input),
 		&mut RefWriteBuffer::new(&mut result),
 		true,
 	)
-	.map_err(|_| "Enc failed")?;
+	.map_err(|_| Error::failed("Enc failed"))?;
 	Ok(result)
 }
 
-fn aes_dec_ecb(key_size: KeySize, key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_dec_ecb(key_size: KeySize, key: &[u8], input: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = ecb_decryptor(key_size, key, PkcsPadding);
 	let mut result = vec![0u8; input.len()];
 	let mut buffer = RefWriteBuffer::new(&mut result);
-	a.decrypt(&mut RefReadBuffer::new(&input), &mut buffer, true)
-		.map_err(|_| "Dec failed")?;
+	a.decrypt(&mut RefReadBuffer::new(input), &mut buffer, true)
+		.map_err(|_| Error::failed("Dec failed"))?;
 	let len = buffer.capacity() - buffer.remaining();
 	let mut result = result.clone();
 	result.truncate(len);
 	Ok(result)
 }
 
-fn aes_dec_cbc(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_dec_cbc(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = cbc_decryptor(key_size, key, iv, PkcsPadding);
 	let mut result = vec![0u8; input.len()];
 	let mut buffer = RefWriteBuffer::new(&mut result);
-	a.decrypt(&mut RefReadBuffer::new(&input), &mut buffer, true)
-		.map_err(|_| "Dec failed")?;
+	a.decrypt(&mut RefReadBuffer::new(input), &mut buffer, true)
+		.map_err(|_| Error::failed("Dec failed"))?;
 	let len = buffer.capacity() - buffer.remaining();
 	let mut result = result.clone();
 	result.truncate(len);
 	Ok(result)
 }
 
-fn aes_dec_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn aes_dec_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	let mut a = ctr(key_size, key, iv);
 	let mut result = vec![0u8; input.len()];
 	let mut buffer = RefWriteBuffer::new(&mut result);
-	a.decrypt(&mut RefReadBuffer::new(&input), &mut buffer, true)
-		.map_err(|_| "Dec failed")?;
+	a.decrypt(&mut RefReadBuffer::new(input), &mut buffer, true)
+		.map_err(|_| Error::failed("Dec failed"))?;
 	Ok(result)
 }
 
@@ -0,0 +1,82 @@
+use crate::config::CONFIG;
+use crate::error::Error;
+use crate::modules::{base, pipe, ModuleManager};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+/// Aliases from the user config
+pub fn apps<'a, 'b>() -> Vec<App<'a, 'b>> {
+	CONFIG
+		.aliases
+		.iter()
+		.map(|(name, expansion)| {
+			SubCommand::with_name(name)
+				.about(expansion.as_str())
+				.setting(AppSettings::AllowLeadingHyphen)
+				.arg(
+					Arg::with_name("ARGS")
+						.help("Args appended to the alias")
+						.multiple(true)
+						.allow_hyphen_values(true)
+						.required(false),
+				)
+		})
+		.collect()
+}
+
+pub fn get(name: &str) -> Option<&'static str> {
+	CONFIG
+		.aliases
+		.iter()
+		.find(|(alias, _)| alias == name)
+		.map(|(_, expansion)| expansion.as_str())
+}
+
+/// Runs the expanded sub command with the args appended, the output is written here
+pub fn run<'a, 'b>(
+	name: &str,
+	matches: &ArgMatches,
+	mm: &ModuleManager<'a, 'b>,
+) -> Result<Vec<String>, Error> {
+	let expansion = get(name).ok_or(Error::invalid("alias", ""))?;
+
+	let args = match pipe::parse(expansion)?.as_slice() {
+		[args] => args.clone(),
+		_ => {
+			return Err(Error::invalid(
+				"alias",
+				format!("{}: expect a sub command", name),
+			))
+		}
+	};
+
+	let command = mm.commands.get(&args[0]).ok_or_else(|| {
+		Error::invalid(
+			"alias",
+			format!(
+				"{}: unknown sub command: {}{}",
+				name,
+				args[0],
+				base::did_you_mean(&args[0], mm.commands.keys().map(String::as_str))
+			),
+		)
+	})?;
+
+	let args = args
+		.into_iter()
+		.chain(
+			matches
+				.values_of("ARGS")
+				.into_iter()
+				.flatten()
+				.map(String::from),
+		)
+		.collect::<Vec<_>>();
+	let matches = mm
+		.get_matches(&command.app, &args, 0)
+		.map_err(|e| Error::failed(e.message))?;
+
+	let result = (command.f)(&matches)?;
+	base::output(command.app.get_name(), &matches, result)?;
+
+	Ok(vec![])
+}
@@ -0,0 +1,475 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::{Arg, ArgMatches};
+use flate2::read::MultiGzDecoder;
+use std::borrow::Cow;
+use std::io::{Cursor, Read};
+use tar::EntryType;
+use zip::ZipArchive;
+
+/// File type bits of the mode in zip entries, as st_mode
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Archive list / extract (tar, tar.gz, zip)".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("archive_list")
+				.about("List the entries of a tar, tar.gz or zip archive, use -f for files\nmode, size, mtime (UTC for tar, local as stored for zip) and path")
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes(matches, archive_list),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("archive_extract")
+				.about("Extract an entry of a tar, tar.gz or zip archive to stdout, use -f and -o for files")
+				.arg(
+					Arg::with_name("ENTRY")
+						.long("entry")
+						.short("e")
+						.help("Path of the entry, as listed by archive_list")
+						.takes_value(true)
+						.required(true),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes_raw(matches, archive_extract),
+			stream: None,
+		},
+	]
+}
+
+struct Entry {
+	path: String,
+	/// `-`, `d`, `l`, `h`, ... as ls and tar -tv
+	kind: char,
+	mode: u32,
+	size: u64,
+	mtime: String,
+	link: Option<String>,
+}
+
+/// Options of the archive commands, only archive_extract has any
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Path of the entry, as listed by archive_list
+	pub entry: String,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			entry: matches.value_of("ENTRY").unwrap_or_default().to_string(),
+		})
+	}
+}
+
+/// List the entries of a tar, tar.gz or zip archive, the input is binary,
+/// mode, size, mtime (UTC for tar, local as stored for zip) and path
+pub fn archive_list(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let entries = match is_zip(input) {
+		true => zip_entries(input)?,
+		false => tar_entries(&tar_bytes(input)?)?,
+	};
+
+	let result = entries
+		.into_iter()
+		.map(|entry| {
+			let mut line = format!(
+				"{}{} {:>10} {} {}",
+				entry.kind,
+				permissions(entry.mode),
+				entry.size,
+				entry.mtime,
+				entry.path
+			);
+			if let Some(link) = entry.link {
+				line.push_str(&format!(" -> {}", link));
+			}
+			line
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Extract an entry of a tar, tar.gz or zip archive, the input and the output are binary
+pub fn archive_extract(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let name = options.entry.as_str();
+	if name.is_empty() {
+		return Err(Error::invalid("entry", ""));
+	}
+
+	let result = match is_zip(input) {
+		true => zip_extract(input, name)?,
+		false => tar_extract(&tar_bytes(input)?, name)?,
+	};
+
+	Ok(result)
+}
+
+fn is_zip(input: &[u8]) -> bool {
+	input.starts_with(b"PK")
+}
+
+/// tar.gz is decompressed, by the gzip magic number, tar is told by the ustar magic
+fn tar_bytes(input: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+	let output = match input.starts_with(&[0x1f, 0x8b]) {
+		true => {
+			let mut output = vec![];
+			MultiGzDecoder::new(input)
+				.read_to_end(&mut output)
+				.map_err(|e| Error::invalid("archive", format!("gzip: {}", e)))?;
+			Cow::Owned(output)
+		}
+		false => Cow::Borrowed(input),
+	};
+
+	if output.get(257..262) != Some(&b"ustar"[..]) {
+		return Err(Error::invalid("archive", "not tar, tar.gz or zip"));
+	}
+
+	Ok(output)
+}
+
+fn tar_entries(input: &[u8]) -> Result<Vec<Entry>, Error> {
+	let invalid = |e| Error::invalid("archive", format!("tar: {}", e));
+
+	let mut archive = tar::Archive::new(input);
+	let mut result = vec![];
+	for entry in archive.entries().map_err(invalid)? {
+		let entry = entry.map_err(invalid)?;
+		let header = entry.header();
+		let entry_type = header.entry_type();
+		let kind = match entry_type {
+			EntryType::Directory => 'd',
+			EntryType::Symlink => 'l',
+			EntryType::Link => 'h',
+			EntryType::Char => 'c',
+			EntryType::Block => 'b',
+			EntryType::Fifo => 'p',
+			_ => '-',
+		};
+		let mtime = header.mtime().map_err(invalid)?;
+		let mtime = Utc.timestamp_opt(mtime as i64, 0).single().ok_or_else(|| {
+			Error::invalid("archive", format!("tar: mtime out of range: {}", mtime))
+		})?;
+		result.push(Entry {
+			path: String::from_utf8_lossy(&entry.path_bytes()).to_string(),
+			kind,
+			mode: header.mode().map_err(invalid)?,
+			size: entry.size(),
+			mtime: mtime.format("%Y-%m-%d %H:%M:%S").to_string(),
+			link: entry
+				.link_name_bytes()
+				.filter(|_| entry_type.is_symlink() || entry_type.is_hard_link())
+				.map(|x| String::from_utf8_lossy(&x).to_string()),
+		});
+	}
+
+	Ok(result)
+}
+
+fn tar_extract(input: &[u8], name: &str) -> Result<Vec<u8>, Error> {
+	let invalid = |e| Error::invalid("archive", format!("tar: {}", e));
+
+	let mut archive = tar::Archive::new(input);
+	let mut paths = vec![];
+	for entry in archive.entries().map_err(invalid)? {
+		let mut entry = entry.map_err(invalid)?;
+		let path = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+		if same_path(&path, name) {
+			if !entry.header().entry_type().is_file() {
+				return Err(Error::invalid("entry", format!("{}: not a file", name)));
+			}
+			let mut output = vec![];
+			entry.read_to_end(&mut output).map_err(invalid)?;
+			return Ok(output);
+		}
+		paths.push(path);
+	}
+
+	Err(not_found(name, paths.iter().map(String::as_str)))
+}
+
+fn zip_entries(input: &[u8]) -> Result<Vec<Entry>, Error> {
+	let invalid = |e| Error::invalid("archive", format!("zip: {}", e));
+
+	let mut archive = ZipArchive::new(Cursor::new(input)).map_err(invalid)?;
+	let mut result = vec![];
+	for i in 0..archive.len() {
+		let file = archive.by_index_raw(i).map_err(invalid)?;
+		// as unzip, if the archive is not made on Unix
+		let mode = file
+			.unix_mode()
+			.unwrap_or(if file.is_dir() { 0o755 } else { 0o644 });
+		let kind = match (file.is_dir(), mode & S_IFMT == S_IFLNK) {
+			(true, _) => 'd',
+			(false, true) => 'l',
+			(false, false) => '-',
+		};
+		result.push(Entry {
+			path: file.name().to_string(),
+			kind,
+			mode,
+			size: file.size(),
+			mtime: file
+				.last_modified()
+				.map(|x| x.to_string())
+				.unwrap_or_else(|| "-".repeat(19)),
+			link: None,
+		});
+	}
+
+	Ok(result)
+}
+
+fn zip_extract(input: &[u8], name: &str) -> Result<Vec<u8>, Error> {
+	let invalid = |e| Error::invalid("archive", format!("zip: {}", e));
+
+	let mut archive = ZipArchive::new(Cursor::new(input)).map_err(invalid)?;
+	let index = archive.file_names().position(|path| same_path(path, name));
+	let index = match index {
+		Some(index) => index,
+		None => return Err(not_found(name, archive.file_names())),
+	};
+
+	let mut file = archive.by_index(index).map_err(invalid)?;
+	if file.is_dir() {
+		return Err(Error::invalid("entry", format!("{}: not a file", name)));
+	}
+	let mut output = vec![];
+	file.read_to_end(&mut output)
+		.map_err(|e| Error::invalid("archive", format!("zip: {}", e)))?;
+
+	Ok(output)
+}
+
+/// Regardless of a leading `./` and a trailing `/`
+fn same_path(path: &str, name: &str) -> bool {
+	let normalize = |x: &str| {
+		let x = x.strip_prefix("./").unwrap_or(x);
+		x.trim_end_matches('/').to_string()
+	};
+	normalize(path) == normalize(name)
+}
+
+fn not_found<'a>(name: &str, paths: impl IntoIterator<Item = &'a str>) -> Error {
+	Error::invalid(
+		"entry",
+		format!("{}: not found{}", name, base::did_you_mean(name, paths)),
+	)
+}
+
+/// e.g. `rwxr-xr-x`, with setuid, setgid and sticky bits as ls
+fn permissions(mode: u32) -> String {
+	let special = [(0o4000, 's'), (0o2000, 's'), (0o1000, 't')];
+	(0..3)
+		.map(|i| {
+			let bits = mode >> (6 - i * 3);
+			let (special_bit, special_char) = special[i];
+			let x = match (bits & 1 != 0, mode & special_bit != 0) {
+				(true, true) => special_char,
+				(false, true) => special_char.to_ascii_uppercase(),
+				(true, false) => 'x',
+				(false, false) => '-',
+			};
+			format!(
+				"{}{}{}",
+				if bits & 4 != 0 { 'r' } else { '-' },
+				if bits & 2 != 0 { 'w' } else { '-' },
+				x
+			)
+		})
+		.collect()
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"archive_list",
+				vec![Case {
+					desc: "tar.gz, tar and zip are detected".to_string(),
+					input: vec!["-f", "release.tar.gz"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"drwxr-xr-x          0 2024-06-01 12:00:00 dtool/",
+						"-rwxr-xr-x    8413296 2024-06-01 12:00:00 dtool/dtool",
+						"-rw-r--r--      11357 2024-06-01 12:00:00 dtool/LICENSE",
+						"lrwxrwxrwx          0 2024-06-01 12:00:00 dtool/dt -> dtool",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"archive_extract",
+				vec![Case {
+					desc: "An entry to stdout".to_string(),
+					input: vec!["-e", "dtool/LICENSE", "-f", "release.zip"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["Apache License", "..."]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+	use flate2::write::GzEncoder;
+	use flate2::Compression;
+	use std::io::Write;
+	use zip::write::SimpleFileOptions;
+	use zip::ZipWriter;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_tar() {
+		let mut builder = tar::Builder::new(vec![]);
+		let mut header = tar::Header::new_gnu();
+		header.set_entry_type(EntryType::Directory);
+		header.set_mode(0o755);
+		header.set_mtime(1717243200);
+		header.set_size(0);
+		builder.append_data(&mut header, "dtool/", &[][..]).unwrap();
+		let mut header = tar::Header::new_gnu();
+		header.set_mode(0o4755);
+		header.set_mtime(1717243200);
+		header.set_size(5);
+		builder
+			.append_data(&mut header, "dtool/dtool", &b"dtool"[..])
+			.unwrap();
+		let mut header = tar::Header::new_gnu();
+		header.set_entry_type(EntryType::Symlink);
+		header.set_mode(0o777);
+		header.set_mtime(1717243200);
+		header.set_size(0);
+		builder
+			.append_link(&mut header, "dtool/dt", "dtool")
+			.unwrap();
+		let tar = builder.into_inner().unwrap();
+
+		let mut gz = GzEncoder::new(vec![], Compression::default());
+		gz.write_all(&tar).unwrap();
+		let gz = gz.finish().unwrap();
+
+		for input in &[&tar, &gz] {
+			let input = tar_bytes(input).unwrap();
+			let result = tar_entries(&input)
+				.unwrap()
+				.into_iter()
+				.map(|x| (x.kind, permissions(x.mode), x.size, x.mtime, x.path, x.link))
+				.collect::<Vec<_>>();
+			assert_eq!(
+				result,
+				vec![
+					(
+						'd',
+						"rwxr-xr-x".to_string(),
+						0,
+						"2024-06-01 12:00:00".to_string(),
+						"dtool/".to_string(),
+						None
+					),
+					(
+						'-',
+						"rwsr-xr-x".to_string(),
+						5,
+						"2024-06-01 12:00:00".to_string(),
+						"dtool/dtool".to_string(),
+						None
+					),
+					(
+						'l',
+						"rwxrwxrwx".to_string(),
+						0,
+						"2024-06-01 12:00:00".to_string(),
+						"dtool/dt".to_string(),
+						Some("dtool".to_string())
+					),
+				]
+			);
+
+			assert_eq!(tar_extract(&input, "./dtool/dtool").unwrap(), b"dtool");
+			assert!(tar_extract(&input, "dtool").is_err());
+			assert_eq!(
+				tar_extract(&input, "dtool/dtol").unwrap_err().to_string(),
+				"Invalid entry: dtool/dtol: not found, did you mean dtool/dtool?"
+			);
+		}
+	}
+
+	#[test]
+	fn test_zip() {
+		let mut zip = ZipWriter::new(Cursor::new(vec![]));
+		let options = SimpleFileOptions::default().unix_permissions(0o644);
+		zip.add_directory("dtool/", options).unwrap();
+		zip.start_file("dtool/LICENSE", options).unwrap();
+		zip.write_all(&b"license ".repeat(100)).unwrap();
+		let zip = zip.finish().unwrap().into_inner();
+
+		assert!(is_zip(&zip));
+		let result = zip_entries(&zip)
+			.unwrap()
+			.into_iter()
+			.map(|x| (x.kind, permissions(x.mode), x.size, x.path))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			result,
+			vec![
+				('d', "rw-r--r--".to_string(), 0, "dtool/".to_string()),
+				(
+					'-',
+					"rw-r--r--".to_string(),
+					800,
+					"dtool/LICENSE".to_string()
+				),
+			]
+		);
+
+		assert_eq!(
+			zip_extract(&zip, "dtool/LICENSE").unwrap(),
+			b"license ".repeat(100)
+		);
+		assert!(zip_extract(&zip, "dtool").is_err());
+		assert!(zip_extract(&zip, "LICENSE").is_err());
+		assert!(zip_entries(&zip[..zip.len() - 10]).is_err());
+		assert!(tar_bytes(b"dtool").is_err());
+	}
+}
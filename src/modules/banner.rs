@@ -0,0 +1,143 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use figlet_rs::FIGfont;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "ASCII art banner".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("banner")
+			.about("Generate figlet style banner")
+			.arg(
+				Arg::with_name("FONT")
+					.long("font")
+					.short("F")
+					.help("Font")
+					.takes_value(true)
+					.possible_values(&["standard", "block"])
+					.default_value("standard")
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, banner),
+		stream: None,
+	}]
+}
+
+/// Options of `banner`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub font: Font,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Font {
+	#[default]
+	Standard,
+	Block,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let font = match matches.value_of("FONT") {
+			Some("block") => Font::Block,
+			_ => Font::Standard,
+		};
+
+		Ok(Options { font })
+	}
+}
+
+/// Generate figlet style banner
+pub fn banner(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let font = match options.font {
+		Font::Block => FIGfont::from_content(include_str!("banner/block.flf")),
+		Font::Standard => FIGfont::standard(),
+	}
+	.map_err(|_| Error::invalid("font", ""))?;
+
+	let mut result = vec![];
+	for line in base::text(input)?.lines() {
+		// chars not in the font are skipped, a line without any is left blank
+		match font.convert(line) {
+			Some(figure) => {
+				result.extend(figure.to_string().lines().map(|x| x.trim_end().to_string()))
+			}
+			None => result.push("".to_string()),
+		}
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"banner",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["dtool"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"      _   _                     _",
+						"   __| | | |_    ___     ___   | |",
+						"  / _` | | __|  / _ \\   / _ \\  | |",
+						" | (_| | | |_  | (_) | | (_) | | |",
+						"  \\__,_|  \\__|  \\___/   \\___/  |_|",
+						"",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Block font".to_string(),
+					input: vec!["-F", "block", "'Build OK'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"██  █ █ ███ █   ██      █  █ █",
+						"█ █ █ █  █  █   █ █    █ █ █ █",
+						"██  █ █  █  █   █ █    █ █ ██",
+						"█ █ █ █  █  █   █ █    █ █ █ █",
+						"██  ███ ███ ███ ██      █  █ █",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -1,36 +1,632 @@
-use clap::ArgMatches;
+use crate::error::Error;
+use crate::modules::similarity::levenshtein;
+use ansi_term::{Colour, Style};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::env;
+use std::fs;
 use std::io;
-use std::io::{BufRead, Read};
+use std::io::{Read, Write};
+use std::iter;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-pub fn input_string(matches: &ArgMatches) -> Result<String, String> {
-	match matches.value_of("INPUT") {
-		Some(input) => Ok(input.to_string()),
-		None => io::stdin()
-			.lock()
-			.lines()
-			.collect::<Result<Vec<String>, io::Error>>()
-			.map(|x| x.join("\n"))
-			.map_err(|_| "Invalid input".to_string()),
+thread_local! {
+	/// Input and output in process (e.g. the lib API) instead of stdin and stdout
+	static IO: RefCell<Option<(Vec<u8>, Vec<u8>)>> = const { RefCell::new(None) };
+	/// One of several INPUT values, used instead of INPUT
+	static INPUT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs f with the input instead of stdin, returns the output written instead of stdout,
+/// the input and output of an outer run (e.g. pipe in serve) are restored after
+pub fn with_io<T>(input: Vec<u8>, f: impl FnOnce() -> T) -> (T, Vec<u8>) {
+	let outer = IO.with(|io| io.borrow_mut().replace((input, vec![])));
+	let result = f();
+	let output = IO
+		.with(|io| std::mem::replace(&mut *io.borrow_mut(), outer))
+		.map(|(_, output)| output)
+		.unwrap_or_default();
+	(result, output)
+}
+
+/// Runs f with the INPUT value instead of the values given, e.g. once for every INPUT value
+pub fn with_input<T>(input: String, f: impl FnOnce() -> T) -> T {
+	INPUT.with(|x| *x.borrow_mut() = Some(input));
+	let result = f();
+	INPUT.with(|x| x.borrow_mut().take());
+	result
+}
+
+/// The INPUT value of the run if several are given, otherwise the INPUT given
+fn input_value(matches: &ArgMatches) -> Option<String> {
+	INPUT
+		.with(|x| x.borrow().clone())
+		.or_else(|| matches.value_of("INPUT").map(String::from))
+}
+
+/// A conversion of the lib API, e.g. `hex::h2s`, the output is the result lines joined by `\n`
+/// unless the sub command writes raw bytes (e.g. `h2b`)
+pub type Conversion<O> = fn(&[u8], &O) -> Result<Vec<u8>, Error>;
+
+/// Options of the conversions of a module, read from the args of its sub commands
+pub trait FromMatches: Sized {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error>;
+}
+
+/// Runs the conversion with the input as UTF-8 text, the output as the result lines
+pub fn convert<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_string(matches)?;
+	f(input.as_bytes(), &options).and_then(lines)
+}
+
+/// Runs the conversion with the input bytes as is, e.g. `b2h`
+pub fn convert_bytes<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_bytes(matches)?;
+	f(&input, &options).and_then(lines)
+}
+
+/// Runs the conversion with the input as UTF-8 text, the output written as raw bytes, e.g. `h2b`
+pub fn convert_raw<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_string(matches)?;
+	let output = f(input.as_bytes(), &options)?;
+	output_bytes(matches, &output)?;
+	Ok(vec![])
+}
+
+/// Runs the conversion with the input bytes as is, the output written as raw bytes, e.g. `compress`
+#[cfg(feature = "compression")]
+pub fn convert_bytes_raw<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_bytes(matches)?;
+	let output = f(&input, &options)?;
+	output_bytes(matches, &output)?;
+	Ok(vec![])
+}
+
+/// Runs the conversion without input, e.g. generating a key pair
+pub fn generate<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	f(&[], &options).and_then(lines)
+}
+
+/// Runs the conversion with the input as hex (raw bytes if BINARY is present), e.g. `m2j`
+#[cfg(feature = "structured-data")]
+pub fn convert_from_hex_or_binary<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_hex_or_binary(matches)?;
+	f(&input, &options).and_then(lines)
+}
+
+/// Runs the conversion with the output as hex (raw bytes if BINARY is present), e.g. `j2m`
+#[cfg(feature = "structured-data")]
+pub fn convert_to_hex_or_binary<O: FromMatches>(
+	matches: &ArgMatches,
+	f: Conversion<O>,
+) -> Result<Vec<String>, Error> {
+	let options = O::from_matches(matches)?;
+	let input = input_string(matches)?;
+	let output = f(input.as_bytes(), &options)?;
+	output_hex_or_binary(matches, output)
+}
+
+/// The input of a conversion as UTF-8 text
+pub fn text(input: &[u8]) -> Result<&str, Error> {
+	std::str::from_utf8(input)
+		.map_err(|e| Error::InvalidInput(format!("not UTF-8 at byte {}", e.valid_up_to())))
+}
+
+/// The result lines of the output of a conversion, none if empty (e.g. nothing matched)
+pub fn lines(output: Vec<u8>) -> Result<Vec<String>, Error> {
+	let output = String::from_utf8(output).map_err(|_| Error::failed("Not UTF-8"))?;
+	if output.is_empty() {
+		return Ok(vec![]);
+	}
+	Ok(output.split('\n').map(String::from).collect())
+}
+
+/// A sub command with the output args, `--format` and `--raw`
+pub fn sub_command<'a, 'b>(name: &str) -> App<'a, 'b> {
+	SubCommand::with_name(name)
+		.args(&output_args())
+		.arg(format_arg())
+		.arg(raw_arg().conflicts_with("OUTPUT_FORMAT"))
+}
+
+/// Optional INPUT, several values run the sub command once for every value, e.g. `hash -a md5 0x61 0x62`
+pub fn input_arg<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("INPUT")
+		.required(false)
+		.multiple(true)
+		.index(1)
+}
+
+/// `--file` (`-f`), `--each-line` and `--watch`, for sub commands with an optional INPUT
+pub fn file_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+	vec![
+		Arg::with_name("INPUT_FILE")
+			.long("file")
+			.short("f")
+			.value_name("FILE")
+			.help(
+				"Read input from file, - for stdin, multiple files or globs are processed in parallel",
+			)
+			.takes_value(true)
+			.multiple(true)
+			.conflicts_with("INPUT")
+			.required(false),
+		Arg::with_name("EACH_LINE")
+			.long("each-line")
+			.help("Run for every line of stdin, one result per line")
+			.conflicts_with_all(&["INPUT", "INPUT_FILE"])
+			.required(false),
+		Arg::with_name("WATCH")
+			.long("watch")
+			.help("Run again whenever the input file changes")
+			.requires("INPUT_FILE")
+			.required(false),
+	]
+}
+
+/// INPUT and the file args
+pub fn input_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+	iter::once(input_arg()).chain(file_args()).collect()
+}
+
+/// `--output` (`-o`), `--quiet` (`-q`), `--verbose` (`-v`), `--timing` and `--no-color` of every sub command
+pub fn output_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+	vec![
+		Arg::with_name("OUTPUT_FILE")
+			.long("output")
+			.short("o")
+			.value_name("FILE")
+			.help("Write output to file")
+			.takes_value(true)
+			.required(false),
+		Arg::with_name("QUIET")
+			.long("quiet")
+			.short("q")
+			.help("Write nothing to stdout, only the exit code (0: ok, 1: failed, e.g. invalid input) tells the result")
+			.required(false),
+		Arg::with_name("VERBOSE")
+			.long("verbose")
+			.short("v")
+			.help("Write the steps (e.g. input read, input format, args) to stderr, -vv also writes the data")
+			.multiple(true)
+			.required(false),
+		Arg::with_name("TIMING")
+			.long("timing")
+			.help("Write the elapsed time and the input bytes processed to stderr when finished")
+			.required(false),
+		Arg::with_name("NO_COLOR")
+			.long("no-color")
+			.help("Disable colors, also disabled by the NO_COLOR environment variable or if stdout is not a terminal")
+			.required(false),
+	]
+}
+
+/// `--format`, for sub commands without an output format of their own
+pub fn format_arg<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("OUTPUT_FORMAT")
+		.long("format")
+		.help("Output format\njson: {\"result\": [...], \"algorithm\": ..., \"warnings\": [...]}")
+		.takes_value(true)
+		.possible_values(&["plain", "json"])
+		.required(false)
+}
+
+/// `--raw`, for sub commands without a flag of their own
+pub fn raw_arg<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("RAW_OUTPUT")
+		.long("raw")
+		.help("Write hex output as raw bytes")
+		.required(false)
+}
+
+/// Short and long names of the options taking values, as listed in the help
+pub fn options(app: &App) -> Vec<(Option<char>, String)> {
+	let mut help = vec![];
+	if app
+		.clone()
+		.template("{options}")
+		.write_help(&mut help)
+		.is_err()
+	{
+		return vec![];
+	}
+	String::from_utf8_lossy(&help)
+		.lines()
+		.filter_map(|line| OPTION.captures(line))
+		.map(|x| {
+			(
+				x.get(1).and_then(|x| x.as_str().chars().next()),
+				x[2].to_string(),
+			)
+		})
+		.collect()
+}
+
+lazy_static! {
+	/// `    -a, --algo <ALGORITHM>` or `        --file <FILE>...` in the help
+	static ref OPTION: Regex = Regex::new(r"^    (?:-(\w), |    )--([\w-]+) <").unwrap();
+	static ref LABEL: Regex = Regex::new(r"^([A-Za-z][\w .-]*): (.*)$").unwrap();
+	static ref HEX_VALUE: Regex = Regex::new(r"^0x[0-9a-fA-F]+$").unwrap();
+}
+
+/// Input bytes read by the sub command (files in parallel included), reported by TIMING
+static INPUT_READ: AtomicU64 = AtomicU64::new(0);
+
+pub fn count_input(len: usize) {
+	INPUT_READ.fetch_add(len as u64, Ordering::Relaxed);
+}
+
+/// `timing: 0.012 s, 1048576 bytes, 87.38 MB/s`
+pub fn timing(elapsed: Duration) -> String {
+	let seconds = elapsed.as_secs_f64();
+	let bytes = INPUT_READ.load(Ordering::Relaxed);
+	let mbs = if seconds > 0.0 {
+		bytes as f64 / seconds / 1_000_000.0
+	} else {
+		0.0
+	};
+	format!("timing: {:.3} s, {} bytes, {:.2} MB/s", seconds, bytes, mbs)
+}
+
+/// The input if neither INPUT nor stdin is given, e.g. a secret kept out of the shell history
+const INPUT_ENV: &str = "DTOOL_INPUT";
+
+fn io_input() -> Option<Vec<u8>> {
+	IO.with(|io| io.borrow().as_ref().map(|(input, _)| input.clone()))
+}
+
+/// The input as UTF-8 text, with a trailing newline (`\n` or `\r\n`) of the file or stdin removed,
+/// other bytes (e.g. `\r` and NUL) are kept
+pub fn input_string(matches: &ArgMatches) -> Result<String, Error> {
+	if let Some(input) = input_value(matches) {
+		verbose(matches, 1, || {
+			format!("input: {} bytes from INPUT", input.len())
+		});
+		verbose(matches, 2, || {
+			format!("input: {}", preview(input.as_bytes()))
+		});
+		count_input(input.len());
+		return Ok(input);
+	}
+	let mut input = String::from_utf8(read_input(matches)?).map_err(|e| {
+		Error::InvalidInput(format!(
+			"not UTF-8 at byte {}",
+			e.utf8_error().valid_up_to()
+		))
+	})?;
+	if input.ends_with('\n') {
+		input.pop();
+		if input.ends_with('\r') {
+			input.pop();
+		}
+	}
+	Ok(input)
+}
+
+/// The input as raw bytes, as is
+pub fn input_bytes(matches: &ArgMatches) -> Result<Vec<u8>, Error> {
+	read_input(matches)
+}
+
+/// Whether the input is DTOOL_INPUT, i.e. neither INPUT_FILE is given nor stdin is piped
+pub fn env_input(matches: &ArgMatches) -> bool {
+	matches.value_of("INPUT_FILE").is_none()
+		&& atty::is(atty::Stream::Stdin)
+		&& env::var_os(INPUT_ENV).is_some()
+}
+
+/// INPUT if present, otherwise the input in process (e.g. a file of multiple INPUT_FILE),
+/// otherwise INPUT_FILE (`-` for stdin), DTOOL_INPUT if stdin is a terminal, or stdin
+fn read_input(matches: &ArgMatches) -> Result<Vec<u8>, Error> {
+	let (input, source) = if let Some(input) = input_value(matches) {
+		(input.into_bytes(), "INPUT".to_string())
+	} else {
+		match (io_input(), matches.value_of("INPUT_FILE")) {
+			(Some(input), _) => (input, "process".to_string()),
+			(None, Some(file)) if file != "-" => (
+				fs::read(file).map_err(|e| Error::io(file, e))?,
+				format!("file {}", file),
+			),
+			(None, None) if env_input(matches) => {
+				let input = env::var(INPUT_ENV)
+					.map_err(|_| Error::InvalidInput(format!("{} not UTF-8", INPUT_ENV)))?;
+				(input.into_bytes(), INPUT_ENV.to_string())
+			}
+			_ => {
+				let mut input = vec![];
+				io::stdin()
+					.lock()
+					.read_to_end(&mut input)
+					.map_err(|e| Error::io("-", e))?;
+				(input, "stdin".to_string())
+			}
+		}
+	};
+	verbose(matches, 1, || {
+		format!("input: {} bytes from {}", input.len(), source)
+	});
+	verbose(matches, 2, || format!("input: {}", preview(&input)));
+	count_input(input.len());
+	Ok(input)
+}
+
+/// Raw bytes if BINARY is present, otherwise hex
+#[cfg(feature = "structured-data")]
+pub fn input_hex_or_binary(matches: &ArgMatches) -> Result<Vec<u8>, Error> {
+	if matches.is_present("BINARY") {
+		verbose(matches, 1, || "input format: binary".to_string());
+		input_bytes(matches)
+	} else {
+		verbose(matches, 1, || "input format: hex".to_string());
+		let input = input_string(matches)?;
+		Ok(input.trim().parse::<Hex>()?.into())
+	}
+}
+
+/// Raw bytes to stdout if BINARY is present, otherwise hex
+#[cfg(feature = "structured-data")]
+pub fn output_hex_or_binary(matches: &ArgMatches, output: Vec<u8>) -> Result<Vec<String>, Error> {
+	if matches.is_present("BINARY") {
+		output_bytes(matches, &output)?;
+		Ok(vec![])
+	} else {
+		Ok(vec![Hex::from(output).into()])
+	}
+}
+
+/// Result lines with the prefix are reported as warnings in JSON output
+pub const WARNING_PREFIX: &str = "warning: ";
+
+/// Raw bytes to OUTPUT_FILE if present, otherwise stdout (nothing if QUIET is present)
+pub fn output_bytes(matches: &ArgMatches, output: &[u8]) -> Result<(), Error> {
+	verbose(matches, 1, || {
+		format!(
+			"output: {} bytes to {}",
+			output.len(),
+			matches.value_of("OUTPUT_FILE").unwrap_or("stdout")
+		)
+	});
+	match matches.value_of("OUTPUT_FILE") {
+		Some(file) => fs::write(file, output).map_err(|e| Error::io(file, e)),
+		None => {
+			let in_process = IO.with(|io| match io.borrow_mut().as_mut() {
+				Some((_, buffer)) => {
+					buffer.extend_from_slice(output);
+					true
+				}
+				None => false,
+			});
+			if in_process || matches.is_present("QUIET") {
+				return Ok(());
+			}
+			io::stdout()
+				.write_all(output)
+				.map_err(|e| Error::io("-", e))
+		}
+	}
+}
+
+/// `{"result": [...], "algorithm": ..., "warnings": [...]}`
+pub fn json_output(name: &str, matches: &ArgMatches, result: Vec<String>) -> Value {
+	let (warnings, result): (Vec<_>, Vec<_>) = result
+		.into_iter()
+		.partition(|x| x.starts_with(WARNING_PREFIX));
+	let warnings = warnings
+		.into_iter()
+		.map(|x| x[WARNING_PREFIX.len()..].to_string())
+		.collect::<Vec<_>>();
+	json!({
+		"result": result,
+		"algorithm": matches.value_of("ALGORITHM").unwrap_or(name),
+		"warnings": warnings,
+	})
+}
+
+/// Writes the result lines, decoded from hex if RAW_OUTPUT is present,
+/// or as a JSON object if OUTPUT_FORMAT is json
+pub fn output(name: &str, matches: &ArgMatches, result: Vec<String>) -> Result<(), Error> {
+	// raw output has been written by the command
+	if result.is_empty() {
+		return Ok(());
+	}
+
+	// the format may be a default from the config, so --raw wins
+	let json =
+		matches.value_of("OUTPUT_FORMAT") == Some("json") && !matches.is_present("RAW_OUTPUT");
+	let output = if json {
+		let value = json_output(name, matches, result);
+		let mut output =
+			serde_json::to_vec_pretty(&value).map_err(|_| Error::invalid("output", ""))?;
+		output.push(b'\n');
+		output
+	} else if matches.is_present("RAW_OUTPUT") {
+		let mut output = vec![];
+		for line in result {
+			let line: Vec<u8> = line
+				.trim()
+				.parse::<Hex>()
+				.map_err(|_| Error::invalid("raw output", "not hex"))?
+				.into();
+			output.extend(line);
+		}
+		output
+	} else {
+		let result = if color(matches) {
+			style(result)
+		} else {
+			result
+		};
+		result
+			.into_iter()
+			.flat_map(|x| x.into_bytes().into_iter().chain(iter::once(b'\n')))
+			.collect()
+	};
+
+	output_bytes(matches, &output)
+}
+
+/// Suggests the closest candidates by edit distance, e.g. ", did you mean sha2_256?",
+/// empty if none is close enough
+pub fn did_you_mean<'a>(value: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+	let value = value.chars().collect::<Vec<_>>();
+	let max = (value.len() / 3).max(1);
+
+	let mut close = candidates
+		.into_iter()
+		.map(|x| (levenshtein(&value, &x.chars().collect::<Vec<_>>()), x))
+		.filter(|(distance, _)| *distance <= max)
+		.collect::<Vec<_>>();
+	close.sort();
+	close.dedup();
+
+	let min = match close.first() {
+		Some((min, _)) => *min,
+		None => return String::new(),
+	};
+	let close = close
+		.into_iter()
+		.take_while(|(distance, _)| *distance == min)
+		.take(3)
+		.map(|(_, x)| x)
+		.collect::<Vec<_>>();
+
+	format!(", did you mean {}?", close.join(" or "))
+}
+
+/// Whether to write colors to stdout: COLOR (always, never, auto) if present, auto by default,
+/// auto is off if NO_COLOR is present or set in the environment, or stdout is not a terminal
+pub fn color(matches: &ArgMatches) -> bool {
+	if matches.is_present("NO_COLOR") {
+		return false;
+	}
+	match matches.value_of("COLOR") {
+		Some("always") => true,
+		Some("never") => false,
+		_ => {
+			no_color_env().is_none()
+				&& !matches.is_present("OUTPUT_FILE")
+				&& IO.with(|io| io.borrow().is_none())
+				&& atty::is(atty::Stream::Stdout)
+		}
 	}
 }
 
-pub fn input_bytes(matches: &ArgMatches) -> Result<Vec<u8>, String> {
-	match matches.value_of("INPUT") {
-		Some(input) => Ok(input.bytes().collect::<Vec<u8>>()),
-		None => io::stdin()
-			.bytes()
-			.collect::<Result<Vec<u8>, io::Error>>()
-			.map_err(|_| "Invalid input".to_string()),
+/// The error message, red if stderr is a terminal and colors are not disabled,
+/// or `{"error": {"kind": ..., "message": ...}}` if OUTPUT_FORMAT is json
+pub fn error(matches: &ArgMatches, e: &Error) -> String {
+	if matches.value_of("OUTPUT_FORMAT") == Some("json") {
+		return json!({ "error": e.to_json() }).to_string();
+	}
+	let e = e.to_string();
+	if !matches.is_present("NO_COLOR") && no_color_env().is_none() && atty::is(atty::Stream::Stderr)
+	{
+		Colour::Red.paint(e).to_string()
+	} else {
+		e
+	}
+}
+
+/// Writes the message to stderr if VERBOSE is given at least `level` times (`-v`: steps, `-vv`: data),
+/// dimmed if stderr is a terminal and colors are not disabled
+pub fn verbose(matches: &ArgMatches, level: u64, message: impl FnOnce() -> String) {
+	if matches.occurrences_of("VERBOSE") < level {
+		return;
+	}
+	let message = format!("verbose: {}", message());
+	if !matches.is_present("NO_COLOR") && no_color_env().is_none() && atty::is(atty::Stream::Stderr)
+	{
+		eprintln!("{}", Style::new().dimmed().paint(message));
+	} else {
+		eprintln!("{}", message);
 	}
 }
 
+/// Hex of the first bytes, with the total length if truncated
+pub fn preview(data: &[u8]) -> String {
+	const MAX: usize = 32;
+	if data.len() > MAX {
+		format!("0x{}... ({} bytes)", hex::encode(&data[..MAX]), data.len())
+	} else {
+		format!("0x{}", hex::encode(data))
+	}
+}
+
+/// https://no-color.org: present and not empty
+fn no_color_env() -> Option<String> {
+	env::var("NO_COLOR").ok().filter(|x| !x.is_empty())
+}
+
+/// Warnings in yellow, dimmed hex prefixes,
+/// and labels in cyan if every line is `label: value` (e.g. a table of hashes)
+fn style(result: Vec<String>) -> Vec<String> {
+	// already colored by the command
+	if result.iter().any(|x| x.contains('\x1b')) {
+		return result;
+	}
+
+	let labelled = result.len() > 1 && result.iter().all(|x| x.is_empty() || LABEL.is_match(x));
+
+	let style_value = |value: &str| {
+		if HEX_VALUE.is_match(value) {
+			format!("{}{}", Style::new().dimmed().paint("0x"), &value[2..])
+		} else {
+			value.to_string()
+		}
+	};
+
+	result
+		.into_iter()
+		.map(|line| {
+			if line.starts_with(WARNING_PREFIX) {
+				return Colour::Yellow.paint(line).to_string();
+			}
+			match LABEL.captures(&line) {
+				Some(captures) if labelled => format!(
+					"{}: {}",
+					Colour::Cyan.paint(&captures[1]),
+					style_value(&captures[2])
+				),
+				_ => style_value(&line),
+			}
+		})
+		.collect()
+}
+
 pub struct Hex(Vec<u8>);
 
 impl FromStr for Hex {
-	type Err = String;
+	type Err = Error;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let s = hex::decode(s.trim_start_matches("0x")).map_err(|_| "Invalid hex".to_string())?;
+		let s = hex::decode(s.trim_start_matches("0x")).map_err(|_| Error::InvalidHex)?;
 		Ok(Self(s))
 	}
 }
@@ -41,15 +637,15 @@ impl From<Vec<u8>> for Hex {
 	}
 }
 
-impl Into<String> for Hex {
-	fn into(self) -> String {
-		format!("0x{}", hex::encode(self.0))
+impl From<Hex> for String {
+	fn from(val: Hex) -> Self {
+		format!("0x{}", hex::encode(val.0))
 	}
 }
 
-impl Into<Vec<u8>> for Hex {
-	fn into(self) -> Vec<u8> {
-		self.0
+impl From<Hex> for Vec<u8> {
+	fn from(val: Hex) -> Self {
+		val.0
 	}
 }
 
@@ -66,7 +662,7 @@ pub mod test {
 
 			assert!(cases.is_some(), "{} should have cases", app.get_name());
 			if let Some(cases) = cases {
-				assert!(cases.len() > 0, "{} should have cases", app.get_name());
+				assert!(!cases.is_empty(), "{} should have cases", app.get_name());
 
 				let f = &command.f.clone();
 				for case in cases {
@@ -83,7 +679,7 @@ pub mod test {
 							.collect();
 						let mut input = vec![app.get_name().to_string()];
 						input.append(&mut ori_input);
-						let expected_output = Ok((&case.output).clone());
+						let expected_output = Ok(case.output.clone());
 						let matches = app.clone().get_matches_from(input.clone());
 						let output = f(&matches);
 						assert_eq!(output, expected_output, "Test: {}", input.join(" "));
@@ -93,3 +689,89 @@ pub mod test {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_did_you_mean() {
+		let candidates = vec!["sha2_224", "sha2_256", "sha3_256", "hash", "h2s", "s2h"];
+		assert_eq!(
+			did_you_mean("sha2256", candidates.clone()),
+			", did you mean sha2_256?"
+		);
+		assert_eq!(
+			did_you_mean("sha2_2567", candidates.clone()),
+			", did you mean sha2_256?"
+		);
+		assert_eq!(
+			did_you_mean("sha_256", candidates.clone()),
+			", did you mean sha2_256 or sha3_256?"
+		);
+		assert_eq!(
+			did_you_mean("hsh", candidates.clone()),
+			", did you mean hash?"
+		);
+		assert_eq!(did_you_mean("md5", candidates), "");
+	}
+
+	#[test]
+	fn test_preview() {
+		assert_eq!(preview(b"abc"), "0x616263");
+		assert_eq!(
+			preview(&[0; 33]),
+			format!("0x{}... (33 bytes)", "00".repeat(32))
+		);
+	}
+
+	#[test]
+	fn test_input() {
+		let matches = clap::App::new("test")
+			.arg(clap::Arg::with_name("INPUT").index(1))
+			.get_matches_from(vec!["test"]);
+
+		let (input, _) = with_io(b"a\r\nb\0\r\n".to_vec(), || input_string(&matches));
+		assert_eq!(input, Ok("a\r\nb\0".to_string()));
+
+		let (input, _) = with_io(b"\xffa\n".to_vec(), || input_string(&matches));
+		assert_eq!(
+			input,
+			Err(Error::InvalidInput("not UTF-8 at byte 0".to_string()))
+		);
+
+		let (input, _) = with_io(b"\xffa\n".to_vec(), || input_bytes(&matches));
+		assert_eq!(input, Ok(b"\xffa\n".to_vec()));
+	}
+
+	#[test]
+	fn test_style() {
+		assert_eq!(
+			style(vec!["0x6162".to_string()]),
+			vec![format!("{}6162", Style::new().dimmed().paint("0x"))]
+		);
+		assert_eq!(
+			style(vec!["md5: 0x61".to_string(), "sha1: abc".to_string()]),
+			vec![
+				format!(
+					"{}: {}61",
+					Colour::Cyan.paint("md5"),
+					Style::new().dimmed().paint("0x")
+				),
+				format!("{}: abc", Colour::Cyan.paint("sha1")),
+			]
+		);
+		// a single line is not a table
+		assert_eq!(style(vec!["a: b".to_string()]), vec!["a: b".to_string()]);
+		assert_eq!(
+			style(vec!["a: b".to_string(), "c".to_string()]),
+			vec!["a: b".to_string(), "c".to_string()]
+		);
+		assert_eq!(
+			style(vec![format!("{}x", WARNING_PREFIX)]),
+			vec![Colour::Yellow
+				.paint(format!("{}x", WARNING_PREFIX))
+				.to_string()]
+		);
+	}
+}
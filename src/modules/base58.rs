@@ -1,7 +1,8 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
 use bs58;
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::ArgMatches;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
@@ -14,73 +15,89 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("h2b58")
+			app: base::sub_command("h2b58")
 				.about("Convert hex to base58")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2b58,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, h2b58),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("h2b58c")
+			app: base::sub_command("h2b58c")
 				.about("Convert hex to base58 check")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2b58c,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, h2b58c),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("b582h")
+			app: base::sub_command("b582h")
 				.about("Convert base58 to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: b582h,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, b582h),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("b58c2h")
+			app: base::sub_command("b58c2h")
 				.about("Convert base58 check to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: b58c2h,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, b58c2h),
+			stream: None,
 		},
 	]
 }
 
-fn h2b58(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
 
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
+}
+
+/// Convert hex to base58
+pub fn h2b58(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::failed("Convert failed"))?
+		.into();
 
 	let result = bs58::encode(input).into_string();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn h2b58c(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+/// Convert hex to base58 check
+pub fn h2b58c(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::failed("Convert failed"))?
+		.into();
 
 	let result = bs58::encode(input).with_check().into_string();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn b582h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let input = bs58::decode(&input)
+/// Convert base58 to hex
+pub fn b582h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = bs58::decode(base::text(input)?)
 		.into_vec()
-		.map_err(|_| "Convert failed")?;
-	let result = Hex::from(input).into();
+		.map_err(|_| Error::failed("Convert failed"))?;
+	let result: String = Hex::from(input).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn b58c2h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let input = bs58::decode(&input)
+/// Convert base58 check to hex
+pub fn b58c2h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = bs58::decode(base::text(input)?)
 		.with_check(None)
 		.into_vec()
-		.map_err(|_| "Convert failed")?;
-	let result = Hex::from(input).into();
+		.map_err(|_| Error::failed("Convert failed"))?;
+	let result: String = Hex::from(input).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
 mod cases {
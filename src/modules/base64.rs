@@ -1,7 +1,9 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
+use crate::modules::stream::{Stream, TextChunks};
 use crate::modules::{base, Command, Module};
 use base64;
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::ArgMatches;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
@@ -14,37 +16,123 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("h2b64")
+			app: base::sub_command("h2b64")
 				.about("Convert hex to base64")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2b64,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, h2b64),
+			stream: Some(h2b64_stream),
 		},
 		Command {
-			app: SubCommand::with_name("b642h")
+			app: base::sub_command("b642h")
 				.about("Convert base64 to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: b642h,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, b642h),
+			stream: Some(b642h_stream),
 		},
 	]
 }
 
-fn h2b64(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
 
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
+}
+
+/// Convert hex to base64
+pub fn h2b64(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::failed("Convert failed"))?
+		.into();
 
 	let result = base64::encode(&input);
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
+}
+
+/// Convert base64 to hex
+pub fn b642h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = base64::decode(base::text(input)?).map_err(|_| Error::failed("Convert failed"))?;
+	let result: String = Hex::from(result).into();
+
+	Ok(result.into_bytes())
+}
+
+fn h2b64_stream(_: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	Ok(Box::new(H2b64Stream {
+		input: TextChunks::new(b"0x", 2),
+		pending: vec![],
+	}))
+}
+
+/// Encodes whole groups of 3 bytes, the rest is encoded with padding at last
+struct H2b64Stream {
+	input: TextChunks,
+	pending: Vec<u8>,
+}
+
+impl Stream for H2b64Stream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		let chunk = self
+			.input
+			.next(chunk)
+			.map_err(|_| Error::failed("Convert failed"))?;
+		self.pending
+			.extend(hex::decode(chunk).map_err(|_| Error::failed("Convert failed"))?);
+
+		let len = self.pending.len() - self.pending.len() % 3;
+		let group = self.pending.drain(..len).collect::<Vec<_>>();
+		Ok(base64::encode(&group).into_bytes())
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		self.pending
+			.extend(hex::decode(self.input.rest()).map_err(|_| Error::failed("Convert failed"))?);
+		Ok(format!("{}\n", base64::encode(&self.pending)).into_bytes())
+	}
+}
+
+fn b642h_stream(_: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	Ok(Box::new(B642hStream {
+		input: TextChunks::new(b"", 4),
+		started: false,
+	}))
 }
 
-fn b642h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// Decodes whole groups of 4 chars
+struct B642hStream {
+	input: TextChunks,
+	started: bool,
+}
+
+impl B642hStream {
+	fn decode(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+		let result = base64::decode(input).map_err(|_| Error::failed("Convert failed"))?;
+		let prefix = if self.started { "" } else { "0x" };
+		self.started = true;
+		Ok(format!("{}{}", prefix, hex::encode(result)).into_bytes())
+	}
+}
 
-	let result = base64::decode(&input).map_err(|_| "Convert failed")?;
-	let result = Hex::from(result).into();
+impl Stream for B642hStream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		let chunk = self
+			.input
+			.next(chunk)
+			.map_err(|_| Error::failed("Convert failed"))?;
+		self.decode(&chunk)
+	}
 
-	Ok(vec![result])
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		let rest = self.input.rest();
+		let mut result = self.decode(&rest)?;
+		result.push(b'\n');
+		Ok(result)
+	}
 }
 
 mod cases {
@@ -0,0 +1,165 @@
+use crate::error::Error;
+use crate::modules::{base, pipe, ModuleManager};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::time::Instant;
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+	SubCommand::with_name("bench")
+		.about("Benchmark a sub command in process, e.g. \"hash -a sha2_256\", reports ops/s and MB/s for every input size")
+		.arg(
+			Arg::with_name("COMMAND")
+				.help("Sub command with args, args can be quoted")
+				.required(true)
+				.index(1),
+		)
+		.arg(
+			Arg::with_name("SIZES")
+				.long("sizes")
+				.short("s")
+				.help("Input sizes in bytes (before hex encoding), separated by commas, K and M suffixes are supported")
+				.takes_value(true)
+				.default_value("1K,64K,1M")
+				.required(false),
+		)
+		.arg(
+			Arg::with_name("ITERATIONS")
+				.long("iterations")
+				.short("n")
+				.help("Iterations for every input size")
+				.takes_value(true)
+				.default_value("100")
+				.required(false),
+		)
+		.arg(
+			Arg::with_name("TYPE")
+				.long("type")
+				.short("t")
+				.help("Input type\nhex: 0x prefixed hex\ntext: ASCII letters\nbinary: raw bytes")
+				.takes_value(true)
+				.possible_values(&["hex", "text", "binary"])
+				.default_value("hex")
+				.required(false),
+		)
+}
+
+pub fn run<'a, 'b>(matches: &ArgMatches, mm: &ModuleManager<'a, 'b>) -> Result<Vec<String>, Error> {
+	let stage = match pipe::parse(
+		matches
+			.value_of("COMMAND")
+			.ok_or(Error::invalid("command", ""))?,
+	)?
+	.as_slice()
+	{
+		[stage] => stage.clone(),
+		_ => return Err(Error::invalid("command", "expect a sub command")),
+	};
+
+	let name = &stage[0];
+	let command = mm.commands.get(name).ok_or_else(|| {
+		Error::invalid(
+			"command",
+			format!(
+				"unknown sub command: {}{}",
+				name,
+				base::did_you_mean(name, mm.commands.keys().map(String::as_str))
+			),
+		)
+	})?;
+	let command_matches = mm
+		.get_matches(&command.app, &stage, 0)
+		.map_err(|e| Error::failed(e.message))?;
+
+	let sizes = matches
+		.value_of("SIZES")
+		.ok_or(Error::invalid("sizes", ""))?
+		.split(',')
+		.map(|x| parse_size(x.trim()).map(|size| (x.trim(), size)))
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	let iterations = matches
+		.value_of("ITERATIONS")
+		.ok_or(Error::invalid("iterations", ""))?
+		.parse::<u32>()
+		.ok()
+		.filter(|x| *x > 0)
+		.ok_or(Error::invalid("iterations", ""))?;
+
+	let input_type = matches.value_of("TYPE").ok_or(Error::invalid("type", ""))?;
+
+	let mut result = vec![];
+	for (label, size) in sizes {
+		let input = generate(input_type, size);
+
+		// warm up, and fail early
+		let run = || base::with_io(input.clone(), || (command.f)(&command_matches)).0;
+		run().map_err(|e| Error::failed(format!("{}: {}", name, e)))?;
+
+		let start = Instant::now();
+		for _ in 0..iterations {
+			run().map_err(|e| Error::failed(format!("{}: {}", name, e)))?;
+		}
+		let seconds = start.elapsed().as_secs_f64();
+
+		let ops = f64::from(iterations) / seconds;
+		let mbs = ops * size as f64 / 1_000_000.0;
+		result.push(format!("{}: {:.1} ops/s, {:.2} MB/s", label, ops, mbs));
+	}
+
+	Ok(result)
+}
+
+/// Bytes, with an optional K (1024) or M (1024 * 1024) suffix
+fn parse_size(size: &str) -> Result<usize, Error> {
+	let (number, unit) = match size.chars().last() {
+		Some('K') | Some('k') => (&size[..size.len() - 1], 1024),
+		Some('M') | Some('m') => (&size[..size.len() - 1], 1024 * 1024),
+		_ => (size, 1),
+	};
+	number
+		.parse::<usize>()
+		.ok()
+		.and_then(|x| x.checked_mul(unit))
+		.ok_or_else(|| Error::invalid("size", size))
+}
+
+/// Pseudo-random (xorshift) input, the same for every run
+fn generate(input_type: &str, size: usize) -> Vec<u8> {
+	let mut state = 0x2545_f491_4f6c_dd1d_u64;
+	let bytes = (0..size)
+		.map(|_| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			(state >> 32) as u8
+		})
+		.collect::<Vec<_>>();
+
+	match input_type {
+		"hex" => format!("0x{}", hex::encode(bytes)).into_bytes(),
+		"text" => bytes.into_iter().map(|x| b'a' + x % 26).collect(),
+		_ => bytes,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_size() {
+		assert_eq!(parse_size("100"), Ok(100));
+		assert_eq!(parse_size("64K"), Ok(64 * 1024));
+		assert_eq!(parse_size("1m"), Ok(1024 * 1024));
+		assert!(parse_size("1G").is_err());
+		assert!(parse_size("").is_err());
+		assert!(parse_size("99999999999999999M").is_err());
+	}
+
+	#[test]
+	fn test_generate() {
+		assert_eq!(generate("binary", 100).len(), 100);
+		assert_eq!(generate("hex", 100).len(), 202);
+		assert!(generate("text", 100).iter().all(u8::is_ascii_lowercase));
+		assert_eq!(generate("hex", 10), generate("hex", 10));
+	}
+}
@@ -0,0 +1,389 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "BSON to JSON (MongoDB extended JSON)".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("bson2j")
+			.about("Convert BSON to JSON")
+			.arg(
+				Arg::with_name("BINARY")
+					.long("binary")
+					.short("b")
+					.help("Input binary instead of hex, e.g. dtool bson2j -b < dump.bson")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("MINIFY")
+					.long("minify")
+					.short("m")
+					.help("Minify")
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert_from_hex_or_binary(matches, bson2j),
+		stream: None,
+	}]
+}
+
+/// Options of `bson2j`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert BSON to JSON, the input is binary
+pub fn bson2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let mut reader = input;
+	let mut values = vec![];
+	while !reader.is_empty() {
+		values.push(read_document(&mut reader)?);
+	}
+
+	// concatenated documents (e.g. mongodump) are converted to array
+	let value = match values.len() {
+		0 => Value::Null,
+		1 => values.remove(0),
+		_ => Value::Array(values),
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+	if reader.len() < len {
+		return Err(Error::invalid("BSON", "unexpected end"));
+	}
+	let (result, rest) = reader.split_at(len);
+	*reader = rest;
+	Ok(result)
+}
+
+fn read_i32(reader: &mut &[u8]) -> Result<i32, Error> {
+	let mut buf = [0u8; 4];
+	buf.copy_from_slice(read_bytes(reader, 4)?);
+	Ok(i32::from_le_bytes(buf))
+}
+
+fn read_i64(reader: &mut &[u8]) -> Result<i64, Error> {
+	let mut buf = [0u8; 8];
+	buf.copy_from_slice(read_bytes(reader, 8)?);
+	Ok(i64::from_le_bytes(buf))
+}
+
+fn read_len(reader: &mut &[u8]) -> Result<usize, Error> {
+	let len = read_i32(reader)?;
+	if len < 0 {
+		return Err(Error::invalid("BSON", "negative length"));
+	}
+	Ok(len as usize)
+}
+
+fn read_cstring(reader: &mut &[u8]) -> Result<String, Error> {
+	let end = reader
+		.iter()
+		.position(|x| *x == 0)
+		.ok_or(Error::invalid("BSON", "unterminated cstring"))?;
+	let result = to_text(&reader[..end])?;
+	*reader = &reader[end + 1..];
+	Ok(result)
+}
+
+fn read_string(reader: &mut &[u8]) -> Result<String, Error> {
+	let len = read_len(reader)?;
+	let bytes = read_bytes(reader, len)?;
+	match bytes.split_last() {
+		Some((0, bytes)) => to_text(bytes),
+		_ => Err(Error::invalid("BSON", "unterminated string")),
+	}
+}
+
+fn to_text(input: &[u8]) -> Result<String, Error> {
+	String::from_utf8(input.to_vec()).map_err(|_| Error::invalid("BSON", "string not UTF-8"))
+}
+
+fn read_document(reader: &mut &[u8]) -> Result<Value, Error> {
+	let len = read_len(reader)?;
+	if len < 5 {
+		return Err(Error::invalid("BSON", "invalid document length"));
+	}
+	let mut document = read_bytes(reader, len - 4)?;
+
+	let mut map = Map::new();
+	loop {
+		let element_type = read_bytes(&mut document, 1)?[0];
+		if element_type == 0 {
+			break;
+		}
+		let name = read_cstring(&mut document)?;
+		let value = read_element(&mut document, element_type)?;
+		map.insert(name, value);
+	}
+	Ok(Value::Object(map))
+}
+
+fn read_array(reader: &mut &[u8]) -> Result<Value, Error> {
+	match read_document(reader)? {
+		Value::Object(map) => Ok(Value::Array(map.into_iter().map(|(_, v)| v).collect())),
+		_ => Err(Error::invalid("BSON", "invalid array")),
+	}
+}
+
+/// Relaxed extended JSON v2
+fn read_element(reader: &mut &[u8], element_type: u8) -> Result<Value, Error> {
+	let wrap = |key: &str, value: Value| {
+		let mut map = Map::new();
+		map.insert(key.to_string(), value);
+		Value::Object(map)
+	};
+
+	let result = match element_type {
+		0x01 => {
+			let v = f64::from_bits(read_i64(reader)? as u64);
+			if v.is_finite() {
+				Value::from(v)
+			} else {
+				let v = match v {
+					_ if v.is_nan() => "NaN",
+					_ if v > 0.0 => "Infinity",
+					_ => "-Infinity",
+				};
+				wrap("$numberDouble", Value::from(v))
+			}
+		}
+		0x02 => Value::String(read_string(reader)?),
+		0x03 => read_document(reader)?,
+		0x04 => read_array(reader)?,
+		0x05 => {
+			let len = read_len(reader)?;
+			let sub_type = read_bytes(reader, 1)?[0];
+			let data = read_bytes(reader, len)?;
+			let mut map = Map::new();
+			map.insert("base64".to_string(), Value::from(base64::encode(data)));
+			map.insert(
+				"subType".to_string(),
+				Value::from(format!("{:02x}", sub_type)),
+			);
+			wrap("$binary", Value::Object(map))
+		}
+		0x06 => wrap("$undefined", Value::Bool(true)),
+		0x07 => wrap("$oid", Value::from(hex::encode(read_bytes(reader, 12)?))),
+		0x08 => Value::Bool(read_bytes(reader, 1)?[0] != 0),
+		0x09 => {
+			let millis = read_i64(reader)?;
+			// relaxed format for years 1970 to 9999
+			let date = if (0..=253_402_300_799_999).contains(&millis) {
+				let date = Utc.timestamp_millis(millis);
+				Value::from(date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+			} else {
+				wrap("$numberLong", Value::from(millis.to_string()))
+			};
+			wrap("$date", date)
+		}
+		0x0a => Value::Null,
+		0x0b => {
+			let mut map = Map::new();
+			map.insert("pattern".to_string(), Value::from(read_cstring(reader)?));
+			map.insert("options".to_string(), Value::from(read_cstring(reader)?));
+			wrap("$regularExpression", Value::Object(map))
+		}
+		0x0c => {
+			let mut map = Map::new();
+			map.insert("$ref".to_string(), Value::from(read_string(reader)?));
+			map.insert(
+				"$id".to_string(),
+				wrap("$oid", Value::from(hex::encode(read_bytes(reader, 12)?))),
+			);
+			wrap("$dbPointer", Value::Object(map))
+		}
+		0x0d => wrap("$code", Value::from(read_string(reader)?)),
+		0x0e => wrap("$symbol", Value::from(read_string(reader)?)),
+		0x0f => {
+			let _len = read_len(reader)?;
+			let mut map = Map::new();
+			map.insert("$code".to_string(), Value::from(read_string(reader)?));
+			map.insert("$scope".to_string(), read_document(reader)?);
+			Value::Object(map)
+		}
+		0x10 => Value::from(read_i32(reader)?),
+		0x11 => {
+			let i = read_i32(reader)? as u32;
+			let t = read_i32(reader)? as u32;
+			let mut map = Map::new();
+			map.insert("t".to_string(), Value::from(t));
+			map.insert("i".to_string(), Value::from(i));
+			wrap("$timestamp", Value::Object(map))
+		}
+		0x12 => Value::from(read_i64(reader)?),
+		0x13 => {
+			let low = read_i64(reader)? as u64;
+			let high = read_i64(reader)? as u64;
+			wrap(
+				"$numberDecimal",
+				Value::from(decimal128_to_string(high, low)),
+			)
+		}
+		0xff => wrap("$minKey", Value::from(1)),
+		0x7f => wrap("$maxKey", Value::from(1)),
+		_ => {
+			return Err(Error::invalid(
+				"BSON",
+				format!("unknown element type 0x{:02x}", element_type),
+			))
+		}
+	};
+	Ok(result)
+}
+
+/// IEEE 754-2008 decimal128 (BID encoding) to string
+fn decimal128_to_string(high: u64, low: u64) -> String {
+	let sign = if high >> 63 == 1 { "-" } else { "" };
+
+	let combination = (high >> 58) & 0x1f;
+	if combination == 0x1f {
+		return "NaN".to_string();
+	}
+	if combination == 0x1e {
+		return format!("{}Infinity", sign);
+	}
+
+	let (exponent, coefficient) = if (high >> 61) & 0x3 == 0x3 {
+		// coefficient out of range, treated as zero
+		((high >> 47) & 0x3fff, 0u128)
+	} else {
+		(
+			(high >> 49) & 0x3fff,
+			(u128::from(high & 0x1_ffff_ffff_ffff) << 64) | u128::from(low),
+		)
+	};
+	let exponent = exponent as i64 - 6176;
+
+	let digits = coefficient.to_string();
+	let adjusted = exponent + digits.len() as i64 - 1;
+
+	let result = if exponent <= 0 && adjusted >= -6 {
+		if exponent == 0 {
+			digits
+		} else {
+			let point = digits.len() as i64 + exponent;
+			if point > 0 {
+				let (int, frac) = digits.split_at(point as usize);
+				format!("{}.{}", int, frac)
+			} else {
+				format!("0.{}{}", "0".repeat(-point as usize), digits)
+			}
+		}
+	} else {
+		let (int, frac) = digits.split_at(1);
+		let frac = if frac.is_empty() {
+			"".to_string()
+		} else {
+			format!(".{}", frac)
+		};
+		format!("{}{}E{:+}", int, frac, adjusted)
+	};
+	format!("{}{}", sign, result)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"bson2j",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec![
+						"-m",
+						"0x4d000000075f6964005e0f8d3f2b3c4a5d6e7f8019026e616d65000600000064746f6f6c00106e00010000000961740000e8665e6f0100000474616773000e0000000230000200000061000000",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						r#"{"_id":{"$oid":"5e0f8d3f2b3c4a5d6e7f8019"},"name":"dtool","n":1,"at":{"$date":"2020-01-01T00:00:00.000Z"},"tags":["a"]}"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "".to_string(),
+					input: vec![
+						"-m",
+						"0x480000001262696700000000000001000013646563000f000000000000000000000000003e300562696e00020000000401020b7265005e61006900117473000300000000105e5f00",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						r#"{"big":1099511627776,"dec":{"$numberDecimal":"1.5"},"bin":{"$binary":{"base64":"AQI=","subType":"04"}},"re":{"$regularExpression":{"pattern":"^a","options":"i"}},"ts":{"$timestamp":{"t":1600000000,"i":3}}}"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: false,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Concatenated documents".to_string(),
+					input: vec!["-m", "0x0c00000010610001000000000c0000001061000200000000"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![r#"[{"a":1},{"a":2}]"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -1,5 +1,7 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 use heck::{CamelCase, KebabCase, MixedCase, ShoutySnakeCase, SnakeCase, TitleCase};
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
@@ -13,7 +15,7 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![Command {
-		app: SubCommand::with_name("case")
+		app: base::sub_command("case")
 			.about("Case conversion")
 			.arg(
 				Arg::with_name("TYPE")
@@ -25,32 +27,88 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						 kebab: good-tool\nsarcasm: gOoD tOoL",
 					)
 					.takes_value(true)
+					.possible_values(&[
+						"upper",
+						"lower",
+						"title",
+						"camel",
+						"pascal",
+						"snake",
+						"shouty_snake",
+						"kebab",
+						"sarcasm",
+					])
 					.required(true),
 			)
-			.arg(Arg::with_name("INPUT").required(false).index(1)),
-		f: case,
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, case),
+		stream: None,
 	}]
 }
 
-fn case(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// The case type of `case`
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub case_type: CaseType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaseType {
+	/// GOOD TOOL
+	Upper,
+	/// good tool
+	Lower,
+	/// Good Tool
+	Title,
+	/// goodTool
+	Camel,
+	/// GoodTool
+	Pascal,
+	/// good_tool
+	Snake,
+	/// GOOD_TOOL
+	ShoutySnake,
+	/// good-tool
+	Kebab,
+	/// gOoD tOoL
+	Sarcasm,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let case_type = match matches.value_of("TYPE") {
+			Some("upper") => CaseType::Upper,
+			Some("lower") => CaseType::Lower,
+			Some("title") => CaseType::Title,
+			Some("camel") => CaseType::Camel,
+			Some("pascal") => CaseType::Pascal,
+			Some("snake") => CaseType::Snake,
+			Some("shouty_snake") => CaseType::ShoutySnake,
+			Some("kebab") => CaseType::Kebab,
+			Some("sarcasm") => CaseType::Sarcasm,
+			_ => return Err(Error::invalid("type", "")),
+		};
+		Ok(Options { case_type })
+	}
+}
 
-	let t = matches.value_of("TYPE").ok_or("Invalid type")?;
+/// Case conversion
+pub fn case(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
-	let result = match t {
-		"upper" => input.to_uppercase(),
-		"lower" => input.to_lowercase(),
-		"title" => input.to_title_case(),
-		"camel" => input.to_mixed_case(),
-		"pascal" => input.to_camel_case(), //heck camel casing is pascal casing
-		"snake" => input.to_snake_case(),
-		"shouty_snake" => input.to_shouty_snake_case(),
-		"kebab" => input.to_kebab_case(),
-		"sarcasm" => to_sarcasm_case(&input),
-		_ => return Err("Invalid type".to_string()),
+	let result = match options.case_type {
+		CaseType::Upper => input.to_uppercase(),
+		CaseType::Lower => input.to_lowercase(),
+		CaseType::Title => input.to_title_case(),
+		CaseType::Camel => input.to_mixed_case(),
+		CaseType::Pascal => input.to_camel_case(), //heck camel casing is pascal casing
+		CaseType::Snake => input.to_snake_case(),
+		CaseType::ShoutySnake => input.to_shouty_snake_case(),
+		CaseType::Kebab => input.to_kebab_case(),
+		CaseType::Sarcasm => to_sarcasm_case(input),
 	};
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
 fn to_sarcasm_case(input: &str) -> String {
@@ -60,24 +118,21 @@ fn to_sarcasm_case(input: &str) -> String {
 	enum Case {
 		Upper,
 		Lower,
-	};
+	}
 	let mut case = Case::Lower;
 	let result = lowercased
 		.chars()
 		.map(|c| {
-			let result = {
-				if c == ' ' {
-					case = Case::Lower;
-					" ".to_string()
-				} else if case == Case::Upper {
-					case = Case::Lower;
-					c.to_uppercase().to_string()
-				} else {
-					case = Case::Upper;
-					c.to_lowercase().to_string()
-				}
-			};
-			result
+			if c == ' ' {
+				case = Case::Lower;
+				" ".to_string()
+			} else if case == Case::Upper {
+				case = Case::Lower;
+				c.to_uppercase().to_string()
+			} else {
+				case = Case::Upper;
+				c.to_lowercase().to_string()
+			}
 		})
 		.collect();
 	result
@@ -0,0 +1,691 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "JSON / CBOR conversion, CBOR diagnostic notation".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("j2cbor")
+				.about("Convert JSON to CBOR")
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Output binary instead of hex")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_to_hex_or_binary(matches, j2cbor),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("cbor2j")
+				.about("Convert CBOR to JSON")
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Input binary instead of hex")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_from_hex_or_binary(matches, cbor2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("cbor_diag")
+				.about("CBOR diagnostic notation")
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Input binary instead of hex")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_from_hex_or_binary(matches, cbor_diag),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the CBOR commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert JSON to CBOR, the output is binary
+pub fn j2cbor(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let mut result = vec![];
+	encode(&from_json(&value)?, &mut result);
+
+	Ok(result)
+}
+
+/// Convert CBOR to JSON, the input is binary
+pub fn cbor2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let mut values = parse(input)?
+		.into_iter()
+		.map(to_json)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	// sequence of items is converted to array
+	let value = match values.len() {
+		0 => Value::Null,
+		1 => values.remove(0),
+		_ => Value::Array(values),
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// CBOR diagnostic notation, the input is binary, an item per line
+pub fn cbor_diag(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = parse(input)?.iter().map(to_diag).collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+enum Cbor {
+	Unsigned(u64),
+	/// -1 - n
+	Negative(u64),
+	/// Chunks of indefinite length string if the second is true
+	Bytes(Vec<Vec<u8>>, bool),
+	Text(Vec<String>, bool),
+	Array(Vec<Cbor>, bool),
+	Map(Vec<(Cbor, Cbor)>, bool),
+	Tag(u64, Box<Cbor>),
+	Simple(u8),
+	Float(f64),
+}
+
+const FALSE: u8 = 20;
+const TRUE: u8 = 21;
+const NULL: u8 = 22;
+const UNDEFINED: u8 = 23;
+const BREAK: u8 = 0xff;
+
+fn parse(input: &[u8]) -> Result<Vec<Cbor>, Error> {
+	let mut reader = input;
+	let mut result = vec![];
+	while !reader.is_empty() {
+		result.push(decode(&mut reader)?);
+	}
+	Ok(result)
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8], len: u64) -> Result<&'a [u8], Error> {
+	if (reader.len() as u64) < len {
+		return Err(Error::invalid("CBOR", "unexpected end"));
+	}
+	let (result, rest) = reader.split_at(len as usize);
+	*reader = rest;
+	Ok(result)
+}
+
+fn read_uint(reader: &mut &[u8], len: u64) -> Result<u64, Error> {
+	Ok(read_bytes(reader, len)?
+		.iter()
+		.fold(0u64, |sum, x| (sum << 8) | u64::from(*x)))
+}
+
+/// None for indefinite length
+fn read_argument(reader: &mut &[u8], info: u8) -> Result<Option<u64>, Error> {
+	let result = match info {
+		0..=23 => u64::from(info),
+		24 => read_uint(reader, 1)?,
+		25 => read_uint(reader, 2)?,
+		26 => read_uint(reader, 4)?,
+		27 => read_uint(reader, 8)?,
+		31 => return Ok(None),
+		_ => return Err(Error::invalid("CBOR", "reserved additional information")),
+	};
+	Ok(Some(result))
+}
+
+fn is_break(reader: &[u8]) -> bool {
+	reader.first() == Some(&BREAK)
+}
+
+fn decode(reader: &mut &[u8]) -> Result<Cbor, Error> {
+	let head = read_bytes(reader, 1)?[0];
+	let (major, info) = (head >> 5, head & 0x1f);
+
+	if major == 7 {
+		return match info {
+			0..=23 => Ok(Cbor::Simple(info)),
+			24 => Ok(Cbor::Simple(read_uint(reader, 1)? as u8)),
+			25 => Ok(Cbor::Float(f16_to_f64(read_uint(reader, 2)? as u16))),
+			26 => Ok(Cbor::Float(f64::from(f32::from_bits(
+				read_uint(reader, 4)? as u32,
+			)))),
+			27 => Ok(Cbor::Float(f64::from_bits(read_uint(reader, 8)?))),
+			_ => Err(Error::invalid("CBOR", "unexpected break")),
+		};
+	}
+
+	let argument = read_argument(reader, info)?;
+
+	let result = match (major, argument) {
+		(0, Some(n)) => Cbor::Unsigned(n),
+		(1, Some(n)) => Cbor::Negative(n),
+		(2, Some(n)) => Cbor::Bytes(vec![read_bytes(reader, n)?.to_vec()], false),
+		(2, None) => {
+			let mut chunks = vec![];
+			while !is_break(reader) {
+				match decode(reader)? {
+					Cbor::Bytes(mut chunk, false) => chunks.append(&mut chunk),
+					_ => return Err(Error::invalid("CBOR", "invalid chunk")),
+				}
+			}
+			read_bytes(reader, 1)?;
+			Cbor::Bytes(chunks, true)
+		}
+		(3, Some(n)) => Cbor::Text(vec![to_text(read_bytes(reader, n)?)?], false),
+		(3, None) => {
+			let mut chunks = vec![];
+			while !is_break(reader) {
+				match decode(reader)? {
+					Cbor::Text(mut chunk, false) => chunks.append(&mut chunk),
+					_ => return Err(Error::invalid("CBOR", "invalid chunk")),
+				}
+			}
+			read_bytes(reader, 1)?;
+			Cbor::Text(chunks, true)
+		}
+		(4, Some(n)) => Cbor::Array(
+			(0..n).map(|_| decode(reader)).collect::<Result<_, _>>()?,
+			false,
+		),
+		(4, None) => {
+			let mut items = vec![];
+			while !is_break(reader) {
+				items.push(decode(reader)?);
+			}
+			read_bytes(reader, 1)?;
+			Cbor::Array(items, true)
+		}
+		(5, Some(n)) => Cbor::Map(
+			(0..n)
+				.map(|_| Ok((decode(reader)?, decode(reader)?)))
+				.collect::<Result<_, Error>>()?,
+			false,
+		),
+		(5, None) => {
+			let mut items = vec![];
+			while !is_break(reader) {
+				items.push((decode(reader)?, decode(reader)?));
+			}
+			read_bytes(reader, 1)?;
+			Cbor::Map(items, true)
+		}
+		(6, Some(n)) => Cbor::Tag(n, Box::new(decode(reader)?)),
+		_ => return Err(Error::invalid("CBOR", "invalid indefinite length")),
+	};
+	Ok(result)
+}
+
+fn to_text(input: &[u8]) -> Result<String, Error> {
+	String::from_utf8(input.to_vec()).map_err(|_| Error::invalid("CBOR", "text not UTF-8"))
+}
+
+fn f16_to_f64(half: u16) -> f64 {
+	let sign = if half >> 15 == 1 { -1.0 } else { 1.0 };
+	let exp = i32::from((half >> 10) & 0x1f);
+	let mant = f64::from(half & 0x3ff);
+	let result = match exp {
+		0 => mant * 2f64.powi(-24),
+		31 if mant == 0.0 => f64::INFINITY,
+		31 => f64::NAN,
+		_ => (mant + 1024.0) * 2f64.powi(exp - 25),
+	};
+	sign * result
+}
+
+fn write_head(major: u8, argument: u64, output: &mut Vec<u8>) {
+	let major = major << 5;
+	if argument < 24 {
+		output.push(major | argument as u8);
+	} else if argument <= 0xff {
+		output.push(major | 24);
+		output.push(argument as u8);
+	} else if argument <= 0xffff {
+		output.push(major | 25);
+		output.extend_from_slice(&(argument as u16).to_be_bytes());
+	} else if argument <= 0xffff_ffff {
+		output.push(major | 26);
+		output.extend_from_slice(&(argument as u32).to_be_bytes());
+	} else {
+		output.push(major | 27);
+		output.extend_from_slice(&argument.to_be_bytes());
+	}
+}
+
+/// Always use definite length, floats are encoded in the shortest of single and double precision
+fn encode(value: &Cbor, output: &mut Vec<u8>) {
+	match value {
+		Cbor::Unsigned(n) => write_head(0, *n, output),
+		Cbor::Negative(n) => write_head(1, *n, output),
+		Cbor::Bytes(chunks, _) => {
+			let bytes = chunks.concat();
+			write_head(2, bytes.len() as u64, output);
+			output.extend_from_slice(&bytes);
+		}
+		Cbor::Text(chunks, _) => {
+			let text = chunks.concat();
+			write_head(3, text.len() as u64, output);
+			output.extend_from_slice(text.as_bytes());
+		}
+		Cbor::Array(items, _) => {
+			write_head(4, items.len() as u64, output);
+			items.iter().for_each(|x| encode(x, output));
+		}
+		Cbor::Map(items, _) => {
+			write_head(5, items.len() as u64, output);
+			items.iter().for_each(|(k, v)| {
+				encode(k, output);
+				encode(v, output);
+			});
+		}
+		Cbor::Tag(tag, value) => {
+			write_head(6, *tag, output);
+			encode(value, output);
+		}
+		Cbor::Simple(n) => write_head(7, u64::from(*n), output),
+		Cbor::Float(v) => {
+			let single = *v as f32;
+			if f64::from(single) == *v {
+				output.push(0xfa);
+				output.extend_from_slice(&single.to_bits().to_be_bytes());
+			} else {
+				output.push(0xfb);
+				output.extend_from_slice(&v.to_bits().to_be_bytes());
+			}
+		}
+	}
+}
+
+/// Integers out of 64-bit range are converted to bignums (tag 2 / 3)
+fn from_json(value: &Value) -> Result<Cbor, Error> {
+	let result = match value {
+		Value::Null => Cbor::Simple(NULL),
+		Value::Bool(true) => Cbor::Simple(TRUE),
+		Value::Bool(false) => Cbor::Simple(FALSE),
+		Value::Number(v) => match (v.as_u64(), v.as_i64()) {
+			(Some(n), _) => Cbor::Unsigned(n),
+			(_, Some(n)) => Cbor::Negative(!(n as u64)),
+			_ => {
+				let s = v.to_string();
+				match (
+					s.starts_with('-'),
+					decimal_to_bytes(s.trim_start_matches('-')),
+				) {
+					(false, Some(bytes)) => Cbor::Tag(
+						TAG_POSITIVE_BIGNUM,
+						Box::new(Cbor::Bytes(vec![bytes], false)),
+					),
+					(true, Some(bytes)) => Cbor::Tag(
+						TAG_NEGATIVE_BIGNUM,
+						Box::new(Cbor::Bytes(vec![decrement(bytes)], false)),
+					),
+					_ => Cbor::Float(v.as_f64().ok_or(Error::invalid("number", ""))?),
+				}
+			}
+		},
+		Value::String(v) => Cbor::Text(vec![v.clone()], false),
+		Value::Array(v) => Cbor::Array(v.iter().map(from_json).collect::<Result<_, _>>()?, false),
+		Value::Object(v) => Cbor::Map(
+			v.iter()
+				.map(|(k, v)| Ok((Cbor::Text(vec![k.clone()], false), from_json(v)?)))
+				.collect::<Result<_, Error>>()?,
+			false,
+		),
+	};
+	Ok(result)
+}
+
+/// Bytes are converted to hex strings, bignums (tag 2 / 3) to numbers,
+/// other tags to {"tag": <tag>, "value": <value>}
+fn to_json(value: Cbor) -> Result<Value, Error> {
+	let result = match value {
+		Cbor::Unsigned(n) => Value::from(n),
+		Cbor::Negative(n) => json::parse(&format!("{}", -1 - i128::from(n)))?,
+		Cbor::Bytes(chunks, _) => Value::String(base::Hex::from(chunks.concat()).into()),
+		Cbor::Text(chunks, _) => Value::String(chunks.concat()),
+		Cbor::Array(items, _) => {
+			Value::Array(items.into_iter().map(to_json).collect::<Result<_, _>>()?)
+		}
+		Cbor::Map(items, _) => {
+			let mut map = Map::new();
+			for (k, v) in items {
+				let k = match k {
+					Cbor::Text(chunks, _) => chunks.concat(),
+					k => to_diag(&k),
+				};
+				map.insert(k, to_json(v)?);
+			}
+			Value::Object(map)
+		}
+		Cbor::Tag(TAG_POSITIVE_BIGNUM, value) => match *value {
+			Cbor::Bytes(chunks, _) => json::parse(&bytes_to_decimal(&chunks.concat()))?,
+			value => tag_to_json(TAG_POSITIVE_BIGNUM, value)?,
+		},
+		Cbor::Tag(TAG_NEGATIVE_BIGNUM, value) => match *value {
+			Cbor::Bytes(chunks, _) => {
+				let n = bytes_to_decimal(&increment(chunks.concat()));
+				json::parse(&format!("-{}", n))?
+			}
+			value => tag_to_json(TAG_NEGATIVE_BIGNUM, value)?,
+		},
+		Cbor::Tag(tag, value) => tag_to_json(tag, *value)?,
+		Cbor::Simple(FALSE) => Value::Bool(false),
+		Cbor::Simple(TRUE) => Value::Bool(true),
+		Cbor::Simple(NULL) | Cbor::Simple(UNDEFINED) => Value::Null,
+		Cbor::Simple(n) => {
+			let mut map = Map::new();
+			map.insert("simple".to_string(), Value::from(n));
+			Value::Object(map)
+		}
+		// NaN and infinity are converted to null
+		Cbor::Float(v) => Value::from(v),
+	};
+	Ok(result)
+}
+
+fn tag_to_json(tag: u64, value: Cbor) -> Result<Value, Error> {
+	let mut map = Map::new();
+	map.insert("tag".to_string(), Value::from(tag));
+	map.insert("value".to_string(), to_json(value)?);
+	Ok(Value::Object(map))
+}
+
+/// Diagnostic notation (RFC 8949 section 8)
+fn to_diag(value: &Cbor) -> String {
+	let join = |items: Vec<String>, indefinite: bool| {
+		let prefix = if indefinite { "_ " } else { "" };
+		format!("{}{}", prefix, items.join(", "))
+	};
+	match value {
+		Cbor::Unsigned(n) => n.to_string(),
+		Cbor::Negative(n) => (-1 - i128::from(*n)).to_string(),
+		Cbor::Bytes(chunks, false) => format!("h'{}'", hex::encode(chunks.concat())),
+		Cbor::Bytes(chunks, true) => format!(
+			"({})",
+			join(
+				chunks
+					.iter()
+					.map(|x| format!("h'{}'", hex::encode(x)))
+					.collect(),
+				true
+			)
+		),
+		Cbor::Text(chunks, false) => Value::String(chunks.concat()).to_string(),
+		Cbor::Text(chunks, true) => format!(
+			"({})",
+			join(
+				chunks
+					.iter()
+					.map(|x| Value::String(x.clone()).to_string())
+					.collect(),
+				true
+			)
+		),
+		Cbor::Array(items, indefinite) => {
+			format!(
+				"[{}]",
+				join(items.iter().map(to_diag).collect(), *indefinite)
+			)
+		}
+		Cbor::Map(items, indefinite) => format!(
+			"{{{}}}",
+			join(
+				items
+					.iter()
+					.map(|(k, v)| format!("{}: {}", to_diag(k), to_diag(v)))
+					.collect(),
+				*indefinite
+			)
+		),
+		Cbor::Tag(tag, value) => format!("{}({})", tag, to_diag(value)),
+		Cbor::Simple(FALSE) => "false".to_string(),
+		Cbor::Simple(TRUE) => "true".to_string(),
+		Cbor::Simple(NULL) => "null".to_string(),
+		Cbor::Simple(UNDEFINED) => "undefined".to_string(),
+		Cbor::Simple(n) => format!("simple({})", n),
+		Cbor::Float(v) if v.is_nan() => "NaN".to_string(),
+		Cbor::Float(v) if v.is_infinite() && *v > 0.0 => "Infinity".to_string(),
+		Cbor::Float(v) if v.is_infinite() => "-Infinity".to_string(),
+		Cbor::Float(v) if v.fract() == 0.0 && v.abs() < 1e16 => format!("{:.1}", v),
+		Cbor::Float(v) => v.to_string(),
+	}
+}
+
+/// Big-endian bytes to decimal string
+fn bytes_to_decimal(bytes: &[u8]) -> String {
+	// little-endian base 10^9 digits
+	let mut digits: Vec<u32> = vec![0];
+	for byte in bytes {
+		let mut carry = u64::from(*byte);
+		for digit in digits.iter_mut() {
+			let x = u64::from(*digit) * 256 + carry;
+			*digit = (x % 1_000_000_000) as u32;
+			carry = x / 1_000_000_000;
+		}
+		while carry > 0 {
+			digits.push((carry % 1_000_000_000) as u32);
+			carry /= 1_000_000_000;
+		}
+	}
+	let mut result = digits.last().map(|x| x.to_string()).unwrap_or_default();
+	for digit in digits.iter().rev().skip(1) {
+		result.push_str(&format!("{:09}", digit));
+	}
+	result
+}
+
+/// Decimal string to big-endian bytes
+fn decimal_to_bytes(decimal: &str) -> Option<Vec<u8>> {
+	// little-endian bytes
+	let mut bytes: Vec<u8> = vec![];
+	for c in decimal.chars() {
+		let mut carry = c.to_digit(10)?;
+		for byte in bytes.iter_mut() {
+			let x = u32::from(*byte) * 10 + carry;
+			*byte = (x & 0xff) as u8;
+			carry = x >> 8;
+		}
+		while carry > 0 {
+			bytes.push((carry & 0xff) as u8);
+			carry >>= 8;
+		}
+	}
+	bytes.reverse();
+	Some(bytes)
+}
+
+fn increment(mut bytes: Vec<u8>) -> Vec<u8> {
+	for byte in bytes.iter_mut().rev() {
+		let (x, overflow) = byte.overflowing_add(1);
+		*byte = x;
+		if !overflow {
+			return bytes;
+		}
+	}
+	bytes.insert(0, 1);
+	bytes
+}
+
+fn decrement(mut bytes: Vec<u8>) -> Vec<u8> {
+	for byte in bytes.iter_mut().rev() {
+		let (x, overflow) = byte.overflowing_sub(1);
+		*byte = x;
+		if !overflow {
+			break;
+		}
+	}
+	bytes.into_iter().skip_while(|x| *x == 0).collect()
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"j2cbor",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"a":1,"b":[true,null,-1,1.5]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["0xa2616101616284f5f620fa3fc00000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Bignum".to_string(),
+						input: vec!["'18446744073709551616'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["0xc249010000000000000000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"cbor2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "0xa2616101616284f5f620fa3fc00000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":1,"b":[true,null,-1,1.5]}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Bytes, bignum and tag".to_string(),
+						input: vec!["-m", "0x8342010fc349010000000000000000c11a514b67b0"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							r#"["0x010f",-18446744073709551617,{"tag":1,"value":1363896240}]"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"cbor_diag",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["0xa2616101616284f5f620f93e00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a": 1, "b": [true, null, -1, 1.5]}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Tag and indefinite length".to_string(),
+						input: vec![
+							"0xc074323031332d30332d32315432303a30343a30305a9f5f42010243030405ffff",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![r#"0("2013-03-21T20:04:00Z")"#, "[_ (_ h'0102', h'030405')]"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
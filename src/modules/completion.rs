@@ -1,4 +1,5 @@
 use crate::app;
+use crate::error::Error;
 use clap::{App, Arg, ArgMatches, Shell, SubCommand};
 use std::io::stdout;
 use std::str::FromStr;
@@ -17,13 +18,13 @@ pub fn app<'a, 'b>() -> App<'a, 'b> {
 		)
 }
 
-pub fn run<'a, 'b>(matches: &ArgMatches) -> Result<Vec<String>, String> {
+pub fn run(matches: &ArgMatches) -> Result<Vec<String>, Error> {
 	let shell = matches
 		.value_of("SHELL")
-		.ok_or("Invalid shell".to_string())?;
-	let shell = Shell::from_str(shell)?;
+		.ok_or(Error::invalid("shell", ""))?;
+	let shell = Shell::from_str(shell).map_err(|e| Error::invalid("shell", e))?;
 
-	let (mut app, _) = app::build_app();
+	let (mut app, _) = app::build_app(None);
 	app.gen_completions_to("dtool", shell, &mut stdout());
 
 	Ok(vec![])
@@ -0,0 +1,717 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::stream::Stream;
+use crate::modules::{base, Command, Module};
+use brotli::{CompressorWriter, DecompressorWriter};
+use clap::{Arg, ArgMatches};
+use flate2::write::{DeflateEncoder, GzEncoder, MultiGzDecoder, ZlibEncoder};
+use flate2::{Compression, Decompress, FlushDecompress, Status};
+use lz4_flex::frame::{FrameDecoder as Lz4Decoder, FrameEncoder as Lz4Encoder};
+use ruzstd::decoding::errors::{FrameDecoderError, ReadFrameHeaderError};
+use ruzstd::decoding::{BlockDecodingStrategy, Dictionary, FrameDecoder};
+use ruzstd::encoding::CompressionLevel;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::mem;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Compress / decompress (gzip, zlib, deflate, zstd, brotli, lz4)".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("compress")
+				.about("Compress, binary input and output, use -f and -o for files")
+				.arg(algorithm_arg())
+				.arg(
+					Arg::with_name("LEVEL")
+						.long("level")
+						.short("l")
+						.help("Compression level\ngzip, zlib, deflate: 0 (none) to 9 (best), 6 if not given\nzstd: 0 (none) or 1 (fast, the only one implemented), 1 if not given\nbrotli: 0 to 11 (best), 11 if not given\nlz4: no levels")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes_raw(matches, compress),
+			stream: Some(compress_stream),
+		},
+		Command {
+			app: base::sub_command("decompress")
+				.about("Decompress, binary input and output, use -f and -o for files")
+				.arg(algorithm_arg())
+				.arg(
+					Arg::with_name("DICTIONARY")
+						.long("dictionary")
+						.short("d")
+						.help("zstd dictionary file the input was compressed with, e.g. by zstd -D, trained by zstd --train")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes_raw(matches, decompress),
+			stream: Some(decompress_stream),
+		},
+	]
+}
+
+fn algorithm_arg<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("ALGORITHM")
+		.long("algorithm")
+		.short("a")
+		.help("gzip: RFC 1952, e.g. .gz files and Content-Encoding: gzip\nzlib: RFC 1950, e.g. PNG IDAT chunks\ndeflate: RFC 1951, raw, e.g. zip entries\nzstd: Zstandard, RFC 8878\nbrotli: RFC 7932, e.g. Content-Encoding: br\nlz4: LZ4 frame format, e.g. .lz4 files")
+		.takes_value(true)
+		.possible_values(&["gzip", "zlib", "deflate", "zstd", "brotli", "lz4"])
+		.default_value("gzip")
+		.required(false)
+}
+
+/// Options of compress and decompress
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub algorithm: Algorithm,
+	/// Compression level, the default of the algorithm if not given
+	pub level: Option<u32>,
+	/// zstd dictionary the input was compressed with, only for decompress
+	pub dictionary: Option<Vec<u8>>,
+}
+
+/// Compression algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Algorithm {
+	/// RFC 1952, e.g. .gz files and Content-Encoding: gzip
+	#[default]
+	Gzip,
+	/// RFC 1950, e.g. PNG IDAT chunks
+	Zlib,
+	/// RFC 1951, raw, e.g. zip entries
+	Deflate,
+	/// Zstandard, RFC 8878
+	Zstd,
+	/// RFC 7932, e.g. Content-Encoding: br
+	Brotli,
+	/// LZ4 frame format, e.g. .lz4 files
+	Lz4,
+}
+
+impl Algorithm {
+	fn name(&self) -> &'static str {
+		match self {
+			Algorithm::Gzip => "gzip",
+			Algorithm::Zlib => "zlib",
+			Algorithm::Deflate => "deflate",
+			Algorithm::Zstd => "zstd",
+			Algorithm::Brotli => "brotli",
+			Algorithm::Lz4 => "lz4",
+		}
+	}
+
+	/// The highest and the default level
+	fn levels(&self) -> (u32, u32) {
+		match self {
+			Algorithm::Zstd => (1, 1),
+			Algorithm::Brotli => (11, 11),
+			Algorithm::Lz4 => (0, 0),
+			_ => (9, 6),
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let algorithm = match matches.value_of("ALGORITHM") {
+			Some("zlib") => Algorithm::Zlib,
+			Some("deflate") => Algorithm::Deflate,
+			Some("zstd") => Algorithm::Zstd,
+			Some("brotli") => Algorithm::Brotli,
+			Some("lz4") => Algorithm::Lz4,
+			_ => Algorithm::Gzip,
+		};
+		let level = match matches.value_of("LEVEL") {
+			Some(level) => Some(level.parse::<u32>().map_err(|_| {
+				Error::invalid(
+					"level",
+					format!(
+						"{}: not in 0..{} of {}",
+						level,
+						algorithm.levels().0,
+						algorithm.name()
+					),
+				)
+			})?),
+			None => None,
+		};
+		let dictionary = match matches.value_of("DICTIONARY") {
+			Some(file) => Some(fs::read(file).map_err(|e| Error::io(file, e))?),
+			None => None,
+		};
+		Ok(Options {
+			algorithm,
+			level,
+			dictionary,
+		})
+	}
+}
+
+/// Compress, the input and the output are binary
+pub fn compress(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	run(compressor(options)?, input)
+}
+
+/// Decompress, the input and the output are binary
+pub fn decompress(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	run(decompressor(options)?, input)
+}
+
+fn compress_stream(matches: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	compressor(&Options::from_matches(matches)?)
+}
+
+fn decompress_stream(matches: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	decompressor(&Options::from_matches(matches)?)
+}
+
+fn compressor(options: &Options) -> Result<Box<dyn Stream>, Error> {
+	let algorithm = options.algorithm;
+
+	let (max, default) = algorithm.levels();
+	let level = match options.level {
+		Some(_) if algorithm == Algorithm::Lz4 => {
+			return Err(Error::invalid("level", "lz4 has no levels"))
+		}
+		Some(level) if level > max => {
+			return Err(Error::invalid(
+				"level",
+				format!("{}: not in 0..{} of {}", level, max, algorithm.name()),
+			))
+		}
+		Some(level) => level,
+		None => default,
+	};
+
+	let codec: Box<dyn Codec> = match algorithm {
+		Algorithm::Zlib => Box::new(ZlibEncoder::new(vec![], Compression::new(level))),
+		Algorithm::Deflate => Box::new(DeflateEncoder::new(vec![], Compression::new(level))),
+		Algorithm::Zstd => {
+			let level = match level {
+				0 => CompressionLevel::Uncompressed,
+				_ => CompressionLevel::Fastest,
+			};
+			Box::new(Buffered::new(move |input| {
+				Ok(ruzstd::encoding::compress_to_vec(input, level))
+			}))
+		}
+		Algorithm::Brotli => Box::new(BrotliEncoder {
+			writer: Some(CompressorWriter::new(vec![], 4096, level, 22)),
+			output: vec![],
+		}),
+		Algorithm::Lz4 => Box::new(Lz4Encoder::new(vec![])),
+		Algorithm::Gzip => Box::new(GzEncoder::new(vec![], Compression::new(level))),
+	};
+	Ok(Box::new(CodecStream {
+		codec,
+		failed: "Compress failed",
+	}))
+}
+
+fn decompressor(options: &Options) -> Result<Box<dyn Stream>, Error> {
+	let algorithm = options.algorithm;
+
+	let dictionary = match &options.dictionary {
+		Some(_) if algorithm != Algorithm::Zstd => {
+			return Err(Error::invalid("dictionary", "only for zstd"));
+		}
+		Some(dictionary) => {
+			let dictionary =
+				Dictionary::decode_dict(dictionary).map_err(|e| Error::invalid("dictionary", e))?;
+			Some(dictionary)
+		}
+		None => None,
+	};
+
+	let codec: Box<dyn Codec> = match algorithm {
+		Algorithm::Zlib => Box::new(Inflate::new(true)),
+		Algorithm::Deflate => Box::new(Inflate::new(false)),
+		Algorithm::Zstd => Box::new(Buffered::new(move |input| {
+			zstd_decompress(input, dictionary)
+		})),
+		Algorithm::Brotli => Box::new(DecompressorWriter::new(vec![], 4096)),
+		Algorithm::Lz4 => Box::new(Buffered::new(|input| {
+			let mut output = vec![];
+			Lz4Decoder::new(input).read_to_end(&mut output)?;
+			Ok(output)
+		})),
+		// concatenated members are decompressed as one, like gzip -d
+		Algorithm::Gzip => Box::new(MultiGzDecoder::new(vec![])),
+	};
+	Ok(Box::new(CodecStream {
+		codec,
+		failed: "Decompress failed",
+	}))
+}
+
+/// The whole input as a single chunk
+fn run(mut stream: Box<dyn Stream>, input: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut result = stream.process(input)?;
+	result.extend(stream.finish()?);
+	Ok(result)
+}
+
+/// An encoder or decoder writing into a buffer, which is taken after every chunk
+trait Codec: Write {
+	fn try_finish(&mut self) -> io::Result<()>;
+
+	fn take(&mut self) -> Vec<u8>;
+}
+
+macro_rules! impl_codec {
+	($($codec:ident),*) => {
+		$(
+			impl Codec for $codec<Vec<u8>> {
+				fn try_finish(&mut self) -> io::Result<()> {
+					$codec::try_finish(self)
+				}
+
+				fn take(&mut self) -> Vec<u8> {
+					mem::take(self.get_mut())
+				}
+			}
+		)*
+	};
+}
+
+impl_codec!(GzEncoder, ZlibEncoder, DeflateEncoder, MultiGzDecoder);
+
+impl Codec for Lz4Encoder<Vec<u8>> {
+	fn try_finish(&mut self) -> io::Result<()> {
+		Lz4Encoder::try_finish(self).map_err(io::Error::from)
+	}
+
+	fn take(&mut self) -> Vec<u8> {
+		mem::take(self.get_mut())
+	}
+}
+
+impl Codec for DecompressorWriter<Vec<u8>> {
+	fn try_finish(&mut self) -> io::Result<()> {
+		self.close()
+	}
+
+	fn take(&mut self) -> Vec<u8> {
+		mem::take(self.get_mut())
+	}
+}
+
+/// The stream is only ended by consuming the writer
+struct BrotliEncoder {
+	writer: Option<CompressorWriter<Vec<u8>>>,
+	output: Vec<u8>,
+}
+
+impl Write for BrotliEncoder {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		match self.writer.as_mut() {
+			Some(writer) => writer.write(buf),
+			None => Err(io::Error::other("finished")),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match self.writer.as_mut() {
+			Some(writer) => writer.flush(),
+			None => Ok(()),
+		}
+	}
+}
+
+impl Codec for BrotliEncoder {
+	fn try_finish(&mut self) -> io::Result<()> {
+		if let Some(writer) = self.writer.take() {
+			self.output.extend(writer.into_inner());
+		}
+		Ok(())
+	}
+
+	fn take(&mut self) -> Vec<u8> {
+		match self.writer.as_mut() {
+			Some(writer) => mem::take(writer.get_mut()),
+			None => mem::take(&mut self.output),
+		}
+	}
+}
+
+type Whole = Box<dyn FnOnce(&[u8]) -> io::Result<Vec<u8>>>;
+
+/// For the codecs reading the whole input, the input is buffered until finished
+struct Buffered {
+	input: Vec<u8>,
+	output: Vec<u8>,
+	f: Option<Whole>,
+}
+
+impl Buffered {
+	fn new(f: impl FnOnce(&[u8]) -> io::Result<Vec<u8>> + 'static) -> Self {
+		Buffered {
+			input: vec![],
+			output: vec![],
+			f: Some(Box::new(f)),
+		}
+	}
+}
+
+impl Write for Buffered {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.input.extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl Codec for Buffered {
+	fn try_finish(&mut self) -> io::Result<()> {
+		if let Some(f) = self.f.take() {
+			self.output = f(&mem::take(&mut self.input))?;
+		}
+		Ok(())
+	}
+
+	fn take(&mut self) -> Vec<u8> {
+		mem::take(&mut self.output)
+	}
+}
+
+/// All frames of the input, skippable frames are skipped, the checksums are verified
+fn zstd_decompress(mut input: &[u8], dictionary: Option<Dictionary>) -> io::Result<Vec<u8>> {
+	let invalid = |e: FrameDecoderError| io::Error::new(io::ErrorKind::InvalidData, e.to_string());
+
+	let mut decoder = FrameDecoder::new();
+	if let Some(dictionary) = dictionary {
+		decoder.add_dict(dictionary).map_err(invalid)?;
+	}
+
+	let mut output = vec![];
+	while !input.is_empty() {
+		match decoder.init(&mut input) {
+			Ok(()) => (),
+			Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame {
+				length,
+				..
+			})) => {
+				input = input.get(length as usize..).unwrap_or_default();
+				continue;
+			}
+			Err(e) => return Err(invalid(e)),
+		}
+		while !decoder.is_finished() {
+			decoder
+				.decode_blocks(&mut input, BlockDecodingStrategy::UptoBytes(1024 * 1024))
+				.map_err(invalid)?;
+			decoder.collect_to_writer(&mut output)?;
+		}
+		decoder.collect_to_writer(&mut output)?;
+
+		if let (Some(expected), Some(actual)) = (
+			decoder.get_checksum_from_data(),
+			decoder.get_calculated_checksum(),
+		) {
+			if expected != actual {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					"checksum mismatched",
+				));
+			}
+		}
+	}
+	Ok(output)
+}
+
+/// zlib or raw deflate decoder, unlike the write decoders of flate2 a truncated input fails
+struct Inflate {
+	decompress: Decompress,
+	output: Vec<u8>,
+	end: bool,
+}
+
+impl Inflate {
+	fn new(zlib_header: bool) -> Self {
+		Inflate {
+			decompress: Decompress::new(zlib_header),
+			output: vec![],
+			end: false,
+		}
+	}
+}
+
+impl Write for Inflate {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let mut input = buf;
+		while !self.end {
+			if self.output.capacity() - self.output.len() < 32 * 1024 {
+				self.output.reserve(64 * 1024);
+			}
+			let (total_in, total_out) = (self.decompress.total_in(), self.decompress.total_out());
+			let status = self
+				.decompress
+				.decompress_vec(input, &mut self.output, FlushDecompress::None)
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+			let consumed = (self.decompress.total_in() - total_in) as usize;
+			let produced = self.decompress.total_out() - total_out;
+			input = &input[consumed..];
+
+			self.end = status == Status::StreamEnd;
+			// more output is pending only if the buffer was filled up
+			let pending = self.output.len() == self.output.capacity();
+			if (input.is_empty() && !pending) || (consumed == 0 && produced == 0) {
+				break;
+			}
+		}
+		if self.end && !input.is_empty() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"data after the end of the stream",
+			));
+		}
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl Codec for Inflate {
+	fn try_finish(&mut self) -> io::Result<()> {
+		match self.end {
+			true => Ok(()),
+			false => Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"unexpected end of the stream",
+			)),
+		}
+	}
+
+	fn take(&mut self) -> Vec<u8> {
+		mem::take(&mut self.output)
+	}
+}
+
+struct CodecStream {
+	codec: Box<dyn Codec>,
+	failed: &'static str,
+}
+
+impl Stream for CodecStream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		self.codec
+			.write_all(chunk)
+			.map_err(|e| Error::failed(format!("{}: {}", self.failed, e)))?;
+		Ok(self.codec.take())
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		self.codec
+			.try_finish()
+			.map_err(|e| Error::failed(format!("{}: {}", self.failed, e)))?;
+		Ok(self.codec.take())
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"compress",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-f", "access.log", "-o", "access.log.gz"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "zlib, best compression".to_string(),
+						input: vec!["-a", "zlib", "-l", "9", "-f", "data.bin", "-o", "data.zz"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "brotli".to_string(),
+						input: vec!["-a", "brotli", "-f", "index.html", "-o", "index.html.br"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"decompress",
+				vec![Case {
+					desc: "A gzip HTTP body, e.g. curl -s -H 'Accept-Encoding: gzip' ... | dtool decompress"
+						.to_string(),
+					input: vec!["-f", "body.gz"].into_iter().map(Into::into).collect(),
+					output: vec!["{\"status\":\"ok\"}"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "zstd with a dictionary".to_string(),
+					input: vec!["-a", "zstd", "-d", "dict", "-f", "event.zst"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["{\"event\":\"login\"}"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_compress() {
+		let input = "dtool ".repeat(1000).into_bytes();
+
+		let levels = vec![
+			(Algorithm::Gzip, vec![Some(0), Some(1), Some(9)]),
+			(Algorithm::Zlib, vec![Some(0), Some(1), Some(9)]),
+			(Algorithm::Deflate, vec![Some(0), Some(1), Some(9)]),
+			(Algorithm::Zstd, vec![Some(0), Some(1)]),
+			(Algorithm::Brotli, vec![Some(0), Some(5), Some(11)]),
+			(Algorithm::Lz4, vec![None]),
+		];
+		for (algorithm, levels) in levels {
+			for level in levels {
+				let options = Options {
+					algorithm,
+					level,
+					dictionary: None,
+				};
+				let compressed = compress(&input, &options).unwrap();
+				if level != Some(0) {
+					assert!(compressed.len() < input.len() / 10);
+				}
+
+				// chunk by chunk
+				let mut stream = decompressor(&options).unwrap();
+				let mut decompressed = vec![];
+				for chunk in compressed.chunks(7) {
+					decompressed.extend(stream.process(chunk).unwrap());
+				}
+				decompressed.extend(stream.finish().unwrap());
+				assert_eq!(decompressed, input);
+			}
+		}
+
+		for (algorithm, level) in [
+			(Algorithm::Gzip, 10),
+			(Algorithm::Zstd, 3),
+			(Algorithm::Lz4, 1),
+		] {
+			let options = Options {
+				algorithm,
+				level: Some(level),
+				dictionary: None,
+			};
+			assert!(compress(b"dtool", &options).is_err());
+		}
+	}
+
+	#[test]
+	fn test_decompress() {
+		let options = Options::default();
+		let compressed = compress(b"dtool", &options).unwrap();
+		assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+
+		let mut twice = compressed.clone();
+		twice.extend(&compressed);
+		assert_eq!(decompress(&twice, &options).unwrap(), b"dtooldtool");
+		assert!(decompress(b"dtool", &options).is_err());
+		for algorithm in [
+			Algorithm::Gzip,
+			Algorithm::Zlib,
+			Algorithm::Deflate,
+			Algorithm::Zstd,
+			Algorithm::Brotli,
+			Algorithm::Lz4,
+		] {
+			let options = Options {
+				algorithm,
+				..Options::default()
+			};
+			let compressed = compress(&[0; 1000], &options).unwrap();
+			assert!(decompress(&compressed[..compressed.len() - 5], &options).is_err());
+		}
+	}
+
+	#[test]
+	fn test_decompress_zstd() {
+		// a skippable frame, then an empty frame with the checksum
+		let input = [
+			0x50, 0x2a, 0x4d, 0x18, 0x02, 0x00, 0x00, 0x00, 0xff, 0xff, 0x28, 0xb5, 0x2f, 0xfd,
+			0x04, 0x00, 0x01, 0x00, 0x00, 0x99, 0xe9, 0xd8, 0x51,
+		];
+		let options = Options {
+			algorithm: Algorithm::Zstd,
+			..Options::default()
+		};
+		assert_eq!(decompress(&input, &options).unwrap(), b"");
+
+		let dictionary = Some(b"[package]".to_vec());
+		let options = Options {
+			dictionary: dictionary.clone(),
+			..Options::default()
+		};
+		assert!(decompress(&input, &options).is_err());
+		let options = Options {
+			algorithm: Algorithm::Zstd,
+			level: None,
+			dictionary,
+		};
+		assert!(decompress(&input, &options).is_err());
+	}
+}
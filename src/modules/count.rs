@@ -0,0 +1,153 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Text statistics".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("count")
+			.about("Count bytes, chars, graphemes, words and lines")
+			.arg(
+				Arg::with_name("TYPE")
+					.long("type")
+					.short("t")
+					.help("Output only the count of the type")
+					.takes_value(true)
+					.possible_values(&["bytes", "chars", "graphemes", "words", "lines"])
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert_bytes(matches, count),
+		stream: None,
+	}]
+}
+
+/// Options of `count`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Output only the count of the type
+	pub count_type: Option<CountType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CountType {
+	Bytes,
+	Chars,
+	Graphemes,
+	Words,
+	Lines,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let count_type = match matches.value_of("TYPE") {
+			Some("bytes") => Some(CountType::Bytes),
+			Some("chars") => Some(CountType::Chars),
+			Some("graphemes") => Some(CountType::Graphemes),
+			Some("words") => Some(CountType::Words),
+			Some("lines") => Some(CountType::Lines),
+			Some(_) => return Err(Error::invalid("type", "")),
+			None => None,
+		};
+
+		Ok(Options { count_type })
+	}
+}
+
+/// Count bytes, chars, graphemes, words and lines,
+/// words are split by Unicode word boundaries (UAX #29), punctuation is not counted
+pub fn count(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = std::str::from_utf8(input).map_err(|_| Error::invalid("UTF-8", ""))?;
+
+	let counts = vec![
+		(CountType::Bytes, "bytes", input.len()),
+		(CountType::Chars, "chars", input.chars().count()),
+		(
+			CountType::Graphemes,
+			"graphemes",
+			input.graphemes(true).count(),
+		),
+		(CountType::Words, "words", input.unicode_words().count()),
+		(CountType::Lines, "lines", input.lines().count()),
+	];
+
+	let result = match options.count_type {
+		Some(t) => counts
+			.into_iter()
+			.filter(|(count_type, _, _)| *count_type == t)
+			.map(|(_, _, count)| count.to_string())
+			.collect::<Vec<_>>(),
+		None => counts
+			.into_iter()
+			.map(|(_, name, count)| format!("{}: {}", name, count))
+			.collect(),
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"count",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'Café 👨‍👩‍👧\nhello, world'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"bytes: 37",
+						"chars: 23",
+						"graphemes: 19",
+						"words: 3",
+						"lines: 2",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "".to_string(),
+					input: vec!["-t", "graphemes", "'e\u{301}'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["1"].into_iter().map(Into::into).collect(),
+					is_example: false,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -0,0 +1,636 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use prettytable::{format, Cell, Row, Table};
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "JSON / CSV conversion, CSV table".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("c2j")
+				.about("Convert CSV to JSON")
+				.arg(
+					Arg::with_name("DELIMITER")
+						.long("delimiter")
+						.short("d")
+						.help("Delimiter, e.g. , ; \\t |")
+						.takes_value(true)
+						.default_value(",")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("NO_HEADER")
+						.long("no-header")
+						.short("n")
+						.help("No header row (output arrays instead of objects)")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("STRING")
+						.long("string")
+						.short("s")
+						.help("Keep all values as strings (no number / bool / null inference)")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, c2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2c")
+				.about("Convert JSON to CSV")
+				.arg(
+					Arg::with_name("DELIMITER")
+						.long("delimiter")
+						.short("d")
+						.help("Delimiter, e.g. , ; \\t |")
+						.takes_value(true)
+						.default_value(",")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("NO_HEADER")
+						.long("no-header")
+						.short("n")
+						.help("No header row")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2c),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("csv_table")
+				.about("CSV table (select columns, head / tail rows)")
+				.arg(
+					Arg::with_name("DELIMITER")
+						.long("delimiter")
+						.short("d")
+						.help("Delimiter, e.g. , ; \\t |")
+						.takes_value(true)
+						.default_value(",")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("NO_HEADER")
+						.long("no-header")
+						.short("n")
+						.help("No header row")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("COLUMNS")
+						.long("columns")
+						.short("c")
+						.help("Columns to select in order, by name or 1-based index, e.g. name,3")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("HEAD")
+						.long("head")
+						.help("Only the first <HEAD> rows")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("TAIL")
+						.long("tail")
+						.help("Only the last <TAIL> rows")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, csv_table),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the CSV commands
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub delimiter: u8,
+	pub no_header: bool,
+	/// Keep all values as strings (no number / bool / null inference)
+	pub string: bool,
+	pub minify: bool,
+	/// Columns to select in order, by name or 1-based index
+	pub columns: Option<Vec<String>>,
+	pub head: Option<usize>,
+	pub tail: Option<usize>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			delimiter: b',',
+			no_header: false,
+			string: false,
+			minify: false,
+			columns: None,
+			head: None,
+			tail: None,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let head = match matches.value_of("HEAD") {
+			Some(head) => Some(head.parse().map_err(|_| Error::invalid("head", ""))?),
+			None => None,
+		};
+
+		let tail = match matches.value_of("TAIL") {
+			Some(tail) => Some(tail.parse().map_err(|_| Error::invalid("tail", ""))?),
+			None => None,
+		};
+
+		Ok(Options {
+			delimiter: parse_delimiter(matches)?,
+			no_header: matches.is_present("NO_HEADER"),
+			string: matches.is_present("STRING"),
+			minify: matches.is_present("MINIFY"),
+			columns: matches
+				.value_of("COLUMNS")
+				.map(|x| x.split(',').map(|x| x.trim().to_string()).collect()),
+			head,
+			tail,
+		})
+	}
+}
+
+/// Convert CSV to JSON
+pub fn c2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let header = !options.no_header;
+
+	let infer = !options.string;
+
+	let records = parse(base::text(input)?, options.delimiter)?;
+
+	let to_value = |x: &String| {
+		if infer {
+			infer_value(x)
+		} else {
+			Value::String(x.clone())
+		}
+	};
+
+	let value = if header {
+		let mut records = records.into_iter();
+		let names = records.next().unwrap_or_default();
+		Value::Array(
+			records
+				.map(|record| {
+					Value::Object(
+						names
+							.iter()
+							.cloned()
+							.zip(record.iter().map(to_value))
+							.collect::<Map<_, _>>(),
+					)
+				})
+				.collect(),
+		)
+	} else {
+		Value::Array(
+			records
+				.iter()
+				.map(|record| Value::Array(record.iter().map(to_value).collect()))
+				.collect(),
+		)
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to CSV
+pub fn j2c(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let header = !options.no_header;
+
+	let value = json::parse(base::text(input)?)?;
+
+	let rows = match value {
+		Value::Array(rows) => rows,
+		_ => return Err(Error::failed("Not array")),
+	};
+
+	let mut records = vec![];
+
+	if rows.iter().all(Value::is_array) {
+		for row in rows {
+			if let Value::Array(row) = row {
+				records.push(row.iter().map(to_field).collect::<Result<Vec<_>, _>>()?);
+			}
+		}
+	} else if rows.iter().all(Value::is_object) {
+		// Union of the keys, in order of appearance
+		let mut names: Vec<String> = vec![];
+		for row in &rows {
+			if let Value::Object(row) = row {
+				for k in row.keys() {
+					if !names.contains(k) {
+						names.push(k.clone());
+					}
+				}
+			}
+		}
+		if header {
+			records.push(names.clone());
+		}
+		for row in &rows {
+			records.push(
+				names
+					.iter()
+					.map(|k| {
+						row.get(k)
+							.map(to_field)
+							.unwrap_or_else(|| Ok("".to_string()))
+					})
+					.collect::<Result<Vec<_>, _>>()?,
+			);
+		}
+	} else {
+		return Err(Error::failed("Not array of arrays or array of objects"));
+	}
+
+	let result = to_string(&records, options.delimiter)?;
+
+	Ok(result.into_bytes())
+}
+
+/// CSV table (select columns, head / tail rows)
+pub fn csv_table(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let mut records = parse(base::text(input)?, options.delimiter)?;
+
+	let names = if options.no_header || records.is_empty() {
+		None
+	} else {
+		Some(records.remove(0))
+	};
+
+	let columns = match &options.columns {
+		Some(columns) => Some(
+			columns
+				.iter()
+				.map(|x| {
+					let x = x.as_str();
+					names
+						.as_ref()
+						.and_then(|names| names.iter().position(|name| name == x))
+						.or_else(|| match x.parse::<usize>() {
+							Ok(index) if index > 0 => Some(index - 1),
+							_ => None,
+						})
+						.ok_or_else(|| Error::invalid("column", x))
+				})
+				.collect::<Result<Vec<_>, _>>()?,
+		),
+		None => None,
+	};
+
+	if let Some(head) = options.head {
+		records.truncate(head);
+	}
+
+	if let Some(tail) = options.tail {
+		let skip = records.len().saturating_sub(tail);
+		records.drain(..skip);
+	}
+
+	let select = |record: &Vec<String>| -> Row {
+		let cells = match &columns {
+			Some(columns) => columns
+				.iter()
+				.map(|i| Cell::new(record.get(*i).map(String::as_str).unwrap_or("")))
+				.collect(),
+			None => record.iter().map(|x| Cell::new(x)).collect(),
+		};
+		Row::new(cells)
+	};
+
+	let mut table = Table::init(records.iter().map(select).collect());
+	match names {
+		Some(names) => {
+			table.set_titles(select(&names));
+			table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+		}
+		None => table.set_format(*format::consts::FORMAT_NO_LINESEP),
+	}
+
+	let result = table.to_string();
+
+	Ok(result
+		.strip_suffix('\n')
+		.unwrap_or(&result)
+		.as_bytes()
+		.to_vec())
+}
+
+fn parse_delimiter(matches: &ArgMatches) -> Result<u8, Error> {
+	let delimiter = matches
+		.value_of("DELIMITER")
+		.ok_or(Error::invalid("delimiter", ""))?;
+	match delimiter {
+		"\\t" | "tab" => Ok(b'\t'),
+		_ if delimiter.len() == 1 => Ok(delimiter.as_bytes()[0]),
+		_ => Err(Error::invalid("delimiter", "")),
+	}
+}
+
+pub fn parse(input: &str, delimiter: u8) -> Result<Vec<Vec<String>>, Error> {
+	csv::ReaderBuilder::new()
+		.delimiter(delimiter)
+		.has_headers(false)
+		.flexible(true)
+		.from_reader(input.as_bytes())
+		.records()
+		.map(|record| {
+			record
+				.map(|record| record.iter().map(Into::into).collect())
+				.map_err(|e| Error::invalid("CSV", e))
+		})
+		.collect()
+}
+
+/// Without the trailing newline
+pub fn to_string(records: &[Vec<String>], delimiter: u8) -> Result<String, Error> {
+	let mut writer = csv::WriterBuilder::new()
+		.delimiter(delimiter)
+		.flexible(true)
+		.from_writer(vec![]);
+	for record in records {
+		writer
+			.write_record(record)
+			.map_err(|e| Error::invalid("CSV", e))?;
+	}
+	let result = writer.into_inner().map_err(|e| Error::invalid("CSV", e))?;
+	let result = String::from_utf8(result).map_err(|_| Error::failed("Not UTF-8"))?;
+	Ok(result.strip_suffix('\n').unwrap_or(&result).to_string())
+}
+
+/// Empty field is null, then try bool and number, otherwise string
+fn infer_value(field: &str) -> Value {
+	match field {
+		"" => Value::Null,
+		"true" => Value::Bool(true),
+		"false" => Value::Bool(false),
+		_ => match json::parse(field) {
+			Ok(value @ Value::Number(_)) => value,
+			_ => Value::String(field.to_string()),
+		},
+	}
+}
+
+/// Nested arrays and objects are written as JSON
+fn to_field(value: &Value) -> Result<String, Error> {
+	match value {
+		Value::Null => Ok("".to_string()),
+		Value::String(v) => Ok(v.clone()),
+		v => json::to_string(v),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"c2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "'name,age,admin\nalice,30,true\nbob,,false'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							r#"[{"name":"alice","age":30,"admin":true},{"name":"bob","age":null,"admin":false}]"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Custom delimiter, no header, no type inference".to_string(),
+						input: vec!["-d", "';'", "-n", "-s", "-m", "'a;1\n\"b;c\";2'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"[["a","1"],["b;c","2"]]"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-d", "'\\t'", "'a\tb\n007\t1.50'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"[",
+							"  {",
+							r#"    "a": "007","#,
+							r#"    "b": 1.50"#,
+							"  }",
+							"]",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"j2c",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							r#"'[{"name":"alice","age":30},{"name":"bob, jr","tags":["x"]}]'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["name,age,tags", "alice,30,", r#""bob, jr",,"[""x""]""#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-d", "'|'", r#"'[["a",1],["b",null]]'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a|1", "b|"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"csv_table",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'name,age\nalice,30\nbob,4'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"+-------+-----+",
+							"| name  | age |",
+							"+-------+-----+",
+							"| alice | 30  |",
+							"| bob   | 4   |",
+							"+-------+-----+",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Select and reorder columns, last rows".to_string(),
+						input: vec!["-c", "'c,1'", "--tail", "1", "'a,b,c\n1,2,3\n4,5,6'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"+---+---+",
+							"| c | a |",
+							"+---+---+",
+							"| 6 | 4 |",
+							"+---+---+",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "--head", "1", "'x,y\nz,w'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["+---+---+", "| x | y |", "+---+---+"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_csv_table() {
+		let run = |input: &str, options: Options| {
+			csv_table(input.as_bytes(), &options).map(|x| String::from_utf8(x).unwrap())
+		};
+
+		let input = "name,age,city\nalice,30,x\nbob,25,y";
+		assert_eq!(
+			run(
+				input,
+				Options {
+					columns: Some(vec!["name".to_string(), "2".to_string()]),
+					tail: Some(1),
+					..Default::default()
+				}
+			)
+			.unwrap()
+			.lines()
+			.collect::<Vec<_>>(),
+			vec![
+				"+------+-----+",
+				"| name | age |",
+				"+------+-----+",
+				"| bob  | 25  |",
+				"+------+-----+",
+			]
+		);
+		assert_eq!(
+			run(
+				"a,b\nc",
+				Options {
+					no_header: true,
+					..Default::default()
+				}
+			)
+			.unwrap()
+			.lines()
+			.collect::<Vec<_>>(),
+			vec!["+---+---+", "| a | b |", "| c |   |", "+---+---+"]
+		);
+		assert!(run(
+			input,
+			Options {
+				columns: Some(vec!["country".to_string()]),
+				..Default::default()
+			}
+		)
+		.is_err());
+	}
+}
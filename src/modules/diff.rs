@@ -0,0 +1,406 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use ansi_term::Colour;
+use clap::{Arg, ArgMatches};
+use std::fs;
+use std::io;
+use std::io::Read;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Text diff".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("diff")
+			.about("Unified diff of two files")
+			.arg(
+				Arg::with_name("CONTEXT")
+					.long("unified")
+					.short("U")
+					.help("Number of context lines")
+					.takes_value(true)
+					.default_value("3")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("COLOR")
+					.long("color")
+					.help("Colorize output")
+					.takes_value(true)
+					.possible_values(&["auto", "always", "never"])
+					.default_value("auto")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("FILE")
+					.help("File, - for stdin")
+					.required(true)
+					.index(1),
+			)
+			.arg(
+				Arg::with_name("OTHER")
+					.help("Other file, stdin is compared with FILE if absent")
+					.required(false)
+					.index(2),
+			),
+		f: run_diff,
+		stream: None,
+	}]
+}
+
+/// Options of `diff`
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// The text compared with the input
+	pub other: String,
+	/// The names in the header
+	pub name: String,
+	pub other_name: String,
+	/// Number of context lines
+	pub context: usize,
+	pub color: bool,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			other: String::new(),
+			name: "a".to_string(),
+			other_name: "b".to_string(),
+			context: 3,
+			color: false,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let context = matches
+			.value_of("CONTEXT")
+			.ok_or(Error::invalid("context", ""))?
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("context", ""))?;
+
+		let (name, other_name) = names(matches)?;
+
+		Ok(Options {
+			other: read(other_name)?,
+			name: name.to_string(),
+			other_name: other_name.to_string(),
+			context,
+			color: base::color(matches),
+		})
+	}
+}
+
+/// Unified diff of the input and `other`, nothing if equal
+pub fn diff(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let text = base::text(input)?;
+
+	let result = unified(
+		text,
+		&options.other,
+		&options.name,
+		&options.other_name,
+		options.context,
+	);
+
+	let result = if options.color {
+		result.into_iter().map(|x| colorize(&x)).collect()
+	} else {
+		result
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn run_diff(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let options = Options::from_matches(matches)?;
+
+	let (name, _) = names(matches)?;
+	let text = read(name)?;
+
+	diff(text.as_bytes(), &options).and_then(base::lines)
+}
+
+/// FILE and OTHER, stdin is compared with FILE if OTHER is absent
+fn names<'a>(matches: &'a ArgMatches) -> Result<(&'a str, &'a str), Error> {
+	match (matches.value_of("FILE"), matches.value_of("OTHER")) {
+		(Some(name), Some(other_name)) => Ok((name, other_name)),
+		(Some(name), None) => Ok(("-", name)),
+		_ => Err(Error::invalid("file", "")),
+	}
+}
+
+fn read(name: &str) -> Result<String, Error> {
+	if name == "-" {
+		let mut result = String::new();
+		io::stdin()
+			.read_to_string(&mut result)
+			.map_err(|_| Error::input(""))?;
+		Ok(result)
+	} else {
+		fs::read_to_string(name).map_err(|e| Error::io(name, e))
+	}
+}
+
+fn colorize(line: &str) -> String {
+	let style = match line.chars().next() {
+		_ if line.starts_with("---") || line.starts_with("+++") => Colour::White.bold(),
+		Some('@') => Colour::Cyan.normal(),
+		Some('-') => Colour::Red.normal(),
+		Some('+') => Colour::Green.normal(),
+		_ => return line.to_string(),
+	};
+	style.paint(line).to_string()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+	Equal(usize),
+	Delete(usize),
+	Insert(usize),
+}
+
+/// Lines are compared with their line endings so that a missing final new line is reported
+fn unified(text: &str, other: &str, name: &str, other_name: &str, context: usize) -> Vec<String> {
+	let a = text.split_inclusive('\n').collect::<Vec<_>>();
+	let b = other.split_inclusive('\n').collect::<Vec<_>>();
+
+	let edits = myers(&a, &b);
+
+	let changes = edits
+		.iter()
+		.enumerate()
+		.filter(|(_, edit)| !matches!(edit, Edit::Equal(_)))
+		.map(|(i, _)| i)
+		.collect::<Vec<_>>();
+	if changes.is_empty() {
+		return vec![];
+	}
+
+	// group the changes whose distance is within twice the context
+	let mut hunks: Vec<(usize, usize)> = vec![];
+	for i in changes {
+		let start = i.saturating_sub(context);
+		let end = (i + context + 1).min(edits.len());
+		match hunks.last_mut() {
+			Some(last) if start <= last.1 => last.1 = end,
+			_ => hunks.push((start, end)),
+		}
+	}
+
+	let mut result = vec![format!("--- {}", name), format!("+++ {}", other_name)];
+	for (start, end) in hunks {
+		// the lines before the hunk on each side
+		let a_start = count_a(&edits[..start]);
+		let b_start = count_b(&edits[..start]);
+
+		let edits = &edits[start..end];
+		let (a_len, b_len) = (count_a(edits), count_b(edits));
+		result.push(format!(
+			"@@ -{} +{} @@",
+			range(a_start, a_len),
+			range(b_start, b_len)
+		));
+
+		for edit in edits {
+			let (prefix, line) = match *edit {
+				Edit::Equal(i) => (' ', a[i]),
+				Edit::Delete(i) => ('-', a[i]),
+				Edit::Insert(j) => ('+', b[j]),
+			};
+			result.push(format!("{}{}", prefix, line.trim_end_matches('\n')));
+			if !line.ends_with('\n') {
+				result.push("\\ No newline at end of file".to_string());
+			}
+		}
+	}
+	result
+}
+
+fn count_a(edits: &[Edit]) -> usize {
+	edits
+		.iter()
+		.filter(|x| !matches!(x, Edit::Insert(_)))
+		.count()
+}
+
+fn count_b(edits: &[Edit]) -> usize {
+	edits
+		.iter()
+		.filter(|x| !matches!(x, Edit::Delete(_)))
+		.count()
+}
+
+/// 1-based start line, the length is omitted if it is 1
+fn range(start: usize, len: usize) -> String {
+	match len {
+		0 => format!("{},0", start),
+		1 => format!("{}", start + 1),
+		_ => format!("{},{}", start + 1, len),
+	}
+}
+
+/// Myers' O(ND) difference algorithm
+fn myers(a: &[&str], b: &[&str]) -> Vec<Edit> {
+	let n = a.len() as isize;
+	let m = b.len() as isize;
+	let max = n + m;
+	let offset = |k: isize| (k + max + 1) as usize;
+
+	let mut v = vec![0isize; 2 * max as usize + 3];
+	let mut trace = vec![];
+	'outer: for d in 0..=max {
+		trace.push(v.clone());
+		for k in (-d..=d).step_by(2) {
+			let mut x = if k == -d || (k != d && v[offset(k - 1)] < v[offset(k + 1)]) {
+				v[offset(k + 1)]
+			} else {
+				v[offset(k - 1)] + 1
+			};
+			let mut y = x - k;
+			while x < n && y < m && a[x as usize] == b[y as usize] {
+				x += 1;
+				y += 1;
+			}
+			v[offset(k)] = x;
+			if x >= n && y >= m {
+				break 'outer;
+			}
+		}
+	}
+
+	let mut result = vec![];
+	let (mut x, mut y) = (n, m);
+	for (d, v) in trace.iter().enumerate().rev() {
+		let d = d as isize;
+		let k = x - y;
+		let prev_k = if k == -d || (k != d && v[offset(k - 1)] < v[offset(k + 1)]) {
+			k + 1
+		} else {
+			k - 1
+		};
+		let prev_x = v[offset(prev_k)];
+		let prev_y = prev_x - prev_k;
+		while x > prev_x && y > prev_y {
+			x -= 1;
+			y -= 1;
+			result.push(Edit::Equal(x as usize));
+		}
+		if d > 0 {
+			if x == prev_x {
+				result.push(Edit::Insert(prev_y as usize));
+			} else {
+				result.push(Edit::Delete(prev_x as usize));
+			}
+		}
+		x = prev_x;
+		y = prev_y;
+	}
+	result.reverse();
+	result
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"diff",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["a.txt", "b.txt"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"--- a.txt",
+						"+++ b.txt",
+						"@@ -1,3 +1,3 @@",
+						" a",
+						"-b",
+						"+B",
+						" c",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Stdin vs file, no context".to_string(),
+					input: vec!["-U", "0", "b.txt", "<", "a.txt"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["--- -", "+++ b.txt", "@@ -2 +2 @@", "-b", "+B"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_unified() {
+		assert_eq!(
+			unified("a\nb\n", "a\nb\n", "a", "b", 3),
+			Vec::<String>::new()
+		);
+		assert_eq!(
+			unified("a\nb\nc\n", "a\nB\nc\n", "a", "b", 3),
+			vec!["--- a", "+++ b", "@@ -1,3 +1,3 @@", " a", "-b", "+B", " c"]
+		);
+		assert_eq!(
+			unified(
+				"1\n2\n3\n4\n5\n6\n7\n8\n",
+				"0\n1\n2\n3\n4\n5\n6\n8",
+				"a",
+				"b",
+				1
+			),
+			vec![
+				"--- a",
+				"+++ b",
+				"@@ -1 +1,2 @@",
+				"+0",
+				" 1",
+				"@@ -6,3 +7,2 @@",
+				" 6",
+				"-7",
+				"-8",
+				"+8",
+				"\\ No newline at end of file",
+			]
+		);
+		assert_eq!(
+			unified("", "a\n", "a", "b", 3),
+			vec!["--- a", "+++ b", "@@ -0,0 +1 @@", "+a"]
+		);
+	}
+}
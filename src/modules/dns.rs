@@ -0,0 +1,386 @@
+use crate::error::Error;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches, SubCommand};
+use dns_parser::{Builder, Packet, QueryClass, QueryType, RData, ResponseCode};
+use serde_json::json;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "DNS lookup".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: SubCommand::with_name("dns")
+.args(&base::output_args())
+.arg(base::raw_arg())
+			.about("DNS lookup")
+			.arg(
+				Arg::with_name("TYPE")
+					.long("type")
+					.short("t")
+					.help("Record type")
+					.takes_value(true)
+					.possible_values(&["a", "aaaa", "cname", "txt", "mx", "ns"])
+					.default_value("a")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("SERVER")
+					.long("server")
+					.short("s")
+					.help("Resolver as IP or IP:port, the first nameserver of /etc/resolv.conf if not given")
+					.takes_value(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("TIMEOUT")
+					.long("timeout")
+					.help("Timeout in seconds")
+					.takes_value(true)
+					.default_value("5")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("FORMAT")
+					.long("format")
+					.short("F")
+					.help("Output format\nplain: name, TTL, type and data of every answer\njson: JSON object")
+					.takes_value(true)
+					.possible_values(&["plain", "json"])
+					.default_value("plain")
+					.required(false),
+			)
+			.arg(base::input_arg().help("Domain name")).args(&base::file_args()),
+		f: dns,
+		stream: None,
+	}]
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Answer {
+	pub name: String,
+	pub kind: String,
+	pub ttl: u32,
+	pub data: String,
+}
+
+fn dns(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let name = base::input_string(matches)?;
+	let name = name.trim().trim_end_matches('.');
+
+	let kind = matches.value_of("TYPE").unwrap_or("a");
+	let query_type = match kind {
+		"aaaa" => QueryType::AAAA,
+		"cname" => QueryType::CNAME,
+		"txt" => QueryType::TXT,
+		"mx" => QueryType::MX,
+		"ns" => QueryType::NS,
+		_ => QueryType::A,
+	};
+
+	let server = server(matches.value_of("SERVER"))?;
+	let timeout = matches
+		.value_of("TIMEOUT")
+		.unwrap_or("5")
+		.parse::<f64>()
+		.ok()
+		.filter(|x| *x > 0.0)
+		.map(Duration::from_secs_f64)
+		.ok_or(Error::invalid("timeout", ""))?;
+
+	base::verbose(matches, 1, || format!("server: {}", server));
+	let answers = lookup(name, query_type, server, timeout)?;
+
+	let result = match matches.value_of("FORMAT") {
+		Some("json") => {
+			let value = json!({
+				"name": name,
+				"type": kind.to_uppercase(),
+				"server": server.to_string(),
+				"answers": answers.iter().map(|x| json!({
+					"name": x.name,
+					"type": x.kind,
+					"ttl": x.ttl,
+					"data": x.data,
+				})).collect::<Vec<_>>(),
+			});
+			json::to_string_pretty(&value, 2)?
+				.lines()
+				.map(Into::into)
+				.collect()
+		}
+		_ => answers
+			.into_iter()
+			.map(|x| format!("{} {} {} {}", x.name, x.ttl, x.kind, x.data))
+			.collect(),
+	};
+
+	Ok(result)
+}
+
+/// The given resolver, or the first nameserver of resolv.conf
+pub fn server(server: Option<&str>) -> Result<SocketAddr, Error> {
+	match server {
+		Some(server) => parse_server(server),
+		None => {
+			let conf = fs::read_to_string(RESOLV_CONF).map_err(|e| Error::io(RESOLV_CONF, e))?;
+			let server = nameserver(&conf).ok_or_else(|| {
+				Error::invalid(
+					"resolver",
+					format!("no nameserver in {}, use --server", RESOLV_CONF),
+				)
+			})?;
+			parse_server(&server)
+		}
+	}
+}
+
+pub fn lookup(
+	name: &str,
+	query_type: QueryType,
+	server: SocketAddr,
+	timeout: Duration,
+) -> Result<Vec<Answer>, Error> {
+	let mut builder = Builder::new_query(query_id(), true);
+	builder.add_question(name, false, query_type, QueryClass::IN);
+	let request = builder.build().map_err(|_| Error::invalid("name", name))?;
+
+	let response = query(server, &request, timeout)?;
+	answers(&response)
+}
+
+/// Over UDP, and again over TCP if the response is truncated (e.g. large TXT records)
+fn query(server: SocketAddr, request: &[u8], timeout: Duration) -> Result<Vec<u8>, Error> {
+	let failed = |e: std::io::Error| Error::invalid("response", format!("{}: {}", server, e));
+
+	let bind = if server.is_ipv4() {
+		"0.0.0.0:0"
+	} else {
+		"[::]:0"
+	};
+	let socket = UdpSocket::bind(bind).map_err(failed)?;
+	socket.set_read_timeout(Some(timeout)).map_err(failed)?;
+	socket.send_to(request, server).map_err(failed)?;
+
+	let mut response = vec![0u8; 4096];
+	let len = socket.recv(&mut response).map_err(failed)?;
+	response.truncate(len);
+
+	let truncated = Packet::parse(&response)
+		.map(|x| x.header.truncated)
+		.unwrap_or(false);
+	if !truncated {
+		return Ok(response);
+	}
+
+	let mut stream = TcpStream::connect_timeout(&server, timeout).map_err(failed)?;
+	stream.set_read_timeout(Some(timeout)).map_err(failed)?;
+	let mut message = (request.len() as u16).to_be_bytes().to_vec();
+	message.extend(request);
+	stream.write_all(&message).map_err(failed)?;
+
+	let mut len = [0u8; 2];
+	stream.read_exact(&mut len).map_err(failed)?;
+	let mut response = vec![0u8; u16::from_be_bytes(len) as usize];
+	stream.read_exact(&mut response).map_err(failed)?;
+	Ok(response)
+}
+
+fn answers(response: &[u8]) -> Result<Vec<Answer>, Error> {
+	let packet = Packet::parse(response).map_err(|e| Error::invalid("response", e))?;
+	match packet.header.response_code {
+		ResponseCode::NoError => (),
+		ResponseCode::NameError => return Err(Error::Failed("No such domain".to_string())),
+		code => return Err(Error::Failed(format!("Lookup failed: {:?}", code))),
+	}
+
+	let name = |x: String| if x.is_empty() { ".".to_string() } else { x };
+	let answers = packet
+		.answers
+		.iter()
+		.map(|x| {
+			let (kind, data) = match &x.data {
+				RData::A(x) => ("A", x.0.to_string()),
+				RData::AAAA(x) => ("AAAA", x.0.to_string()),
+				RData::CNAME(x) => ("CNAME", name(x.0.to_string())),
+				RData::NS(x) => ("NS", name(x.0.to_string())),
+				RData::MX(x) => (
+					"MX",
+					format!("{} {}", x.preference, name(x.exchange.to_string())),
+				),
+				RData::TXT(x) => (
+					"TXT",
+					x.iter()
+						.map(|x| format!("{:?}", String::from_utf8_lossy(x)))
+						.collect::<Vec<_>>()
+						.join(" "),
+				),
+				RData::PTR(x) => ("PTR", name(x.0.to_string())),
+				RData::SOA(x) => ("SOA", name(x.primary_ns.to_string())),
+				RData::SRV(x) => (
+					"SRV",
+					format!("{} {} {} {}", x.priority, x.weight, x.port, x.target),
+				),
+				RData::Unknown(x) => ("UNKNOWN", hex::encode(x)),
+			};
+			Answer {
+				name: name(x.name.to_string()),
+				kind: kind.to_string(),
+				ttl: x.ttl,
+				data,
+			}
+		})
+		.collect();
+	Ok(answers)
+}
+
+/// The first `nameserver` line of resolv.conf
+fn nameserver(conf: &str) -> Option<String> {
+	conf.lines()
+		.map(|x| x.split(['#', ';']).next().unwrap_or_default())
+		.find_map(|line| {
+			let mut parts = line.split_whitespace();
+			match parts.next() {
+				Some("nameserver") => parts.next().map(Into::into),
+				_ => None,
+			}
+		})
+}
+
+/// `IP` or `IP:port`, IPv6 with a port in brackets, e.g. `[::1]:53`
+fn parse_server(server: &str) -> Result<SocketAddr, Error> {
+	server
+		.parse::<SocketAddr>()
+		.or_else(|_| {
+			// without the zone index, e.g. fe80::1%eth0
+			let ip = server.split('%').next().unwrap_or_default();
+			ip.parse().map(|ip| SocketAddr::new(ip, 53))
+		})
+		.map_err(|_| Error::invalid("resolver", server))
+}
+
+/// Not cryptographically random, only to match the response
+fn query_id() -> u16 {
+	let nanos = std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|x| x.subsec_nanos())
+		.unwrap_or_default();
+	(nanos ^ std::process::id()) as u16
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"dns",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["-t", "mx", "example.com"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["example.com 86400 MX 0 ."]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Custom resolver".to_string(),
+					input: vec!["-s", "1.1.1.1", "example.com"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["example.com 300 A 23.192.228.80"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_answers() {
+		let mut response = vec![
+			0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+		];
+		// question: example.com A IN
+		response.extend(b"\x07example\x03com\x00\x00\x01\x00\x01");
+		// answer: CNAME of the question name, compressed to offset 12
+		response.extend(b"\xc0\x0c\x00\x05\x00\x01\x00\x00\x00\x3c\x00\x06\x03www\xc0\x0c");
+		// answer: A of www.example.com, compressed to the CNAME data at offset 41
+		response.extend(b"\xc0\x29\x00\x01\x00\x01\x00\x00\x01\x2c\x00\x04\x5d\xb8\xd8\x22");
+
+		assert_eq!(
+			answers(&response).unwrap(),
+			vec![
+				Answer {
+					name: "example.com".to_string(),
+					kind: "CNAME".to_string(),
+					ttl: 60,
+					data: "www.example.com".to_string(),
+				},
+				Answer {
+					name: "www.example.com".to_string(),
+					kind: "A".to_string(),
+					ttl: 300,
+					data: "93.184.216.34".to_string(),
+				},
+			]
+		);
+
+		// NXDOMAIN
+		response[3] = 0x83;
+		assert_eq!(answers(&response).unwrap_err().kind(), "failed");
+	}
+
+	#[test]
+	fn test_server() {
+		let conf = "# comment\nsearch local\nnameserver 10.0.0.1 # home\nnameserver 8.8.8.8\n";
+		assert_eq!(nameserver(conf), Some("10.0.0.1".to_string()));
+		assert_eq!(nameserver("search local\n"), None);
+
+		assert_eq!(parse_server("10.0.0.1").unwrap().to_string(), "10.0.0.1:53");
+		assert_eq!(
+			parse_server("[::1]:5353").unwrap().to_string(),
+			"[::1]:5353"
+		);
+		assert_eq!(
+			parse_server("fe80::1%eth0").unwrap().to_string(),
+			"[fe80::1]:53"
+		);
+		assert!(parse_server("resolver").is_err());
+	}
+}
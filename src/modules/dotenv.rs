@@ -0,0 +1,360 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: ".env to JSON, JSON to .env, .env merge".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("env2j")
+				.about("Convert .env to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, env2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2env")
+				.about("Convert JSON to .env")
+				.arg(
+					Arg::with_name("EXPORT")
+						.long("export")
+						.short("e")
+						.help("Add export prefix")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2env),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("env_merge")
+				.about(".env merge (later overrides earlier)")
+				.arg(
+					Arg::with_name("EXPORT")
+						.long("export")
+						.short("e")
+						.help("Add export prefix")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("INPUTS")
+						.value_name("INPUT")
+						.required(true)
+						.multiple(true)
+						.index(1),
+				),
+			f: |matches| base::generate(matches, env_merge),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the .env commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+	/// Add export prefix
+	pub export: bool,
+	/// .env files content to merge, later overrides earlier
+	pub inputs: Vec<String>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+			export: matches.is_present("EXPORT"),
+			inputs: matches
+				.values_of("INPUTS")
+				.map(|x| x.map(String::from).collect())
+				.unwrap_or_default(),
+		})
+	}
+}
+
+/// Convert .env to JSON
+pub fn env2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = Value::Object(
+		parse(base::text(input)?)?
+			.into_iter()
+			.map(|(k, v)| (k, Value::String(v)))
+			.collect(),
+	);
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to .env
+pub fn j2env(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	let entries = map
+		.into_iter()
+		.map(|(k, v)| {
+			let v = match v {
+				Value::String(v) => v,
+				Value::Null => "".to_string(),
+				v => json::to_string(&v)?,
+			};
+			Ok((k, v))
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	Ok(to_lines(entries, options.export).join("\n").into_bytes())
+}
+
+/// .env merge (later overrides earlier), the files content are `inputs`
+pub fn env_merge(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	if options.inputs.is_empty() {
+		return Err(Error::input(""));
+	}
+
+	let mut map = Map::new();
+	for input in &options.inputs {
+		for (k, v) in parse(input)? {
+			map.insert(k, Value::String(v));
+		}
+	}
+
+	let entries = map
+		.into_iter()
+		.map(|(k, v)| (k, v.as_str().unwrap_or_default().to_string()))
+		.collect();
+
+	Ok(to_lines(entries, options.export).join("\n").into_bytes())
+}
+
+/// `KEY=value`, optional `export` prefix, # comments,
+/// double quoted values support escapes and may span lines, single quoted values are literal
+fn parse(input: &str) -> Result<Vec<(String, String)>, Error> {
+	let mut result = vec![];
+	let mut rest = input;
+
+	let line_no = |rest: &str| input[..input.len() - rest.len()].matches('\n').count() + 1;
+	let skip_line = |rest: &str| rest.find('\n').map(|x| x + 1).unwrap_or_else(|| rest.len());
+
+	loop {
+		rest = rest.trim_start();
+		if rest.is_empty() {
+			break;
+		}
+		if rest.starts_with('#') {
+			rest = &rest[skip_line(rest)..];
+			continue;
+		}
+		if rest.starts_with("export ") {
+			rest = rest["export ".len()..].trim_start();
+		}
+
+		let line = &rest[..skip_line(rest)];
+		let index = line
+			.find('=')
+			.ok_or_else(|| Error::invalid(".env", format!("line {}", line_no(rest))))?;
+		let key = line[..index].trim();
+		if key.is_empty() || key.contains(char::is_whitespace) {
+			return Err(Error::invalid(".env", format!("line {}", line_no(rest))));
+		}
+		let key = key.to_string();
+		rest = rest[index + 1..].trim_start_matches(&[' ', '\t'][..]);
+
+		let value = match rest.chars().next() {
+			Some(quote) if quote == '"' || quote == '\'' => {
+				let (value, len) = parse_quoted(&rest[1..], quote).ok_or_else(|| {
+					Error::invalid(
+						".env",
+						format!("unterminated quote at line {}", line_no(rest)),
+					)
+				})?;
+				rest = &rest[1 + len..];
+				value
+			}
+			_ => {
+				let value = &rest[..skip_line(rest)];
+				// inline comment
+				let value = match value.find(" #") {
+					Some(index) => &value[..index],
+					None => value,
+				};
+				value.trim().to_string()
+			}
+		};
+		result.push((key, value));
+
+		rest = &rest[skip_line(rest)..];
+	}
+
+	Ok(result)
+}
+
+/// Returns the value and the length including the closing quote
+fn parse_quoted(input: &str, quote: char) -> Option<(String, usize)> {
+	let mut result = String::new();
+	let mut chars = input.char_indices();
+	while let Some((i, c)) = chars.next() {
+		match c {
+			c if c == quote => return Some((result, i + 1)),
+			'\\' if quote == '"' => match chars.next()?.1 {
+				'n' => result.push('\n'),
+				'r' => result.push('\r'),
+				't' => result.push('\t'),
+				c => result.push(c),
+			},
+			c => result.push(c),
+		}
+	}
+	None
+}
+
+fn to_lines(entries: Vec<(String, String)>, export: bool) -> Vec<String> {
+	let prefix = if export { "export " } else { "" };
+	entries
+		.into_iter()
+		.map(|(k, v)| format!("{}{}={}", prefix, k, quote(&v)))
+		.collect()
+}
+
+fn quote(value: &str) -> String {
+	let need_quote = value
+		.chars()
+		.any(|c| c.is_whitespace() || "#\"'\\$`".contains(c));
+	if !need_quote {
+		return value.to_string();
+	}
+	let mut result = String::from("\"");
+	for c in value.chars() {
+		match c {
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			'"' | '\\' => {
+				result.push('\\');
+				result.push(c);
+			}
+			c => result.push(c),
+		}
+	}
+	result.push('"');
+	result
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"env2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-m",
+							"'# comment\nexport HOST=localhost\nPORT=8080 # inline comment\nNAME=\"a \\\"b\\\"\\nc\"\nRAW='$HOME\\n'\nEMPTY='",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							r#"{"HOST":"localhost","PORT":"8080","NAME":"a \"b\"\nc","RAW":"$HOME\\n","EMPTY":""}"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "'HOST=localhost\nKEY=\"multi\nline\"'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"HOST":"localhost","KEY":"multi\nline"}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"j2env",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![r#"'{"HOST":"localhost","PORT":8080,"NAME":"a \"b\""}'"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["HOST=localhost", "PORT=8080", r#"NAME="a \"b\"""#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"env_merge",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'HOST=localhost\nPORT=8080'", "'PORT=9090\nDEBUG=true'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["HOST=localhost", "PORT=9090", "DEBUG=true"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -1,8 +1,7 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
-use lazy_static::lazy_static;
-use std::collections::HashMap;
+use clap::{Arg, ArgMatches};
 
 mod p256;
 mod p384;
@@ -17,116 +16,50 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	}
 }
 
+type GkF = fn(compress: bool) -> Result<(Vec<u8>, Vec<u8>), Error>;
+
+type SignF = fn(
+	secret_key: Vec<u8>,
+	message: Vec<u8>,
+	sig_form: SignatureFormEnum,
+) -> Result<Vec<u8>, Error>;
+
+type VerifyF = fn(
+	public_key: Vec<u8>,
+	sig: Vec<u8>,
+	message: Vec<u8>,
+	sig_form: SignatureFormEnum,
+) -> Result<(), Error>;
+
+type PkF = fn(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, Error>;
+
 struct Curve {
-	name: &'static str,
-	help: &'static str,
-	gk_f: fn(compress: bool) -> Result<(Vec<u8>, Vec<u8>), String>,
-	sign_f: fn(
-		secret_key: Vec<u8>,
-		message: Vec<u8>,
-		sig_form: SignatureFormEnum,
-	) -> Result<Vec<u8>, String>,
-	verify_f: fn(
-		public_key: Vec<u8>,
-		sig: Vec<u8>,
-		message: Vec<u8>,
-		sig_form: SignatureFormEnum,
-	) -> Result<(), String>,
-	pk_f: fn(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, String>,
+	gk_f: GkF,
+	sign_f: SignF,
+	verify_f: VerifyF,
+	pk_f: PkF,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub enum SignatureFormEnum {
 	Der,
+	#[default]
 	Fixed,
 }
 
-struct SignatureForm {
-	name: &'static str,
-	help: &'static str,
-	e: SignatureFormEnum,
-}
-
-lazy_static! {
-	static ref RAW_CURVES: Vec<Curve> = vec![
-		Curve {
-			name: "secp256k1",
-			help: "Secp256k1",
-			gk_f: secp256k1::ec_gk_secp256k1,
-			sign_f: secp256k1::ec_sign_secp256k1,
-			verify_f: secp256k1::ec_verify_secp256k1,
-			pk_f: secp256k1::ec_pk_secp256k1,
-		},
-		Curve {
-			name: "p256",
-			help: "NIST P-256",
-			gk_f: p256::ec_gk_p256,
-			sign_f: p256::ec_sign_p256,
-			verify_f: p256::ec_verify_p256,
-			pk_f: p256::ec_pk_p256,
-		},
-		Curve {
-			name: "p384",
-			help: "NIST P-384",
-			gk_f: p384::ec_gk_p384,
-			sign_f: p384::ec_sign_p384,
-			verify_f: p384::ec_verify_p384,
-			pk_f: p384::ec_pk_p384,
-		},
-		Curve {
-			name: "sm2",
-			help: "Chinese National Standard SM2",
-			gk_f: sm2::ec_gk_sm2,
-			sign_f: sm2::ec_sign_sm2,
-			verify_f: sm2::ec_verify_sm2,
-			pk_f: sm2::ec_pk_sm2,
-		},
-	];
-	static ref RAW_SIGNATURE_FORMS: Vec<SignatureForm> = vec![
-		SignatureForm {
-			name: "der",
-			help: "ASN1 DER",
-			e: SignatureFormEnum::Der,
-		},
-		SignatureForm {
-			name: "fixed",
-			help: "Fixed",
-			e: SignatureFormEnum::Fixed,
-		},
-	];
-	static ref CURVES: HashMap<&'static str, &'static Curve> =
-		RAW_CURVES.iter().map(|x| (x.name, x)).collect();
-	static ref CURVE_NAMES: Vec<&'static str> = RAW_CURVES.iter().map(|x| x.name).collect();
-	static ref CURVE_HELP: String = "Curve\n".to_string()
-		+ &RAW_CURVES
-			.iter()
-			.map(|a| { format!("{}: {}", a.name, a.help) })
-			.collect::<Vec<String>>()
-			.join("\n")
-		+ "\n";
-	static ref SIGNATURE_FORMS: HashMap<&'static str, &'static SignatureForm> =
-		RAW_SIGNATURE_FORMS.iter().map(|x| (x.name, x)).collect();
-	static ref SIGNATURE_FORM_NAMES: Vec<&'static str> =
-		RAW_SIGNATURE_FORMS.iter().map(|x| x.name).collect();
-	static ref SIGNATURE_FORM_HELP: String = "Signature form\n".to_string()
-		+ &RAW_SIGNATURE_FORMS
-			.iter()
-			.map(|a| { format!("{}: {}", a.name, a.help) })
-			.collect::<Vec<String>>()
-			.join("\n")
-		+ "\n";
-}
+// CURVES, CURVE_NAMES, CURVE_HELP, SIGNATURE_FORMS, SIGNATURE_FORM_NAMES and SIGNATURE_FORM_HELP
+include!(concat!(env!("OUT_DIR"), "/ecdsa_registries.rs"));
 
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("ec_gk")
+			app: base::sub_command("ec_gk")
 				.about("Elliptic-curve generate key pair (Secret key, Public key)")
 				.arg(
 					Arg::with_name("CURVE")
 						.long("curve")
 						.short("c")
-						.help(&CURVE_HELP)
+						.help(CURVE_HELP)
 						.takes_value(true)
 						.possible_values(&CURVE_NAMES)
 						.required(true),
@@ -138,22 +71,19 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.help("Compress")
 						.required(false),
 				),
-			f: ec_gk,
+			f: |matches| base::generate(matches, ec_gk),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("ec_sign")
+			app: base::sub_command("ec_sign")
 				.about("Elliptic-curve sign")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Message (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Message (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("CURVE")
 						.long("curve")
 						.short("c")
-						.help(&CURVE_HELP)
+						.help(CURVE_HELP)
 						.takes_value(true)
 						.possible_values(&CURVE_NAMES)
 						.required(true),
@@ -164,34 +94,33 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("s")
 						.help("Secret key (Private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				)
 				.arg(
 					Arg::with_name("SIGNATURE_FORM")
 						.long("sig-form")
-						.short("f")
-						.help(&SIGNATURE_FORM_HELP)
+						.short("F")
+						.help(SIGNATURE_FORM_HELP)
 						.takes_value(true)
 						.possible_values(&SIGNATURE_FORM_NAMES)
 						.default_value("fixed")
 						.required(false),
 				),
-			f: ec_sign,
+			f: |matches| base::convert(matches, ec_sign),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("ec_verify")
+			app: base::sub_command("ec_verify")
 				.about("Elliptic-curve verify")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Message (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Message (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("CURVE")
 						.long("curve")
 						.short("c")
-						.help(&CURVE_HELP)
+						.help(CURVE_HELP)
 						.takes_value(true)
 						.possible_values(&CURVE_NAMES)
 						.required(true),
@@ -215,23 +144,24 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 				.arg(
 					Arg::with_name("SIGNATURE_FORM")
 						.long("sig-form")
-						.short("f")
-						.help(&SIGNATURE_FORM_HELP)
+						.short("F")
+						.help(SIGNATURE_FORM_HELP)
 						.takes_value(true)
 						.possible_values(&SIGNATURE_FORM_NAMES)
 						.default_value("fixed")
 						.required(false),
 				),
-			f: ec_verify,
+			f: |matches| base::convert(matches, ec_verify),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("ec_pk")
+			app: base::sub_command("ec_pk")
 				.about("Elliptic-curve calculate public key")
 				.arg(
 					Arg::with_name("CURVE")
 						.long("curve")
 						.short("c")
-						.help(&CURVE_HELP)
+						.help(CURVE_HELP)
 						.takes_value(true)
 						.possible_values(&CURVE_NAMES)
 						.required(true),
@@ -242,6 +172,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("s")
 						.help("Secret key (Private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(false),
 				)
 				.arg(
@@ -251,109 +183,131 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.help("Compress")
 						.required(false),
 				),
-			f: ec_pk,
+			f: |matches| base::generate(matches, ec_pk),
+			stream: None,
 		},
 	]
 }
 
-fn ec_gk(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let curve = matches.value_of("CURVE").ok_or("Invalid curve")?;
+/// Options of the elliptic-curve commands, the keys and the signature are read where needed
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub curve: String,
+	pub compress: bool,
+	pub secret_key: Vec<u8>,
+	pub public_key: Vec<u8>,
+	pub signature: Vec<u8>,
+	pub signature_form: SignatureFormEnum,
+}
 
-	let curve = CURVES.get(curve).ok_or("Invalid curve")?;
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let curve = matches
+			.value_of("CURVE")
+			.ok_or(Error::invalid("curve", ""))?
+			.to_string();
+
+		let hex_arg = |name: &str, what: &str| -> Result<Vec<u8>, Error> {
+			match matches.value_of(name) {
+				Some(value) => Ok(value
+					.parse::<Hex>()
+					.map_err(|_| Error::invalid(what, ""))?
+					.into()),
+				None => Ok(vec![]),
+			}
+		};
+
+		let signature_form = match matches.value_of("SIGNATURE_FORM") {
+			Some(sig_form) => *SIGNATURE_FORMS
+				.get(sig_form)
+				.ok_or(Error::invalid("signature form", ""))?,
+			None => SignatureFormEnum::default(),
+		};
+
+		Ok(Options {
+			curve,
+			compress: matches.is_present("COMPRESS"),
+			secret_key: hex_arg("SECRET_KEY", "secret key")?,
+			public_key: hex_arg("PUBLIC_KEY", "public key")?,
+			signature: hex_arg("SIGNATURE", "signature")?,
+			signature_form,
+		})
+	}
+}
 
-	let compress = matches.is_present("COMPRESS");
+/// Elliptic-curve generate key pair (Secret key, Public key), there is no input
+pub fn ec_gk(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let curve = get_curve(&options.curve)?;
 
-	let (private_key, public_key) = (curve.gk_f)(compress)?;
+	let (private_key, public_key) = (curve.gk_f)(options.compress)?;
 
 	let (private_key, public_key): (String, String) =
 		(Hex::from(private_key).into(), Hex::from(public_key).into());
 
 	let result = format!("({}, {})", private_key, public_key);
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn ec_sign(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let curve = matches.value_of("CURVE").ok_or("Invalid curve")?;
+/// Elliptic-curve sign, the input is the message (hex)
+pub fn ec_sign(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let curve = get_curve(&options.curve)?;
 
-	let curve = CURVES.get(curve).ok_or("Invalid curve")?;
-
-	let secret_key = matches.value_of("SECRET_KEY").ok_or("Invalid secret key")?;
-	let secret_key: Vec<u8> = secret_key
+	let input: Vec<u8> = base::text(input)?
 		.parse::<Hex>()
-		.map_err(|_| "Invalid secret key")?
+		.map_err(|_| Error::input(""))?
 		.into();
 
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
-
-	let sig_form = matches
-		.value_of("SIGNATURE_FORM")
-		.ok_or("Invalid signature form")?;
-	let sig_form = SIGNATURE_FORMS
-		.get(sig_form)
-		.ok_or("Invalid signature form")?
-		.e
-		.clone();
+	let sig = (curve.sign_f)(options.secret_key.clone(), input, options.signature_form)?;
 
-	let sig = (curve.sign_f)(secret_key, input, sig_form)?;
+	let result: String = Hex::from(sig).into();
 
-	let result = Hex::from(sig).into();
-
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn ec_verify(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let curve = matches.value_of("CURVE").ok_or("Invalid curve")?;
-
-	let curve = CURVES.get(curve).ok_or("Invalid curve")?;
+/// Elliptic-curve verify, the input is the message (hex)
+pub fn ec_verify(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let curve = get_curve(&options.curve)?;
 
-	let public_key = matches.value_of("PUBLIC_KEY").ok_or("Invalid public key")?;
-	let public_key: Vec<u8> = public_key
+	let input: Vec<u8> = base::text(input)?
 		.parse::<Hex>()
-		.map_err(|_| "Invalid secret key")?
+		.map_err(|_| Error::input(""))?
 		.into();
 
-	let sig_form = matches
-		.value_of("SIGNATURE_FORM")
-		.ok_or("Invalid signature form")?;
-	let sig_form = SIGNATURE_FORMS
-		.get(sig_form)
-		.ok_or("Invalid signature form")?
-		.e
-		.clone();
-
-	let sig = matches.value_of("SIGNATURE").ok_or("Invalid signature")?;
-	let sig: Vec<u8> = sig.parse::<Hex>().map_err(|_| "Invalid signature")?.into();
-
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
-
-	(curve.verify_f)(public_key, sig, input, sig_form)?;
+	(curve.verify_f)(
+		options.public_key.clone(),
+		options.signature.clone(),
+		input,
+		options.signature_form,
+	)?;
 
 	let result = "true".to_string();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn ec_pk(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let curve = matches.value_of("CURVE").ok_or("Invalid curve")?;
-
-	let curve = CURVES.get(curve).ok_or("Invalid curve")?;
+/// Elliptic-curve calculate public key, there is no input
+pub fn ec_pk(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let curve = get_curve(&options.curve)?;
 
-	let secret_key = matches.value_of("SECRET_KEY").ok_or("Invalid secret key")?;
-	let secret_key: Vec<u8> = secret_key
-		.parse::<Hex>()
-		.map_err(|_| "Invalid secret key")?
-		.into();
+	let public_key = (curve.pk_f)(options.secret_key.clone(), options.compress)?;
 
-	let compress = matches.is_present("COMPRESS");
+	let result: String = Hex::from(public_key).into();
 
-	let public_key = (curve.pk_f)(secret_key, compress)?;
-
-	let result = Hex::from(public_key).into();
+	Ok(result.into_bytes())
+}
 
-	Ok(vec![result])
+fn get_curve(curve: &str) -> Result<&'static Curve, Error> {
+	CURVES.get(curve).ok_or_else(|| {
+		Error::invalid(
+			"curve",
+			format!(
+				"{}{}",
+				curve,
+				base::did_you_mean(curve, CURVES.keys().cloned())
+			),
+		)
+	})
 }
 
 mod cases {
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::modules::ecdsa::SignatureFormEnum;
 use crate::modules::Case;
 use linked_hash_map::LinkedHashMap;
@@ -8,17 +9,17 @@ use ring::signature::{
 };
 use untrusted::Input;
 
-pub fn ec_gk_p256(compress: bool) -> Result<(Vec<u8>, Vec<u8>), String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_gk_p256(compress: bool) -> Result<(Vec<u8>, Vec<u8>), Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let secret_key =
 		EcdsaKeyPair::generate_private_key(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
-			.map_err(|_| "")?;
+			.map_err(|_| Error::failed(""))?;
 	let pair =
 		EcdsaKeyPair::from_private_key(&ECDSA_P256_SHA256_FIXED_SIGNING, secret_key.as_ref())
-			.map_err(|_| "Invalid secret key")?;
+			.map_err(|_| Error::invalid("secret key", ""))?;
 
 	let public_key = pair.public_key();
 	let public_key = public_key.as_ref().to_vec();
@@ -30,17 +31,17 @@ pub fn ec_sign_p256(
 	secret_key: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<Vec<u8>, String> {
+) -> Result<Vec<u8>, Error> {
 	let algo = match sig_form {
 		SignatureFormEnum::Fixed => &ECDSA_P256_SHA256_FIXED_SIGNING,
 		SignatureFormEnum::Der => &ECDSA_P256_SHA256_ASN1_SIGNING,
 	};
 
-	let pair = EcdsaKeyPair::from_private_key(&algo, secret_key.as_ref())
-		.map_err(|_| "Invalid secret key")?;
+	let pair = EcdsaKeyPair::from_private_key(algo, secret_key.as_ref())
+		.map_err(|_| Error::invalid("secret key", ""))?;
 	let sig = pair
 		.sign(&SystemRandom::new(), &message)
-		.map_err(|_| "Failed to sign")?;
+		.map_err(|_| Error::failed("Failed to sign"))?;
 
 	Ok(sig.as_ref().to_vec())
 }
@@ -50,31 +51,30 @@ pub fn ec_verify_p256(
 	sig: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<(), String> {
+) -> Result<(), Error> {
 	let algo = match sig_form {
 		SignatureFormEnum::Fixed => &ECDSA_P256_SHA256_FIXED,
 		SignatureFormEnum::Der => &ECDSA_P256_SHA256_ASN1,
 	};
 
-	let result = algo
-		.verify(
-			Input::from(&public_key),
-			Input::from(&message),
-			Input::from(&sig),
-		)
-		.map_err(|e| format!("Invalid signature: {}", e))?;
+	algo.verify(
+		Input::from(&public_key),
+		Input::from(&message),
+		Input::from(&sig),
+	)
+	.map_err(|e| Error::invalid("signature", e))?;
 
-	Ok(result)
+	Ok(())
 }
 
-pub fn ec_pk_p256(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_pk_p256(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let pair =
 		EcdsaKeyPair::from_private_key(&ECDSA_P256_SHA256_FIXED_SIGNING, secret_key.as_ref())
-			.map_err(|_| "Invalid secret key")?;
+			.map_err(|_| Error::invalid("secret key", ""))?;
 
 	let public_key = pair.public_key();
 	let public_key = public_key.as_ref().to_vec();
@@ -107,7 +107,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "P-256 DER signature form".to_string(),
-				 input: vec!["-c", "p256", "-s", "0xf0b3b41add2d79932cdf2a4ba083c16e72647ddcd8718e2187d1567ed5a611c9", "-f", "der", "0x616263"].into_iter().map(Into::into).collect(),
+				 input: vec!["-c", "p256", "-s", "0xf0b3b41add2d79932cdf2a4ba083c16e72647ddcd8718e2187d1567ed5a611c9", "-F", "der", "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["0x3045022100ed94d4f7022cc2335ef5e34432fed541932e2c2b0c1430e2d51c06e66320302b022002cc2e13e6f5bde7f079a026399e2a6012c5ce4ad2babbe8e1e3444010b72d78"].into_iter().map(Into::into).collect(),
 				 is_example: false,
 				 is_test: false,
@@ -128,7 +128,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "P-256 DER signature form".to_string(),
-				 input: vec!["-c", "p256", "-p", "0x045c79019e39199effa07576de6e3745fa1dba402854314aef05790e9e827cf7782ac5feb26e28039f94d73078c57b5f29be14ef9da57cb53e16e2839bdbbee630", "-f", "der", "-S",
+				 input: vec!["-c", "p256", "-p", "0x045c79019e39199effa07576de6e3745fa1dba402854314aef05790e9e827cf7782ac5feb26e28039f94d73078c57b5f29be14ef9da57cb53e16e2839bdbbee630", "-F", "der", "-S",
 				             "0x3045022100ed94d4f7022cc2335ef5e34432fed541932e2c2b0c1430e2d51c06e66320302b022002cc2e13e6f5bde7f079a026399e2a6012c5ce4ad2babbe8e1e3444010b72d78",
 				             "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["true"].into_iter().map(Into::into).collect(),
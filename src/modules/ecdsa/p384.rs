@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::modules::ecdsa::SignatureFormEnum;
 use crate::modules::Case;
 use linked_hash_map::LinkedHashMap;
@@ -8,17 +9,17 @@ use ring::signature::{
 };
 use untrusted::Input;
 
-pub fn ec_gk_p384(compress: bool) -> Result<(Vec<u8>, Vec<u8>), String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_gk_p384(compress: bool) -> Result<(Vec<u8>, Vec<u8>), Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let secret_key =
 		EcdsaKeyPair::generate_private_key(&ECDSA_P384_SHA384_FIXED_SIGNING, &SystemRandom::new())
-			.map_err(|_| "")?;
+			.map_err(|_| Error::failed(""))?;
 	let pair =
 		EcdsaKeyPair::from_private_key(&ECDSA_P384_SHA384_FIXED_SIGNING, secret_key.as_ref())
-			.map_err(|_| "Invalid secret key")?;
+			.map_err(|_| Error::invalid("secret key", ""))?;
 
 	let public_key = pair.public_key();
 	let public_key = public_key.as_ref().to_vec();
@@ -30,17 +31,17 @@ pub fn ec_sign_p384(
 	secret_key: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<Vec<u8>, String> {
+) -> Result<Vec<u8>, Error> {
 	let algo = match sig_form {
 		SignatureFormEnum::Fixed => &ECDSA_P384_SHA384_FIXED_SIGNING,
 		SignatureFormEnum::Der => &ECDSA_P384_SHA384_ASN1_SIGNING,
 	};
 
-	let pair = EcdsaKeyPair::from_private_key(&algo, secret_key.as_ref())
-		.map_err(|_| "Invalid secret key")?;
+	let pair = EcdsaKeyPair::from_private_key(algo, secret_key.as_ref())
+		.map_err(|_| Error::invalid("secret key", ""))?;
 	let sig = pair
 		.sign(&SystemRandom::new(), &message)
-		.map_err(|_| "Failed to sign")?;
+		.map_err(|_| Error::failed("Failed to sign"))?;
 
 	Ok(sig.as_ref().to_vec())
 }
@@ -50,31 +51,30 @@ pub fn ec_verify_p384(
 	sig: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<(), String> {
+) -> Result<(), Error> {
 	let algo = match sig_form {
 		SignatureFormEnum::Fixed => &ECDSA_P384_SHA384_FIXED,
 		SignatureFormEnum::Der => &ECDSA_P384_SHA384_ASN1,
 	};
 
-	let result = algo
-		.verify(
-			Input::from(&public_key),
-			Input::from(&message),
-			Input::from(&sig),
-		)
-		.map_err(|e| format!("Invalid signature: {}", e))?;
+	algo.verify(
+		Input::from(&public_key),
+		Input::from(&message),
+		Input::from(&sig),
+	)
+	.map_err(|e| Error::invalid("signature", e))?;
 
-	Ok(result)
+	Ok(())
 }
 
-pub fn ec_pk_p384(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_pk_p384(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let pair =
 		EcdsaKeyPair::from_private_key(&ECDSA_P384_SHA384_FIXED_SIGNING, secret_key.as_ref())
-			.map_err(|_| "Invalid secret key")?;
+			.map_err(|_| Error::invalid("secret key", ""))?;
 
 	let public_key = pair.public_key();
 	let public_key = public_key.as_ref().to_vec();
@@ -107,7 +107,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "P-384 DER signature form".to_string(),
-				 input: vec!["-c", "p384", "-s", "0xfbc89e8fae9340747f162330345f7cfac7387a2049f6bedb55f7a30faf8b1d24da9b1e618db7b215daa1c7b0fd54858f", "-f", "der", "0x616263"].into_iter().map(Into::into).collect(),
+				 input: vec!["-c", "p384", "-s", "0xfbc89e8fae9340747f162330345f7cfac7387a2049f6bedb55f7a30faf8b1d24da9b1e618db7b215daa1c7b0fd54858f", "-F", "der", "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["0x3065023100e48b9cd154ecd8dfd138f2e3c5d79af62b3cdc413e52565822edcc96786b03d8e996f132cf793b17c267dc177a5e6525023043dd0485f762b48e3a4a9daeeef57ceff2cf84da6a00b6a65293ee7233efe392ba4514a475476815dddfbbb7ea9e269c"].into_iter().map(Into::into).collect(),
 				 is_example: false,
 				 is_test: false,
@@ -128,7 +128,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "P-384 DER signature form".to_string(),
-				 input: vec!["-c", "p384", "-p", "0x044978c6c7be1a5c5194983a945d2d8c81ae4b421dd89d12c6dd1756d2387fa2601993657eeb93d289a57625a70c2830db5f06f988a3e4549e26e8b6d27c7f1e6e8949d6ce5bf3f88a0f5eebaa14499d4379bc81cca6e9ff17d18b8efb370fffe3", "-f", "der", "-S",
+				 input: vec!["-c", "p384", "-p", "0x044978c6c7be1a5c5194983a945d2d8c81ae4b421dd89d12c6dd1756d2387fa2601993657eeb93d289a57625a70c2830db5f06f988a3e4549e26e8b6d27c7f1e6e8949d6ce5bf3f88a0f5eebaa14499d4379bc81cca6e9ff17d18b8efb370fffe3", "-F", "der", "-S",
 				             "0x3065023100e48b9cd154ecd8dfd138f2e3c5d79af62b3cdc413e52565822edcc96786b03d8e996f132cf793b17c267dc177a5e6525023043dd0485f762b48e3a4a9daeeef57ceff2cf84da6a00b6a65293ee7233efe392ba4514a475476815dddfbbb7ea9e269c",
 				             "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["true"].into_iter().map(Into::into).collect(),
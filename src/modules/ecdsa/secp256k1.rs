@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::modules::base::Hex;
 use crate::modules::ecdsa::SignatureFormEnum;
 use crate::modules::Case;
@@ -9,13 +10,13 @@ use signatory::public_key::PublicKeyed;
 use signatory::signature::{Signature, Signer, Verifier};
 use signatory_secp256k1::{EcdsaSigner, EcdsaVerifier, PublicKey, SecretKey};
 
-pub fn ec_gk_secp256k1(compress: bool) -> Result<(Vec<u8>, Vec<u8>), String> {
+pub fn ec_gk_secp256k1(compress: bool) -> Result<(Vec<u8>, Vec<u8>), Error> {
 	let (secret_key, public_key) = Secp256k1::new().generate_keypair(&mut thread_rng());
 
 	let secret_key: Vec<u8> = secret_key
 		.to_string()
 		.parse::<Hex>()
-		.map_err(|_| "Invalid secret key")?
+		.map_err(|_| Error::invalid("secret key", ""))?
 		.into();
 
 	let public_key = match compress {
@@ -29,9 +30,9 @@ pub fn ec_sign_secp256k1(
 	secret_key: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<Vec<u8>, String> {
+) -> Result<Vec<u8>, Error> {
 	let secret_key =
-		SecretKey::from_bytes(secret_key).map_err(|e| format!("Invalid secret key: {}", e))?;
+		SecretKey::from_bytes(secret_key).map_err(|e| Error::invalid("secret key", e))?;
 	let signer = EcdsaSigner::from(&secret_key);
 
 	let signature = match sig_form {
@@ -53,35 +54,34 @@ pub fn ec_verify_secp256k1(
 	sig: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<(), String> {
-	let public_key = PublicKey::from_bytes(public_key).ok_or("Invalid public key")?;
+) -> Result<(), Error> {
+	let public_key = PublicKey::from_bytes(public_key).ok_or(Error::invalid("public key", ""))?;
 	let verifier = EcdsaVerifier::from(&public_key);
 
 	let result = match sig_form {
 		SignatureFormEnum::Fixed => {
 			let sig =
-				FixedSignature::from_bytes(sig).map_err(|e| format!("Invalid signature: {}", e))?;
+				FixedSignature::from_bytes(sig).map_err(|e| Error::invalid("signature", e))?;
 			verifier
 				.verify(&message, &sig)
-				.map_err(|e| format!("{}", e))
+				.map_err(|e| Error::failed(format!("{}", e)))
 		}
 		SignatureFormEnum::Der => {
-			let sig =
-				Asn1Signature::from_bytes(sig).map_err(|e| format!("Invalid signature: {}", e))?;
+			let sig = Asn1Signature::from_bytes(sig).map_err(|e| Error::invalid("signature", e))?;
 			verifier
 				.verify(&message, &sig)
-				.map_err(|e| format!("{}", e))
+				.map_err(|e| Error::failed(format!("{}", e)))
 		}
 	};
 	result
 }
 
-pub fn ec_pk_secp256k1(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, String> {
+pub fn ec_pk_secp256k1(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, Error> {
 	let secret_key =
-		SecretKey::from_bytes(secret_key).map_err(|e| format!("Invalid secret key: {}", e))?;
+		SecretKey::from_bytes(secret_key).map_err(|e| Error::invalid("secret key", e))?;
 	let signer = EcdsaSigner::from(&secret_key);
 
-	let public_key: PublicKey = signer.public_key().map_err(|_| "Failed")?;
+	let public_key: PublicKey = signer.public_key().map_err(|_| Error::failed("Failed"))?;
 
 	let public_key = public_key.as_bytes();
 
@@ -122,7 +122,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "Secp256k1 DER signature form".to_string(),
-				 input: vec!["-c", "secp256k1", "-s", "0x9cb4f775e9b67118242cea15285555c287a7e3d2f86ba238c1fe87284b898e9a", "-f", "der", "0x616263"].into_iter().map(Into::into).collect(),
+				 input: vec!["-c", "secp256k1", "-s", "0x9cb4f775e9b67118242cea15285555c287a7e3d2f86ba238c1fe87284b898e9a", "-F", "der", "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["0x304402207c77b65a27984b0e124a0ae2eec6bbf2b338a5c999b943abda576108f92e953602204b0b983da055493c87fd138fe5673992b2a48ef85d9ad30c98fc1afcc5fc7bc0"].into_iter().map(Into::into).collect(),
 				 is_example: false,
 				 is_test: true,
@@ -143,7 +143,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
 			 },
 			 Case {
 				 desc: "Secp256k1 DER signature form".to_string(),
-				 input: vec!["-c", "secp256k1", "-p", "0x03391aa7238b79e1aad1e038c95306171a8ac7499357dc99586f96c5f3b9618d60", "-f", "der", "-S",
+				 input: vec!["-c", "secp256k1", "-p", "0x03391aa7238b79e1aad1e038c95306171a8ac7499357dc99586f96c5f3b9618d60", "-F", "der", "-S",
 				             "0x304402207c77b65a27984b0e124a0ae2eec6bbf2b338a5c999b943abda576108f92e953602204b0b983da055493c87fd138fe5673992b2a48ef85d9ad30c98fc1afcc5fc7bc0",
 				             "0x616263"].into_iter().map(Into::into).collect(),
 				 output: vec!["true"].into_iter().map(Into::into).collect(),
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::modules::ecdsa::SignatureFormEnum;
 use crate::modules::Case;
 use linked_hash_map::LinkedHashMap;
@@ -8,9 +9,9 @@ use yogcrypt::basic::field::field_p::FieldElement;
 use yogcrypt::sm2;
 use yogcrypt::sm2::{PubKey, SecKey, Signature};
 
-pub fn ec_gk_sm2(compress: bool) -> Result<(Vec<u8>, Vec<u8>), String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_gk_sm2(compress: bool) -> Result<(Vec<u8>, Vec<u8>), Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let secret_key = sm2::get_sec_key();
@@ -26,9 +27,9 @@ pub fn ec_sign_sm2(
 	secret_key: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<Vec<u8>, String> {
+) -> Result<Vec<u8>, Error> {
 	if let SignatureFormEnum::Der = sig_form {
-		return Err("DER form is not supported".to_string());
+		return Err(Error::failed("DER form is not supported"));
 	}
 
 	let secret_key = vec_to_secret_key(secret_key)?;
@@ -46,9 +47,9 @@ pub fn ec_verify_sm2(
 	sig: Vec<u8>,
 	message: Vec<u8>,
 	sig_form: SignatureFormEnum,
-) -> Result<(), String> {
+) -> Result<(), Error> {
 	if let SignatureFormEnum::Der = sig_form {
-		return Err("DER form is not supported".to_string());
+		return Err(Error::failed("DER form is not supported"));
 	}
 
 	let public_key = vec_to_public_key(public_key)?;
@@ -58,13 +59,13 @@ pub fn ec_verify_sm2(
 
 	match verified {
 		true => Ok(()),
-		false => Err("Invalid signature".to_string()),
+		false => Err(Error::invalid("signature", "")),
 	}
 }
 
-pub fn ec_pk_sm2(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, String> {
-	if compress == true {
-		return Err("Compress is not supported".to_string());
+pub fn ec_pk_sm2(secret_key: Vec<u8>, compress: bool) -> Result<Vec<u8>, Error> {
+	if compress {
+		return Err(Error::failed("Compress is not supported"));
 	}
 
 	let secret_key = vec_to_secret_key(secret_key)?;
@@ -79,8 +80,7 @@ fn secret_key_to_vec(secret_key: SecKey) -> Vec<u8> {
 		.value
 		.iter()
 		.rev()
-		.map(|x| x.to_be_bytes().to_vec())
-		.flatten()
+		.flat_map(|x| x.to_be_bytes().to_vec())
 		.collect();
 	result
 }
@@ -94,8 +94,7 @@ fn public_key_to_vec(public_key: PubKey) -> Vec<u8> {
 				.value
 				.iter()
 				.rev()
-				.map(|i| i.to_be_bytes().to_vec())
-				.flatten(),
+				.flat_map(|i| i.to_be_bytes().to_vec()),
 		)
 		.chain(
 			public_key
@@ -104,16 +103,15 @@ fn public_key_to_vec(public_key: PubKey) -> Vec<u8> {
 				.value
 				.iter()
 				.rev()
-				.map(|i| i.to_be_bytes().to_vec())
-				.flatten(),
+				.flat_map(|i| i.to_be_bytes().to_vec()),
 		)
 		.collect::<Vec<_>>();
 	result
 }
 
-fn vec_to_secret_key(vec: Vec<u8>) -> Result<SecKey, String> {
+fn vec_to_secret_key(vec: Vec<u8>) -> Result<SecKey, Error> {
 	if vec.len() != 32 {
-		return Err("Invalid secret key".to_string());
+		return Err(Error::invalid("secret key", ""));
 	}
 
 	let secret_key = slice_to_u64x4(&vec);
@@ -121,9 +119,9 @@ fn vec_to_secret_key(vec: Vec<u8>) -> Result<SecKey, String> {
 	Ok(secret_key)
 }
 
-fn vec_to_public_key(vec: Vec<u8>) -> Result<PubKey, String> {
+fn vec_to_public_key(vec: Vec<u8>) -> Result<PubKey, Error> {
 	if vec.len() != 65 || vec[0] != 4 {
-		return Err("Invalid public key".to_string());
+		return Err(Error::invalid("public key", ""));
 	}
 
 	let x_slice = &vec[1..33];
@@ -147,24 +145,22 @@ fn signature_to_vec(sig: Signature) -> Vec<u8> {
 				.value
 				.iter()
 				.rev()
-				.map(|i| i.to_be_bytes().to_vec())
-				.flatten(),
+				.flat_map(|i| i.to_be_bytes().to_vec()),
 		)
 		.chain(
 			sig.s
 				.value
 				.iter()
 				.rev()
-				.map(|i| i.to_be_bytes().to_vec())
-				.flatten(),
+				.flat_map(|i| i.to_be_bytes().to_vec()),
 		)
 		.collect::<Vec<_>>();
 	result
 }
 
-fn vec_to_signature(vec: Vec<u8>) -> Result<Signature, String> {
+fn vec_to_signature(vec: Vec<u8>) -> Result<Signature, Error> {
 	if vec.len() != 64 {
-		return Err("Invalid signature".to_string());
+		return Err(Error::invalid("signature", ""));
 	}
 
 	let r_slice = &vec[0..32];
@@ -180,7 +176,7 @@ fn vec_to_signature(vec: Vec<u8>) -> Result<Signature, String> {
 fn slice_to_u64x4(slice: &[u8]) -> U64x4 {
 	U64x4 {
 		value: [
-			u64::from_be_bytes({ slice_to_arr(&slice[24..32]) }),
+			u64::from_be_bytes(slice_to_arr(&slice[24..32])),
 			u64::from_be_bytes(slice_to_arr(&slice[16..24])),
 			u64::from_be_bytes(slice_to_arr(&slice[8..16])),
 			u64::from_be_bytes(slice_to_arr(&slice[0..8])),
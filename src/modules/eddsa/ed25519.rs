@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::modules::eddsa::AltSecretKey;
 use crate::modules::Case;
 use linked_hash_map::LinkedHashMap;
@@ -5,10 +6,11 @@ use ring::signature::{Ed25519KeyPair, KeyPair, VerificationAlgorithm, ED25519};
 use secp256k1::rand::{thread_rng, Rng};
 use untrusted::Input;
 
-pub fn ed_gk_ed25519() -> Result<(Vec<u8>, Vec<u8>), String> {
+pub fn ed_gk_ed25519() -> Result<(Vec<u8>, Vec<u8>), Error> {
 	let seed = random_32_bytes(&mut thread_rng());
 
-	let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|_| "Invalid secret key")?;
+	let key_pair =
+		Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|_| Error::invalid("secret key", ""))?;
 
 	let secret_key = seed.to_vec();
 
@@ -17,7 +19,7 @@ pub fn ed_gk_ed25519() -> Result<(Vec<u8>, Vec<u8>), String> {
 	Ok((secret_key, public_key))
 }
 
-pub fn ed_sign_ed25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec<u8>, String> {
+pub fn ed_sign_ed25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let key_pair = get_key_pair(secret_key)?;
 
 	let signature = key_pair.sign(&message);
@@ -27,32 +29,28 @@ pub fn ed_sign_ed25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec
 	Ok(signature)
 }
 
-pub fn ed_verify_ed25519(
-	public_key: Vec<u8>,
-	sig: Vec<u8>,
-	message: Vec<u8>,
-) -> Result<(), String> {
-	let result = ED25519
+pub fn ed_verify_ed25519(public_key: Vec<u8>, sig: Vec<u8>, message: Vec<u8>) -> Result<(), Error> {
+	ED25519
 		.verify(
 			Input::from(&public_key),
 			Input::from(&message),
 			Input::from(&sig),
 		)
-		.map_err(|e| format!("Invalid signature: {}", e))?;
+		.map_err(|e| Error::invalid("signature", e))?;
 
-	Ok(result)
+	Ok(())
 }
 
-pub fn ed_sk_ed25519(mini_secret_key: Vec<u8>) -> Result<Vec<u8>, String> {
+pub fn ed_sk_ed25519(mini_secret_key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let key_pair = Ed25519KeyPair::from_seed_unchecked(&mini_secret_key)
-		.map_err(|_| "Invalid mini secret key")?;
+		.map_err(|_| Error::invalid("mini secret key", ""))?;
 
 	let secret_key = key_pair.private_key().to_vec();
 
 	Ok(secret_key)
 }
 
-pub fn ed_pk_ed25519(secret_key: AltSecretKey) -> Result<Vec<u8>, String> {
+pub fn ed_pk_ed25519(secret_key: AltSecretKey) -> Result<Vec<u8>, Error> {
 	let key_pair = get_key_pair(secret_key)?;
 
 	let public_key = key_pair.public_key();
@@ -67,18 +65,12 @@ fn random_32_bytes<R: Rng + ?Sized>(rng: &mut R) -> [u8; 32] {
 	ret
 }
 
-fn get_key_pair(secret_key: AltSecretKey) -> Result<Ed25519KeyPair, String> {
+fn get_key_pair(secret_key: AltSecretKey) -> Result<Ed25519KeyPair, Error> {
 	let key_pair = match secret_key {
-		AltSecretKey::MiniSecretKey(key) => {
-			let key_pair =
-				Ed25519KeyPair::from_seed_unchecked(&key).map_err(|_| "Invalid mini secret key")?;
-			key_pair
-		}
-		AltSecretKey::SecretKey(key) => {
-			let key_pair = Ed25519KeyPair::from_private_key_unchecked(&key)
-				.map_err(|_| "Invalid secret key")?;
-			key_pair
-		}
+		AltSecretKey::MiniSecretKey(key) => Ed25519KeyPair::from_seed_unchecked(&key)
+			.map_err(|_| Error::invalid("mini secret key", ""))?,
+		AltSecretKey::SecretKey(key) => Ed25519KeyPair::from_private_key_unchecked(&key)
+			.map_err(|_| Error::invalid("secret key", ""))?,
 	};
 	Ok(key_pair)
 }
@@ -89,7 +81,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
          vec![
              Case {
                  desc: "".to_string(),
-                 input: Vec::<String>::new().into_iter().map(Into::into).collect(),
+                 input: Vec::<String>::new().into_iter().collect(),
                  output: vec!["(0xb850164d1feec8698acca329947c9885bd1d94034d2fbbe6080598adbe15b298, 0x892c89a4cd631d08da314607223814775604535a05f50e959d21209d01740eba)"].into_iter().map(Into::into).collect(),
                  is_example: true,
                  is_test: false,
@@ -0,0 +1,506 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, dns, Command, Module};
+use clap::{Arg, ArgMatches};
+use dns_parser::QueryType;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// RFC 5321 4.5.3.1
+const MAX_LOCAL_LEN: usize = 64;
+const MAX_DOMAIN_LEN: usize = 255;
+const MAX_LABEL_LEN: usize = 63;
+const MAX_ADDRESS_LEN: usize = 254;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Email address validation".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("email")
+			.about("Validate email address per RFC 5321 / 5322, split into local part and domain")
+			.arg(
+				Arg::with_name("MX")
+					.long("mx")
+					.help("Verify the domain receives mail: MX records, or the A / AAAA records if none (implicit MX)"),
+			)
+			.arg(
+				Arg::with_name("SERVER")
+					.long("server")
+					.short("s")
+					.help("Resolver for --mx as IP or IP:port, the first nameserver of /etc/resolv.conf if not given")
+					.takes_value(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("TIMEOUT")
+					.long("timeout")
+					.help("Timeout of --mx in seconds")
+					.takes_value(true)
+					.default_value("5")
+					.required(false),
+			)
+			.arg(base::input_arg().help("Email address, e.g. user@example.com or Name <user@example.com>")).args(&base::file_args()),
+		f: |matches| base::convert(matches, email),
+		stream: None,
+	}]
+}
+
+#[derive(Debug, PartialEq)]
+struct Address {
+	name: Option<String>,
+	local: String,
+	quoted: bool,
+	domain: Domain,
+}
+
+#[derive(Debug, PartialEq)]
+enum Domain {
+	Name { unicode: String, ascii: String },
+	Literal(IpAddr),
+}
+
+/// Options of email
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Verify the domain receives mail, needs network access
+	pub mx: Option<Mx>,
+}
+
+/// Resolver of the MX verification
+#[derive(Debug, Clone)]
+pub struct Mx {
+	pub server: SocketAddr,
+	pub timeout: Duration,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let mx = if matches.is_present("MX") {
+			let server = dns::server(matches.value_of("SERVER"))?;
+			let timeout = matches
+				.value_of("TIMEOUT")
+				.unwrap_or("5")
+				.parse::<f64>()
+				.ok()
+				.filter(|x| *x > 0.0)
+				.map(Duration::from_secs_f64)
+				.ok_or(Error::invalid("timeout", ""))?;
+			base::verbose(matches, 1, || format!("server: {}", server));
+			Some(Mx { server, timeout })
+		} else {
+			None
+		};
+		Ok(Options { mx })
+	}
+}
+
+/// Validate email address per RFC 5321 / 5322, split into local part and domain,
+/// warnings are prefixed with `warning: `
+pub fn email(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let address = parse(base::text(input)?)?;
+
+	let mut result = vec![];
+	let mut warnings = vec![];
+	if let Some(name) = &address.name {
+		result.push(format!("name: {}", name));
+	}
+	result.push(format!("local: {}", address.local));
+	if address.quoted {
+		warnings.push("quoted local part, valid but rejected by many systems".to_string());
+	}
+	if !address.local.is_ascii() {
+		warnings.push("non-ASCII local part, needs SMTPUTF8 (RFC 6531)".to_string());
+	}
+	match &address.domain {
+		Domain::Name { unicode, ascii } => {
+			result.push(format!("domain: {}", unicode));
+			if unicode != ascii {
+				result.push(format!("domain_ascii: {}", ascii));
+			}
+			result.push(format!("idn: {}", unicode != ascii));
+			if !ascii.contains('.') {
+				warnings.push("no top-level domain, only deliverable locally".to_string());
+			}
+		}
+		Domain::Literal(ip) => {
+			result.push(format!("domain: {}", ip));
+			result.push("idn: false".to_string());
+			warnings.push("domain literal, rejected by most mail servers".to_string());
+		}
+	}
+
+	if let Some(Mx { server, timeout }) = &options.mx {
+		let ascii = match &address.domain {
+			Domain::Name { ascii, .. } => ascii,
+			Domain::Literal(_) => {
+				return Err(Error::invalid(
+					"email",
+					"no MX records for a domain literal",
+				))
+			}
+		};
+		result.extend(mx(ascii, *server, *timeout)?);
+	}
+
+	result.extend(
+		warnings
+			.into_iter()
+			.map(|x| format!("{}{}", base::WARNING_PREFIX, x)),
+	);
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// MX records, or the A / AAAA records if there are none (RFC 5321 5.1)
+fn mx(domain: &str, server: SocketAddr, timeout: Duration) -> Result<Vec<String>, Error> {
+	let mut records = dns::lookup(domain, QueryType::MX, server, timeout)?
+		.into_iter()
+		.filter(|x| x.kind == "MX")
+		.filter_map(|x| {
+			let (preference, exchange) = x.data.split_once(' ')?;
+			Some((preference.parse::<u16>().ok()?, exchange.to_string()))
+		})
+		.collect::<Vec<_>>();
+	records.sort();
+
+	// RFC 7505
+	if records.iter().any(|(_, exchange)| exchange == ".") {
+		return Err(Error::Failed(format!(
+			"No mail accepted: {} has a null MX",
+			domain
+		)));
+	}
+	if !records.is_empty() {
+		return Ok(records
+			.into_iter()
+			.map(|(preference, exchange)| format!("mx: {} {}", preference, exchange))
+			.collect());
+	}
+
+	for query_type in [QueryType::A, QueryType::AAAA] {
+		let hosts = dns::lookup(domain, query_type, server, timeout)?
+			.into_iter()
+			.filter(|x| x.kind == "A" || x.kind == "AAAA")
+			.map(|x| format!("mx: 0 {} (implicit, {} {})", domain, x.kind, x.data))
+			.collect::<Vec<_>>();
+		if !hosts.is_empty() {
+			return Ok(hosts);
+		}
+	}
+	Err(Error::Failed(format!(
+		"No mail accepted: {} has no MX, A or AAAA records",
+		domain
+	)))
+}
+
+/// `addr-spec` or `name-addr` of RFC 5322 3.4, without comments and folding white space
+fn parse(input: &str) -> Result<Address, Error> {
+	let (name, addr_spec) = match input.strip_suffix('>').and_then(|x| x.rsplit_once('<')) {
+		Some((name, addr_spec)) => {
+			let name = name.trim();
+			let name = name
+				.strip_prefix('"')
+				.and_then(|x| x.strip_suffix('"'))
+				.unwrap_or(name);
+			(Some(name.to_string()).filter(|x| !x.is_empty()), addr_spec)
+		}
+		None => (None, input),
+	};
+
+	if addr_spec.len() > MAX_ADDRESS_LEN {
+		return Err(Error::invalid(
+			"email",
+			format!("longer than {} octets", MAX_ADDRESS_LEN),
+		));
+	}
+
+	let (local, quoted, domain) = if addr_spec.starts_with('"') {
+		let (local, rest) = quoted_string(addr_spec)?;
+		let domain = rest.strip_prefix('@').ok_or(Error::invalid(
+			"email",
+			"expected @ after the quoted local part",
+		))?;
+		(local, true, domain)
+	} else {
+		let (local, domain) = addr_spec
+			.split_once('@')
+			.ok_or(Error::invalid("email", "missing @"))?;
+		dot_atom(local)?;
+		(local.to_string(), false, domain)
+	};
+
+	if local.len() > MAX_LOCAL_LEN {
+		return Err(Error::invalid(
+			"email",
+			format!("local part longer than {} octets", MAX_LOCAL_LEN),
+		));
+	}
+
+	let domain = match domain.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+		Some(literal) => domain_literal(literal)?,
+		None => domain_name(domain)?,
+	};
+
+	Ok(Address {
+		name,
+		local,
+		quoted,
+		domain,
+	})
+}
+
+/// `atext` of RFC 5322 3.2.3, and UTF-8 of RFC 6532
+fn is_atext(c: char) -> bool {
+	c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || !c.is_ascii()
+}
+
+fn dot_atom(local: &str) -> Result<(), Error> {
+	if local.is_empty() {
+		return Err(Error::invalid("email", "empty local part"));
+	}
+	if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+		return Err(Error::invalid(
+			"email",
+			"dots at the start, the end or in a row of the local part need quoting",
+		));
+	}
+	match local.chars().find(|c| *c != '.' && !is_atext(*c)) {
+		Some(c) => Err(Error::invalid(
+			"email",
+			format!("{:?} in the local part needs quoting", c),
+		)),
+		None => Ok(()),
+	}
+}
+
+/// The unescaped content of the quoted string and the rest
+fn quoted_string(input: &str) -> Result<(String, &str), Error> {
+	let mut content = String::new();
+	let mut chars = input.char_indices().skip(1);
+	while let Some((i, c)) = chars.next() {
+		match c {
+			'"' => return Ok((content, &input[i + 1..])),
+			'\\' => match chars.next() {
+				Some((_, c)) if c == ' ' || c == '\t' || c.is_ascii_graphic() => content.push(c),
+				_ => {
+					return Err(Error::invalid(
+						"email",
+						"invalid quoted pair in the local part",
+					))
+				}
+			},
+			c if c == ' ' || c == '\t' || (c.is_ascii_graphic() || !c.is_ascii()) => {
+				content.push(c)
+			}
+			c => {
+				return Err(Error::invalid(
+					"email",
+					format!("{:?} in the quoted local part", c),
+				))
+			}
+		}
+	}
+	Err(Error::invalid("email", "unterminated quoted local part"))
+}
+
+/// `[192.0.2.1]` or `[IPv6:2001:db8::1]` of RFC 5321 4.1.3
+fn domain_literal(literal: &str) -> Result<Domain, Error> {
+	let ip = match literal.strip_prefix("IPv6:") {
+		Some(ip) => ip.parse::<Ipv6Addr>().map(IpAddr::V6).ok(),
+		None => literal.parse::<Ipv4Addr>().map(IpAddr::V4).ok(),
+	};
+	ip.map(Domain::Literal)
+		.ok_or_else(|| Error::invalid("email", format!("invalid domain literal [{}]", literal)))
+}
+
+/// Host name of letters, digits and hyphens, IDN converted with IDNA
+fn domain_name(domain: &str) -> Result<Domain, Error> {
+	if domain.is_empty() {
+		return Err(Error::invalid("email", "empty domain"));
+	}
+	let ascii = idna::domain_to_ascii(domain)
+		.map_err(|_| Error::invalid("email", format!("invalid international domain {}", domain)))?;
+	if ascii.len() > MAX_DOMAIN_LEN {
+		return Err(Error::invalid(
+			"email",
+			format!("domain longer than {} octets", MAX_DOMAIN_LEN),
+		));
+	}
+	for label in ascii.split('.') {
+		if label.is_empty() {
+			return Err(Error::invalid("email", "empty domain label"));
+		}
+		if label.len() > MAX_LABEL_LEN {
+			return Err(Error::invalid(
+				"email",
+				format!(
+					"domain label longer than {} octets: {}",
+					MAX_LABEL_LEN, label
+				),
+			));
+		}
+		if label.starts_with('-')
+			|| label.ends_with('-')
+			|| !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+		{
+			return Err(Error::invalid(
+				"email",
+				format!("invalid domain label: {}", label),
+			));
+		}
+	}
+	let (unicode, _) = idna::domain_to_unicode(&ascii);
+	Ok(Domain::Name { unicode, ascii })
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"email",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'John Doe <john.doe+news@example.com>'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"name: John Doe",
+						"local: john.doe+news",
+						"domain: example.com",
+						"idn: false",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "IDN".to_string(),
+					input: vec!["info@bücher.de"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"local: info",
+						"domain: bücher.de",
+						"domain_ascii: xn--bcher-kva.de",
+						"idn: true",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Quoted local part".to_string(),
+					input: vec![r#"'"john doe"@example.com'"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"local: john doe",
+						"domain: example.com",
+						"idn: false",
+						"warning: quoted local part, valid but rejected by many systems",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Verify MX records".to_string(),
+					input: vec!["--mx", "user@gmail.com"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"local: user",
+						"domain: gmail.com",
+						"idn: false",
+						"mx: 5 gmail-smtp-in.l.google.com",
+						"mx: 10 alt1.gmail-smtp-in.l.google.com",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_parse() {
+		let address = parse(r#""a\"b@c"@[IPv6:2001:db8::1]"#).unwrap();
+		assert_eq!(address.local, r#"a"b@c"#);
+		assert!(address.quoted);
+		assert_eq!(
+			address.domain,
+			Domain::Literal("2001:db8::1".parse().unwrap())
+		);
+
+		let address = parse("user@XN--BCHER-KVA.de").unwrap();
+		assert_eq!(
+			address.domain,
+			Domain::Name {
+				unicode: "bücher.de".to_string(),
+				ascii: "xn--bcher-kva.de".to_string(),
+			}
+		);
+
+		assert!(parse("user@localhost").is_ok());
+		assert!(parse("!#$%&'*+-/=?^_`{|}~@example.com").is_ok());
+
+		let invalid = [
+			"example.com",
+			"@example.com",
+			"user@",
+			".user@example.com",
+			"us..er@example.com",
+			"us er@example.com",
+			"a@b@example.com",
+			"user@-example.com",
+			"user@exa_mple.com",
+			"user@example..com",
+			"user@[300.0.0.1]",
+			r#""unterminated@example.com"#,
+		];
+		for input in invalid {
+			assert!(parse(input).is_err(), "{}", input);
+		}
+
+		let long_local = format!("{}@example.com", "a".repeat(MAX_LOCAL_LEN + 1));
+		assert!(parse(&long_local).is_err());
+		let long_label = format!("user@{}.com", "a".repeat(MAX_LABEL_LEN + 1));
+		assert!(parse(&long_label).is_err());
+	}
+}
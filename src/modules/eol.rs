@@ -0,0 +1,231 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Line ending detection / conversion".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("eol")
+			.about("Detect or convert line endings (LF, CRLF, CR)")
+			.arg(
+				Arg::with_name("TO")
+					.long("to")
+					.short("t")
+					.help("Convert to the line ending")
+					.takes_value(true)
+					.possible_values(&["lf", "crlf", "cr"])
+					.conflicts_with("CHECK")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("CHECK")
+					.long("check")
+					.short("c")
+					.help("Fail if line endings are mixed")
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: run_eol,
+		stream: None,
+	}]
+}
+
+/// Options of `eol`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Convert to the line ending, otherwise detect
+	pub to: Option<LineEnding>,
+	/// Fail if line endings are mixed
+	pub check: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+	Lf,
+	Crlf,
+	Cr,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let to = match matches.value_of("TO") {
+			Some("crlf") => Some(LineEnding::Crlf),
+			Some("cr") => Some(LineEnding::Cr),
+			Some(_) => Some(LineEnding::Lf),
+			None => None,
+		};
+
+		Ok(Options {
+			to,
+			check: matches.is_present("CHECK"),
+		})
+	}
+}
+
+fn run_eol(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let options = Options::from_matches(matches)?;
+	let input = base::input_bytes(matches)?;
+	let output = eol(&input, &options)?;
+	match options.to {
+		Some(_) => {
+			// written as is, the line endings would be changed by line based output
+			base::output_bytes(matches, &output)?;
+			Ok(vec![])
+		}
+		None => base::lines(output),
+	}
+}
+
+/// Detect or convert line endings (LF, CRLF, CR), the input is bytes
+pub fn eol(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	if let Some(to) = options.to {
+		let to: &[u8] = match to {
+			LineEnding::Crlf => b"\r\n",
+			LineEnding::Cr => b"\r",
+			LineEnding::Lf => b"\n",
+		};
+		return Ok(convert(input, to));
+	}
+
+	let (lf, crlf, cr) = detect(input);
+
+	let kinds = [("lf", lf), ("crlf", crlf), ("cr", cr)]
+		.iter()
+		.filter(|(_, count)| *count > 0)
+		.map(|(name, _)| *name)
+		.collect::<Vec<_>>();
+	let summary = match kinds.as_slice() {
+		[] => "none",
+		[kind] => kind,
+		_ => "mixed",
+	};
+
+	let result = [
+		format!("lf: {}", lf),
+		format!("crlf: {}", crlf),
+		format!("cr: {}", cr),
+		summary.to_string(),
+	];
+
+	if options.check && summary == "mixed" {
+		return Err(Error::failed(result.join("\n")));
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Returns the counts of LF, CRLF and CR
+fn detect(input: &[u8]) -> (usize, usize, usize) {
+	let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+	let mut i = 0;
+	while i < input.len() {
+		match (input[i], input.get(i + 1)) {
+			(b'\r', Some(b'\n')) => {
+				crlf += 1;
+				i += 1;
+			}
+			(b'\r', _) => cr += 1,
+			(b'\n', _) => lf += 1,
+			_ => (),
+		}
+		i += 1;
+	}
+	(lf, crlf, cr)
+}
+
+fn convert(input: &[u8], to: &[u8]) -> Vec<u8> {
+	let mut result = Vec::with_capacity(input.len());
+	let mut i = 0;
+	while i < input.len() {
+		match (input[i], input.get(i + 1)) {
+			(b'\r', Some(b'\n')) => {
+				result.extend_from_slice(to);
+				i += 1;
+			}
+			(b'\r', _) | (b'\n', _) => result.extend_from_slice(to),
+			(c, _) => result.push(c),
+		}
+		i += 1;
+	}
+	result
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"eol",
+			vec![
+				Case {
+					desc: "Detect".to_string(),
+					input: vec!["'a\r\nb\nc\r\n'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["lf: 1", "crlf: 2", "cr: 0", "mixed"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Check".to_string(),
+					input: vec!["-c", "'a\r\nb\r\n'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["lf: 0", "crlf: 2", "cr: 0", "crlf"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Convert".to_string(),
+					input: vec!["-t", "lf", "<", "windows.txt", ">", "unix.txt"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![],
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_convert() {
+		assert_eq!(convert(b"a\r\nb\nc\rd", b"\n"), b"a\nb\nc\nd".to_vec());
+		assert_eq!(convert(b"a\nb\r\n", b"\r\n"), b"a\r\nb\r\n".to_vec());
+		assert_eq!(convert(b"a\n\nb", b"\r"), b"a\r\rb".to_vec());
+	}
+}
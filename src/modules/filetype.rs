@@ -0,0 +1,791 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, mime, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::ArgMatches;
+use std::convert::{TryFrom, TryInto};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "File type identification".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("filetype")
+			.about("Identify the file type from the leading bytes (magic numbers), like file(1)\nwith details, e.g. the architecture of ELF, PE and Mach-O executables")
+			.args(&base::input_args()),
+		f: |matches| base::convert_bytes(matches, filetype),
+		stream: None,
+	}]
+}
+
+/// The description and the details of a recognized format
+type Identified = (String, Vec<String>);
+
+type Identifier = fn(&[u8]) -> Option<Identified>;
+
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
+
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
+}
+
+/// Identify the file type from the leading bytes (magic numbers), like file(1),
+/// with details, e.g. the architecture of ELF, PE and Mach-O executables
+pub fn filetype(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let detection = mime::detect(input);
+	let (description, details) =
+		identify(input).unwrap_or_else(|| (fallback(input, &detection), vec![]));
+
+	let mut result = vec![
+		format!("type: {}", description),
+		format!("mime: {}", detection.mime),
+	];
+	if !detection.extension.is_empty() {
+		result.push(format!("extension: {}", detection.extension));
+	}
+	result.extend(details);
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// None if not recognized, or truncated
+fn identify(input: &[u8]) -> Option<Identified> {
+	let identifiers: [Identifier; 10] = [elf, pe, mach_o, fat, png, gif, pdf, sqlite, gzip, wasm];
+	identifiers.iter().find_map(|f| f(input))
+}
+
+fn fallback(input: &[u8], detection: &mime::Detection) -> String {
+	match detection.kind {
+		"empty" => "empty".to_string(),
+		"text" if detection.mime == "text/plain" => match input.is_ascii() {
+			true => "ASCII text".to_string(),
+			false => "UTF-8 text".to_string(),
+		},
+		_ if !detection.extension.is_empty() => {
+			format!("{} data", detection.extension.to_uppercase())
+		}
+		_ => "data".to_string(),
+	}
+}
+
+/// Integers at offsets, None if out of the input
+struct Reader<'a> {
+	input: &'a [u8],
+	big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+	fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+		let bytes = self.input.get(offset..offset.checked_add(N)?)?;
+		bytes.try_into().ok()
+	}
+
+	fn u16(&self, offset: usize) -> Option<u16> {
+		let bytes = self.bytes(offset)?;
+		Some(match self.big_endian {
+			true => u16::from_be_bytes(bytes),
+			false => u16::from_le_bytes(bytes),
+		})
+	}
+
+	fn u32(&self, offset: usize) -> Option<u32> {
+		let bytes = self.bytes(offset)?;
+		Some(match self.big_endian {
+			true => u32::from_be_bytes(bytes),
+			false => u32::from_le_bytes(bytes),
+		})
+	}
+
+	fn u64(&self, offset: usize) -> Option<u64> {
+		let bytes = self.bytes(offset)?;
+		Some(match self.big_endian {
+			true => u64::from_be_bytes(bytes),
+			false => u64::from_le_bytes(bytes),
+		})
+	}
+}
+
+fn elf(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(b"\x7fELF") {
+		return None;
+	}
+	let is_64 = match input.get(4)? {
+		1 => false,
+		2 => true,
+		_ => return None,
+	};
+	let big_endian = match input.get(5)? {
+		1 => false,
+		2 => true,
+		_ => return None,
+	};
+	let r = Reader { input, big_endian };
+
+	let e_type = r.u16(16)?;
+	let machine = match r.u16(18)? {
+		2 => "SPARC".to_string(),
+		3 => "Intel 80386".to_string(),
+		8 => "MIPS".to_string(),
+		20 => "PowerPC".to_string(),
+		21 => "64-bit PowerPC".to_string(),
+		22 => "IBM S/390".to_string(),
+		40 => "ARM".to_string(),
+		43 => "SPARC V9".to_string(),
+		50 => "IA-64".to_string(),
+		62 => "x86-64".to_string(),
+		183 => "ARM aarch64".to_string(),
+		243 => "RISC-V".to_string(),
+		247 => "eBPF".to_string(),
+		258 => "LoongArch".to_string(),
+		x => format!("machine {}", x),
+	};
+	let os_abi = match input.get(7)? {
+		0 => "SYSV".to_string(),
+		2 => "NetBSD".to_string(),
+		3 => "GNU/Linux".to_string(),
+		6 => "Solaris".to_string(),
+		9 => "FreeBSD".to_string(),
+		12 => "OpenBSD".to_string(),
+		255 => "standalone".to_string(),
+		x => format!("ABI {}", x),
+	};
+	let (entry, phoff, phentsize, phnum) = match is_64 {
+		true => (r.u64(24)?, r.u64(32)?, r.u16(54)?, r.u16(56)?),
+		false => (r.u32(24)? as u64, r.u32(28)? as u64, r.u16(42)?, r.u16(44)?),
+	};
+
+	// PT_DYNAMIC and PT_INTERP of the program headers
+	let mut dynamic = false;
+	let mut interpreter = None;
+	for i in 0..phnum as u64 {
+		let offset = (i * phentsize as u64)
+			.checked_add(phoff)
+			.and_then(|x| usize::try_from(x).ok())?;
+		match r.u32(offset) {
+			Some(2) => dynamic = true,
+			Some(3) => {
+				let (p_offset, p_filesz) = match is_64 {
+					true => (r.u64(offset + 8)?, r.u64(offset + 32)?),
+					false => (r.u32(offset + 4)? as u64, r.u32(offset + 16)? as u64),
+				};
+				interpreter = p_offset
+					.checked_add(p_filesz)
+					.and_then(|end| input.get(p_offset as usize..end as usize))
+					.map(|x| {
+						String::from_utf8_lossy(x)
+							.trim_end_matches('\0')
+							.to_string()
+					});
+			}
+			Some(_) => (),
+			None => break,
+		}
+	}
+
+	let kind = match e_type {
+		1 => "relocatable",
+		2 => "executable",
+		3 if interpreter.is_some() => "pie executable",
+		3 => "shared object",
+		4 => "core file",
+		_ => "unknown type",
+	};
+	let linking = match (e_type, dynamic) {
+		(2, true) | (3, true) => Some("dynamically linked"),
+		(2, false) | (3, false) => Some("statically linked"),
+		_ => None,
+	};
+
+	let bits = if is_64 { 64 } else { 32 };
+	let mut description = format!(
+		"ELF {}-bit {} {}, {}",
+		bits,
+		if big_endian { "MSB" } else { "LSB" },
+		kind,
+		machine
+	);
+	let mut details = vec![
+		format!("class: {}-bit", bits),
+		format!("endian: {}", if big_endian { "big" } else { "little" }),
+		format!("elf_type: {}", kind),
+		format!("machine: {}", machine),
+		format!("os_abi: {}", os_abi),
+		format!("entry: 0x{:x}", entry),
+	];
+	if let Some(linking) = linking {
+		description.push_str(&format!(", {}", linking));
+		details.push(format!("linking: {}", linking));
+	}
+	if let Some(interpreter) = interpreter {
+		details.push(format!("interpreter: {}", interpreter));
+	}
+
+	Some((description, details))
+}
+
+fn pe(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(b"MZ") {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: false,
+	};
+
+	let signature = r.u32(0x3c)? as usize;
+	if r.bytes(signature) != Some(*b"PE\0\0") {
+		return Some(("MS-DOS executable".to_string(), vec![]));
+	}
+
+	// COFF header, then the optional header
+	let coff = signature + 4;
+	let machine = match r.u16(coff)? {
+		0x14c => "Intel 80386".to_string(),
+		0x8664 => "x86-64".to_string(),
+		0x1c0 => "ARM".to_string(),
+		0x1c4 => "ARMv7 Thumb".to_string(),
+		0xaa64 => "Aarch64".to_string(),
+		0x200 => "Intel Itanium".to_string(),
+		0x5064 => "RISC-V 64-bit".to_string(),
+		0xebc => "EFI byte code".to_string(),
+		x => format!("machine 0x{:x}", x),
+	};
+	let sections = r.u16(coff + 2)?;
+	let dll = r.u16(coff + 18)? & 0x2000 != 0;
+	let optional = coff + 20;
+	let (format, directories) = match r.u16(optional)? {
+		0x10b => ("PE32", optional + 92),
+		0x20b => ("PE32+", optional + 108),
+		_ => return None,
+	};
+	let subsystem = match r.u16(optional + 68)? {
+		1 => "native".to_string(),
+		2 => "GUI".to_string(),
+		3 => "console".to_string(),
+		7 => "POSIX".to_string(),
+		9 => "Windows CE GUI".to_string(),
+		10 => "EFI application".to_string(),
+		11 => "EFI boot service driver".to_string(),
+		12 => "EFI runtime driver".to_string(),
+		13 => "EFI ROM".to_string(),
+		14 => "XBOX".to_string(),
+		16 => "boot application".to_string(),
+		x => format!("subsystem {}", x),
+	};
+	// the CLR runtime header, the 15th data directory
+	let dotnet = r.u32(directories)? > 14 && r.u32(directories + 4 + 14 * 8)? != 0;
+
+	let kind = if dll { "DLL" } else { "executable" };
+	let mut description = format!(
+		"{} {} ({}) {}, for MS Windows",
+		format, kind, subsystem, machine
+	);
+	let mut details = vec![
+		format!("format: {}", format),
+		format!("pe_type: {}", kind),
+		format!("machine: {}", machine),
+		format!("subsystem: {}", subsystem),
+		format!("sections: {}", sections),
+	];
+	if dotnet {
+		description.push_str(", Mono/.Net assembly");
+		details.push("dotnet: yes".to_string());
+	}
+
+	Some((description, details))
+}
+
+fn mach_o_cpu(cpu: u32) -> String {
+	match cpu {
+		7 => "i386".to_string(),
+		0x0100_0007 => "x86_64".to_string(),
+		12 => "arm".to_string(),
+		0x0100_000c => "arm64".to_string(),
+		0x0200_000c => "arm64_32".to_string(),
+		18 => "ppc".to_string(),
+		0x0100_0012 => "ppc64".to_string(),
+		x => format!("cpu 0x{:x}", x),
+	}
+}
+
+fn mach_o(input: &[u8]) -> Option<Identified> {
+	let (is_64, big_endian) = match input.get(0..4)? {
+		[0xfe, 0xed, 0xfa, 0xce] => (false, true),
+		[0xce, 0xfa, 0xed, 0xfe] => (false, false),
+		[0xfe, 0xed, 0xfa, 0xcf] => (true, true),
+		[0xcf, 0xfa, 0xed, 0xfe] => (true, false),
+		_ => return None,
+	};
+	let r = Reader { input, big_endian };
+
+	let cpu = mach_o_cpu(r.u32(4)?);
+	let kind = match r.u32(12)? {
+		1 => "object".to_string(),
+		2 => "executable".to_string(),
+		4 => "core".to_string(),
+		6 => "dynamically linked shared library".to_string(),
+		7 => "dynamic linker".to_string(),
+		8 => "bundle".to_string(),
+		10 => "dSYM companion file".to_string(),
+		11 => "kext bundle".to_string(),
+		x => format!("filetype {}", x),
+	};
+
+	let bits = if is_64 { 64 } else { 32 };
+	let description = format!("Mach-O {}-bit {}, {}", bits, kind, cpu);
+	let details = vec![
+		format!("class: {}-bit", bits),
+		format!("macho_type: {}", kind),
+		format!("cpu: {}", cpu),
+	];
+
+	Some((description, details))
+}
+
+/// Mach-O universal binaries, and Java classes of the same magic number
+fn fat(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(&[0xca, 0xfe, 0xba, 0xbe]) {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: true,
+	};
+
+	// the number of architectures is small, the major version of Java classes is at least 45
+	let count = r.u32(4)?;
+	if count >= 45 {
+		let (minor, major) = (r.u16(4)?, r.u16(6)?);
+		let java = match major {
+			49.. => format!("Java SE {}", major - 44),
+			_ => format!("JDK 1.{}", major.saturating_sub(44)),
+		};
+		return Some((
+			format!(
+				"compiled Java class data, version {}.{} ({})",
+				major, minor, java
+			),
+			vec![
+				format!("class_version: {}.{}", major, minor),
+				format!("java: {}", java),
+			],
+		));
+	}
+
+	let cpus = (0..count as usize)
+		.map(|i| r.u32(8 + i * 20).map(mach_o_cpu))
+		.collect::<Option<Vec<_>>>()?;
+	let description = format!(
+		"Mach-O universal binary with {} architectures: [{}]",
+		count,
+		cpus.join(", ")
+	);
+
+	Some((
+		description,
+		vec![format!("architectures: {}", cpus.join(", "))],
+	))
+}
+
+fn png(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(b"\x89PNG\r\n\x1a\n") || input.get(12..16)? != b"IHDR" {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: true,
+	};
+
+	let (width, height) = (r.u32(16)?, r.u32(20)?);
+	let depth = *input.get(24)?;
+	let color = match input.get(25)? {
+		0 => format!("{}-bit grayscale", depth),
+		2 => format!("{}-bit/color RGB", depth),
+		3 => format!("{}-bit colormap", depth),
+		4 => format!("{}-bit gray+alpha", depth),
+		6 => format!("{}-bit/color RGBA", depth),
+		x => format!("{}-bit color type {}", depth, x),
+	};
+	let interlace = match input.get(28)? {
+		0 => "non-interlaced",
+		_ => "interlaced",
+	};
+
+	let description = format!(
+		"PNG image data, {} x {}, {}, {}",
+		width, height, color, interlace
+	);
+	let details = vec![
+		format!("width: {}", width),
+		format!("height: {}", height),
+		format!("color: {}", color),
+		format!("interlace: {}", interlace),
+	];
+
+	Some((description, details))
+}
+
+fn gif(input: &[u8]) -> Option<Identified> {
+	let version = match input.get(0..6)? {
+		b"GIF87a" => "87a",
+		b"GIF89a" => "89a",
+		_ => return None,
+	};
+	let r = Reader {
+		input,
+		big_endian: false,
+	};
+
+	let (width, height) = (r.u16(6)?, r.u16(8)?);
+
+	let description = format!(
+		"GIF image data, version {}, {} x {}",
+		version, width, height
+	);
+	let details = vec![
+		format!("version: {}", version),
+		format!("width: {}", width),
+		format!("height: {}", height),
+	];
+
+	Some((description, details))
+}
+
+fn pdf(input: &[u8]) -> Option<Identified> {
+	let version = input.strip_prefix(b"%PDF-")?;
+	let version = version
+		.iter()
+		.take(4)
+		.take_while(|x| x.is_ascii_digit() || **x == b'.')
+		.map(|x| *x as char)
+		.collect::<String>();
+	if version.is_empty() {
+		return None;
+	}
+
+	Some((
+		format!("PDF document, version {}", version),
+		vec![format!("version: {}", version)],
+	))
+}
+
+fn sqlite(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(b"SQLite format 3\0") {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: true,
+	};
+
+	let page_size = match r.u16(16)? {
+		1 => 65536,
+		x => x as u32,
+	};
+	let pages = r.u32(28)?;
+	let encoding = match r.u32(56)? {
+		2 => "UTF-16le",
+		3 => "UTF-16be",
+		_ => "UTF-8",
+	};
+	// e.g. 3045001 for 3.45.1
+	let version = r.u32(96)?;
+	let version = format!(
+		"{}.{}.{}",
+		version / 1_000_000,
+		version / 1000 % 1000,
+		version % 1000
+	);
+
+	let description = format!(
+		"SQLite 3.x database, last written using SQLite version {}",
+		version
+	);
+	let details = vec![
+		format!("page_size: {}", page_size),
+		format!("pages: {}", pages),
+		format!("encoding: {}", encoding),
+		format!("sqlite_version: {}", version),
+	];
+
+	Some((description, details))
+}
+
+fn gzip(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(&[0x1f, 0x8b]) {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: false,
+	};
+
+	let method = match input.get(2)? {
+		8 => "deflate".to_string(),
+		x => format!("method {}", x),
+	};
+	let flags = *input.get(3)?;
+	let mtime = r.u32(4)?;
+	let os = match input.get(9)? {
+		0 => "FAT".to_string(),
+		3 => "Unix".to_string(),
+		7 => "Macintosh".to_string(),
+		11 => "NTFS".to_string(),
+		255 => "unknown".to_string(),
+		x => format!("OS {}", x),
+	};
+
+	// FNAME after FEXTRA, zero terminated
+	let mut name = None;
+	if flags & 0x08 != 0 {
+		let mut offset = 10;
+		if flags & 0x04 != 0 {
+			offset += 2 + r.u16(10)? as usize;
+		}
+		let rest = input.get(offset..)?;
+		let end = rest.iter().position(|x| *x == 0)?;
+		name = Some(String::from_utf8_lossy(&rest[..end]).to_string());
+	}
+
+	let mut description = "gzip compressed data".to_string();
+	let mut details = vec![format!("method: {}", method)];
+	if let Some(name) = name {
+		description.push_str(&format!(", was \"{}\"", name));
+		details.push(format!("name: {}", name));
+	}
+	if mtime != 0 {
+		let time = Utc
+			.timestamp_opt(mtime as i64, 0)
+			.single()?
+			.format("%Y-%m-%d %H:%M:%S UTC");
+		description.push_str(&format!(", last modified: {}", time));
+		details.push(format!("mtime: {}", time));
+	}
+	description.push_str(&format!(", from {}", os));
+	details.push(format!("os: {}", os));
+
+	Some((description, details))
+}
+
+fn wasm(input: &[u8]) -> Option<Identified> {
+	if !input.starts_with(b"\0asm") {
+		return None;
+	}
+	let r = Reader {
+		input,
+		big_endian: false,
+	};
+
+	let version = r.u32(4)?;
+
+	Some((
+		format!(
+			"WebAssembly (wasm) binary module version 0x{:x}{}",
+			version,
+			if version == 1 { " (MVP)" } else { "" }
+		),
+		vec![format!("version: {}", version)],
+	))
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"filetype",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["-f", "/bin/ls"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"type: ELF 64-bit LSB pie executable, x86-64, dynamically linked",
+						"mime: application/x-executable",
+						"extension: elf",
+						"class: 64-bit",
+						"endian: little",
+						"elf_type: pie executable",
+						"machine: x86-64",
+						"os_abi: SYSV",
+						"entry: 0x6d30",
+						"linking: dynamically linked",
+						"interpreter: /lib64/ld-linux-x86-64.so.2",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "PDF".to_string(),
+					input: vec!["'%PDF-1.7'"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"type: PDF document, version 1.7",
+						"mime: application/pdf",
+						"extension: pdf",
+						"version: 1.7",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_elf() {
+		// ELF64 header, PT_INTERP and PT_DYNAMIC program headers, and the interpreter
+		let mut input = vec![0u8; 64 + 56 * 2];
+		input[..8].copy_from_slice(b"\x7fELF\x02\x01\x01\x00");
+		input[16..18].copy_from_slice(&3u16.to_le_bytes());
+		input[18..20].copy_from_slice(&183u16.to_le_bytes());
+		input[24..32].copy_from_slice(&0x1000u64.to_le_bytes());
+		input[32..40].copy_from_slice(&64u64.to_le_bytes());
+		input[54..56].copy_from_slice(&56u16.to_le_bytes());
+		input[56..58].copy_from_slice(&2u16.to_le_bytes());
+		input[64..68].copy_from_slice(&3u32.to_le_bytes());
+		input[72..80].copy_from_slice(&176u64.to_le_bytes());
+		input[96..104].copy_from_slice(&8u64.to_le_bytes());
+		input[120..124].copy_from_slice(&2u32.to_le_bytes());
+		input.extend(b"/lib/ld\0");
+
+		let (description, details) = identify(&input).unwrap();
+		assert_eq!(
+			description,
+			"ELF 64-bit LSB pie executable, ARM aarch64, dynamically linked"
+		);
+		assert!(details.contains(&"entry: 0x1000".to_string()));
+		assert!(details.contains(&"interpreter: /lib/ld".to_string()));
+
+		assert!(identify(&input[..20]).is_none());
+
+		let (description, _) =
+			identify(&std::fs::read(std::env::current_exe().unwrap()).unwrap()).unwrap();
+		assert!(
+			description.starts_with("ELF")
+				|| description.starts_with("PE")
+				|| description.starts_with("Mach-O")
+		);
+	}
+
+	#[test]
+	fn test_pe() {
+		let mut input = vec![0u8; 0x80 + 24 + 240];
+		input[..2].copy_from_slice(b"MZ");
+		input[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+		input[0x80..0x84].copy_from_slice(b"PE\0\0");
+		input[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+		input[0x86..0x88].copy_from_slice(&5u16.to_le_bytes());
+		input[0x96..0x98].copy_from_slice(&0x2022u16.to_le_bytes());
+		let optional = 0x98;
+		input[optional..optional + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+		input[optional + 68..optional + 70].copy_from_slice(&2u16.to_le_bytes());
+		input[optional + 108..optional + 112].copy_from_slice(&16u32.to_le_bytes());
+
+		let (description, details) = identify(&input).unwrap();
+		assert_eq!(description, "PE32+ DLL (GUI) x86-64, for MS Windows");
+		assert!(details.contains(&"sections: 5".to_string()));
+
+		let clr = optional + 112 + 14 * 8;
+		input[clr..clr + 4].copy_from_slice(&0x2000u32.to_le_bytes());
+		let (description, _) = identify(&input).unwrap();
+		assert!(description.ends_with("Mono/.Net assembly"));
+
+		assert_eq!(identify(b"MZ").map(|x| x.0), None);
+		assert_eq!(
+			identify(&input[..0x40]).map(|x| x.0),
+			Some("MS-DOS executable".to_string())
+		);
+	}
+
+	#[test]
+	fn test_identify() {
+		let cases: Vec<(&[u8], &str)> = vec![
+			(
+				b"\xcf\xfa\xed\xfe\x0c\x00\x00\x01\x00\x00\x00\x00\x02\x00\x00\x00",
+				"Mach-O 64-bit executable, arm64",
+			),
+			(
+				b"\xca\xfe\xba\xbe\x00\x00\x00\x02\x01\x00\x00\x07\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x01\x00\x00\x0c",
+				"Mach-O universal binary with 2 architectures: [x86_64, arm64]",
+			),
+			(
+				b"\xca\xfe\xba\xbe\x00\x00\x00\x41",
+				"compiled Java class data, version 65.0 (Java SE 21)",
+			),
+			(
+				b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x03\x20\0\0\x02\x58\x08\x06\0\0\0",
+				"PNG image data, 800 x 600, 8-bit/color RGBA, non-interlaced",
+			),
+			(b"GIF89a\x0a\x00\x14\x00", "GIF image data, version 89a, 10 x 20"),
+			(
+				b"\x1f\x8b\x08\x08\x00\x00\x00\x00\x00\x03a.txt\0",
+				"gzip compressed data, was \"a.txt\", from Unix",
+			),
+			(
+				b"\0asm\x01\0\0\0",
+				"WebAssembly (wasm) binary module version 0x1 (MVP)",
+			),
+		];
+		for (input, description) in cases {
+			assert_eq!(identify(input).unwrap().0, description);
+		}
+
+		let mut input = b"SQLite format 3\0\x10\x00".to_vec();
+		input.resize(100, 0);
+		input[96..100].copy_from_slice(&3045001u32.to_be_bytes());
+		let (description, details) = identify(&input).unwrap();
+		assert_eq!(
+			description,
+			"SQLite 3.x database, last written using SQLite version 3.45.1"
+		);
+		assert!(details.contains(&"page_size: 4096".to_string()));
+	}
+
+	#[test]
+	fn test_filetype() {
+		let run = |input: &str| {
+			let result = filetype(input.as_bytes(), &Options).unwrap();
+			String::from_utf8(result)
+				.unwrap()
+				.lines()
+				.next()
+				.unwrap()
+				.to_string()
+		};
+
+		assert_eq!(run("hello"), "type: ASCII text");
+		assert_eq!(run("héllo"), "type: UTF-8 text");
+		assert_eq!(run("{\"a\":1}"), "type: JSON data");
+		assert_eq!(run("PK\x03\x04"), "type: ZIP data");
+	}
+}
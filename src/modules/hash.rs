@@ -1,12 +1,13 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
+use crate::modules::stream::{Stream, TextChunks};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
-use crc::crc32;
+use clap::{Arg, ArgMatches};
+use crc::{crc32, Hasher32};
 use crypto::blake2b::Blake2b;
-use lazy_static::lazy_static;
+use crypto::digest::Digest as CryptoDigest;
 use ring::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY};
 use sha2::{Digest, Sha224, Sha256, Sha384, Sha512, Sha512Trunc224, Sha512Trunc256};
-use std::collections::HashMap;
 use yogcrypt::sm3::sm3_enc;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
@@ -18,154 +19,32 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 }
 
 struct Algorithm {
-	name: &'static str,
-	help: &'static str,
 	f: AlgorithmF,
 }
 
+type NormalF = fn(data: Vec<u8>) -> Result<Vec<u8>, Error>;
+
+type WithKeyF = fn(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Error>;
+
 enum AlgorithmF {
-	Normal(fn(data: Vec<u8>) -> Result<Vec<u8>, String>),
-	WithKey(fn(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, String>),
+	Normal(NormalF),
+	WithKey(WithKeyF),
 }
 
-lazy_static! {
-	static ref RAW_ALGORITHMS: Vec<Algorithm> = vec![
-		Algorithm {
-			name: "md5",
-			help: "MD5",
-			f: AlgorithmF::Normal(md5),
-		},
-		Algorithm {
-			name: "sha1",
-			help: "SHA-1",
-			f: AlgorithmF::Normal(sha1),
-		},
-		Algorithm {
-			name: "sha2_224",
-			help: "SHA-2 224",
-			f: AlgorithmF::Normal(sha2_224),
-		},
-		Algorithm {
-			name: "sha2_256",
-			help: "SHA-2 256",
-			f: AlgorithmF::Normal(sha2_256),
-		},
-		Algorithm {
-			name: "sha2_384",
-			help: "SHA-2 384",
-			f: AlgorithmF::Normal(sha2_384),
-		},
-		Algorithm {
-			name: "sha2_512",
-			help: "SHA-2 512",
-			f: AlgorithmF::Normal(sha2_512),
-		},
-		Algorithm {
-			name: "sha2_512_224",
-			help: "SHA-2 512 truncate 224",
-			f: AlgorithmF::Normal(sha2_512_224),
-		},
-		Algorithm {
-			name: "sha2_512_256",
-			help: "SHA-2 512 truncate 256",
-			f: AlgorithmF::Normal(sha2_512_256),
-		},
-		Algorithm {
-			name: "sha3_224",
-			help: "SHA-3 224",
-			f: AlgorithmF::Normal(sha3_224),
-		},
-		Algorithm {
-			name: "sha3_256",
-			help: "SHA-3 256",
-			f: AlgorithmF::Normal(sha3_256),
-		},
-		Algorithm {
-			name: "sha3_384",
-			help: "SHA-3 384",
-			f: AlgorithmF::Normal(sha3_384),
-		},
-		Algorithm {
-			name: "sha3_512",
-			help: "SHA-3 512",
-			f: AlgorithmF::Normal(sha3_512),
-		},
-		Algorithm {
-			name: "sha3_k_224",
-			help: "SHA-3 keccak 224",
-			f: AlgorithmF::Normal(sha3_k_224),
-		},
-		Algorithm {
-			name: "sha3_k_256",
-			help: "SHA-3 keccak 256",
-			f: AlgorithmF::Normal(sha3_k_256),
-		},
-		Algorithm {
-			name: "sha3_k_384",
-			help: "SHA-3 keccak 384",
-			f: AlgorithmF::Normal(sha3_k_384),
-		},
-		Algorithm {
-			name: "sha3_k_512",
-			help: "SHA-3 keccak 512",
-			f: AlgorithmF::Normal(sha3_k_512),
-		},
-		Algorithm {
-			name: "ripemd_160",
-			help: "RIPEMD-160",
-			f: AlgorithmF::Normal(ripemd_160),
-		},
-		Algorithm {
-			name: "crc_32",
-			help: "CRC32",
-			f: AlgorithmF::Normal(crc_32),
-		},
-		Algorithm {
-			name: "blake2b_160",
-			help: "Blake2b 160",
-			f: AlgorithmF::WithKey(blake2b_160),
-		},
-		Algorithm {
-			name: "blake2b_256",
-			help: "Blake2b 256",
-			f: AlgorithmF::WithKey(blake2b_256),
-		},
-		Algorithm {
-			name: "blake2b_384",
-			help: "Blake2b 384",
-			f: AlgorithmF::WithKey(blake2b_384),
-		},
-		Algorithm {
-			name: "blake2b_512",
-			help: "Blake2b 512",
-			f: AlgorithmF::WithKey(blake2b_512),
-		},
-		Algorithm {
-			name: "sm3",
-			help: "Chinese National Standard SM3",
-			f: AlgorithmF::Normal(sm3),
-		},
-	];
-	static ref ALGORITHMS: HashMap<&'static str, &'static Algorithm> =
-		RAW_ALGORITHMS.iter().map(|x| (x.name, x)).collect();
-	static ref ALGORITHM_HELP: String = "Hash algorithm\n".to_string()
-		+ &RAW_ALGORITHMS
-			.iter()
-			.map(|a| { format!("{}: {}", a.name, a.help) })
-			.collect::<Vec<String>>()
-			.join("\n");
-}
+// ALGORITHMS, ALGORITHM_NAMES and ALGORITHM_HELP
+include!(concat!(env!("OUT_DIR"), "/hash_algorithms.rs"));
 
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![Command {
-		app: SubCommand::with_name("hash")
+		app: base::sub_command("hash")
 			.about("Hex to hash")
 			.arg(
 				Arg::with_name("ALGORITHM")
 					.long("algo")
 					.short("a")
-					.help(&ALGORITHM_HELP)
+					.help(ALGORITHM_HELP)
 					.takes_value(true)
+					.possible_values(&ALGORITHM_NAMES)
 					.required(true),
 			)
 			.arg(
@@ -176,177 +55,454 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 					.takes_value(true)
 					.required(false),
 			)
-			.arg(Arg::with_name("INPUT").required(false).index(1)),
-		f: hash,
+			.arg(
+				Arg::with_name("BINARY")
+					.long("binary")
+					.short("b")
+					.help("Input binary instead of hex")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("VERIFY")
+					.long("verify")
+					.help("Expected hash, exit with 1 if mismatched")
+					.takes_value(true)
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert_bytes(matches, hash),
+		stream: Some(hash_stream),
 	}]
 }
 
-fn hash(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+/// Options of `hash`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// e.g. `sha2_256`
+	pub algorithm: String,
+	/// Key for Blake2b
+	pub key: Vec<u8>,
+	/// Input binary instead of hex
+	pub binary: bool,
+	/// Expected hash, `OK` if matched, otherwise `Error::Mismatched`
+	pub verify: Option<Vec<u8>>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let algorithm = matches
+			.value_of("ALGORITHM")
+			.ok_or(Error::invalid("algorithm", ""))?;
+		let key: Vec<u8> = match matches.value_of("KEY") {
+			Some(key) => key
+				.parse::<Hex>()
+				.map_err(|_| Error::invalid("key", ""))?
+				.into(),
+			None => vec![],
+		};
+		let binary = matches.is_present("BINARY");
+		let verify = match matches.value_of("VERIFY") {
+			Some(expected) => Some(
+				expected
+					.parse::<Hex>()
+					.map_err(|_| Error::invalid("hash", ""))?
+					.into(),
+			),
+			None => None,
+		};
+
+		base::verbose(matches, 1, || {
+			format!("input format: {}", if binary { "binary" } else { "hex" })
+		});
+		base::verbose(matches, 1, || {
+			match ALGORITHMS.get(algorithm).map(|x| &x.f) {
+				Some(AlgorithmF::WithKey(_)) => {
+					format!("algorithm: {}, key: {} bytes", algorithm, key.len())
+				}
+				_ => format!("algorithm: {}", algorithm),
+			}
+		});
+
+		Ok(Options {
+			algorithm: algorithm.to_string(),
+			key,
+			binary,
+			verify,
+		})
+	}
+}
 
-	let a_name = matches.value_of("ALGORITHM").ok_or("Invalid algorithm")?;
+/// Hex to hash
+pub fn hash(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = if options.binary {
+		input.to_vec()
+	} else {
+		base::text(input)?
+			.trim()
+			.parse::<Hex>()
+			.map_err(|_| Error::failed("Convert failed"))?
+			.into()
+	};
 
+	let a_name = options.algorithm.as_str();
 	let result = match ALGORITHMS.get(a_name) {
 		Some(a) => match a.f {
 			AlgorithmF::Normal(f) => (f)(input)?,
-			AlgorithmF::WithKey(f) => {
-				let key = match matches.value_of("KEY") {
-					Some(key) => key.parse::<Hex>().map_err(|_| "Invalid key")?.into(),
-					None => vec![],
-				};
-				(f)(input, key)?
-			}
+			AlgorithmF::WithKey(f) => (f)(input, options.key.clone())?,
 		},
-		None => return Err("Invalid algorithm".to_string()),
+		None => {
+			return Err(Error::algorithm(
+				a_name,
+				base::did_you_mean(a_name, ALGORITHMS.keys().cloned()),
+			))
+		}
+	};
+
+	let result = verify(options.verify.as_deref(), result)?;
+
+	Ok(result.into_bytes())
+}
+
+/// Hashes with an algorithm without key, e.g. to verify a download
+#[cfg(feature = "web")]
+pub fn digest(name: &str, data: Vec<u8>) -> Result<Vec<u8>, Error> {
+	match ALGORITHMS.get(name).map(|x| &x.f) {
+		Some(AlgorithmF::Normal(f)) => Ok((f)(data)?),
+		_ => Err(Error::UnsupportedAlgorithm {
+			algorithm: name.to_string(),
+			suggestion: String::new(),
+		}),
+	}
+}
+
+/// The hex of the result if not to verify, otherwise OK if matched
+fn verify(expected: Option<&[u8]>, result: Vec<u8>) -> Result<String, Error> {
+	match expected {
+		Some(expected) if expected == &result[..] => Ok("OK".to_string()),
+		Some(_) => Err(Error::Mismatched(Hex::from(result).into())),
+		None => Ok(Hex::from(result).into()),
+	}
+}
+
+fn hash_stream(matches: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	let a_name = matches
+		.value_of("ALGORITHM")
+		.ok_or(Error::invalid("algorithm", ""))?;
+
+	let key = match matches.value_of("KEY") {
+		Some(key) => key
+			.parse::<Hex>()
+			.map_err(|_| Error::invalid("key", ""))?
+			.into(),
+		None => vec![],
 	};
 
-	let result = Hex::from(result).into();
+	let hasher = hasher(a_name, &key)?;
+
+	// None for binary input
+	let input = if matches.is_present("BINARY") {
+		None
+	} else {
+		Some(TextChunks::new(b"0x", 2))
+	};
 
-	Ok(vec![result])
+	let verify = match matches.value_of("VERIFY") {
+		Some(expected) => Some(
+			expected
+				.parse::<Hex>()
+				.map_err(|_| Error::invalid("hash", ""))?
+				.into(),
+		),
+		None => None,
+	};
+
+	Ok(Box::new(HashStream {
+		input,
+		hasher,
+		verify,
+	}))
 }
 
-fn md5(data: Vec<u8>) -> Result<Vec<u8>, String> {
+struct HashStream {
+	input: Option<TextChunks>,
+	hasher: Box<dyn Hasher>,
+	verify: Option<Vec<u8>>,
+}
+
+impl Stream for HashStream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		match &mut self.input {
+			Some(input) => {
+				let chunk = input
+					.next(chunk)
+					.map_err(|_| Error::failed("Convert failed"))?;
+				let chunk = hex::decode(chunk).map_err(|_| Error::failed("Convert failed"))?;
+				self.hasher.update(&chunk);
+			}
+			None => self.hasher.update(chunk),
+		}
+		Ok(vec![])
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		if let Some(input) = &mut self.input {
+			let rest = hex::decode(input.rest()).map_err(|_| Error::failed("Convert failed"))?;
+			self.hasher.update(&rest);
+		}
+		let result = verify(self.verify.as_deref(), self.hasher.finish()?)?;
+		Ok(format!("{}\n", result).into_bytes())
+	}
+}
+
+/// Incremental hash
+trait Hasher {
+	fn update(&mut self, data: &[u8]);
+	fn finish(&mut self) -> Result<Vec<u8>, Error>;
+}
+
+fn hasher(name: &str, key: &[u8]) -> Result<Box<dyn Hasher>, Error> {
+	let hasher: Box<dyn Hasher> = match name {
+		"md5" => Box::new(md5::Context::new()),
+		"sha1" => Box::new(Context::new(&SHA1_FOR_LEGACY_USE_ONLY)),
+		"sha2_224" => Box::new(DigestHasher(Sha224::new())),
+		"sha2_256" => Box::new(DigestHasher(Sha256::new())),
+		"sha2_384" => Box::new(DigestHasher(Sha384::new())),
+		"sha2_512" => Box::new(DigestHasher(Sha512::new())),
+		"sha2_512_224" => Box::new(DigestHasher(Sha512Trunc224::new())),
+		"sha2_512_256" => Box::new(DigestHasher(Sha512Trunc256::new())),
+		"sha3_224" => Box::new(DigestHasher(sha3::Sha3_224::default())),
+		"sha3_256" => Box::new(DigestHasher(sha3::Sha3_256::default())),
+		"sha3_384" => Box::new(DigestHasher(sha3::Sha3_384::default())),
+		"sha3_512" => Box::new(DigestHasher(sha3::Sha3_512::default())),
+		"sha3_k_224" => Box::new(DigestHasher(sha3::Keccak224::default())),
+		"sha3_k_256" => Box::new(DigestHasher(sha3::Keccak256::default())),
+		"sha3_k_384" => Box::new(DigestHasher(sha3::Keccak384::default())),
+		"sha3_k_512" => Box::new(DigestHasher(sha3::Keccak512::default())),
+		"ripemd_160" => Box::new(DigestHasher(ripemd160::Ripemd160::default())),
+		"crc_32" => Box::new(crc32::Digest::new(crc32::IEEE)),
+		"blake2b_160" => Box::new(Blake2b::new_keyed(20, key)),
+		"blake2b_256" => Box::new(Blake2b::new_keyed(32, key)),
+		"blake2b_384" => Box::new(Blake2b::new_keyed(48, key)),
+		"blake2b_512" => Box::new(Blake2b::new_keyed(64, key)),
+		// no incremental API
+		"sm3" => Box::new(BufferHasher(vec![], sm3)),
+		_ => {
+			return Err(Error::algorithm(
+				name,
+				base::did_you_mean(name, ALGORITHMS.keys().cloned()),
+			))
+		}
+	};
+	Ok(hasher)
+}
+
+impl Hasher for md5::Context {
+	fn update(&mut self, data: &[u8]) {
+		self.consume(data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(self.clone().compute().0.to_vec())
+	}
+}
+
+impl Hasher for Context {
+	fn update(&mut self, data: &[u8]) {
+		Context::update(self, data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(self.clone().finish().as_ref().to_vec())
+	}
+}
+
+struct DigestHasher<D>(D);
+
+impl<D: Digest + Clone> Hasher for DigestHasher<D> {
+	fn update(&mut self, data: &[u8]) {
+		self.0.input(data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(self.0.clone().result().to_vec())
+	}
+}
+
+impl Hasher for crc32::Digest {
+	fn update(&mut self, data: &[u8]) {
+		self.write(data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(self.sum32().to_be_bytes().to_vec())
+	}
+}
+
+impl Hasher for Blake2b {
+	fn update(&mut self, data: &[u8]) {
+		self.input(data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		let mut result = vec![0u8; self.output_bytes()];
+		self.result(&mut result);
+		Ok(result)
+	}
+}
+
+/// Buffers the data for the algorithm without an incremental API
+struct BufferHasher(Vec<u8>, fn(Vec<u8>) -> Result<Vec<u8>, Error>);
+
+impl Hasher for BufferHasher {
+	fn update(&mut self, data: &[u8]) {
+		self.0.extend_from_slice(data);
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		(self.1)(self.0.clone())
+	}
+}
+
+fn md5(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	Ok(md5::compute(data).0.to_vec())
 }
 
-fn sha1(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha1(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut context = Context::new(&SHA1_FOR_LEGACY_USE_ONLY);
 	context.update(&data);
 	let result = context.finish().as_ref().to_vec();
 	Ok(result)
 }
 
-fn sha2_224(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_224(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha224::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha2_256(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_256(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha256::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha2_384(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_384(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha384::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha2_512(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_512(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha512::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha2_512_224(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_512_224(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha512Trunc224::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha2_512_256(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha2_512_256(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = Sha512Trunc256::new();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_224(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_224(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Sha3_224::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_256(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_256(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Sha3_256::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_384(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_384(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Sha3_384::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_512(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_512(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Sha3_512::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_k_224(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_k_224(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Keccak224::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_k_256(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_k_256(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Keccak256::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_k_384(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_k_384(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Keccak384::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn sha3_k_512(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sha3_k_512(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = sha3::Keccak512::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn ripemd_160(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn ripemd_160(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut hasher = ripemd160::Ripemd160::default();
 	hasher.input(data);
 	let result = hasher.result().to_vec();
 	Ok(result)
 }
 
-fn crc_32(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn crc_32(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let result = crc32::checksum_ieee(&data);
 	let result = result.to_be_bytes().to_vec();
 
 	Ok(result)
 }
 
-fn blake2b_160(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, String> {
+fn blake2b_160(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	blake2b(data, 20, key)
 }
 
-fn blake2b_256(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, String> {
+fn blake2b_256(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	blake2b(data, 32, key)
 }
 
-fn blake2b_384(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, String> {
+fn blake2b_384(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	blake2b(data, 48, key)
 }
 
-fn blake2b_512(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, String> {
+fn blake2b_512(data: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	blake2b(data, 64, key)
 }
 
-fn blake2b(data: Vec<u8>, size: usize, key: Vec<u8>) -> Result<Vec<u8>, String> {
+fn blake2b(data: Vec<u8>, size: usize, key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mut result = vec![0u8; size];
 
 	Blake2b::blake2b(&mut result, &data, &key);
@@ -354,13 +510,12 @@ fn blake2b(data: Vec<u8>, size: usize, key: Vec<u8>) -> Result<Vec<u8>, String>
 	Ok(result)
 }
 
-fn sm3(data: Vec<u8>) -> Result<Vec<u8>, String> {
+fn sm3(data: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let result = sm3_enc(&data);
 
 	let result: Vec<u8> = result
 		.iter()
-		.map(|x| x.to_be_bytes().to_vec())
-		.flatten()
+		.flat_map(|x| x.to_be_bytes().to_vec())
 		.collect();
 
 	Ok(result)
@@ -590,6 +745,22 @@ mod cases {
 					 is_test: true,
 					 since: "0.7.0".to_string(),
 				 },
+				 Case {
+					 desc: "Binary input".to_string(),
+					 input: vec!["-a", "md5", "-b", "abc"].into_iter().map(Into::into).collect(),
+					 output: vec!["0x900150983cd24fb0d6963f7d28e17f72"].into_iter().map(Into::into).collect(),
+					 is_example: true,
+					 is_test: true,
+					 since: "0.10.0".to_string(),
+				 },
+				 Case {
+					 desc: "Verify".to_string(),
+					 input: vec!["-a", "md5", "--verify", "0x900150983cd24fb0d6963f7d28e17f72", "0x616263"].into_iter().map(Into::into).collect(),
+					 output: vec!["OK"].into_iter().map(Into::into).collect(),
+					 is_example: true,
+					 is_test: true,
+					 since: "0.10.0".to_string(),
+				 },
 			 ]),
 		].into_iter().collect()
 	}
@@ -604,4 +775,20 @@ mod tests {
 	fn test_cases() {
 		test_module(module());
 	}
+
+	#[test]
+	fn test_hasher() {
+		let data = (0..1000u32).map(|x| x as u8).collect::<Vec<_>>();
+		for (name, a) in ALGORITHMS.entries() {
+			let expected = match a.f {
+				AlgorithmF::Normal(f) => f(data.clone()),
+				AlgorithmF::WithKey(f) => f(data.clone(), vec![1, 2, 3]),
+			};
+			let mut hasher = hasher(name, &[1, 2, 3]).unwrap();
+			for chunk in data.chunks(7) {
+				hasher.update(chunk);
+			}
+			assert_eq!(hasher.finish(), expected, "Test: {}", name);
+		}
+	}
 }
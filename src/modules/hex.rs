@@ -1,9 +1,9 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
+use crate::modules::stream::{Stream, TextChunks};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::ArgMatches;
 use regex::Regex;
-use std::io;
-use std::io::Write;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
@@ -16,90 +16,144 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("h2s")
+			app: base::sub_command("h2s")
 				.about("Convert hex to UTF-8 string")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2s,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, h2s),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("s2h")
+			app: base::sub_command("s2h")
 				.about("Convert UTF-8 string to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: s2h,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, s2h),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("h2b")
+			app: base::sub_command("h2b")
 				.about("Convert hex to binary")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2b,
+				.args(&base::input_args()),
+			f: |matches| base::convert_raw(matches, h2b),
+			stream: Some(h2b_stream),
 		},
 		Command {
-			app: SubCommand::with_name("b2h")
+			app: base::sub_command("b2h")
 				.about("Convert binary to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: b2h,
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes(matches, b2h),
+			stream: Some(b2h_stream),
 		},
 		Command {
-			app: SubCommand::with_name("h2a")
+			app: base::sub_command("h2a")
 				.about("Convert hex to byte array")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: h2a,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, h2a),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("a2h")
+			app: base::sub_command("a2h")
 				.about("Convert byte array to hex")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: a2h,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, a2h),
+			stream: None,
 		},
 	]
 }
 
-fn h2s(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
 
-	let result = String::from_utf8(input).map_err(|_| "Not UTF-8")?;
-
-	Ok(vec![result])
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
 }
 
-fn s2h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches).map_err(|_| "Not UTF-8")?;
+/// Convert hex to UTF-8 string
+pub fn h2s(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = h2b(input, &Options)?;
 
-	let input = input.as_bytes().to_vec();
-	let result: String = Hex::from(input).into();
+	String::from_utf8(result)
+		.map(String::into_bytes)
+		.map_err(|_| Error::failed("Not UTF-8"))
+}
 
-	Ok(vec![result])
+/// Convert UTF-8 string to hex
+pub fn s2h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result: String = Hex::from(input.to_vec()).into();
+
+	Ok(result.into_bytes())
 }
 
-fn h2b_inner(matches: &ArgMatches) -> Result<Vec<u8>, String> {
-	let input = base::input_string(matches)?;
-	let result: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+/// Convert hex to binary
+pub fn h2b(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+	let result: Vec<u8> = input
+		.parse::<Hex>()
+		.map_err(|_| Error::failed("Convert failed"))?
+		.into();
 
 	Ok(result)
 }
 
-fn h2b(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let result = h2b_inner(matches)?;
+/// Convert binary to hex
+pub fn b2h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	s2h(input, &Options)
+}
+
+fn h2b_stream(_: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	Ok(Box::new(H2bStream(TextChunks::new(b"0x", 2))))
+}
 
-	io::stdout()
-		.write_all(&result)
-		.map_err(|_| "Convert failed")?;
+struct H2bStream(TextChunks);
 
-	Ok(vec![])
+impl Stream for H2bStream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		let chunk = self
+			.0
+			.next(chunk)
+			.map_err(|_| Error::failed("Convert failed"))?;
+		hex::decode(chunk).map_err(|_| Error::failed("Convert failed"))
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		hex::decode(self.0.rest()).map_err(|_| Error::failed("Convert failed"))
+	}
 }
 
-fn b2h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_bytes(matches)?;
+fn b2h_stream(_: &ArgMatches) -> Result<Box<dyn Stream>, Error> {
+	Ok(Box::new(B2hStream { started: false }))
+}
 
-	let result: String = Hex::from(input).into();
+struct B2hStream {
+	started: bool,
+}
 
-	Ok(vec![result])
+impl B2hStream {
+	fn prefix(&mut self) -> &'static str {
+		if self.started {
+			""
+		} else {
+			self.started = true;
+			"0x"
+		}
+	}
 }
 
-fn h2a(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Convert failed")?.into();
+impl Stream for B2hStream {
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		Ok(format!("{}{}", self.prefix(), hex::encode(chunk)).into_bytes())
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(format!("{}\n", self.prefix()).into_bytes())
+	}
+}
+
+/// Convert hex to byte array
+pub fn h2a(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = h2b(input, &Options)?;
 
 	let result = input
 		.into_iter()
@@ -108,11 +162,12 @@ fn h2a(matches: &ArgMatches) -> Result<Vec<String>, String> {
 		.join(", ");
 	let result = format!("[{}]", result);
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn a2h(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches).map_err(|_| "Not UTF-8")?;
+/// Convert byte array to hex
+pub fn a2h(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
 	let input = input.trim_start_matches("[").trim_end_matches("]");
 	let input = Regex::new(", *")
@@ -123,10 +178,9 @@ fn a2h(matches: &ArgMatches) -> Result<Vec<String>, String> {
 		.into_iter()
 		.map(|x| x.parse::<u8>())
 		.collect::<Result<Vec<_>, _>>()
-		.map_err(|_| "Invalid byte array")?;
+		.map_err(|_| Error::invalid("byte array", ""))?;
 
-	let result: String = Hex::from(input).into();
-	Ok(vec![result])
+	s2h(&input, &Options)
 }
 
 mod cases {
@@ -225,9 +279,9 @@ mod tests {
 
 	#[test]
 	fn test_h2b() {
-		let app = &commands()[1].app;
-
-		let matches = app.clone().get_matches_from(vec!["h2b", "0x61626364"]);
-		assert_eq!(h2b_inner(&matches), Ok(vec![0x61, 0x62, 0x63, 0x64]));
+		assert_eq!(
+			h2b(b"0x61626364", &Options),
+			Ok(vec![0x61, 0x62, 0x63, 0x64])
+		);
 	}
 }
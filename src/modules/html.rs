@@ -1,10 +1,13 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
 use clap::{Arg, ArgMatches, SubCommand};
 use escaper;
+use scraper::{ElementRef, Html, Node};
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
-		desc: "HTML entity encode / decode".to_string(),
+		desc: "HTML entity encode / decode, HTML to text / markdown".to_string(),
 		commands: commands(),
 		get_cases: cases::cases,
 	}
@@ -13,34 +16,366 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("he")
+			app: base::sub_command("he")
 				.about("HTML entity encode")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: he,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, he),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("hd")
+			app: base::sub_command("hd")
 				.about("HTML entity decode")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: hd,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, hd),
+			stream: None,
+		},
+		Command {
+			app: SubCommand::with_name("html_text")
+				.args(&base::output_args())
+				.arg(base::raw_arg())
+				.about("HTML to readable text or markdown, keeping headings, links, lists and tables")
+				.arg(
+					Arg::with_name("FORMAT")
+						.long("format")
+						.short("F")
+						.help("Output format\ntext: plain text, links as text (URL)\nmarkdown: CommonMark")
+						.takes_value(true)
+						.possible_values(&["text", "markdown"])
+						.default_value("text")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, html_text),
+			stream: None,
 		},
 	]
 }
 
-fn he(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// The output format of `html_text`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub markdown: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			markdown: matches.value_of("FORMAT") == Some("markdown"),
+		})
+	}
+}
+
+/// HTML entity encode
+pub fn he(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = escaper::encode_minimal(base::text(input)?);
+
+	Ok(result.into_bytes())
+}
+
+/// HTML entity decode
+pub fn hd(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result =
+		escaper::decode_html(base::text(input)?).map_err(|_| Error::failed("Decode failed"))?;
 
-	let result = escaper::encode_minimal(&input);
+	Ok(result.into_bytes())
+}
+
+/// HTML to readable text or markdown, keeping headings, links, lists and tables
+pub fn html_text(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let html = Html::parse_document(base::text(input)?);
+	let converter = Converter {
+		markdown: options.markdown,
+	};
+
+	let result = converter.blocks(html.root_element()).join("\n\n");
+
+	Ok(result.into_bytes())
+}
 
-	Ok(vec![result])
+/// Not rendered at all
+const SKIPPED: &[&str] = &[
+	"head", "script", "style", "noscript", "template", "svg", "iframe", "object", "select",
+];
+
+const BLOCKS: &[&str] = &[
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"body",
+	"caption",
+	"dd",
+	"details",
+	"dialog",
+	"div",
+	"dl",
+	"dt",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hgroup",
+	"hr",
+	"html",
+	"legend",
+	"li",
+	"main",
+	"nav",
+	"ol",
+	"p",
+	"pre",
+	"section",
+	"summary",
+	"table",
+	"tbody",
+	"td",
+	"tfoot",
+	"th",
+	"thead",
+	"tr",
+	"ul",
+];
+
+struct Converter {
+	markdown: bool,
 }
 
-fn hd(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+impl Converter {
+	/// The paragraphs, headings, lists, ... of the element
+	fn blocks(&self, element: ElementRef) -> Vec<String> {
+		let mut blocks = vec![];
+		let mut inline = String::new();
+		let flush = |inline: &mut String, blocks: &mut Vec<String>| {
+			let paragraph = inline
+				.lines()
+				.map(str::trim)
+				.filter(|x| !x.is_empty())
+				.collect::<Vec<_>>()
+				.join("\n");
+			if !paragraph.is_empty() {
+				blocks.push(paragraph);
+			}
+			inline.clear();
+		};
+
+		for child in element.children() {
+			match child.value() {
+				Node::Text(text) => inline.push_str(&collapse(text)),
+				Node::Element(e) if BLOCKS.contains(&e.name()) => {
+					flush(&mut inline, &mut blocks);
+					let child = ElementRef::wrap(child).expect("element");
+					let block = self.block(child);
+					if !block.is_empty() {
+						blocks.push(block);
+					}
+				}
+				Node::Element(_) => {
+					let child = ElementRef::wrap(child).expect("element");
+					inline.push_str(&self.inline(child));
+				}
+				_ => (),
+			}
+		}
+		flush(&mut inline, &mut blocks);
+		blocks
+	}
+
+	fn block(&self, element: ElementRef) -> String {
+		let name = element.value().name();
+		match name {
+			"h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+				let text = self.inline_text(element);
+				match self.markdown {
+					true => {
+						let level = name[1..].parse().unwrap_or(1);
+						format!("{} {}", "#".repeat(level), text)
+					}
+					false => text,
+				}
+			}
+			"pre" => {
+				let text = element.text().collect::<String>();
+				let text = text.strip_prefix('\n').unwrap_or(&text).trim_end();
+				match self.markdown {
+					true => {
+						let language = element
+							.children()
+							.filter_map(ElementRef::wrap)
+							.filter_map(|x| x.value().attr("class"))
+							.flat_map(str::split_whitespace)
+							.find_map(|x| x.strip_prefix("language-"))
+							.unwrap_or_default();
+						format!("```{}\n{}\n```", language, text)
+					}
+					false => text.to_string(),
+				}
+			}
+			"blockquote" => {
+				let prefix = if self.markdown { "> " } else { "  " };
+				prefix_lines(&self.blocks(element).join("\n\n"), prefix, prefix)
+			}
+			"ul" | "ol" => {
+				let mut number = element
+					.value()
+					.attr("start")
+					.and_then(|x| x.parse::<usize>().ok())
+					.unwrap_or(1);
+				element
+					.children()
+					.filter_map(ElementRef::wrap)
+					.filter(|x| x.value().name() == "li")
+					.map(|item| {
+						let marker = match name {
+							"ol" => format!("{}. ", number),
+							_ => "- ".to_string(),
+						};
+						number += 1;
+						let indent = " ".repeat(marker.len());
+						prefix_lines(&self.blocks(item).join("\n"), &marker, &indent)
+					})
+					.collect::<Vec<_>>()
+					.join("\n")
+			}
+			"hr" => "---".to_string(),
+			"table" => self.table(element),
+			_ => self.blocks(element).join("\n\n"),
+		}
+	}
+
+	/// Rows of cells separated by |, the first row as the header in markdown
+	fn table(&self, element: ElementRef) -> String {
+		let rows = element
+			.descendants()
+			.filter_map(ElementRef::wrap)
+			.filter(|x| x.value().name() == "tr")
+			.map(|row| {
+				row.children()
+					.filter_map(ElementRef::wrap)
+					.filter(|x| matches!(x.value().name(), "td" | "th"))
+					.map(|cell| self.inline_text(cell).replace('\n', " "))
+					.collect::<Vec<_>>()
+			})
+			.filter(|x| !x.is_empty())
+			.collect::<Vec<_>>();
+
+		let mut lines = vec![];
+		for (i, row) in rows.iter().enumerate() {
+			match self.markdown {
+				true => {
+					lines.push(format!("| {} |", row.join(" | ")));
+					if i == 0 {
+						lines.push(format!("|{}", " --- |".repeat(row.len())));
+					}
+				}
+				false => lines.push(row.join(" | ")),
+			}
+		}
+		lines.join("\n")
+	}
+
+	/// The text of the element with line breaks and surrounding white space trimmed
+	fn inline_text(&self, element: ElementRef) -> String {
+		self.inline_content(element)
+			.split_whitespace()
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+
+	fn inline_content(&self, element: ElementRef) -> String {
+		element
+			.children()
+			.map(|child| match child.value() {
+				Node::Text(text) => collapse(text),
+				Node::Element(_) => self.inline(ElementRef::wrap(child).expect("element")),
+				_ => String::new(),
+			})
+			.collect()
+	}
+
+	fn inline(&self, element: ElementRef) -> String {
+		let name = element.value().name();
+		if SKIPPED.contains(&name) {
+			return String::new();
+		}
+		let content = || self.inline_content(element);
+		let wrap = |mark: &str| {
+			let content = content();
+			match (self.markdown, content.trim().is_empty()) {
+				(true, false) => format!("{}{}{}", mark, content.trim(), mark),
+				_ => content,
+			}
+		};
+		match name {
+			"br" => "\n".to_string(),
+			"a" => {
+				let text = content().trim().to_string();
+				let href = element.value().attr("href").unwrap_or_default();
+				if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+					text
+				} else if self.markdown {
+					format!("[{}]({})", text, href)
+				} else if text.is_empty() || text == href {
+					href.to_string()
+				} else {
+					format!("{} ({})", text, href)
+				}
+			}
+			"img" => {
+				let alt = element.value().attr("alt").unwrap_or_default();
+				match (self.markdown, element.value().attr("src")) {
+					(true, Some(src)) => format!("![{}]({})", alt, src),
+					_ => alt.to_string(),
+				}
+			}
+			"strong" | "b" => wrap("**"),
+			"em" | "i" => wrap("*"),
+			"del" | "s" | "strike" => wrap("~~"),
+			"code" | "kbd" | "samp" => wrap("`"),
+			_ if BLOCKS.contains(&name) => format!(" {} ", content()),
+			_ => content(),
+		}
+	}
+}
 
-	let result = escaper::decode_html(&input).map_err(|_| "Decode failed")?;
+/// Runs of white space as a single space, as browsers render text
+fn collapse(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut space = false;
+	for c in text.chars() {
+		if c.is_whitespace() {
+			if !space {
+				result.push(' ');
+			}
+			space = true;
+		} else {
+			result.push(c);
+			space = false;
+		}
+	}
+	result
+}
 
-	Ok(vec![result])
+/// The first line after `first`, the others after `rest`
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+	text.lines()
+		.enumerate()
+		.map(|(i, line)| {
+			let prefix = if i == 0 { first } else { rest };
+			match line.is_empty() {
+				true => prefix.trim_end().to_string(),
+				false => format!("{}{}", prefix, line),
+			}
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
 }
 
 mod cases {
@@ -71,6 +406,49 @@ mod cases {
 					since: "0.4.0".to_string(),
 				}],
 			),
+			(
+				"html_text",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							r#"'<h1>Title</h1><p>Some <b>bold</b> text and <a href="https://example.com">a link</a></p><ul><li>one</li><li>two</li></ul>'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"Title", "", "Some bold text and a link (https://example.com)", "", "- one", "- two",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Markdown".to_string(),
+						input: vec![
+							"-F",
+							"markdown",
+							r#"'<h1>Title</h1><p>Some <b>bold</b> text and <a href="https://example.com">a link</a></p><ul><li>one</li><li>two</li></ul>'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"# Title", "", "Some **bold** text and [a link](https://example.com)", "", "- one", "- two",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
 		]
 		.into_iter()
 		.collect()
@@ -86,4 +464,29 @@ mod tests {
 	fn test_cases() {
 		test_module(module());
 	}
+
+	#[test]
+	fn test_html_text() {
+		let html = Html::parse_document(
+			r#"<head><title>x</title><script>x()</script></head>
+			<blockquote><p>a<br>b</p></blockquote>
+			<ol start="2"><li><p>c</p><ul><li>d</li></ul></li></ol>
+			<pre><code class="language-rust">fn main() {
+    x
+}</code></pre>
+			<table><tr><th>h1</th><th>h2</th></tr><tr><td>1</td><td><i>2</i></td></tr></table>"#,
+		);
+
+		let markdown = Converter { markdown: true };
+		assert_eq!(
+			markdown.blocks(html.root_element()).join("\n\n"),
+			"> a\n> b\n\n2. c\n   - d\n\n```rust\nfn main() {\n    x\n}\n```\n\n| h1 | h2 |\n| --- | --- |\n| 1 | *2* |"
+		);
+
+		let text = Converter { markdown: false };
+		assert_eq!(
+			text.blocks(html.root_element()).join("\n\n"),
+			"  a\n  b\n\n2. c\n   - d\n\nfn main() {\n    x\n}\n\nh1 | h2\n1 | 2"
+		);
+	}
 }
@@ -0,0 +1,747 @@
+use crate::error::Error;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use url::Url;
+
+const MAX_REDIRECTS: usize = 10;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "HTTP client".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("http")
+			.about("HTTP request, the body is read from INPUT, --file or stdin for post, put and patch")
+			.arg(
+				Arg::with_name("METHOD")
+					.possible_values(&["get", "post", "put", "patch", "delete", "head"])
+					.case_insensitive(true)
+					.required(true)
+					.index(1),
+			)
+			.arg(Arg::with_name("URL").required(true).index(2))
+			.arg(
+				Arg::with_name("HEADER")
+					.long("header")
+					.short("H")
+					.help("Request header as name: value, repeat for several")
+					.takes_value(true)
+					.multiple(true)
+					.number_of_values(1)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("SHOW")
+					.long("show")
+					.short("s")
+					.help("Sections of the response, comma separated\nstatus: status line\nheaders: response headers\nbody: response body\nbody by default, status,headers for head")
+					.takes_value(true)
+					.possible_values(&["status", "headers", "body"])
+					.use_delimiter(true)
+					.multiple(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("TIMEOUT")
+					.long("timeout")
+					.short("t")
+					.help("Timeout in seconds")
+					.takes_value(true)
+					.default_value("30")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("LOCATION")
+					.long("location")
+					.short("L")
+					.help("Follow redirects")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("INSECURE")
+					.long("insecure")
+					.short("k")
+					.help("Skip TLS certificate verification")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("FAIL")
+					.long("fail")
+					.help("Fail on HTTP errors (status 400 or above)")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("INPUT")
+					.help("Request body")
+					.required(false)
+					.index(3),
+			)
+			.args(&base::file_args()),
+		f: http,
+		stream: None,
+	}]
+}
+
+/// Plain TCP or TLS
+pub trait Connection: Read + Write {
+	/// Of the underlying TCP stream
+	fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Connection for TcpStream {
+	fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		TcpStream::set_read_timeout(self, timeout)
+	}
+}
+
+impl Connection for SslStream<TcpStream> {
+	fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		self.get_ref().set_read_timeout(timeout)
+	}
+}
+
+impl Connection for Box<dyn Connection> {
+	fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		(**self).set_read_timeout(timeout)
+	}
+}
+
+struct Response {
+	status_line: String,
+	status: u16,
+	headers: Vec<(String, String)>,
+	body: Vec<u8>,
+	/// The end of the body is known without the server closing the connection
+	delimited: bool,
+}
+
+impl Response {
+	fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(k, _)| k.eq_ignore_ascii_case(name))
+			.map(|(_, v)| v.as_str())
+	}
+}
+
+fn http(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let mut method = matches.value_of("METHOD").unwrap_or("get").to_uppercase();
+	let mut url = parse_url(matches.value_of("URL").unwrap_or_default())?;
+
+	let mut body = match method.as_str() {
+		"POST" | "PUT" | "PATCH" => {
+			// nothing to read if no body is given in a terminal
+			let no_body = !matches.is_present("INPUT")
+				&& !matches.is_present("INPUT_FILE")
+				&& atty::is(atty::Stream::Stdin);
+			if no_body {
+				vec![]
+			} else {
+				base::input_bytes(matches)?
+			}
+		}
+		_ => vec![],
+	};
+
+	let mut headers = headers(matches)?;
+
+	let timeout = matches
+		.value_of("TIMEOUT")
+		.unwrap_or("30")
+		.parse::<f64>()
+		.ok()
+		.filter(|x| *x > 0.0)
+		.map(Duration::from_secs_f64)
+		.ok_or(Error::invalid("timeout", ""))?;
+	let insecure = matches.is_present("INSECURE");
+
+	let mut redirects = 0;
+	let response = loop {
+		base::verbose(matches, 1, || format!("request: {} {}", method, url));
+		let mut connection = connect(&url, timeout, insecure)?;
+		let request = request(&method, &url, &headers, &body);
+		connection
+			.write_all(&request)
+			.map_err(|e| failed(&url, e))?;
+		let response = parse_response(&read_all(&mut connection, &url, &method)?, &method)?;
+
+		let location = response.header("Location").map(|x| x.to_string());
+		match location {
+			Some(location)
+				if matches.is_present("LOCATION") && (300..400).contains(&response.status) =>
+			{
+				redirects += 1;
+				if redirects > MAX_REDIRECTS {
+					return Err(Error::Failed(format!(
+						"Too many redirects: {}",
+						MAX_REDIRECTS
+					)));
+				}
+				let next = url
+					.join(&location)
+					.map_err(|e| Error::invalid("redirect", format!("{}: {}", location, e)))?;
+				// like browsers, 301 and 302 also change the method to get, 307 and 308 keep it
+				if [301, 302, 303].contains(&response.status) && method != "HEAD" {
+					method = "GET".to_string();
+					body = vec![];
+				}
+				// credentials are not sent to another origin
+				if next.origin() != url.origin() {
+					headers.retain(|(k, _)| {
+						!k.eq_ignore_ascii_case("Authorization")
+							&& !k.eq_ignore_ascii_case("Cookie")
+					});
+				}
+				url = next;
+			}
+			_ => break response,
+		}
+	};
+
+	if matches.is_present("FAIL") && response.status >= 400 {
+		return Err(Error::Failed(format!(
+			"HTTP error: {}",
+			response.status_line
+		)));
+	}
+
+	let default_show: &[&str] = if method == "HEAD" {
+		&["status", "headers"]
+	} else {
+		&["body"]
+	};
+	let show = matches
+		.values_of("SHOW")
+		.map(|x| x.collect::<Vec<_>>())
+		.unwrap_or_else(|| default_show.to_vec());
+
+	// written as is, binary bodies would be broken by line based output
+	if show == ["body"] && std::str::from_utf8(&response.body).is_err() {
+		base::output_bytes(matches, &response.body)?;
+		return Ok(vec![]);
+	}
+
+	let mut sections = vec![];
+	if show.contains(&"status") {
+		sections.push(vec![response.status_line.clone()]);
+	}
+	if show.contains(&"headers") {
+		sections.push(
+			response
+				.headers
+				.iter()
+				.map(|(k, v)| format!("{}: {}", k, v))
+				.collect(),
+		);
+	}
+	if show.contains(&"body") {
+		sections.push(
+			String::from_utf8_lossy(&response.body)
+				.lines()
+				.map(Into::into)
+				.collect(),
+		);
+	}
+
+	// sections are separated by an empty line
+	let result = sections
+		.into_iter()
+		.filter(|x| !x.is_empty())
+		.collect::<Vec<_>>()
+		.join(&String::new());
+	Ok(result)
+}
+
+/// http and https only, e.g. not ftp
+fn parse_url(url: &str) -> Result<Url, Error> {
+	let url = Url::parse(url).map_err(|e| Error::invalid("URL", format!("{}: {}", url, e)))?;
+	match url.scheme() {
+		"http" | "https" => Ok(url),
+		scheme => Err(Error::invalid(
+			"URL",
+			format!("unsupported scheme {}", scheme),
+		)),
+	}
+}
+
+/// TCP with TLS for https, the certificate is verified unless insecure
+pub fn connect(url: &Url, timeout: Duration, insecure: bool) -> Result<Box<dyn Connection>, Error> {
+	let host = url.host_str().ok_or(Error::invalid("URL", "no host"))?;
+	let port = url
+		.port_or_known_default()
+		.ok_or(Error::invalid("URL", "no port"))?;
+
+	let stream = tcp_connect(host, port, timeout).map_err(|e| failed(url, e))?;
+
+	match url.scheme() {
+		"https" | "wss" => {
+			let mut builder =
+				SslConnector::builder(SslMethod::tls()).map_err(|e| Error::invalid("TLS", e))?;
+			if insecure {
+				builder.set_verify(SslVerifyMode::NONE);
+			}
+			// brackets of IPv6 hosts are not part of the server name
+			let host = host.trim_start_matches('[').trim_end_matches(']');
+			let stream = builder
+				.build()
+				.connect(host, stream)
+				.map_err(|e| Error::Failed(format!("TLS handshake failed: {}: {}", url, e)))?;
+			Ok(Box::new(stream))
+		}
+		_ => Ok(Box::new(stream)),
+	}
+}
+
+/// The HEADER options as name and value
+pub fn headers(matches: &ArgMatches) -> Result<Vec<(String, String)>, Error> {
+	matches
+		.values_of("HEADER")
+		.into_iter()
+		.flatten()
+		.map(|x| {
+			let mut parts = x.splitn(2, ':');
+			match (parts.next(), parts.next()) {
+				(Some(k), Some(v)) if !k.trim().is_empty() => {
+					Ok((k.trim().to_string(), v.trim().to_string()))
+				}
+				_ => Err(Error::invalid("header", x)),
+			}
+		})
+		.collect()
+}
+
+/// The first address of the host accepting the connection
+pub fn tcp_connect(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+	let host = host.trim_start_matches('[').trim_end_matches(']');
+	let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no address");
+	for address in (host, port).to_socket_addrs()? {
+		match TcpStream::connect_timeout(&address, timeout) {
+			Ok(stream) => {
+				stream.set_read_timeout(Some(timeout))?;
+				stream.set_write_timeout(Some(timeout))?;
+				return Ok(stream);
+			}
+			Err(e) => last_error = e,
+		}
+	}
+	Err(last_error)
+}
+
+fn failed(url: &Url, e: io::Error) -> Error {
+	Error::Failed(format!("Request failed: {}: {}", url, e))
+}
+
+fn request(method: &str, url: &Url, headers: &[(String, String)], body: &[u8]) -> Vec<u8> {
+	let mut target = url.path().to_string();
+	if let Some(query) = url.query() {
+		target.push('?');
+		target.push_str(query);
+	}
+	let host = match url.port() {
+		Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+		None => url.host_str().unwrap_or_default().to_string(),
+	};
+
+	let has = |name: &str| headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+	let mut lines = vec![format!("{} {} HTTP/1.1", method, target)];
+	if !has("Host") {
+		lines.push(format!("Host: {}", host));
+	}
+	if !has("User-Agent") {
+		lines.push(format!("User-Agent: dtool/{}", env!("CARGO_PKG_VERSION")));
+	}
+	if !has("Accept") {
+		lines.push("Accept: */*".to_string());
+	}
+	if !body.is_empty() || ["POST", "PUT", "PATCH"].contains(&method) {
+		lines.push(format!("Content-Length: {}", body.len()));
+	}
+	lines.push("Connection: close".to_string());
+	lines.extend(headers.iter().map(|(k, v)| format!("{}: {}", k, v)));
+
+	let mut request = lines.join("\r\n").into_bytes();
+	request.extend(b"\r\n\r\n");
+	request.extend(body);
+	request
+}
+
+/// Until the server closes the connection, some TLS servers close without notifying,
+/// so an error after a complete response is ignored, any other error is returned
+fn read_all(
+	connection: &mut Box<dyn Connection>,
+	url: &Url,
+	method: &str,
+) -> Result<Vec<u8>, Error> {
+	let mut data = vec![];
+	let mut buffer = [0u8; 8192];
+	loop {
+		match connection.read(&mut buffer) {
+			Ok(0) => break,
+			Ok(n) => data.extend_from_slice(&buffer[..n]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => {
+				let complete = parse_response(&data, method)
+					.map(|x| x.delimited)
+					.unwrap_or(false);
+				if complete {
+					break;
+				}
+				return Err(failed(url, e));
+			}
+		}
+	}
+	Ok(data)
+}
+
+fn parse_response(data: &[u8], method: &str) -> Result<Response, Error> {
+	let invalid = || Error::invalid("response", "not HTTP");
+
+	let head_end = data
+		.windows(4)
+		.position(|x| x == b"\r\n\r\n")
+		.ok_or_else(invalid)?;
+	let head = String::from_utf8_lossy(&data[..head_end]);
+	let mut lines = head.split("\r\n");
+
+	let status_line = lines.next().ok_or_else(invalid)?.to_string();
+	if !status_line.starts_with("HTTP/") {
+		return Err(invalid());
+	}
+	let status = status_line
+		.split_whitespace()
+		.nth(1)
+		.and_then(|x| x.parse::<u16>().ok())
+		.ok_or_else(invalid)?;
+
+	let headers = lines
+		.filter_map(|line| {
+			let mut parts = line.splitn(2, ':');
+			Some((
+				parts.next()?.trim().to_string(),
+				parts.next()?.trim().to_string(),
+			))
+		})
+		.collect::<Vec<_>>();
+
+	let mut response = Response {
+		status_line,
+		status,
+		headers,
+		body: vec![],
+		delimited: true,
+	};
+
+	let body = &data[head_end + 4..];
+	let no_body = method == "HEAD" || status == 204 || status == 304 || status < 200;
+	response.body = if no_body {
+		vec![]
+	} else if response
+		.header("Transfer-Encoding")
+		.map(|x| x.to_lowercase().contains("chunked"))
+		.unwrap_or(false)
+	{
+		dechunk(body)?
+	} else {
+		match response
+			.header("Content-Length")
+			.and_then(|x| x.parse::<usize>().ok())
+		{
+			Some(len) if len > body.len() => {
+				return Err(Error::invalid(
+					"response",
+					format!(
+						"body of {} bytes shorter than Content-Length {}",
+						body.len(),
+						len
+					),
+				))
+			}
+			Some(len) => body[..len].to_vec(),
+			None => {
+				response.delimited = false;
+				body.to_vec()
+			}
+		}
+	};
+
+	Ok(response)
+}
+
+/// Chunk sizes in hex, each followed by the chunk, until the last chunk of size 0
+fn dechunk(mut data: &[u8]) -> Result<Vec<u8>, Error> {
+	let invalid = || Error::invalid("response", "broken chunked body");
+
+	let mut body = vec![];
+	loop {
+		let line_end = data
+			.windows(2)
+			.position(|x| x == b"\r\n")
+			.ok_or_else(invalid)?;
+		let size = String::from_utf8_lossy(&data[..line_end]);
+		// chunk extensions after ; are ignored
+		let size = size.split(';').next().unwrap_or_default().trim();
+		let size = usize::from_str_radix(size, 16).map_err(|_| invalid())?;
+		data = &data[line_end + 2..];
+		if size == 0 {
+			return Ok(body);
+		}
+		if data.len() < size {
+			return Err(invalid());
+		}
+		body.extend_from_slice(&data[..size]);
+		data = data.get(size + 2..).unwrap_or_default();
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"http",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec![
+						"get",
+						"https://httpbin.org/get?a=1",
+						"-H",
+						"'Accept: application/json'",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						"{",
+						r#"  "args": {"#,
+						r#"    "a": "1""#,
+						"  },",
+						r#"  ..."#,
+						"}",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Status and headers".to_string(),
+					input: vec!["head", "https://example.com"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["HTTP/1.1 200 OK", "", "Content-Type: text/html", "..."]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Post".to_string(),
+					input: vec![
+						"post",
+						"https://httpbin.org/post",
+						"-H",
+						"'Content-Type: application/json'",
+						"-s",
+						"status",
+						r#"'{"a":1}'"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec!["HTTP/1.1 200 OK"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+	use std::net::TcpListener;
+	use std::thread;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_request() {
+		let url = Url::parse("http://example.com:8080/a?b=1").unwrap();
+		let headers = vec![("X-A".to_string(), "1".to_string())];
+		assert_eq!(
+			String::from_utf8(request("POST", &url, &headers, b"{}")).unwrap(),
+			format!(
+				"POST /a?b=1 HTTP/1.1\r\nHost: example.com:8080\r\nUser-Agent: dtool/{}\r\nAccept: */*\r\nContent-Length: 2\r\nConnection: close\r\nX-A: 1\r\n\r\n{{}}",
+				env!("CARGO_PKG_VERSION")
+			)
+		);
+	}
+
+	#[test]
+	fn test_parse_response() {
+		let response = parse_response(
+			b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nX-A: 1\r\n\r\nabcdef",
+			"GET",
+		)
+		.unwrap();
+		assert_eq!(response.status, 200);
+		assert_eq!(response.header("x-a"), Some("1"));
+		assert_eq!(response.body, b"abc");
+
+		let response = parse_response(
+			b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\na;x=1\r\n0123456789\r\n0\r\n\r\n",
+			"GET",
+		)
+		.unwrap();
+		assert_eq!(response.body, b"abc0123456789");
+
+		let response =
+			parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\n", "HEAD").unwrap();
+		assert!(response.body.is_empty());
+
+		assert!(parse_response(b"SSH-2.0\r\n\r\n", "GET").is_err());
+		assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nab", "GET").is_err());
+	}
+
+	#[test]
+	fn test_http() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		thread::spawn(move || {
+			for stream in listener.incoming().take(2) {
+				let mut stream = stream.unwrap();
+				let mut request = vec![0u8; 1024];
+				let n = stream.read(&mut request).unwrap();
+				let request = String::from_utf8_lossy(&request[..n]).to_string();
+				let response = if request.starts_with("GET /old ") {
+					"HTTP/1.1 301 Moved Permanently\r\nLocation: /new\r\nContent-Length: 0\r\n\r\n"
+						.to_string()
+				} else {
+					let path = request.split_whitespace().nth(1).unwrap_or_default();
+					format!(
+						"HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+						path.len(),
+						path
+					)
+				};
+				stream.write_all(response.as_bytes()).unwrap();
+			}
+		});
+
+		let app = &commands()[0].app;
+		let url = format!("http://127.0.0.1:{}/old", port);
+		let matches =
+			app.clone()
+				.get_matches_from(vec!["http", "get", &url, "-L", "-s", "status,body"]);
+		assert_eq!(http(&matches).unwrap(), vec!["HTTP/1.1 200 OK", "", "/new"]);
+	}
+
+	#[test]
+	fn test_redirect() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		thread::spawn(move || {
+			for stream in listener.incoming().take(4) {
+				let mut stream = stream.unwrap();
+				let mut request = vec![0u8; 1024];
+				let n = stream.read(&mut request).unwrap();
+				let request = String::from_utf8_lossy(&request[..n]).to_string();
+				let response = match request.split_whitespace().nth(1).unwrap_or_default() {
+					"/same" => {
+						"HTTP/1.1 307 Temporary Redirect\r\nLocation: /echo\r\n\r\n".to_string()
+					}
+					"/other" => format!(
+						"HTTP/1.1 302 Found\r\nLocation: http://localhost:{}/echo\r\n\r\n",
+						port
+					),
+					_ => format!(
+						"HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+						request.len(),
+						request
+					),
+				};
+				stream.write_all(response.as_bytes()).unwrap();
+			}
+		});
+
+		let app = &commands()[0].app;
+		let run = |path: &str| {
+			let url = format!("http://127.0.0.1:{}{}", port, path);
+			let matches = app.clone().get_matches_from(vec![
+				"http",
+				"post",
+				&url,
+				"-L",
+				"-H",
+				"Authorization: Bearer a",
+				"-H",
+				"X-A: 1",
+				"{}",
+			]);
+			http(&matches).unwrap().join("\n")
+		};
+
+		// 307 keeps the method, the body and the headers
+		let request = run("/same");
+		assert!(request.starts_with("POST /echo "));
+		assert!(request.contains("Authorization: Bearer a"));
+		assert!(request.ends_with("{}"));
+
+		// 302 changes to get without body, credentials are dropped for another origin
+		let request = run("/other");
+		assert!(request.starts_with("GET /echo "));
+		assert!(request.contains(&format!("Host: localhost:{}", port)));
+		assert!(!request.contains("Authorization"));
+		assert!(!request.contains("Content-Length"));
+		assert!(request.contains("X-A: 1"));
+	}
+
+	#[test]
+	fn test_truncated() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = vec![0u8; 1024];
+			assert!(stream.read(&mut request).unwrap() > 0);
+			stream
+				.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc")
+				.unwrap();
+			thread::sleep(Duration::from_secs(2));
+		});
+
+		let app = &commands()[0].app;
+		let url = format!("http://127.0.0.1:{}/", port);
+		let matches = app
+			.clone()
+			.get_matches_from(vec!["http", "get", &url, "-t", "0.2"]);
+		assert!(http(&matches).is_err());
+	}
+}
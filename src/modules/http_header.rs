@@ -0,0 +1,575 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::ArgMatches;
+
+/// HSTS preload lists require at least a year
+const HSTS_PRELOAD_MAX_AGE: u64 = 31_536_000;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "HTTP header explain".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("http_explain")
+			.about("Explain HTTP headers, e.g. pasted from browser devtools: cache-control, HSTS, CSP, cookies and more")
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, http_explain),
+		stream: None,
+	}]
+}
+
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
+
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
+}
+
+/// Explain HTTP headers, e.g. pasted from browser devtools: cache-control, HSTS, CSP, cookies and more,
+/// warnings are prefixed with `warning: `
+pub fn http_explain(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let headers = parse(base::text(input)?);
+	if headers.is_empty() {
+		return Err(Error::invalid("headers", "no name: value line"));
+	}
+
+	let mut explainer = Explainer {
+		lines: vec![],
+		warnings: vec![],
+	};
+	for (name, value) in &headers {
+		explainer.lines.push(format!("{}: {}", name, value));
+		explainer.explain(name, value);
+	}
+
+	let mut result = explainer.lines;
+	result.extend(
+		explainer
+			.warnings
+			.into_iter()
+			.map(|x| format!("{}{}", base::WARNING_PREFIX, x)),
+	);
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// `name: value` lines, the request or status line is skipped, folded lines are joined,
+/// and HTTP/2 pseudo headers (e.g. `:status: 200`) are kept
+fn parse(input: &str) -> Vec<(String, String)> {
+	let mut headers: Vec<(String, String)> = vec![];
+	for line in input.lines() {
+		if line.trim().is_empty() {
+			continue;
+		}
+		if line.starts_with(' ') || line.starts_with('\t') {
+			if let Some((_, value)) = headers.last_mut() {
+				value.push(' ');
+				value.push_str(line.trim());
+			}
+			continue;
+		}
+		let (pseudo, line) = match line.strip_prefix(':') {
+			Some(line) => (":", line),
+			None => ("", line),
+		};
+		let mut parts = line.splitn(2, ':');
+		if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+			let name = name.trim();
+			if !name.is_empty() && !name.contains(' ') {
+				headers.push((format!("{}{}", pseudo, name), value.trim().to_string()));
+			}
+		}
+	}
+	headers
+}
+
+struct Explainer {
+	lines: Vec<String>,
+	warnings: Vec<String>,
+}
+
+impl Explainer {
+	fn push(&mut self, desc: String) {
+		self.lines.push(format!("  {}", desc));
+	}
+
+	fn explain(&mut self, name: &str, value: &str) {
+		match name.to_lowercase().as_str() {
+			"cache-control" => self.cache_control(value),
+			"strict-transport-security" => self.hsts(value),
+			"content-security-policy" => self.csp(value, false),
+			"content-security-policy-report-only" => self.csp(value, true),
+			"set-cookie" => self.set_cookie(value),
+			"access-control-allow-origin" => {
+				if value == "*" {
+					self.push("any origin may read the response".to_string());
+				} else {
+					self.push(format!("only {} may read the response", value));
+				}
+			}
+			"access-control-allow-credentials" if value == "true" => {
+				self.push("cross-origin requests may include cookies".to_string())
+			}
+			"x-content-type-options" if value.eq_ignore_ascii_case("nosniff") => {
+				self.push("browsers must not guess the content type from the content".to_string())
+			}
+			"x-frame-options" => {
+				let desc = match value.to_lowercase().as_str() {
+					"deny" => "must not be framed".to_string(),
+					"sameorigin" => "may only be framed by the same origin".to_string(),
+					_ => format!("unknown option, {} is obsolete", value),
+				};
+				self.push(desc);
+			}
+			"referrer-policy" => self.push(format!(
+				"referrer sent: {}",
+				match value.to_lowercase().as_str() {
+					"no-referrer" => "never",
+					"no-referrer-when-downgrade" => "full URL unless HTTPS to HTTP",
+					"origin" => "origin only",
+					"origin-when-cross-origin" => "full URL to the same origin, origin otherwise",
+					"same-origin" => "full URL to the same origin only",
+					"strict-origin" => "origin only, none from HTTPS to HTTP",
+					"strict-origin-when-cross-origin" =>
+						"full URL to the same origin, origin otherwise, none from HTTPS to HTTP",
+					"unsafe-url" => "full URL always",
+					_ => "unknown policy",
+				}
+			)),
+			"age" => {
+				if let Ok(age) = value.parse::<u64>() {
+					self.push(format!("cached for {}", duration(age)));
+				}
+			}
+			"vary" => self.push(format!("caches keep a response per value of: {}", value)),
+			"etag" => self.push(if value.starts_with("W/") {
+				"weak validator, for If-None-Match".to_string()
+			} else {
+				"strong validator, for If-None-Match and If-Match".to_string()
+			}),
+			_ => (),
+		}
+	}
+
+	fn cache_control(&mut self, value: &str) {
+		let directives = directives(value, ',');
+		for (directive, argument) in &directives {
+			let seconds = argument.as_deref().and_then(|x| x.parse::<u64>().ok());
+			let desc = match (directive.as_str(), seconds) {
+				("max-age", Some(x)) => format!("fresh for {}", duration(x)),
+				("s-maxage", Some(x)) => format!("fresh in shared caches for {}", duration(x)),
+				("stale-while-revalidate", Some(x)) => format!(
+					"may be served stale for {} while revalidating in background",
+					duration(x)
+				),
+				("stale-if-error", Some(x)) => {
+					format!("may be served stale for {} on errors", duration(x))
+				}
+				("public", _) => "may be stored by shared caches (e.g. CDNs)".to_string(),
+				("private", _) => "browser cache only, not shared caches".to_string(),
+				("no-cache", _) => "may be stored, but must be revalidated before use".to_string(),
+				("no-store", _) => "must not be stored by any cache".to_string(),
+				("must-revalidate", _) => "must not be served stale once expired".to_string(),
+				("proxy-revalidate", _) => {
+					"shared caches must not serve it stale once expired".to_string()
+				}
+				("immutable", _) => "not revalidated while fresh, even on reload".to_string(),
+				("no-transform", _) => "intermediaries must not modify the body".to_string(),
+				("max-stale", _) => "request: accepts stale responses".to_string(),
+				("min-fresh", Some(x)) => {
+					format!("request: must stay fresh for at least {}", duration(x))
+				}
+				("only-if-cached", _) => "request: only from cache".to_string(),
+				_ => "unknown directive".to_string(),
+			};
+			self.push(format!("{}: {}", directive_text(directive, argument), desc));
+		}
+
+		let has = |name: &str| directives.iter().any(|(x, _)| x == name);
+		if has("no-store") && (has("max-age") || has("public")) {
+			self.warnings
+				.push("cache-control: no-store overrides max-age and public".to_string());
+		}
+		if has("public") && has("private") {
+			self.warnings
+				.push("cache-control: both public and private".to_string());
+		}
+	}
+
+	fn hsts(&mut self, value: &str) {
+		let directives = directives(value, ';');
+		let mut max_age = None;
+		for (directive, argument) in &directives {
+			let desc = match directive.as_str() {
+				"max-age" => match argument
+					.as_deref()
+					.map(|x| x.trim_matches('"').parse::<u64>())
+				{
+					Some(Ok(0)) => "HSTS is removed".to_string(),
+					Some(Ok(x)) => {
+						max_age = Some(x);
+						format!("HTTPS only for {}", duration(x))
+					}
+					_ => "invalid".to_string(),
+				},
+				"includesubdomains" => "subdomains too".to_string(),
+				"preload" => "asks to be in the browser preload lists".to_string(),
+				_ => "unknown directive".to_string(),
+			};
+			self.push(format!("{}: {}", directive_text(directive, argument), desc));
+		}
+
+		let has = |name: &str| directives.iter().any(|(x, _)| x == name);
+		if !has("max-age") {
+			self.warnings
+				.push("strict-transport-security: max-age is required".to_string());
+		}
+		if has("preload") {
+			if max_age.map(|x| x < HSTS_PRELOAD_MAX_AGE).unwrap_or(true) {
+				self.warnings.push(
+					"strict-transport-security: preload requires max-age of at least 1 year"
+						.to_string(),
+				);
+			}
+			if !has("includesubdomains") {
+				self.warnings.push(
+					"strict-transport-security: preload requires includeSubDomains".to_string(),
+				);
+			}
+		}
+	}
+
+	fn csp(&mut self, value: &str, report_only: bool) {
+		if report_only {
+			self.push("violations are reported, not blocked".to_string());
+		}
+
+		let mut names = vec![];
+		for directive in value.split(';').map(str::trim).filter(|x| !x.is_empty()) {
+			let mut parts = directive.split_whitespace();
+			let name = parts.next().unwrap_or_default().to_lowercase();
+			let sources = parts.collect::<Vec<_>>();
+
+			let desc = match name.as_str() {
+				"default-src" => "fallback for the other fetch directives",
+				"script-src" => "scripts",
+				"script-src-elem" => "script elements",
+				"script-src-attr" => "inline event handlers",
+				"style-src" => "stylesheets",
+				"img-src" => "images",
+				"font-src" => "fonts",
+				"connect-src" => "fetch, XHR, WebSocket and EventSource",
+				"media-src" => "audio and video",
+				"object-src" => "plugins (object, embed)",
+				"frame-src" => "frames",
+				"child-src" => "frames and workers",
+				"worker-src" => "workers",
+				"manifest-src" => "web app manifests",
+				"base-uri" => "base element URLs",
+				"form-action" => "form submission targets",
+				"frame-ancestors" => "pages that may frame this one",
+				"upgrade-insecure-requests" => "HTTP requests are upgraded to HTTPS",
+				"block-all-mixed-content" => "HTTP content on HTTPS pages is blocked",
+				"sandbox" => "sandboxed like an iframe with the sandbox attribute",
+				"report-uri" | "report-to" => "where violations are reported",
+				"require-trusted-types-for" => "trusted types required for DOM sinks",
+				"trusted-types" => "allowed trusted types policies",
+				_ => "unknown directive",
+			};
+			let sources_desc = if sources.is_empty() {
+				"".to_string()
+			} else {
+				format!(" from {}", sources.join(" "))
+			};
+			self.push(format!("{}: {}{}", name, desc, sources_desc));
+
+			if ["default-src", "script-src"].contains(&name.as_str()) {
+				for source in &sources {
+					let warning = match *source {
+						"'unsafe-inline'" => "inline scripts allowed, XSS is not mitigated",
+						"'unsafe-eval'" => "eval allowed",
+						"*" | "http:" | "https:" | "data:" => "scripts from any host allowed",
+						_ => continue,
+					};
+					self.warnings.push(format!(
+						"content-security-policy: {} {}, {}",
+						name, source, warning
+					));
+				}
+			}
+			names.push(name);
+		}
+
+		let has = |name: &str| names.iter().any(|x| x == name);
+		if !has("default-src") && !has("script-src") {
+			self.warnings.push(
+				"content-security-policy: no default-src or script-src, scripts are not restricted"
+					.to_string(),
+			);
+		}
+		if !has("default-src") && !has("object-src") {
+			self.warnings.push(
+				"content-security-policy: no default-src or object-src, plugins are not restricted"
+					.to_string(),
+			);
+		}
+	}
+
+	fn set_cookie(&mut self, value: &str) {
+		let mut parts = value.split(';').map(str::trim);
+		let name = parts
+			.next()
+			.and_then(|x| x.split('=').next())
+			.unwrap_or_default()
+			.to_string();
+		self.push(format!("cookie {}", name));
+
+		let attributes = parts
+			.filter(|x| !x.is_empty())
+			.map(|x| {
+				let mut kv = x.splitn(2, '=');
+				(
+					kv.next().unwrap_or_default().trim().to_lowercase(),
+					kv.next().map(|x| x.trim().to_string()),
+				)
+			})
+			.collect::<Vec<_>>();
+		for (attribute, argument) in &attributes {
+			let desc = match (attribute.as_str(), argument.as_deref()) {
+				("expires", Some(x)) => format!("expires at {}", x),
+				("max-age", Some(x)) => match x.parse::<i64>() {
+					Ok(x) if x <= 0 => "deleted now".to_string(),
+					Ok(x) => format!("expires in {}", duration(x as u64)),
+					Err(_) => "invalid".to_string(),
+				},
+				("domain", Some(x)) => format!("sent to {} and its subdomains", x),
+				("path", Some(x)) => format!("sent for paths under {}", x),
+				("secure", _) => "sent over HTTPS only".to_string(),
+				("httponly", _) => "not readable by JavaScript".to_string(),
+				("samesite", Some(x)) => match x.to_lowercase().as_str() {
+					"strict" => "not sent on cross-site requests".to_string(),
+					"lax" => "sent on cross-site top-level navigations only".to_string(),
+					"none" => "sent on cross-site requests".to_string(),
+					_ => "invalid".to_string(),
+				},
+				("partitioned", _) => "stored per top-level site (CHIPS)".to_string(),
+				_ => "unknown attribute".to_string(),
+			};
+			self.push(format!("{}: {}", directive_text(attribute, argument), desc));
+		}
+
+		let has = |name: &str| attributes.iter().any(|(x, _)| x == name);
+		let same_site_none = attributes.iter().any(|(x, v)| {
+			x == "samesite" && v.as_deref().map(|v| v.eq_ignore_ascii_case("none")) == Some(true)
+		});
+		if same_site_none && !has("secure") {
+			self.warnings.push(format!(
+				"set-cookie {}: SameSite=None requires Secure",
+				name
+			));
+		}
+		if !has("expires") && !has("max-age") {
+			self.push("session cookie, deleted when the browser closes".to_string());
+		}
+		if !has("httponly") {
+			self.warnings.push(format!(
+				"set-cookie {}: readable by JavaScript, no HttpOnly",
+				name
+			));
+		}
+		if name.starts_with("__Secure-") && !has("secure") {
+			self.warnings.push(format!(
+				"set-cookie {}: __Secure- prefix requires Secure",
+				name
+			));
+		}
+		if name.starts_with("__Host-") {
+			let root_path = attributes
+				.iter()
+				.any(|(x, v)| x == "path" && v.as_deref() == Some("/"));
+			if !has("secure") || has("domain") || !root_path {
+				self.warnings.push(format!(
+					"set-cookie {}: __Host- prefix requires Secure, Path=/ and no Domain",
+					name
+				));
+			}
+		}
+	}
+}
+
+/// Lower case directives with optional arguments, e.g. `max-age=60`
+fn directives(value: &str, separator: char) -> Vec<(String, Option<String>)> {
+	value
+		.split(separator)
+		.map(str::trim)
+		.filter(|x| !x.is_empty())
+		.map(|x| {
+			let mut kv = x.splitn(2, '=');
+			(
+				kv.next().unwrap_or_default().trim().to_lowercase(),
+				kv.next().map(|x| x.trim().to_string()),
+			)
+		})
+		.collect()
+}
+
+fn directive_text(directive: &str, argument: &Option<String>) -> String {
+	match argument {
+		Some(argument) => format!("{}={}", directive, argument),
+		None => directive.to_string(),
+	}
+}
+
+/// In the largest whole unit, e.g. `365 days`
+fn duration(seconds: u64) -> String {
+	let units = [
+		(86400, "day"),
+		(3600, "hour"),
+		(60, "minute"),
+		(1, "second"),
+	];
+	let (size, unit) = units
+		.iter()
+		.find(|(size, _)| seconds >= *size && seconds.is_multiple_of(*size))
+		.unwrap_or(&(1, "second"));
+	let n = seconds / size;
+	format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" })
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"http_explain",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'Cache-Control: public, max-age=31536000, immutable'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"Cache-Control: public, max-age=31536000, immutable",
+						"  public: may be stored by shared caches (e.g. CDNs)",
+						"  max-age=31536000: fresh for 365 days",
+						"  immutable: not revalidated while fresh, even on reload",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "HSTS".to_string(),
+					input: vec!["'strict-transport-security: max-age=86400; preload'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"strict-transport-security: max-age=86400; preload",
+						"  max-age=86400: HTTPS only for 1 day",
+						"  preload: asks to be in the browser preload lists",
+						"warning: strict-transport-security: preload requires max-age of at least 1 year",
+						"warning: strict-transport-security: preload requires includeSubDomains",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "CSP".to_string(),
+					input: vec!["'content-security-policy: default-src https:; img-src *'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"content-security-policy: default-src https:; img-src *",
+						"  default-src: fallback for the other fetch directives from https:",
+						"  img-src: images from *",
+						"warning: content-security-policy: default-src https:, scripts from any host allowed",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Cookie".to_string(),
+					input: vec![
+						"'Set-Cookie: id=a3f; Max-Age=3600; Secure; HttpOnly; SameSite=Lax'",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						"Set-Cookie: id=a3f; Max-Age=3600; Secure; HttpOnly; SameSite=Lax",
+						"  cookie id",
+						"  max-age=3600: expires in 1 hour",
+						"  secure: sent over HTTPS only",
+						"  httponly: not readable by JavaScript",
+						"  samesite=Lax: sent on cross-site top-level navigations only",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_parse() {
+		let input =
+			"HTTP/1.1 200 OK\r\n:status: 200\r\nX-A: 1\r\n  2\r\nnot a header\r\n\r\nVary: Accept";
+		assert_eq!(
+			parse(input),
+			vec![
+				(":status".to_string(), "200".to_string()),
+				("X-A".to_string(), "1 2".to_string()),
+				("Vary".to_string(), "Accept".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn test_duration() {
+		assert_eq!(duration(31536000), "365 days");
+		assert_eq!(duration(7200), "2 hours");
+		assert_eq!(duration(90), "90 seconds");
+		assert_eq!(duration(0), "0 seconds");
+	}
+}
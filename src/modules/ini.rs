@@ -0,0 +1,529 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, toml, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "INI / properties conversion (to JSON, TOML)".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("ini2j")
+				.about("Convert INI to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ini2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2ini")
+				.about("Convert JSON to INI")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2ini),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ini2t")
+				.about("Convert INI to TOML")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ini2t),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("t2ini")
+				.about("Convert TOML to INI")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, t2ini),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("prop2j")
+				.about("Convert properties to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, prop2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2prop")
+				.about("Convert JSON to properties")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2prop),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the INI / properties commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert INI to JSON
+pub fn ini2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse_ini(base::text(input)?)?;
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to INI
+pub fn j2ini(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let result = to_ini(&value)?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert INI to TOML
+pub fn ini2t(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse_ini(base::text(input)?)?;
+
+	let value = toml::from_json(&value)?;
+
+	let result = toml::to_string(&value)?;
+
+	Ok(result.into_bytes())
+}
+
+/// Convert TOML to INI
+pub fn t2ini(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = toml::to_json(toml::parse(base::text(input)?)?);
+
+	let result = to_ini(&value)?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert properties to JSON
+pub fn prop2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse_properties(base::text(input)?)?;
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to properties
+pub fn j2prop(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	// nested objects are flattened with dots
+	let mut entries = vec![];
+	flatten("", &map, &mut entries)?;
+
+	let result = entries
+		.into_iter()
+		.map(|(k, v)| {
+			format!(
+				"{}={}",
+				escape_properties(&k, true),
+				escape_properties(&v, false)
+			)
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Keys before the first section are at the top level, values are strings
+fn parse_ini(input: &str) -> Result<Value, Error> {
+	let mut root = Map::new();
+	let mut section: Option<String> = None;
+
+	for (i, line) in input.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+			continue;
+		}
+		if line.starts_with('[') {
+			let name = line
+				.strip_suffix(']')
+				.map(|x| x[1..].trim().to_string())
+				.ok_or_else(|| Error::invalid("INI", format!("line {}", i + 1)))?;
+			root.entry(name.clone())
+				.or_insert_with(|| Value::Object(Map::new()));
+			section = Some(name);
+			continue;
+		}
+		let index = line
+			.find(&['=', ':'][..])
+			.ok_or_else(|| Error::invalid("INI", format!("line {}", i + 1)))?;
+		let key = line[..index].trim().to_string();
+		let value = unquote(line[index + 1..].trim());
+		let map = match &section {
+			Some(section) => match root.get_mut(section) {
+				Some(Value::Object(map)) => map,
+				_ => return Err(Error::invalid("INI", format!("line {}", i + 1))),
+			},
+			None => &mut root,
+		};
+		map.insert(key, Value::String(value));
+	}
+
+	Ok(Value::Object(root))
+}
+
+fn unquote(value: &str) -> String {
+	for quote in &['"', '\''] {
+		if value.len() >= 2 && value.starts_with(*quote) && value.ends_with(*quote) {
+			return value[1..value.len() - 1].to_string();
+		}
+	}
+	value.to_string()
+}
+
+/// Scalars of the top level object are written before sections,
+/// objects are converted to sections (nested with dots), arrays to JSON
+fn to_ini(value: &Value) -> Result<Vec<String>, Error> {
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	let mut result = vec![];
+	write_section(None, map, &mut result)?;
+
+	Ok(result)
+}
+
+fn write_section(
+	name: Option<&str>,
+	map: &Map<String, Value>,
+	result: &mut Vec<String>,
+) -> Result<(), Error> {
+	if let Some(name) = name {
+		if !result.is_empty() {
+			result.push("".to_string());
+		}
+		result.push(format!("[{}]", name));
+	}
+	for (k, v) in map {
+		if !v.is_object() {
+			result.push(format!("{} = {}", k, to_scalar(v)?));
+		}
+	}
+	for (k, v) in map {
+		if let Value::Object(v) = v {
+			let name = match name {
+				Some(name) => format!("{}.{}", name, k),
+				None => k.clone(),
+			};
+			write_section(Some(&name), v, result)?;
+		}
+	}
+	Ok(())
+}
+
+fn to_scalar(value: &Value) -> Result<String, Error> {
+	match value {
+		Value::String(v) => Ok(v.clone()),
+		Value::Null => Ok("".to_string()),
+		v => json::to_string(v),
+	}
+}
+
+/// Java properties: `key=value`, `key: value` or `key value`,
+/// comments start with # or !, lines ending with \ are continued
+fn parse_properties(input: &str) -> Result<Value, Error> {
+	let mut map = Map::new();
+
+	let mut lines = input.lines();
+	while let Some(line) = lines.next() {
+		let mut line = line.trim_start().to_string();
+		if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+			continue;
+		}
+		while ends_with_continuation(&line) {
+			line.pop();
+			match lines.next() {
+				Some(next) => line.push_str(next.trim_start()),
+				None => break,
+			}
+		}
+
+		// the key ends at the first unescaped separator
+		let mut key_end = line.len();
+		let mut escaped = false;
+		for (i, c) in line.char_indices() {
+			if escaped {
+				escaped = false;
+			} else if c == '\\' {
+				escaped = true;
+			} else if c == '=' || c == ':' || c.is_whitespace() {
+				key_end = i;
+				break;
+			}
+		}
+		let key = &line[..key_end];
+		let rest = line[key_end..].trim_start();
+		let rest = match rest.chars().next() {
+			Some(c) if c == '=' || c == ':' => rest[1..].trim_start(),
+			_ => rest,
+		};
+		map.insert(
+			unescape_properties(key)?,
+			Value::String(unescape_properties(rest)?),
+		);
+	}
+
+	Ok(Value::Object(map))
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+	line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+fn unescape_properties(input: &str) -> Result<String, Error> {
+	let mut result = String::new();
+	let mut chars = input.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			result.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('t') => result.push('\t'),
+			Some('n') => result.push('\n'),
+			Some('r') => result.push('\r'),
+			Some('f') => result.push('\x0c'),
+			Some('u') => {
+				let hex = chars.by_ref().take(4).collect::<String>();
+				let c = u32::from_str_radix(&hex, 16)
+					.ok()
+					.and_then(std::char::from_u32)
+					.ok_or_else(|| Error::invalid("properties", format!("\\u{}", hex)))?;
+				result.push(c);
+			}
+			Some(c) => result.push(c),
+			None => (),
+		}
+	}
+	Ok(result)
+}
+
+fn escape_properties(input: &str, is_key: bool) -> String {
+	let mut result = String::new();
+	for (i, c) in input.chars().enumerate() {
+		match c {
+			'\\' => result.push_str("\\\\"),
+			'\t' => result.push_str("\\t"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\x0c' => result.push_str("\\f"),
+			'=' | ':' | '#' | '!' if is_key || i == 0 => {
+				result.push('\\');
+				result.push(c);
+			}
+			' ' if is_key || i == 0 => result.push_str("\\ "),
+			c => result.push(c),
+		}
+	}
+	result
+}
+
+fn flatten(
+	prefix: &str,
+	map: &Map<String, Value>,
+	result: &mut Vec<(String, String)>,
+) -> Result<(), Error> {
+	for (k, v) in map {
+		let key = if prefix.is_empty() {
+			k.clone()
+		} else {
+			format!("{}.{}", prefix, k)
+		};
+		match v {
+			Value::Object(v) => flatten(&key, v, result)?,
+			v => result.push((key, to_scalar(v)?)),
+		}
+	}
+	Ok(())
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"ini2j",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![
+						"-m",
+						"'name = dtool\n; comment\n[server]\nhost = \"localhost\"\nport: 8080'",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![r#"{"name":"dtool","server":{"host":"localhost","port":"8080"}}"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"j2ini",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![
+						r#"'{"name":"dtool","server":{"host":"localhost","port":8080,"tls":{"on":true}}}'"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						"name = dtool",
+						"",
+						"[server]",
+						"host = localhost",
+						"port = 8080",
+						"",
+						"[server.tls]",
+						"on = true",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"ini2t",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'name = dtool\n[server]\nhost = localhost'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![r#"name = "dtool""#, "", "[server]", r#"host = "localhost""#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"t2ini",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'name = \"dtool\"\n[server]\nport = 8080'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["name = dtool", "", "[server]", "port = 8080"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"prop2j",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![
+							"-m",
+							"'# comment\napp.name=dtool\napp.desc: a \\\n    tool\nkey\\ with\\ space value\\u0021'",
+						]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						r#"{"app.name":"dtool","app.desc":"a tool","key with space":"value!"}"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"j2prop",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![r#"'{"app":{"name":"dtool","port":8080},"a b":"x=y"}'"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["app.name=dtool", "app.port=8080", "a\\ b=x=y"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
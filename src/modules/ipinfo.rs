@@ -0,0 +1,293 @@
+use crate::error::Error;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches, SubCommand};
+use maxminddb::{MaxMindDBError, Reader};
+use serde_json::{Map, Value};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "IP geolocation and ASN lookup in local MMDB databases".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: SubCommand::with_name("ipinfo")
+.args(&base::output_args())
+.arg(base::raw_arg())
+			.about("Country, city, ASN and organization of an IP, from local MMDB databases (e.g. GeoLite2 Country / City and ASN)")
+			.arg(
+				Arg::with_name("DB")
+					.long("db")
+					.short("d")
+					.help("MMDB database path, repeat for several, e.g. a city and an ASN database, the first with a field wins")
+					.takes_value(true)
+					.multiple(true)
+					.number_of_values(1)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("FORMAT")
+					.long("format")
+					.short("F")
+					.help("Output format\nplain: one field per line\njson: JSON object")
+					.takes_value(true)
+					.possible_values(&["plain", "json"])
+					.default_value("plain")
+					.required(false),
+			)
+			.arg(base::input_arg().help("IP")).args(&base::file_args()),
+		f: ipinfo,
+		stream: None,
+	}]
+}
+
+fn ipinfo(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let input = base::input_string(matches)?;
+	let ip = input
+		.trim()
+		.parse::<IpAddr>()
+		.map_err(|_| Error::invalid("IP", input.trim()))?;
+
+	let dbs = matches
+		.values_of("DB")
+		.ok_or(Error::invalid("database", "no --db given"))?;
+	let mut records = vec![];
+	for db in dbs {
+		let reader = Reader::open_readfile(db).map_err(|e| invalid_database(db, e))?;
+		match reader.lookup_prefix::<Value>(ip) {
+			Ok(record) => records.push(record),
+			Err(MaxMindDBError::AddressNotFoundError(_)) => {
+				base::verbose(matches, 1, || format!("not found in {}", db))
+			}
+			Err(e) => return Err(invalid_database(db, e)),
+		}
+	}
+	if records.is_empty() {
+		return Err(Error::Failed(format!("Address not found: {}", ip)));
+	}
+
+	let info = info(ip, &records);
+
+	let result = match matches.value_of("FORMAT") {
+		Some("json") => json::to_string_pretty(&Value::Object(info), 2)?
+			.lines()
+			.map(Into::into)
+			.collect(),
+		_ => info
+			.into_iter()
+			.filter(|(_, v)| !v.is_null())
+			.map(|(k, v)| match v {
+				Value::String(v) => format!("{}: {}", k, v),
+				v => format!("{}: {}", k, v),
+			})
+			.collect(),
+	};
+
+	Ok(result)
+}
+
+fn invalid_database(db: &str, e: MaxMindDBError) -> Error {
+	Error::Invalid {
+		what: "database".to_string(),
+		detail: format!("{}: {}", db, e),
+	}
+}
+
+/// The fields of the records of every database the IP is found in, the first with a field wins
+fn info(ip: IpAddr, records: &[(Value, usize)]) -> Map<String, Value> {
+	let first = |f: &dyn Fn(&Value) -> &Value| {
+		records
+			.iter()
+			.map(|(record, _)| f(record))
+			.find(|x| !x.is_null())
+			.cloned()
+			.unwrap_or(Value::Null)
+	};
+
+	let network = records
+		.first()
+		.map(|(_, prefix_len)| format!("{}/{}", network(ip, *prefix_len), prefix_len));
+
+	let mut info = Map::new();
+	info.insert("ip".to_string(), ip.to_string().into());
+	info.insert("network".to_string(), network.into());
+	info.insert("country".to_string(), first(&|x| &country(x)["iso_code"]));
+	info.insert(
+		"country_name".to_string(),
+		first(&|x| &country(x)["names"]["en"]),
+	);
+	info.insert("city".to_string(), first(&|x| &x["city"]["names"]["en"]));
+	info.insert(
+		"asn".to_string(),
+		first(&|x| &x["autonomous_system_number"]),
+	);
+	info.insert(
+		"organization".to_string(),
+		first(&|x| {
+			["autonomous_system_organization", "organization", "isp"]
+				.iter()
+				.map(|key| &x[*key])
+				.find(|x| !x.is_null())
+				.unwrap_or(&Value::Null)
+		}),
+	);
+	info
+}
+
+/// The country of the location, or the registered country if unknown, e.g. in ASN databases
+fn country(record: &Value) -> &Value {
+	match &record["country"] {
+		Value::Null => &record["registered_country"],
+		country => country,
+	}
+}
+
+/// The IP with the bits after the prefix cleared
+fn network(ip: IpAddr, prefix_len: usize) -> IpAddr {
+	match ip {
+		IpAddr::V4(ip) => {
+			let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+			IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+		}
+		IpAddr::V6(ip) => {
+			let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+			IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+		}
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"ipinfo",
+			vec![Case {
+				desc: "".to_string(),
+				input: vec![
+					"--db",
+					"GeoLite2-City.mmdb",
+					"--db",
+					"GeoLite2-ASN.mmdb",
+					"8.8.8.8",
+				]
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+				output: vec![
+					"ip: 8.8.8.8",
+					"network: 8.8.8.0/24",
+					"country: US",
+					"country_name: United States",
+					"asn: 15169",
+					"organization: GOOGLE",
+				]
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+				is_example: true,
+				is_test: false,
+				since: "0.10.0".to_string(),
+			}],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+	use serde_json::json;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	/// A minimal IPv4 database, 0.0.0.0/1 maps to the record and 128.0.0.0/1 is not found
+	fn database(record: &[u8]) -> Vec<u8> {
+		let string = |x: &str| [&[0x40 | x.len() as u8][..], x.as_bytes()].concat();
+		let uint16 = |x: u8| vec![0xa1, x];
+
+		// one node, the left record points to the data section, the right is empty
+		let mut db = vec![0x00, 0x00, 0x11, 0x00, 0x00, 0x01];
+		db.extend([0u8; 16]);
+		db.extend(record);
+
+		db.extend(b"\xab\xcd\xefMaxMind.com");
+		db.push(0xe9);
+		db.extend(string("binary_format_major_version"));
+		db.extend(uint16(2));
+		db.extend(string("binary_format_minor_version"));
+		db.extend(uint16(0));
+		db.extend(string("build_epoch"));
+		db.extend([0x01, 0x02, 0x00]);
+		db.extend(string("database_type"));
+		db.extend(string("Test"));
+		db.extend(string("description"));
+		db.push(0xe0);
+		db.extend(string("ip_version"));
+		db.extend(uint16(4));
+		db.extend(string("languages"));
+		db.extend([0x00, 0x04]);
+		db.extend(string("node_count"));
+		db.extend([0xc1, 0x01]);
+		db.extend(string("record_size"));
+		db.extend(uint16(24));
+		db
+	}
+
+	#[test]
+	fn test_info() {
+		let string = |x: &str| [&[0x40 | x.len() as u8][..], x.as_bytes()].concat();
+
+		// {"country": {"iso_code": "US"}, "autonomous_system_number": 15169}
+		let mut record = vec![0xe2];
+		record.extend(string("country"));
+		record.push(0xe1);
+		record.extend(string("iso_code"));
+		record.extend(string("US"));
+		record.extend(string("autonomous_system_number"));
+		record.extend([0xc2, 0x3b, 0x41]);
+
+		let reader = Reader::from_source(database(&record)).unwrap();
+
+		let ip = "8.8.8.8".parse().unwrap();
+		let found = reader.lookup_prefix::<Value>(ip).unwrap();
+		assert_eq!(found.1, 1);
+		assert_eq!(
+			Value::Object(info(ip, &[found])),
+			json!({
+				"ip": "8.8.8.8",
+				"network": "0.0.0.0/1",
+				"country": "US",
+				"country_name": null,
+				"city": null,
+				"asn": 15169,
+				"organization": null,
+			})
+		);
+
+		assert!(matches!(
+			reader.lookup_prefix::<Value>("200.1.1.1".parse().unwrap()),
+			Err(MaxMindDBError::AddressNotFoundError(_))
+		));
+	}
+
+	#[test]
+	fn test_network() {
+		let ip = "8.8.8.8".parse().unwrap();
+		assert_eq!(network(ip, 24).to_string(), "8.8.8.0");
+		assert_eq!(network(ip, 0).to_string(), "0.0.0.0");
+		assert_eq!(network(ip, 32).to_string(), "8.8.8.8");
+		let ip = "2001:db8::1".parse().unwrap();
+		assert_eq!(network(ip, 32).to_string(), "2001:db8::");
+	}
+}
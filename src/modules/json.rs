@@ -0,0 +1,913 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches, SubCommand};
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map, Serializer, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc:
+			"JSON (format, minify, sort keys, canonicalize, JSON5, JSONPath query, diff, flatten, check)"
+				.to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("json_fmt")
+				.about("JSON format (pretty print or minify)")
+				.arg(
+					Arg::with_name("INDENT")
+						.long("indent")
+						.short("i")
+						.help("Indent width")
+						.takes_value(true)
+						.default_value("2")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("RELAXED")
+						.long("relaxed")
+						.short("r")
+						.help("Accept JSON5 (comments, trailing commas, unquoted keys, etc)")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("SORT_KEYS")
+						.long("sort-keys")
+						.short("s")
+						.help("Sort object keys")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("CANONICAL")
+						.long("canonical")
+						.short("c")
+						.help("Canonicalize (RFC 8785 JCS), for hashing and signing")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, json_fmt),
+			stream: None,
+		},
+		Command {
+			app: SubCommand::with_name("json_get")
+				.args(&base::output_args())
+				.arg(base::format_arg())
+				.about("JSON query by JSONPath")
+				.arg(
+					Arg::with_name("PATH")
+						.long("path")
+						.short("p")
+						.help("JSONPath, e.g. $.store.book[0].title")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("RAW")
+						.long("raw")
+						.short("r")
+						.help("Output strings without quotes")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, json_get),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("json_diff")
+				.about("JSON structural diff (exit with 1 if different)")
+				.arg(
+					Arg::with_name("TOLERANCE")
+						.long("tolerance")
+						.short("t")
+						.help("Numeric tolerance")
+						.takes_value(true)
+						.default_value("0")
+						.required(false),
+				)
+				.arg(Arg::with_name("INPUT").required(false).index(1))
+				.args(&base::file_args())
+				.arg(Arg::with_name("OTHER").required(false).index(2)),
+			f: |matches| base::convert(matches, json_diff),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("json_flatten")
+				.about("JSON flatten (nested to dot-notation keys)")
+				.arg(
+					Arg::with_name("SEPARATOR")
+						.long("separator")
+						.short("s")
+						.help("Key separator")
+						.takes_value(true)
+						.default_value(".")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, json_flatten),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("json_unflatten")
+				.about("JSON unflatten (dot-notation keys to nested)")
+				.arg(
+					Arg::with_name("SEPARATOR")
+						.long("separator")
+						.short("s")
+						.help("Key separator")
+						.takes_value(true)
+						.default_value(".")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, json_unflatten),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("json_check")
+				.about("JSON check (exit with 1 if invalid)")
+				.arg(
+					Arg::with_name("RELAXED")
+						.long("relaxed")
+						.short("r")
+						.help("Accept JSON5 (comments, trailing commas, unquoted keys, etc)")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, json_check),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the JSON commands
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub indent: usize,
+	pub minify: bool,
+	pub relaxed: bool,
+	pub sort_keys: bool,
+	pub canonical: bool,
+	pub path: String,
+	pub raw: bool,
+	pub tolerance: f64,
+	pub other: String,
+	pub separator: String,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			indent: 2,
+			minify: false,
+			relaxed: false,
+			sort_keys: false,
+			canonical: false,
+			path: String::new(),
+			raw: false,
+			tolerance: 0.0,
+			other: String::new(),
+			separator: ".".to_string(),
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let default = Options::default();
+
+		let indent = match matches.value_of("INDENT") {
+			Some(indent) => indent.parse().map_err(|_| Error::invalid("indent", ""))?,
+			None => default.indent,
+		};
+
+		let tolerance = match matches.value_of("TOLERANCE") {
+			Some(tolerance) => tolerance
+				.parse()
+				.map_err(|_| Error::invalid("tolerance", ""))?,
+			None => default.tolerance,
+		};
+
+		Ok(Options {
+			indent,
+			minify: matches.is_present("MINIFY"),
+			relaxed: matches.is_present("RELAXED"),
+			sort_keys: matches.is_present("SORT_KEYS"),
+			canonical: matches.is_present("CANONICAL"),
+			path: matches.value_of("PATH").unwrap_or_default().to_string(),
+			raw: matches.is_present("RAW"),
+			tolerance,
+			other: matches.value_of("OTHER").unwrap_or_default().to_string(),
+			separator: matches
+				.value_of("SEPARATOR")
+				.map(String::from)
+				.unwrap_or(default.separator),
+		})
+	}
+}
+
+/// JSON format (pretty print or minify)
+pub fn json_fmt(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let value = if options.relaxed {
+		parse_json5(input)?
+	} else {
+		parse(input)?
+	};
+
+	let value = if options.sort_keys {
+		sort_keys(value)
+	} else {
+		value
+	};
+
+	let result = if options.canonical {
+		to_canonical_string(&value)?
+	} else if options.minify {
+		to_string(&value)?
+	} else {
+		to_string_pretty(&value, options.indent)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// JSON query by JSONPath, a result per line
+pub fn json_get(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse(base::text(input)?)?;
+
+	let result = jsonpath_lib::select(&value, &options.path)
+		.map_err(|e| Error::invalid("path", e))?
+		.into_iter()
+		.map(|x| match x {
+			Value::String(x) if options.raw => Ok(x.clone()),
+			x => to_string(x),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// JSON structural diff against `other`, fails with the differences if different
+pub fn json_diff(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse(base::text(input)?)?;
+	let other = parse(&options.other)?;
+
+	let result = diff(&value, &other, options.tolerance)?;
+
+	if result.is_empty() {
+		Ok(vec![])
+	} else {
+		Err(Error::failed(result.join("\n")))
+	}
+}
+
+/// Compare structurally: key order is ignored, numbers are equal within the tolerance.
+/// Returns lines of `+ path: value`, `- path: value` and `~ path: old -> new`
+fn diff(a: &Value, b: &Value, tolerance: f64) -> Result<Vec<String>, Error> {
+	let mut result = vec![];
+	diff_at("$", a, b, tolerance, &mut result)?;
+	Ok(result)
+}
+
+fn diff_at(
+	path: &str,
+	a: &Value,
+	b: &Value,
+	tolerance: f64,
+	result: &mut Vec<String>,
+) -> Result<(), Error> {
+	match (a, b) {
+		(Value::Object(a), Value::Object(b)) => {
+			for (k, v) in a {
+				let path = format!("{}{}", path, key_path(k));
+				match b.get(k) {
+					Some(other) => diff_at(&path, v, other, tolerance, result)?,
+					None => result.push(format!("- {}: {}", path, to_string(v)?)),
+				}
+			}
+			for (k, v) in b {
+				if !a.contains_key(k) {
+					result.push(format!("+ {}{}: {}", path, key_path(k), to_string(v)?));
+				}
+			}
+		}
+		(Value::Array(a), Value::Array(b)) => {
+			for (i, v) in a.iter().enumerate() {
+				let path = format!("{}[{}]", path, i);
+				match b.get(i) {
+					Some(other) => diff_at(&path, v, other, tolerance, result)?,
+					None => result.push(format!("- {}: {}", path, to_string(v)?)),
+				}
+			}
+			for (i, v) in b.iter().enumerate().skip(a.len()) {
+				result.push(format!("+ {}[{}]: {}", path, i, to_string(v)?));
+			}
+		}
+		(Value::Number(x), Value::Number(y)) => {
+			let equal = x == y
+				|| match (x.as_f64(), y.as_f64()) {
+					(Some(x), Some(y)) => (x - y).abs() <= tolerance,
+					_ => false,
+				};
+			if !equal {
+				result.push(format!("~ {}: {} -> {}", path, x, y));
+			}
+		}
+		(a, b) => {
+			if a != b {
+				result.push(format!(
+					"~ {}: {} -> {}",
+					path,
+					to_string(a)?,
+					to_string(b)?
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+fn key_path(key: &str) -> String {
+	let is_ident = key
+		.chars()
+		.next()
+		.map(|c| c.is_ascii_alphabetic() || c == '_')
+		.unwrap_or(false)
+		&& key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+	if is_ident {
+		format!(".{}", key)
+	} else {
+		format!("[{}]", Value::String(key.to_string()))
+	}
+}
+
+/// JSON flatten (nested to dot-notation keys)
+pub fn json_flatten(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse(base::text(input)?)?;
+
+	let mut map = Map::new();
+	flatten("", &value, &options.separator, &mut map);
+
+	let value = Value::Object(map);
+
+	let result = if options.minify {
+		to_string(&value)?
+	} else {
+		to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// JSON unflatten (dot-notation keys to nested)
+pub fn json_unflatten(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse(base::text(input)?)?;
+
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	let value = unflatten(map, &options.separator)?;
+
+	let result = if options.minify {
+		to_string(&value)?
+	} else {
+		to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// JSON check, fails if invalid
+pub fn json_check(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	if options.relaxed {
+		parse_json5(input)?;
+	} else {
+		parse(input)?;
+	}
+
+	Ok(b"Valid".to_vec())
+}
+
+/// Array items are keyed by index, empty objects and arrays are kept as values
+pub fn flatten(prefix: &str, value: &Value, separator: &str, result: &mut Map<String, Value>) {
+	let key = |k: &str| {
+		if prefix.is_empty() {
+			k.to_string()
+		} else {
+			format!("{}{}{}", prefix, separator, k)
+		}
+	};
+	match value {
+		Value::Object(map) if !map.is_empty() => {
+			for (k, v) in map {
+				flatten(&key(k), v, separator, result);
+			}
+		}
+		Value::Array(array) if !array.is_empty() => {
+			for (i, v) in array.iter().enumerate() {
+				flatten(&key(&i.to_string()), v, separator, result);
+			}
+		}
+		value => {
+			result.insert(prefix.to_string(), value.clone());
+		}
+	}
+}
+
+/// Objects with keys 0, 1, 2... are converted to arrays
+pub fn unflatten(map: Map<String, Value>, separator: &str) -> Result<Value, Error> {
+	let mut root = Value::Object(Map::new());
+	for (k, v) in map {
+		let mut target = &mut root;
+		for segment in k.split(separator) {
+			target = match target {
+				Value::Object(map) => map
+					.entry(segment.to_string())
+					.or_insert_with(|| Value::Object(Map::new())),
+				_ => return Err(Error::failed(format!("Conflict key: {}", k))),
+			};
+		}
+		match target {
+			Value::Object(map) if map.is_empty() => *target = v,
+			_ => return Err(Error::failed(format!("Conflict key: {}", k))),
+		}
+	}
+	Ok(to_arrays(root))
+}
+
+fn to_arrays(value: Value) -> Value {
+	match value {
+		Value::Object(map) => {
+			let is_array = !map.is_empty()
+				&& map
+					.keys()
+					.enumerate()
+					.all(|(i, k)| k.parse::<usize>() == Ok(i));
+			let values = map.into_iter().map(|(k, v)| (k, to_arrays(v)));
+			if is_array {
+				Value::Array(values.map(|(_, v)| v).collect())
+			} else {
+				Value::Object(values.collect())
+			}
+		}
+		value => value,
+	}
+}
+
+fn sort_keys(value: Value) -> Value {
+	match value {
+		Value::Object(map) => {
+			let mut entries = map.into_iter().collect::<Vec<_>>();
+			entries.sort_by(|a, b| a.0.cmp(&b.0));
+			Value::Object(
+				entries
+					.into_iter()
+					.map(|(k, v)| (k, sort_keys(v)))
+					.collect(),
+			)
+		}
+		Value::Array(array) => Value::Array(array.into_iter().map(sort_keys).collect()),
+		value => value,
+	}
+}
+
+/// RFC 8785: keys sorted by UTF-16 code units, numbers in ECMAScript format, no whitespace
+pub fn to_canonical_string(value: &Value) -> Result<String, Error> {
+	let result = match value {
+		Value::Number(v) => {
+			let v = v.as_f64().ok_or(Error::invalid("number", ""))?;
+			to_es_number(v)?
+		}
+		Value::Array(array) => format!(
+			"[{}]",
+			array
+				.iter()
+				.map(to_canonical_string)
+				.collect::<Result<Vec<_>, _>>()?
+				.join(",")
+		),
+		Value::Object(map) => {
+			let mut entries = map.iter().collect::<Vec<_>>();
+			entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+			let entries = entries
+				.into_iter()
+				.map(|(k, v)| {
+					Ok(format!(
+						"{}:{}",
+						to_string(&Value::String(k.clone()))?,
+						to_canonical_string(v)?
+					))
+				})
+				.collect::<Result<Vec<_>, Error>>()?;
+			format!("{{{}}}", entries.join(","))
+		}
+		value => to_string(value)?,
+	};
+	Ok(result)
+}
+
+/// Number::toString of ECMAScript
+fn to_es_number(v: f64) -> Result<String, Error> {
+	if !v.is_finite() {
+		return Err(Error::invalid("number", ""));
+	}
+	if v == 0.0 {
+		return Ok("0".to_string());
+	}
+	let sign = if v < 0.0 { "-" } else { "" };
+
+	// shortest round-trip digits and exponent
+	let exp = format!("{:e}", v.abs());
+	let mut parts = exp.split('e');
+	let digits = parts.next().unwrap_or_default().replace('.', "");
+	let e: i32 = parts
+		.next()
+		.unwrap_or_default()
+		.parse()
+		.map_err(|_| Error::invalid("number", ""))?;
+
+	let k = digits.len() as i32;
+	let n = e + 1;
+	let result = if k <= n && n <= 21 {
+		format!("{}{}", digits, "0".repeat((n - k) as usize))
+	} else if 0 < n && n <= 21 {
+		format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+	} else if -6 < n && n <= 0 {
+		format!("0.{}{}", "0".repeat(-n as usize), digits)
+	} else {
+		let (first, rest) = digits.split_at(1);
+		let rest = if rest.is_empty() {
+			"".to_string()
+		} else {
+			format!(".{}", rest)
+		};
+		format!(
+			"{}{}e{}{}",
+			first,
+			rest,
+			if n > 0 { "+" } else { "-" },
+			(n - 1).abs()
+		)
+	};
+	Ok(format!("{}{}", sign, result))
+}
+
+pub fn parse(input: &str) -> Result<Value, Error> {
+	serde_json::from_str(input).map_err(|e| Error::invalid("JSON", e))
+}
+
+pub fn parse_json5(input: &str) -> Result<Value, Error> {
+	json5::from_str(input).map_err(|e| Error::invalid("JSON5", e))
+}
+
+pub fn to_string(value: &Value) -> Result<String, Error> {
+	serde_json::to_string(value).map_err(|_| Error::invalid("JSON", ""))
+}
+
+pub fn to_string_pretty(value: &Value, indent: usize) -> Result<String, Error> {
+	let indent = " ".repeat(indent);
+	let mut result = Vec::new();
+	let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+	let mut serializer = Serializer::with_formatter(&mut result, formatter);
+	value
+		.serialize(&mut serializer)
+		.map_err(|_| Error::invalid("JSON", ""))?;
+	String::from_utf8(result).map_err(|_| Error::failed("Not UTF-8"))
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"json_fmt",
+				vec![
+					Case {
+						desc: "Pretty print".to_string(),
+						input: vec![r#"'{"b":1,"a":[true,null]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"{",
+							r#"  "b": 1,"#,
+							r#"  "a": ["#,
+							"    true,",
+							"    null",
+							"  ]",
+							"}",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Minify".to_string(),
+						input: vec!["-m", r#"'{ "b": 1, "a": [ 1.10, 1e400 ] }'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"b":1,"a":[1.10,1e400]}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-i", "4", r#"'{"a":123456789012345678901234567890}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["{", r#"    "a": 123456789012345678901234567890"#, "}"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Normalize JSON5".to_string(),
+						input: vec![
+							"-r",
+							"-m",
+							r#"'{a: 1, /* comment */ b: [0x10, "x",], c: 'y',}'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![r#"{"a":1,"b":[16,"x"],"c":"y"}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Sort keys".to_string(),
+						input: vec!["-s", "-m", r#"'{"b":1,"a":{"d":2,"c":3}}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":{"c":3,"d":2},"b":1}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Canonicalize (RFC 8785 JCS)".to_string(),
+						input: vec![
+							"-c",
+							r#"'{"b":[1.0E+30,4.50,2e-3,1e21,1e-7],"a":"\u20ac\n","\u00e9":true}'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![r#"{"a":"€\n","b":[1e+30,4.5,0.002,1e+21,1e-7],"é":true}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"json_get",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-p", "'$.a[*].b'", r#"'{"a":[{"b":1},{"b":"x"}]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1", r#""x""#].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Output raw strings".to_string(),
+						input: vec![
+							"-r",
+							"-p",
+							"'$..name'",
+							r#"'{"a":{"name":"x"},"name":"y"}'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["y", "x"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-p", "'$.a[?(@.n > 1)]'", r#"'{"a":[{"n":1},{"n":2}]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"n":2}"#].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"json_diff",
+				vec![
+					Case {
+						desc: "Key order is ignored".to_string(),
+						input: vec![r#"'{"a":1,"b":[true]}'"#, r#"'{"b":[true],"a":1}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Numeric tolerance".to_string(),
+						input: vec!["-t", "0.01", r#"'{"a":1.001}'"#, r#"'{"a":1}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Print added / removed / changed paths".to_string(),
+						input: vec![r#"'{"a":1,"b":2}'"#, r#"'{"a":3,"c":4}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["~ $.a: 1 -> 3", "- $.b: 2", "+ $.c: 4"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"json_flatten",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", r#"'{"a":{"b":1,"c":[true,{"d":null}]},"e":{}}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a.b":1,"a.c.0":true,"a.c.1.d":null,"e":{}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Custom separator".to_string(),
+						input: vec!["-s", "'/'", "-m", r#"'{"a":{"b":1}}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a/b":1}"#].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"json_unflatten",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-m", r#"'{"a.b":1,"a.c.0":true,"a.c.1.d":null,"e":{}}'"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![r#"{"a":{"b":1,"c":[true,{"d":null}]},"e":{}}"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"json_check",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"a":[1,2]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Valid"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "JSON5".to_string(),
+						input: vec!["-r", "'{a:[1,2,],}'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Valid"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_diff() {
+		let a = parse(r#"{"a":1,"b":[1,2],"c":{"d":"x"},"e f":null}"#).unwrap();
+		let b = parse(r#"{"c":{"d":"y"},"b":[1.0],"a":1.05,"g":true}"#).unwrap();
+		assert_eq!(
+			diff(&a, &b, 0.1).unwrap(),
+			vec![
+				"- $.b[1]: 2",
+				r#"~ $.c.d: "x" -> "y""#,
+				r#"- $["e f"]: null"#,
+				"+ $.g: true",
+			]
+		);
+		assert_eq!(
+			diff(&a, &b, 0.0).unwrap()[0],
+			"~ $.a: 1 -> 1.05".to_string()
+		);
+	}
+}
@@ -0,0 +1,402 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use linked_hash_map::LinkedHashMap;
+use std::cmp::{Ordering, Reverse};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Lines sort, unique, count".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("lines_sort")
+				.about("Sort lines")
+				.arg(
+					Arg::with_name("MODE")
+						.long("mode")
+						.short("m")
+						.help("Sort mode\nlexical: by code point\nnumeric: by the leading number\nnatural: numbers in text compared by value, e.g. file2 before file10")
+						.takes_value(true)
+						.possible_values(&["lexical", "numeric", "natural"])
+						.default_value("lexical")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("REVERSE")
+						.long("reverse")
+						.short("r")
+						.help("Reverse")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("UNIQUE")
+						.long("unique")
+						.short("u")
+						.help("Remove duplicate lines")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("IGNORE_CASE")
+						.long("ignore-case")
+						.short("i")
+						.help("Case insensitive")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, lines_sort),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("lines_uniq")
+				.about("Remove duplicate lines (keep the first, not necessarily adjacent)")
+				.arg(
+					Arg::with_name("IGNORE_CASE")
+						.long("ignore-case")
+						.short("i")
+						.help("Case insensitive")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, lines_uniq),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("lines_count")
+				.about("Count lines by frequency (most frequent first)")
+				.arg(
+					Arg::with_name("IGNORE_CASE")
+						.long("ignore-case")
+						.short("i")
+						.help("Case insensitive")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, lines_count),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the lines commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub mode: Mode,
+	pub reverse: bool,
+	/// Remove duplicate lines
+	pub unique: bool,
+	pub ignore_case: bool,
+}
+
+/// Sort mode
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Mode {
+	/// By code point
+	#[default]
+	Lexical,
+	/// By the leading number
+	Numeric,
+	/// Numbers in text compared by value, e.g. file2 before file10
+	Natural,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let mode = match matches.value_of("MODE") {
+			Some("numeric") => Mode::Numeric,
+			Some("natural") => Mode::Natural,
+			_ => Mode::Lexical,
+		};
+
+		Ok(Options {
+			mode,
+			reverse: matches.is_present("REVERSE"),
+			unique: matches.is_present("UNIQUE"),
+			ignore_case: matches.is_present("IGNORE_CASE"),
+		})
+	}
+}
+
+/// Sort lines
+pub fn lines_sort(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let ignore_case = options.ignore_case;
+
+	let mut lines = base::text(input)?.lines().collect::<Vec<_>>();
+	if options.unique {
+		lines = unique(lines, ignore_case);
+	}
+
+	let key = |x: &str| {
+		if ignore_case {
+			x.to_lowercase()
+		} else {
+			x.to_string()
+		}
+	};
+	match options.mode {
+		Mode::Numeric => lines.sort_by(|a, b| {
+			leading_number(a)
+				.partial_cmp(&leading_number(b))
+				.unwrap_or(Ordering::Equal)
+				.then_with(|| key(a).cmp(&key(b)))
+		}),
+		Mode::Natural => lines.sort_by(|a, b| natural_cmp(&key(a), &key(b))),
+		Mode::Lexical => lines.sort_by_key(|x| key(x)),
+	}
+
+	if options.reverse {
+		lines.reverse();
+	}
+
+	Ok(lines.join("\n").into_bytes())
+}
+
+/// Remove duplicate lines (keep the first, not necessarily adjacent)
+pub fn lines_uniq(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let lines = unique(base::text(input)?.lines().collect(), options.ignore_case);
+
+	Ok(lines.join("\n").into_bytes())
+}
+
+/// Count lines by frequency (most frequent first)
+pub fn lines_count(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let ignore_case = options.ignore_case;
+
+	// keyed by the normalized line, the first occurrence is shown
+	let mut counts = LinkedHashMap::<String, (&str, usize)>::new();
+	for line in input.lines() {
+		let key = if ignore_case {
+			line.to_lowercase()
+		} else {
+			line.to_string()
+		};
+		counts.entry(key).or_insert((line, 0)).1 += 1;
+	}
+
+	// stable, so lines with the same count keep the order of first occurrence
+	let mut counts = counts.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+	counts.sort_by_key(|(_, count)| Reverse(*count));
+
+	let width = counts
+		.first()
+		.map(|(_, count)| count.to_string().len())
+		.unwrap_or(0);
+
+	let result = counts
+		.into_iter()
+		.map(|(line, count)| format!("{:>width$} {}", count, line, width = width))
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn unique(lines: Vec<&str>, ignore_case: bool) -> Vec<&str> {
+	let mut seen = std::collections::HashSet::new();
+	lines
+		.into_iter()
+		.filter(|x| {
+			if ignore_case {
+				seen.insert(x.to_lowercase())
+			} else {
+				seen.insert(x.to_string())
+			}
+		})
+		.collect()
+}
+
+/// Lines without leading number are treated as 0
+fn leading_number(line: &str) -> f64 {
+	let line = line.trim_start();
+	let end = line
+		.char_indices()
+		.take_while(|(i, c)| {
+			c.is_ascii_digit() || *c == '.' || (*i == 0 && (*c == '-' || *c == '+'))
+		})
+		.map(|(i, c)| i + c.len_utf8())
+		.last()
+		.unwrap_or(0);
+	line[..end].parse::<f64>().unwrap_or(0.0)
+}
+
+/// Digit runs are compared by value, other runs by code point, e.g. file2 < file10
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+	let (mut a_rest, mut b_rest) = (a, b);
+	loop {
+		let (a_chunk, a_next) = next_chunk(a_rest);
+		let (b_chunk, b_next) = next_chunk(b_rest);
+		let ordering = match (a_chunk, b_chunk) {
+			(None, None) => return a.cmp(b),
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(a_chunk), Some(b_chunk)) => {
+				let a_digit = a_chunk.starts_with(|c: char| c.is_ascii_digit());
+				let b_digit = b_chunk.starts_with(|c: char| c.is_ascii_digit());
+				if a_digit && b_digit {
+					let a_value = a_chunk.trim_start_matches('0');
+					let b_value = b_chunk.trim_start_matches('0');
+					a_value
+						.len()
+						.cmp(&b_value.len())
+						.then_with(|| a_value.cmp(b_value))
+				} else {
+					a_chunk.cmp(b_chunk)
+				}
+			}
+		};
+		if ordering != Ordering::Equal {
+			return ordering;
+		}
+		a_rest = a_next;
+		b_rest = b_next;
+	}
+}
+
+/// Splits off the leading run of digits or non-digits
+fn next_chunk(input: &str) -> (Option<&str>, &str) {
+	let digit = match input.chars().next() {
+		Some(c) => c.is_ascii_digit(),
+		None => return (None, input),
+	};
+	let end = input
+		.find(|c: char| c.is_ascii_digit() != digit)
+		.unwrap_or(input.len());
+	(Some(&input[..end]), &input[end..])
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"lines_sort",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'b\nc\na'"].into_iter().map(Into::into).collect(),
+						output: vec!["a", "b", "c"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Numeric, reverse".to_string(),
+						input: vec!["-m", "numeric", "-r", "'10 a\n9 b\n-1.5 c'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["10 a", "9 b", "-1.5 c"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Natural, unique".to_string(),
+						input: vec!["-m", "natural", "-u", "'file10\nfile2\nfile1\nfile2'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["file1", "file2", "file10"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Natural, versioned file names".to_string(),
+						input: vec![
+							"-m",
+							"natural",
+							"'dtool-0.10.0.tar.gz\ndtool-0.9.2.tar.gz\ndtool-0.9.10.tar.gz'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"dtool-0.9.2.tar.gz",
+							"dtool-0.9.10.tar.gz",
+							"dtool-0.10.0.tar.gz",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"lines_uniq",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'b\na\nb\nc\na'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["b", "a", "c"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"lines_count",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'b\na\nb\nc\na\nb'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["3 b", "2 a", "1 c"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Case insensitive".to_string(),
+						input: vec!["-i", "'A\na\nb'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2 A", "1 b"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -0,0 +1,285 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Lorem ipsum generator".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("lorem")
+			.about("Generate lorem ipsum placeholder text")
+			.arg(
+				Arg::with_name("UNIT")
+					.long("unit")
+					.short("u")
+					.help("Unit")
+					.takes_value(true)
+					.possible_values(&["words", "sentences", "paragraphs"])
+					.default_value("paragraphs")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("COUNT")
+					.long("count")
+					.short("n")
+					.help("Count of units")
+					.takes_value(true)
+					.default_value("1")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("SEED")
+					.long("seed")
+					.short("s")
+					.help("Seed for reproducible output")
+					.takes_value(true)
+					.required(false),
+			),
+		f: |matches| base::generate(matches, lorem),
+		stream: None,
+	}]
+}
+
+const WORDS: &str = "lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor incididunt ut labore et dolore magna aliqua enim ad minim veniam quis nostrud exercitation ullamco laboris nisi aliquip ex ea commodo consequat duis aute irure in reprehenderit voluptate velit esse cillum eu fugiat nulla pariatur excepteur sint occaecat cupidatat non proident sunt culpa qui officia deserunt mollit anim id est laborum at";
+
+/// The classic opening words
+const OPENING: usize = 5;
+
+/// Options of lorem
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub unit: Unit,
+	/// Count of units
+	pub count: usize,
+	/// Seed for reproducible output, the current time if not given
+	pub seed: Option<u64>,
+}
+
+/// Unit of the text
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Unit {
+	Words,
+	Sentences,
+	#[default]
+	Paragraphs,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			unit: Unit::default(),
+			count: 1,
+			seed: None,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let count = matches
+			.value_of("COUNT")
+			.ok_or(Error::invalid("count", ""))?
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("count", ""))?;
+		let seed = match matches.value_of("SEED") {
+			Some(seed) => Some(
+				seed.parse::<u64>()
+					.map_err(|_| Error::invalid("seed", ""))?,
+			),
+			None => None,
+		};
+		let unit = match matches.value_of("UNIT") {
+			Some("words") => Unit::Words,
+			Some("sentences") => Unit::Sentences,
+			_ => Unit::Paragraphs,
+		};
+		Ok(Options { unit, count, seed })
+	}
+}
+
+/// Generate lorem ipsum placeholder text, there is no input
+pub fn lorem(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let count = options.count;
+
+	let seed = match options.seed {
+		Some(seed) => seed,
+		None => SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_err(|_| Error::invalid("now", ""))?
+			.as_nanos() as u64,
+	};
+
+	let mut generator = Generator {
+		rng: Rng(seed),
+		index: 0,
+	};
+
+	let result = match options.unit {
+		Unit::Words => vec![(0..count)
+			.map(|_| generator.word())
+			.collect::<Vec<_>>()
+			.join(" ")],
+		Unit::Sentences => vec![(0..count)
+			.map(|_| generator.sentence())
+			.collect::<Vec<_>>()
+			.join(" ")],
+		Unit::Paragraphs => {
+			let mut result = vec![];
+			for i in 0..count {
+				if i > 0 {
+					result.push("".to_string());
+				}
+				result.push(generator.paragraph());
+			}
+			result
+		}
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// SplitMix64, small and stable across versions so that seeded output is reproducible
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Inclusive range
+	fn range(&mut self, low: usize, high: usize) -> usize {
+		low + (self.next() % (high - low + 1) as u64) as usize
+	}
+}
+
+struct Generator {
+	rng: Rng,
+	/// Count of words generated
+	index: usize,
+}
+
+impl Generator {
+	fn word(&mut self) -> &'static str {
+		let words = WORDS.split(' ').collect::<Vec<_>>();
+		let result = if self.index < OPENING {
+			words[self.index]
+		} else {
+			words[self.rng.range(0, words.len() - 1)]
+		};
+		self.index += 1;
+		result
+	}
+
+	fn sentence(&mut self) -> String {
+		let len = self.rng.range(6, 14);
+		let mut result = String::new();
+		for i in 0..len {
+			let word = self.word();
+			if i == 0 {
+				let mut chars = word.chars();
+				if let Some(c) = chars.next() {
+					result.extend(c.to_uppercase());
+					result.push_str(chars.as_str());
+				}
+			} else {
+				// occasional comma, not right before the end
+				if i > 2 && i < len - 2 && self.rng.range(0, 7) == 0 {
+					result.push(',');
+				}
+				result.push(' ');
+				result.push_str(word);
+			}
+		}
+		result.push('.');
+		result
+	}
+
+	fn paragraph(&mut self) -> String {
+		let len = self.rng.range(3, 6);
+		(0..len)
+			.map(|_| self.sentence())
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"lorem",
+			vec![
+				Case {
+					desc: "Words".to_string(),
+					input: vec!["-u", "words", "-n", "5"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["lorem ipsum dolor sit amet"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Sentences, seeded".to_string(),
+					input: vec!["-u", "sentences", "-n", "2", "-s", "1"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["Lorem ipsum dolor sit amet tempor lorem, sunt veniam laborum lorem. Velit anim aute occaecat sed nisi ut."]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Paragraphs".to_string(),
+					input: vec!["-n", "2"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"Lorem ipsum dolor sit amet cupidatat adipiscing. Irure minim pariatur at reprehenderit nisi cupidatat. Aliquip elit commodo velit minim dolore sunt elit sunt aliquip ex. Sit magna ipsum lorem duis incididunt magna.",
+						"",
+						"Cillum quis pariatur excepteur sed laboris tempor ipsum. Sint mollit veniam adipiscing cillum non veniam reprehenderit eiusmod. Cupidatat fugiat dolor aliquip labore laboris aliquip sit mollit proident ullamco elit non.",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -0,0 +1,196 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use pulldown_cmark::{html, Event, HeadingLevel, Options as ParserOptions, Parser, Tag, TagEnd};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Markdown to HTML".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("md")
+			.about("Render CommonMark to HTML, with tables and strikethrough")
+			.arg(
+				Arg::with_name("STANDALONE")
+					.long("standalone")
+					.short("s")
+					.help("Wrap in a complete HTML document"),
+			)
+			.arg(
+				Arg::with_name("TITLE")
+					.long("title")
+					.help(
+						"Title of the standalone document, the first level 1 heading if not given",
+					)
+					.takes_value(true)
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, md),
+		stream: None,
+	}]
+}
+
+/// Options of `md`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Wrap in a complete HTML document
+	pub standalone: bool,
+	/// Title of the standalone document, the first level 1 heading if not given
+	pub title: Option<String>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			standalone: matches.is_present("STANDALONE"),
+			title: matches.value_of("TITLE").map(String::from),
+		})
+	}
+}
+
+/// Render CommonMark to HTML, with tables and strikethrough
+pub fn md(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let parser_options = ParserOptions::ENABLE_TABLES | ParserOptions::ENABLE_STRIKETHROUGH;
+	let mut body = String::new();
+	html::push_html(&mut body, Parser::new_ext(input, parser_options));
+	let body = body.trim_end();
+
+	let result = match options.standalone {
+		true => {
+			let title = match &options.title {
+				Some(title) => title.clone(),
+				None => title(input).unwrap_or_else(|| "Document".to_string()),
+			};
+			format!(
+				"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>",
+				escaper::encode_minimal(&title),
+				body
+			)
+		}
+		false => body.to_string(),
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// The text of the first level 1 heading
+fn title(input: &str) -> Option<String> {
+	let mut events = Parser::new(input).skip_while(|event| {
+		!matches!(
+			event,
+			Event::Start(Tag::Heading {
+				level: HeadingLevel::H1,
+				..
+			})
+		)
+	});
+	events.next()?;
+	let title = events
+		.take_while(|event| !matches!(event, Event::End(TagEnd::Heading(_))))
+		.filter_map(|event| match event {
+			Event::Text(text) | Event::Code(text) => Some(text.into_string()),
+			_ => None,
+		})
+		.collect::<String>();
+	Some(title)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"md",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'# Title\n\nSome **bold** and ~~struck~~ text'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"<h1>Title</h1>",
+						"<p>Some <strong>bold</strong> and <del>struck</del> text</p>",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Standalone document".to_string(),
+					input: vec!["-s", "'# Title'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"<!DOCTYPE html>",
+						"<html>",
+						"<head>",
+						"<meta charset=\"utf-8\">",
+						"<title>Title</title>",
+						"</head>",
+						"<body>",
+						"<h1>Title</h1>",
+						"</body>",
+						"</html>",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Preview a README section".to_string(),
+					input: vec!["-s", "-f", "README.md"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["<!DOCTYPE html>", "..."]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: false,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_title() {
+		assert_eq!(
+			title("intro\n\n## Sub\n\n# The `md` command\n\n# Other"),
+			Some("The md command".to_string())
+		);
+		assert_eq!(title("## Sub"), None);
+	}
+}
@@ -0,0 +1,192 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::ArgMatches;
+use infer::MatcherType;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "MIME type detection".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("mime")
+			.about("Detect MIME type from the content (magic bytes), not the file extension")
+			.args(&base::input_args()),
+		f: |matches| base::convert_bytes(matches, mime),
+		stream: None,
+	}]
+}
+
+pub struct Detection {
+	pub mime: &'static str,
+	pub extension: &'static str,
+	pub kind: &'static str,
+	pub confidence: &'static str,
+}
+
+/// No options
+#[derive(Debug, Clone, Default)]
+pub struct Options;
+
+impl FromMatches for Options {
+	fn from_matches(_: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options)
+	}
+}
+
+/// Detect MIME type from the content (magic bytes), not the file extension
+pub fn mime(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let detection = detect(input);
+
+	let mut result = vec![format!("mime: {}", detection.mime)];
+	if !detection.extension.is_empty() {
+		result.push(format!("extension: {}", detection.extension));
+	}
+	result.push(format!("kind: {}", detection.kind));
+	result.push(format!("confidence: {}", detection.confidence));
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Magic bytes first, then text heuristics
+pub fn detect(input: &[u8]) -> Detection {
+	if let Some(kind) = infer::get(input) {
+		let (name, confidence) = match kind.matcher_type() {
+			MatcherType::App => ("application", "high, magic bytes"),
+			MatcherType::Archive => ("archive", "high, magic bytes"),
+			MatcherType::Audio => ("audio", "high, magic bytes"),
+			MatcherType::Book => ("book", "high, magic bytes"),
+			MatcherType::Doc => ("document", "high, magic bytes"),
+			MatcherType::Font => ("font", "high, magic bytes"),
+			MatcherType::Image => ("image", "high, magic bytes"),
+			MatcherType::Video => ("video", "high, magic bytes"),
+			MatcherType::Text => ("text", "medium, leading text signature"),
+			MatcherType::Custom => ("custom", "high, magic bytes"),
+		};
+		return Detection {
+			mime: kind.mime_type(),
+			extension: kind.extension(),
+			kind: name,
+			confidence,
+		};
+	}
+
+	if input.is_empty() {
+		return Detection {
+			mime: "application/x-empty",
+			extension: "",
+			kind: "empty",
+			confidence: "high, no content",
+		};
+	}
+
+	match std::str::from_utf8(input) {
+		Ok(text) => {
+			let trimmed = text.trim_start();
+			if (trimmed.starts_with('{') || trimmed.starts_with('['))
+				&& serde_json::from_str::<serde_json::Value>(text).is_ok()
+			{
+				Detection {
+					mime: "application/json",
+					extension: "json",
+					kind: "text",
+					confidence: "medium, parsed as JSON",
+				}
+			} else {
+				Detection {
+					mime: "text/plain",
+					extension: "txt",
+					kind: "text",
+					confidence: "low, valid UTF-8 without magic bytes",
+				}
+			}
+		}
+		Err(_) => Detection {
+			mime: "application/octet-stream",
+			extension: "",
+			kind: "binary",
+			confidence: "unknown, no magic bytes",
+		},
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"mime",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["-f", "blob"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"mime: image/png",
+						"extension: png",
+						"kind: image",
+						"confidence: high, magic bytes",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Text".to_string(),
+					input: vec![r#"'{"a":1}'"#].into_iter().map(Into::into).collect(),
+					output: vec![
+						"mime: application/json",
+						"extension: json",
+						"kind: text",
+						"confidence: medium, parsed as JSON",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_detect() {
+		let cases: Vec<(&[u8], &str, &str)> = vec![
+			(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "image/png", "image"),
+			(b"%PDF-1.7\n", "application/pdf", "archive"),
+			(b"\x1f\x8b\x08\0\0\0\0\0", "application/gzip", "archive"),
+			(b"\0asm\x01\0\0\0", "application/wasm", "application"),
+			(b"<!DOCTYPE html><html>", "text/html", "text"),
+			(b"hello", "text/plain", "text"),
+			(b"", "application/x-empty", "empty"),
+			(b"\xff\x00\xfe", "application/octet-stream", "binary"),
+		];
+		for (input, mime, kind) in cases {
+			let detection = detect(input);
+			assert_eq!((detection.mime, detection.kind), (mime, kind));
+		}
+	}
+}
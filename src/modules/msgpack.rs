@@ -0,0 +1,251 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use rmpv::Value as MsgPack;
+use serde_json::{Map, Value};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "JSON / MessagePack conversion".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("j2m")
+				.about("Convert JSON to MessagePack")
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Output binary instead of hex")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_to_hex_or_binary(matches, j2m),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("m2j")
+				.about("Convert MessagePack to JSON")
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Input binary instead of hex")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_from_hex_or_binary(matches, m2j),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the MessagePack commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert JSON to MessagePack, the output is binary
+pub fn j2m(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let mut result = vec![];
+	rmpv::encode::write_value(&mut result, &from_json(&value))
+		.map_err(|e| Error::invalid("MessagePack", e))?;
+
+	Ok(result)
+}
+
+/// Convert MessagePack to JSON, the input is binary
+pub fn m2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let mut reader = input;
+	let mut values = vec![];
+	while !reader.is_empty() {
+		let value =
+			rmpv::decode::read_value(&mut reader).map_err(|e| Error::invalid("MessagePack", e))?;
+		values.push(to_json(value)?);
+	}
+
+	// concatenated values are converted to array
+	let value = match values.len() {
+		0 => Value::Null,
+		1 => values.remove(0),
+		_ => Value::Array(values),
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Binary is converted to hex string,
+/// ext is converted to {"type": <type>, "data": <hex>}
+fn to_json(value: MsgPack) -> Result<Value, Error> {
+	let result = match value {
+		MsgPack::Nil => Value::Null,
+		MsgPack::Boolean(v) => Value::Bool(v),
+		MsgPack::Integer(v) => match (v.as_u64(), v.as_i64()) {
+			(Some(v), _) => Value::from(v),
+			(_, Some(v)) => Value::from(v),
+			_ => return Err(Error::invalid("integer", "")),
+		},
+		MsgPack::F32(v) => Value::from(v),
+		MsgPack::F64(v) => Value::from(v),
+		MsgPack::String(v) => match v.into_str() {
+			Some(v) => Value::String(v),
+			None => return Err(Error::failed("Not UTF-8")),
+		},
+		MsgPack::Binary(v) => Value::String(base::Hex::from(v).into()),
+		MsgPack::Array(v) => Value::Array(v.into_iter().map(to_json).collect::<Result<_, _>>()?),
+		MsgPack::Map(v) => {
+			let mut map = Map::new();
+			for (k, v) in v {
+				let k = match to_json(k)? {
+					Value::String(k) => k,
+					k => json::to_string(&k)?,
+				};
+				map.insert(k, to_json(v)?);
+			}
+			Value::Object(map)
+		}
+		MsgPack::Ext(t, v) => {
+			let mut map = Map::new();
+			map.insert("type".to_string(), Value::from(t));
+			map.insert("data".to_string(), Value::String(base::Hex::from(v).into()));
+			Value::Object(map)
+		}
+	};
+	Ok(result)
+}
+
+fn from_json(value: &Value) -> MsgPack {
+	match value {
+		Value::Null => MsgPack::Nil,
+		Value::Bool(v) => MsgPack::Boolean(*v),
+		Value::Number(v) => match (v.as_u64(), v.as_i64(), v.as_f64()) {
+			(Some(v), _, _) => MsgPack::from(v),
+			(_, Some(v), _) => MsgPack::from(v),
+			(_, _, Some(v)) => MsgPack::F64(v),
+			_ => MsgPack::Nil,
+		},
+		Value::String(v) => MsgPack::from(v.as_str()),
+		Value::Array(v) => MsgPack::Array(v.iter().map(from_json).collect()),
+		Value::Object(v) => MsgPack::Map(
+			v.iter()
+				.map(|(k, v)| (MsgPack::from(k.as_str()), from_json(v)))
+				.collect(),
+		),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"j2m",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"a":1,"b":[true,null,-1,1.5]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["0x82a16101a16294c3c0ffcb3ff8000000000000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Output binary".to_string(),
+						input: vec!["-b", r#"'{"a":1}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"m2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "0x82a16101a16294c3c0ffcb3ff8000000000000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":1,"b":[true,null,-1,1.5]}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Binary and ext".to_string(),
+						input: vec!["-m", "0x92c4020102d40107"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"["0x0102",{"type":1,"data":"0x07"}]"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
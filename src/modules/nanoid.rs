@@ -0,0 +1,308 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use std::collections::HashSet;
+
+/// Alphabets by name, the others are taken as the characters of the alphabet
+const ALPHABETS: [(&str, &str); 5] = [
+	(
+		"url",
+		"_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+	),
+	(
+		"alphanumeric",
+		"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+	),
+	(
+		"nolookalikes",
+		"346789ABCDEFGHJKLMNPQRTUVWXYabcdefghijkmnpqrtwxyz",
+	),
+	("hex", "0123456789abcdef"),
+	("numbers", "0123456789"),
+];
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "NanoID generate".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("nanoid")
+			.about("NanoID generate, or estimate the collision probability of the length and alphabet")
+			.arg(
+				Arg::with_name("LENGTH")
+					.long("length")
+					.short("l")
+					.help("Number of characters")
+					.takes_value(true)
+					.default_value("21")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("ALPHABET")
+					.long("alphabet")
+					.short("a")
+					.help("url: A-Za-z0-9_-\nalphanumeric: A-Za-z0-9\nnolookalikes: without 1, l, I, 0, O, o, u, v, 5, S, s, 2, Z\nhex: 0-9a-f\nnumbers: 0-9\nor the characters of the alphabet, e.g. 0123456789ABCDEF")
+					.takes_value(true)
+					.default_value("url")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("COUNT")
+					.long("count")
+					.short("c")
+					.help("Number of NanoIDs")
+					.takes_value(true)
+					.default_value("1")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("ESTIMATE")
+					.long("estimate")
+					.short("e")
+					.help("Estimate the collision probability instead of generating"),
+			)
+			.arg(
+				Arg::with_name("IDS")
+					.long("ids")
+					.help("Number of IDs the collision probability is estimated for")
+					.takes_value(true)
+					.default_value("1000000")
+					.required(false),
+			),
+		f: |matches| base::generate(matches, nanoid),
+		stream: None,
+	}]
+}
+
+/// Options of nanoid
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Number of characters
+	pub length: usize,
+	/// Name of the alphabet, e.g. `url`, or the characters of the alphabet
+	pub alphabet: String,
+	pub count: usize,
+	/// Estimate the collision probability for the number of IDs instead of generating
+	pub estimate: Option<f64>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			length: 21,
+			alphabet: "url".to_string(),
+			count: 1,
+			estimate: None,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let length = matches
+			.value_of("LENGTH")
+			.unwrap_or("21")
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("length", ""))?;
+		let count = matches
+			.value_of("COUNT")
+			.unwrap_or("1")
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("count", ""))?;
+		let estimate = match matches.is_present("ESTIMATE") {
+			true => Some(
+				matches
+					.value_of("IDS")
+					.unwrap_or("1000000")
+					.parse::<f64>()
+					.map_err(|_| Error::invalid("ids", ""))?,
+			),
+			false => None,
+		};
+		Ok(Options {
+			length,
+			alphabet: matches.value_of("ALPHABET").unwrap_or("url").to_string(),
+			count,
+			estimate,
+		})
+	}
+}
+
+/// NanoID generate, or estimate the collision probability of the length and alphabet, there is no input
+pub fn nanoid(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let length = options.length;
+	if length == 0 {
+		return Err(Error::invalid("length", ""));
+	}
+	let alphabet = alphabet(&options.alphabet)?;
+
+	if let Some(ids) = options.estimate {
+		let ids = Some(ids)
+			.filter(|x| *x >= 1.0)
+			.ok_or(Error::invalid("ids", ""))?;
+		return Ok(estimate(alphabet.len(), length, ids)
+			.join("\n")
+			.into_bytes());
+	}
+
+	let result = (0..options.count)
+		.map(|_| nanoid::format(nanoid::rngs::default, &alphabet, length))
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn alphabet(name: &str) -> Result<Vec<char>, Error> {
+	let alphabet = ALPHABETS
+		.iter()
+		.find(|(x, _)| *x == name)
+		.map(|(_, x)| *x)
+		.unwrap_or(name);
+
+	// NanoID picks the characters with random bytes, and counts the length in bytes
+	if !alphabet.is_ascii() {
+		return Err(Error::invalid("alphabet", "only ASCII characters"));
+	}
+	let mut seen = HashSet::new();
+	if let Some(c) = alphabet.chars().find(|c| !seen.insert(*c)) {
+		return Err(Error::invalid(
+			"alphabet",
+			format!("duplicated character: {}", c),
+		));
+	}
+	if alphabet.len() < 2 {
+		return Err(Error::invalid("alphabet", "at least 2 characters"));
+	}
+
+	Ok(alphabet.chars().collect())
+}
+
+/// By the birthday problem, in logarithms since the number of possible IDs overflows f64
+fn estimate(alphabet: usize, length: usize, ids: f64) -> Vec<String> {
+	let ln_space = length as f64 * (alphabet as f64).ln();
+	let bits = ln_space / 2f64.ln();
+
+	// n = sqrt(2 * space * ln(1 / (1 - p)))
+	let ids_for_one_percent = (0.5 * (2f64.ln() + ln_space + (-(0.99f64).ln()).ln())).exp();
+
+	// p = 1 - exp(-n * (n - 1) / (2 * space))
+	let exponent = ((ids * (ids - 1.0) / 2.0).ln() - ln_space).exp();
+	let probability = -(-exponent).exp_m1();
+
+	vec![
+		format!("alphabet: {} characters", alphabet),
+		format!("length: {}", length),
+		format!("entropy: {:.1} bits", bits),
+		format!("ids_for_1%_collision: {:.1e}", ids_for_one_percent),
+		format!("collision_probability: {:.1e} ({} IDs)", probability, ids),
+	]
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"nanoid",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: Vec::<String>::new(),
+					output: vec!["V1StGXR8_Z5jdHi6B-myT"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Custom alphabet".to_string(),
+					input: vec!["-a", "0123456789ABCDEF", "-l", "12", "-c", "2"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["4F90D13A42C8", "B7E2A0915D3F"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Collision probability".to_string(),
+					input: vec!["-e"].into_iter().map(Into::into).collect(),
+					output: vec![
+						"alphabet: 64 characters",
+						"length: 21",
+						"entropy: 126.0 bits",
+						"ids_for_1%_collision: 1.3e18",
+						"collision_probability: 5.9e-27 (1000000 IDs)",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_nanoid() {
+		let options = Options {
+			length: 32,
+			alphabet: "hex".to_string(),
+			count: 3,
+			estimate: None,
+		};
+		let result = base::lines(nanoid(&[], &options).unwrap()).unwrap();
+		assert_eq!(result.len(), 3);
+		for id in &result {
+			assert_eq!(id.len(), 32);
+			assert!(id
+				.chars()
+				.all(|c| c.is_ascii_hexdigit() && !c.is_uppercase()));
+		}
+
+		for alphabet in &["aab", "a", "αβγ"] {
+			let options = Options {
+				alphabet: alphabet.to_string(),
+				..Options::default()
+			};
+			assert!(nanoid(&[], &options).is_err());
+		}
+	}
+
+	#[test]
+	fn test_estimate() {
+		// 8 hex characters, 1% at about 9300 IDs
+		let result = estimate(16, 8, 9300.0);
+		assert_eq!(result[2], "entropy: 32.0 bits");
+		assert_eq!(result[3], "ids_for_1%_collision: 9.3e3");
+		assert_eq!(result[4], "collision_probability: 1.0e-2 (9300 IDs)");
+	}
+}
@@ -0,0 +1,257 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::Value;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "NDJSON (JSON Lines) split, join, filter".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("ndjson_split")
+				.about("Convert JSON array to NDJSON")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ndjson_split),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ndjson_join")
+				.about("Convert NDJSON to JSON array")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ndjson_join),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ndjson_filter")
+				.about("NDJSON filter lines by expression")
+				.arg(
+					Arg::with_name("EXPRESSION")
+						.long("expression")
+						.short("e")
+						.help(
+							"JSONPath filter expression, e.g. @.level == 'error' && @.code >= 500",
+						)
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("COUNT")
+						.long("count")
+						.short("c")
+						.help("Output the number of matched lines")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ndjson_filter),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the NDJSON commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+	/// JSONPath filter expression, e.g. `@.level == 'error' && @.code >= 500`
+	pub expression: String,
+	/// Output the number of matched lines
+	pub count: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+			expression: matches
+				.value_of("EXPRESSION")
+				.unwrap_or_default()
+				.to_string(),
+			count: matches.is_present("COUNT"),
+		})
+	}
+}
+
+/// Convert JSON array to NDJSON
+pub fn ndjson_split(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let array = match value {
+		Value::Array(array) => array,
+		_ => return Err(Error::failed("Not array")),
+	};
+
+	let result = array
+		.iter()
+		.map(json::to_string)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert NDJSON to JSON array
+pub fn ndjson_join(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = Value::Array(
+		parse(base::text(input)?)?
+			.into_iter()
+			.map(|(_, v)| v)
+			.collect(),
+	);
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// NDJSON filter lines by expression
+pub fn ndjson_filter(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	if options.expression.is_empty() {
+		return Err(Error::invalid("expression", ""));
+	}
+
+	// each line is wrapped in an array and selected by the JSONPath filter
+	let path = format!("$[?({})]", options.expression);
+
+	let mut result = vec![];
+	for (line, value) in parse(base::text(input)?)? {
+		let wrapped = Value::Array(vec![value]);
+		let selected =
+			jsonpath_lib::select(&wrapped, &path).map_err(|e| Error::invalid("expression", e))?;
+		if !selected.is_empty() {
+			result.push(line);
+		}
+	}
+
+	if options.count {
+		return Ok(result.len().to_string().into_bytes());
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Returns the lines and the values, blank lines are skipped
+fn parse(input: &str) -> Result<Vec<(String, Value)>, Error> {
+	input
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.trim().is_empty())
+		.map(|(i, line)| {
+			let value =
+				json::parse(line).map_err(|e| Error::failed(format!("Line {}: {}", i + 1, e)))?;
+			Ok((line.to_string(), value))
+		})
+		.collect()
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"ndjson_split",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![r#"'[{"a": 1}, {"a": 2}]'"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![r#"{"a":1}"#, r#"{"a":2}"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"ndjson_join",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-m", "'{\"a\":1}\n\n{\"a\":2}'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![r#"[{"a":1},{"a":2}]"#]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"ndjson_filter",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-e",
+							"'@.level == \"error\"'",
+							"'{\"level\":\"info\"}\n{\"level\":\"error\",\"code\":500}'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![r#"{"level":"error","code":500}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Count".to_string(),
+						input: vec![
+							"-c",
+							"-e",
+							"'@.code >= 400'",
+							"'{\"code\":200}\n{\"code\":404}\n{\"code\":500}'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["2"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -1,6 +1,7 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 use parity_codec::{Compact, Decode, Encode};
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
@@ -14,7 +15,7 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("ne")
+			app: base::sub_command("ne")
 				.about("Number encode")
 				.arg(
 					Arg::with_name("TYPE")
@@ -22,13 +23,15 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("t")
 						.help("Number type\nu8\nu16\nu32\nu64\nu128\nc: Compact")
 						.takes_value(true)
+						.possible_values(&["u8", "u16", "u32", "u64", "u128", "c"])
 						.required(true),
 				)
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: ne,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ne),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("nd")
+			app: base::sub_command("nd")
 				.about("Number decode")
 				.arg(
 					Arg::with_name("TYPE")
@@ -36,94 +39,124 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("t")
 						.help("Number type: u8, u16, u32, u64, u128, c(Compact)")
 						.takes_value(true)
+						.possible_values(&["u8", "u16", "u32", "u64", "u128", "c"])
 						.required(true),
 				)
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: nd,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, nd),
+			stream: None,
 		},
 	]
 }
 
-fn ne(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// The number type of `ne` and `nd`
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub number_type: NumberType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberType {
+	U8,
+	U16,
+	U32,
+	U64,
+	U128,
+	Compact,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let number_type = match matches.value_of("TYPE") {
+			Some("u8") => NumberType::U8,
+			Some("u16") => NumberType::U16,
+			Some("u32") => NumberType::U32,
+			Some("u64") => NumberType::U64,
+			Some("u128") => NumberType::U128,
+			Some("c") => NumberType::Compact,
+			_ => return Err(Error::invalid("number type", "")),
+		};
+		Ok(Options { number_type })
+	}
+}
 
-	let t = matches.value_of("TYPE").ok_or("Invalid number type")?;
+/// Number encode
+pub fn ne(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
-	let result = match t {
-		"u8" => {
-			let input = input.parse::<u8>().map_err(|_| "Invalid input")?;
+	let result = match options.number_type {
+		NumberType::U8 => {
+			let input = input.parse::<u8>().map_err(|_| Error::input(""))?;
 			vec![input]
 		}
-		"u16" => {
-			let input = input.parse::<u16>().map_err(|_| "Invalid input")?;
+		NumberType::U16 => {
+			let input = input.parse::<u16>().map_err(|_| Error::input(""))?;
 			input.encode()
 		}
-		"u32" => {
-			let input = input.parse::<u32>().map_err(|_| "Invalid input")?;
+		NumberType::U32 => {
+			let input = input.parse::<u32>().map_err(|_| Error::input(""))?;
 			input.encode()
 		}
-		"u64" => {
-			let input = input.parse::<u64>().map_err(|_| "Invalid input")?;
+		NumberType::U64 => {
+			let input = input.parse::<u64>().map_err(|_| Error::input(""))?;
 			input.encode()
 		}
-		"u128" => {
-			let input = input.parse::<u128>().map_err(|_| "Invalid input")?;
+		NumberType::U128 => {
+			let input = input.parse::<u128>().map_err(|_| Error::input(""))?;
 			input.encode()
 		}
-		"c" => {
-			let input = Compact(input.parse::<u128>().map_err(|_| "Invalid input")?);
+		NumberType::Compact => {
+			let input = Compact(input.parse::<u128>().map_err(|_| Error::input(""))?);
 			input.encode()
 		}
-		_ => return Err("Invalid input".to_string()),
 	};
 
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn nd(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let t = matches.value_of("TYPE").ok_or("Invalid number type")?;
-
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
+/// Number decode
+pub fn nd(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::input(""))?
+		.into();
 
 	let mut input = &input[..];
 
-	let result = match t {
-		"u8" => {
-			let input: u8 = if input.len() > 0 {
+	let result = match options.number_type {
+		NumberType::U8 => {
+			let input: u8 = if !input.is_empty() {
 				input[0]
 			} else {
-				return Err("Invalid input".to_string());
+				return Err(Error::input(""));
 			};
 			format!("{}", input)
 		}
-		"u16" => {
-			let input: u16 = Decode::decode(&mut input).ok_or("Invalid input")?;
+		NumberType::U16 => {
+			let input: u16 = Decode::decode(&mut input).ok_or(Error::input(""))?;
 			format!("{}", input)
 		}
-		"u32" => {
-			let input: u32 = Decode::decode(&mut input).ok_or("Invalid input")?;
+		NumberType::U32 => {
+			let input: u32 = Decode::decode(&mut input).ok_or(Error::input(""))?;
 			format!("{}", input)
 		}
-		"u64" => {
-			let input: u64 = Decode::decode(&mut input).ok_or("Invalid input")?;
+		NumberType::U64 => {
+			let input: u64 = Decode::decode(&mut input).ok_or(Error::input(""))?;
 			format!("{}", input)
 		}
-		"u128" => {
-			let input: u128 = Decode::decode(&mut input).ok_or("Invalid input")?;
+		NumberType::U128 => {
+			let input: u128 = Decode::decode(&mut input).ok_or(Error::input(""))?;
 			format!("{}", input)
 		}
-		"c" => {
-			let input: Compact<u128> = Decode::decode(&mut input).ok_or("Invalid input")?;
+		NumberType::Compact => {
+			let input: Compact<u128> = Decode::decode(&mut input).ok_or(Error::input(""))?;
 			format!("{}", input.0)
 		}
-		_ => return Err("Invalid input".to_string()),
 	};
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
 mod cases {
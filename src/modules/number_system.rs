@@ -1,5 +1,7 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
@@ -11,7 +13,7 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![Command {
-		app: SubCommand::with_name("ns")
+		app: base::sub_command("ns")
 			.about("Number system")
 			.arg(
 				Arg::with_name("DECIMAL")
@@ -30,7 +32,7 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 			.arg(
 				Arg::with_name("OCTAL")
 					.long("octal")
-					.short("o")
+					.short("O")
 					.help("Output octal result")
 					.required(false),
 			)
@@ -41,38 +43,60 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 					.help("Output hexadecimal result")
 					.required(false),
 			)
-			.arg(Arg::with_name("INPUT").required(false).index(1)),
-		f: ns,
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, ns),
+		stream: None,
 	}]
 }
 
-fn ns(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// Output bases, every base if none is given
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub decimal: bool,
+	pub binary: bool,
+	pub octal: bool,
+	pub hexadecimal: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			decimal: matches.is_present("DECIMAL"),
+			binary: matches.is_present("BINARY"),
+			octal: matches.is_present("OCTAL"),
+			hexadecimal: matches.is_present("HEXADECIMAL"),
+		})
+	}
+}
+
+/// Number system
+pub fn ns(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
 	let (radix, number) = match input {
 		_ if input.starts_with("0b") => (2, &input[2..]),
 		_ if input.starts_with("0o") => (8, &input[2..]),
 		_ if input.starts_with("0x") => (16, &input[2..]),
-		_ => (10, &input[..]),
+		_ => (10, input),
 	};
 
-	let number = u64::from_str_radix(number, radix).map_err(|_| "Invalid input")?;
+	let number = u64::from_str_radix(number, radix).map_err(|_| Error::input(""))?;
 
 	let mut results = Vec::new();
 
-	if matches.is_present("DECIMAL") {
+	if options.decimal {
 		results.push(format!("{}", number));
 	}
-	if matches.is_present("BINARY") {
+	if options.binary {
 		results.push(format!("0b{:b}", number));
 	}
-	if matches.is_present("OCTAL") {
+	if options.octal {
 		results.push(format!("0o{:o}", number));
 	}
-	if matches.is_present("HEXADECIMAL") {
+	if options.hexadecimal {
 		results.push(format!("0x{:x}", number));
 	}
-	if results.len() == 0 {
+	if results.is_empty() {
 		results = vec![
 			format!("{}", number),
 			format!("0b{:b}", number),
@@ -81,7 +105,7 @@ fn ns(matches: &ArgMatches) -> Result<Vec<String>, String> {
 		];
 	}
 
-	Ok(results)
+	Ok(results.join("\n").into_bytes())
 }
 
 mod cases {
@@ -132,7 +156,7 @@ mod cases {
 				},
 				Case {
 					desc: "Output octal".to_string(),
-					input: vec!["-o", "256"].into_iter().map(Into::into).collect(),
+					input: vec!["-O", "256"].into_iter().map(Into::into).collect(),
 					output: vec!["0o400"].into_iter().map(Into::into).collect(),
 					is_example: true,
 					is_test: true,
@@ -1,6 +1,7 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 use ring::pbkdf2::{
 	derive, PBKDF2_HMAC_SHA1, PBKDF2_HMAC_SHA256, PBKDF2_HMAC_SHA384, PBKDF2_HMAC_SHA512,
 };
@@ -17,7 +18,7 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("pbkdf2").about("Pbkdf2")
+			app: base::sub_command("pbkdf2").about("Pbkdf2")
 				.arg(
 					Arg::with_name("ITERATIONS")
 						.long("iterations")
@@ -30,6 +31,7 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.long("algorithm")
 						.short("a").help("Algorithm\nsha1: SHA-1\nsha2_256: SHA-2 256\nsha2_384: SHA-2 384\nsha2_512: SHA-2 512")
 						.takes_value(true)
+						.possible_values(&["sha1", "sha2_256", "sha2_384", "sha2_512"])
 						.default_value("sha1")
 						.required(false))
 				.arg(
@@ -47,76 +49,118 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.default_value("128")
 						.required(false))
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Secret (Hex)")
-						.required(false)
-						.index(1)),
+				.arg(base::input_arg().help("Secret (Hex)")).args(&base::file_args()),
+
+			f: |matches| base::convert(matches, pbkdf2),
 
-			f: pbkdf2,
+			stream: None,
 		}
 	]
 }
 
-fn pbkdf2(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let algo = match matches.value_of("ALGORITHM") {
-		Some("sha1") => PBKDF2_HMAC_SHA1,
-		Some("sha2_256") => PBKDF2_HMAC_SHA256,
-		Some("sha2_384") => PBKDF2_HMAC_SHA384,
-		Some("sha2_512") => PBKDF2_HMAC_SHA512,
-		_ => return Err("Invalid algorithm".to_string()),
-	};
+/// Options of `pbkdf2`
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub algorithm: Algorithm,
+	/// Not 0
+	pub iterations: u32,
+	pub salt: Vec<u8>,
+	/// In bits, a multiple of 8
+	pub key_length: u32,
+}
 
-	let iterations = match matches.value_of("ITERATIONS") {
-		Some(iterations) => iterations
-			.parse::<u32>()
-			.map_err(|_| "Invalid Iterations".to_string()),
-		_ => Err("Invalid Iterations".to_string()),
-	}
-	.and_then(|x| {
-		if x > 0 {
-			NonZeroU32::new(x).ok_or("Invalid Iterations".to_string())
-		} else {
-			Err("Invalid Iterations".to_string())
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			algorithm: Algorithm::Sha1,
+			iterations: 1,
+			salt: vec![],
+			key_length: 128,
 		}
-	})?;
-
-	let salt: Vec<u8> = match matches.value_of("SALT") {
-		Some(salt) => salt.parse::<Hex>().map_err(|_| "Invalid salt".to_string()),
-		_ => Err("Invalid salt".to_string()),
-	}?
-	.into();
-
-	let key_length = match matches.value_of("KEY_LENGTH") {
-		Some(key_length) => key_length
-			.parse::<u32>()
-			.map_err(|_| "Invalid key length".to_string()),
-		_ => Err("Invalid key length".to_string()),
 	}
-	.and_then(|x| {
-		if x > 0 {
-			Ok(x)
-		} else {
-			Err("Invalid key length".to_string())
-		}
-	})?;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+	Sha1,
+	Sha2_256,
+	Sha2_384,
+	Sha2_512,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let algorithm = match matches.value_of("ALGORITHM") {
+			Some("sha1") => Algorithm::Sha1,
+			Some("sha2_256") => Algorithm::Sha2_256,
+			Some("sha2_384") => Algorithm::Sha2_384,
+			Some("sha2_512") => Algorithm::Sha2_512,
+			_ => return Err(Error::invalid("algorithm", "")),
+		};
+
+		let iterations = match matches.value_of("ITERATIONS") {
+			Some(iterations) => iterations
+				.parse::<u32>()
+				.map_err(|_| Error::invalid("Iterations", "")),
+			_ => Err(Error::invalid("Iterations", "")),
+		}?;
+
+		let salt: Vec<u8> = match matches.value_of("SALT") {
+			Some(salt) => salt.parse::<Hex>().map_err(|_| Error::invalid("salt", "")),
+			_ => Err(Error::invalid("salt", "")),
+		}?
+		.into();
+
+		let key_length = match matches.value_of("KEY_LENGTH") {
+			Some(key_length) => key_length
+				.parse::<u32>()
+				.map_err(|_| Error::invalid("key length", "")),
+			_ => Err(Error::invalid("key length", "")),
+		}?;
+
+		Ok(Options {
+			algorithm,
+			iterations,
+			salt,
+			key_length,
+		})
+	}
+}
+
+/// Pbkdf2, the input is the secret (hex)
+pub fn pbkdf2(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let algo = match options.algorithm {
+		Algorithm::Sha1 => PBKDF2_HMAC_SHA1,
+		Algorithm::Sha2_256 => PBKDF2_HMAC_SHA256,
+		Algorithm::Sha2_384 => PBKDF2_HMAC_SHA384,
+		Algorithm::Sha2_512 => PBKDF2_HMAC_SHA512,
+	};
+
+	let iterations = NonZeroU32::new(options.iterations).ok_or(Error::invalid("Iterations", ""))?;
+
+	let key_length = options.key_length;
+	if key_length == 0 {
+		return Err(Error::invalid("key length", ""));
+	}
 
-	let key_byte_length = if key_length % 8 == 0 {
+	let key_byte_length = if key_length.is_multiple_of(8) {
 		Ok(key_length / 8)
 	} else {
-		Err("Invalid key length (must be a multiple of 8)".to_string())
+		Err(Error::invalid("key length (must be a multiple of 8)", ""))
 	}?;
 
-	let secret = base::input_string(matches)?;
-	let secret: Vec<u8> = secret.parse::<Hex>().map_err(|_| "Invalid secret")?.into();
+	let secret: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::invalid("secret", ""))?
+		.into();
 
 	let mut result = vec![0u8; key_byte_length as usize];
 
-	derive(algo, iterations, &salt, &secret, &mut result);
+	derive(algo, iterations, &options.salt, &secret, &mut result);
 
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
 mod cases {
@@ -0,0 +1,141 @@
+use crate::error::Error;
+use crate::modules::{base, ModuleManager};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+	SubCommand::with_name("pipe")
+		.about("Run sub commands as a pipeline in process, e.g. \"s2h | hash -a sha2_256 | h2b58\"")
+		.arg(
+			Arg::with_name("PIPELINE")
+				.help("Pipeline, sub commands separated by |, args can be quoted")
+				.required(true)
+				.index(1),
+		)
+		.arg(
+			Arg::with_name("INPUT")
+				.help("Input of the first sub command, stdin if absent")
+				.required(false)
+				.index(2),
+		)
+}
+
+pub fn run<'a, 'b>(matches: &ArgMatches, mm: &ModuleManager<'a, 'b>) -> Result<Vec<String>, Error> {
+	let pipeline = matches
+		.value_of("PIPELINE")
+		.ok_or(Error::invalid("pipeline", ""))?;
+
+	let stages = parse(pipeline)?;
+
+	let mut input = base::input_bytes(matches)?;
+	let mut result = vec![];
+	for stage in stages {
+		let name = &stage[0];
+		let command = mm.commands.get(name).ok_or_else(|| {
+			Error::invalid(
+				"pipeline",
+				format!(
+					"unknown sub command: {}{}",
+					name,
+					base::did_you_mean(name, mm.commands.keys().map(String::as_str))
+				),
+			)
+		})?;
+
+		// the output of the previous stage is passed in process as the input,
+		// raw bytes (e.g. of h2b) as is, otherwise the result lines joined by \n
+		let matches = mm
+			.get_matches(&command.app, &stage, 0)
+			.map_err(|e| Error::failed(e.message))?;
+		let (stage_result, output) = base::with_io(input, || (command.f)(&matches));
+		result = stage_result.map_err(|e| Error::failed(format!("{}: {}", name, e)))?;
+		input = if result.is_empty() {
+			output
+		} else {
+			result.join("\n").into_bytes()
+		};
+	}
+
+	// raw bytes of the last stage
+	if result.is_empty() {
+		base::output_bytes(matches, &input)?;
+	}
+	Ok(result)
+}
+
+/// Splits into stages by unquoted `|`, and stages into args,
+/// args can be quoted by ' (literal) or " (with \ escapes)
+pub fn parse(pipeline: &str) -> Result<Vec<Vec<String>>, Error> {
+	let mut stages = vec![];
+	let mut args = vec![];
+	// None if not in an arg
+	let mut arg: Option<String> = None;
+
+	let mut chars = pipeline.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'|' => {
+				args.extend(arg.take());
+				stages.push(args);
+				args = vec![];
+			}
+			c if c.is_whitespace() => args.extend(arg.take()),
+			'\'' => {
+				let arg = arg.get_or_insert_with(String::new);
+				loop {
+					match chars.next() {
+						Some('\'') => break,
+						Some(c) => arg.push(c),
+						None => return Err(Error::invalid("pipeline", "unclosed quote")),
+					}
+				}
+			}
+			'"' => {
+				let arg = arg.get_or_insert_with(String::new);
+				loop {
+					match chars.next() {
+						Some('"') => break,
+						Some('\\') => match chars.next() {
+							Some(c) => arg.push(c),
+							None => return Err(Error::invalid("pipeline", "unclosed quote")),
+						},
+						Some(c) => arg.push(c),
+						None => return Err(Error::invalid("pipeline", "unclosed quote")),
+					}
+				}
+			}
+			'\\' => match chars.next() {
+				Some(c) => arg.get_or_insert_with(String::new).push(c),
+				None => return Err(Error::invalid("pipeline", "trailing \\")),
+			},
+			c => arg.get_or_insert_with(String::new).push(c),
+		}
+	}
+	args.extend(arg.take());
+	stages.push(args);
+
+	if stages.iter().any(Vec::is_empty) {
+		return Err(Error::invalid("pipeline", "empty sub command"));
+	}
+
+	Ok(stages)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(
+			parse("s2h | hash -a sha2_256|h2b58").unwrap(),
+			vec![vec!["s2h"], vec!["hash", "-a", "sha2_256"], vec!["h2b58"]]
+		);
+		assert_eq!(
+			parse(r#"re_replace -p 'a|b' -r "\"x\"" | s2h"#).unwrap(),
+			vec![vec!["re_replace", "-p", "a|b", "-r", "\"x\""], vec!["s2h"]]
+		);
+		assert_eq!(parse("s2h | ''").unwrap(), vec![vec!["s2h"], vec![""]]);
+		assert!(parse("s2h || h2s").is_err());
+		assert!(parse("re -p 'a").is_err());
+	}
+}
@@ -0,0 +1,122 @@
+use crate::error::Error;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use lazy_static::lazy_static;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const PREFIX: &str = "dtool-";
+
+lazy_static! {
+	/// `dtool-<name>` executables on PATH, the first one wins
+	static ref PLUGINS: Vec<(String, PathBuf)> = discover();
+	static ref PLUGIN_ABOUTS: Vec<String> = PLUGINS
+		.iter()
+		.map(|(_, path)| format!("Plugin {}", path.display()))
+		.collect();
+}
+
+pub fn apps<'a, 'b>() -> Vec<App<'a, 'b>> {
+	PLUGINS
+		.iter()
+		.zip(PLUGIN_ABOUTS.iter())
+		.map(|((name, _), about)| {
+			SubCommand::with_name(name)
+				.about(about.as_str())
+				.setting(AppSettings::AllowLeadingHyphen)
+				.setting(AppSettings::DisableHelpFlags)
+				.setting(AppSettings::DisableVersion)
+				.arg(
+					Arg::with_name("ARGS")
+						.help("Args forwarded to the plugin")
+						.multiple(true)
+						.allow_hyphen_values(true)
+						.required(false),
+				)
+		})
+		.collect()
+}
+
+pub fn contains(name: &str) -> bool {
+	PLUGINS.iter().any(|(plugin, _)| plugin == name)
+}
+
+/// Runs the plugin with the args, stdin and stdout are inherited,
+/// exits with the status of the plugin if it failed
+pub fn run(name: &str, matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let path = PLUGINS
+		.iter()
+		.find(|(plugin, _)| plugin == name)
+		.map(|(_, path)| path)
+		.ok_or(Error::invalid("plugin", ""))?;
+
+	let args = matches.values_of("ARGS").into_iter().flatten();
+
+	let status = process::Command::new(path)
+		.args(args)
+		.status()
+		.map_err(|e| Error::invalid("plugin", format!("{}: {}", path.display(), e)))?;
+
+	if !status.success() {
+		process::exit(status.code().unwrap_or(1));
+	}
+
+	Ok(vec![])
+}
+
+fn discover() -> Vec<(String, PathBuf)> {
+	let paths = match env::var_os("PATH") {
+		Some(paths) => paths,
+		None => return vec![],
+	};
+
+	let mut result: Vec<(String, PathBuf)> = vec![];
+	for dir in env::split_paths(&paths) {
+		let entries = match fs::read_dir(&dir) {
+			Ok(entries) => entries,
+			Err(_) => continue,
+		};
+		let mut plugins = entries
+			.filter_map(|entry| entry.ok().map(|x| x.path()))
+			.filter_map(|path| {
+				let name = plugin_name(&path)?;
+				Some((name, path))
+			})
+			.filter(|(_, path)| is_executable(path))
+			.collect::<Vec<_>>();
+		plugins.sort();
+		for plugin in plugins {
+			if !result.iter().any(|(name, _)| *name == plugin.0) {
+				result.push(plugin);
+			}
+		}
+	}
+	result
+}
+
+fn plugin_name(path: &Path) -> Option<String> {
+	let name = if cfg!(windows) {
+		path.file_stem()?
+	} else {
+		path.file_name()?
+	};
+	let name = name.to_str()?.strip_prefix(PREFIX)?;
+	if name.is_empty() {
+		return None;
+	}
+	Some(name.to_string())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+	use std::os::unix::fs::PermissionsExt;
+	fs::metadata(path)
+		.map(|x| x.is_file() && x.permissions().mode() & 0o111 != 0)
+		.unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+	path.is_file() && path.extension().map(|x| x == "exe").unwrap_or(false)
+}
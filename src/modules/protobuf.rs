@@ -0,0 +1,1179 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use linked_hash_map::LinkedHashMap;
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+use std::fs;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Protobuf to JSON, JSON to protobuf (with .proto)".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("pb2j")
+				.about("Convert protobuf to JSON")
+				.arg(
+					Arg::with_name("PROTO")
+						.long("proto")
+						.short("p")
+						.help(".proto file, fields are decoded by number if absent")
+						.takes_value(true)
+						.requires("TYPE")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("TYPE")
+						.long("type")
+						.short("t")
+						.help("Message type, e.g. Person or tutorial.Person")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Input binary instead of hex")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_from_hex_or_binary(matches, pb2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2pb")
+				.about("Convert JSON to protobuf")
+				.arg(
+					Arg::with_name("PROTO")
+						.long("proto")
+						.short("p")
+						.help(".proto file")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("TYPE")
+						.long("type")
+						.short("t")
+						.help("Message type, e.g. Person or tutorial.Person")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("BINARY")
+						.long("binary")
+						.short("b")
+						.help("Output binary instead of hex")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert_to_hex_or_binary(matches, j2pb),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the protobuf commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Content of the .proto file, fields are decoded by number if absent
+	pub proto: Option<String>,
+	/// Message type, e.g. Person or tutorial.Person
+	pub message_type: Option<String>,
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let proto = match matches.value_of("PROTO") {
+			Some(path) => Some(
+				fs::read_to_string(path)
+					.map_err(|e| Error::invalid("proto", format!("{}: {}", path, e)))?,
+			),
+			None => None,
+		};
+
+		Ok(Options {
+			proto,
+			message_type: matches.value_of("TYPE").map(String::from),
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert protobuf to JSON, the input is binary
+pub fn pb2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = match &options.proto {
+		Some(proto) => {
+			let schema = Schema::parse(proto)?;
+			let name = schema.find_message(
+				options
+					.message_type
+					.as_deref()
+					.ok_or(Error::invalid("type", ""))?,
+			)?;
+			decode(&schema, &name, input)?
+		}
+		None => decode_raw(input)?,
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to protobuf, the output is binary
+pub fn j2pb(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let schema = Schema::parse(
+		options
+			.proto
+			.as_deref()
+			.ok_or(Error::invalid("proto", ""))?,
+	)?;
+	let name = schema.find_message(
+		options
+			.message_type
+			.as_deref()
+			.ok_or(Error::invalid("type", ""))?,
+	)?;
+
+	encode(&schema, &name, &value)
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+	Scalar(String),
+	/// Unresolved type name
+	Named(String),
+	Message(String),
+	Enum(String),
+	Map(Box<Kind>, Box<Kind>),
+}
+
+#[derive(Debug)]
+struct Field {
+	name: String,
+	number: u64,
+	repeated: bool,
+	packed: Option<bool>,
+	kind: Kind,
+}
+
+#[derive(Debug, Default)]
+struct Schema {
+	proto3: bool,
+	/// Keyed by full name with leading dot, e.g. .tutorial.Person
+	messages: LinkedHashMap<String, Vec<Field>>,
+	enums: LinkedHashMap<String, Vec<(String, i64)>>,
+}
+
+impl Schema {
+	/// Messages, enums, maps and oneofs are supported, imports, groups and extensions are not
+	fn parse(input: &str) -> Result<Self, Error> {
+		let mut parser = Parser {
+			tokens: tokenize(input)?,
+			pos: 0,
+		};
+		let mut schema = Schema::default();
+		let mut scope = "".to_string();
+
+		while let Some(token) = parser.next_opt() {
+			match token.as_str() {
+				"syntax" => {
+					parser.expect("=")?;
+					schema.proto3 = parser.next()?.trim_matches(&['"', '\''][..]) == "proto3";
+					parser.expect(";")?;
+				}
+				"package" => {
+					scope = format!(".{}", parser.next()?);
+					parser.expect(";")?;
+				}
+				"message" => schema.parse_message(&mut parser, &scope)?,
+				"enum" => schema.parse_enum(&mut parser, &scope)?,
+				";" => (),
+				_ => parser.skip_statement()?,
+			}
+		}
+
+		schema.resolve()?;
+		Ok(schema)
+	}
+
+	fn parse_message(&mut self, parser: &mut Parser, scope: &str) -> Result<(), Error> {
+		let name = format!("{}.{}", scope, parser.next()?);
+		parser.expect("{")?;
+		// reserve the position so that the messages keep the declaration order
+		self.messages.insert(name.clone(), vec![]);
+
+		let mut fields = vec![];
+		let mut in_oneof = false;
+		loop {
+			let token = parser.next()?;
+			match token.as_str() {
+				"}" if in_oneof => in_oneof = false,
+				"}" => break,
+				"message" => self.parse_message(parser, &name)?,
+				"enum" => self.parse_enum(parser, &name)?,
+				"oneof" => {
+					parser.next()?;
+					parser.expect("{")?;
+					in_oneof = true;
+				}
+				"option" | "reserved" | "extensions" | "extend" => parser.skip_statement()?,
+				";" => (),
+				"group" => return Err(Error::invalid("proto", "group is not supported")),
+				"map" => {
+					parser.expect("<")?;
+					let key = Kind::Scalar(parser.next()?);
+					parser.expect(",")?;
+					let value = to_kind(parser.next()?);
+					parser.expect(">")?;
+					let (field_name, number, _) = parse_field_rest(parser)?;
+					fields.push(Field {
+						name: field_name,
+						number,
+						repeated: false,
+						packed: None,
+						kind: Kind::Map(Box::new(key), Box::new(value)),
+					});
+				}
+				_ => {
+					let (repeated, type_name) = match token.as_str() {
+						"repeated" => (true, parser.next()?),
+						"optional" | "required" => (false, parser.next()?),
+						_ => (false, token),
+					};
+					let (field_name, number, packed) = parse_field_rest(parser)?;
+					fields.push(Field {
+						name: field_name,
+						number,
+						repeated,
+						packed,
+						kind: to_kind(type_name),
+					});
+				}
+			}
+		}
+
+		self.messages.insert(name, fields);
+		Ok(())
+	}
+
+	fn parse_enum(&mut self, parser: &mut Parser, scope: &str) -> Result<(), Error> {
+		let name = format!("{}.{}", scope, parser.next()?);
+		parser.expect("{")?;
+
+		let mut values = vec![];
+		loop {
+			let token = parser.next()?;
+			match token.as_str() {
+				"}" => break,
+				"option" | "reserved" => parser.skip_statement()?,
+				";" => (),
+				_ => {
+					parser.expect("=")?;
+					let number = parse_int(&parser.next()?)?;
+					if parser.peek() == Some("[") {
+						parser.skip_options()?;
+					}
+					parser.expect(";")?;
+					values.push((token, number));
+				}
+			}
+		}
+
+		self.enums.insert(name, values);
+		Ok(())
+	}
+
+	fn resolve(&mut self) -> Result<(), Error> {
+		let mut resolved = vec![];
+		for (name, fields) in &self.messages {
+			for (i, field) in fields.iter().enumerate() {
+				resolved.push((name.clone(), i, self.resolve_kind(&field.kind, name)?));
+			}
+		}
+		for (name, i, kind) in resolved {
+			if let Some(fields) = self.messages.get_mut(&name) {
+				fields[i].kind = kind;
+			}
+		}
+		Ok(())
+	}
+
+	fn resolve_kind(&self, kind: &Kind, scope: &str) -> Result<Kind, Error> {
+		let type_name = match kind {
+			Kind::Named(type_name) => type_name,
+			Kind::Map(key, value) => {
+				return Ok(Kind::Map(
+					key.clone(),
+					Box::new(self.resolve_kind(value, scope)?),
+				))
+			}
+			kind => return Ok(kind.clone()),
+		};
+
+		// search from the innermost scope outwards
+		let mut candidates = vec![];
+		if type_name.starts_with('.') {
+			candidates.push(type_name.to_string());
+		} else {
+			let mut scope = scope;
+			loop {
+				candidates.push(format!("{}.{}", scope, type_name));
+				match scope.rfind('.') {
+					Some(index) => scope = &scope[..index],
+					None => break,
+				}
+			}
+		}
+
+		for candidate in candidates {
+			if self.messages.contains_key(&candidate) {
+				return Ok(Kind::Message(candidate));
+			}
+			if self.enums.contains_key(&candidate) {
+				return Ok(Kind::Enum(candidate));
+			}
+		}
+		Err(Error::invalid(
+			"proto",
+			format!("unknown type {}", type_name),
+		))
+	}
+
+	/// Full name or unique short name
+	fn find_message(&self, name: &str) -> Result<String, Error> {
+		let full_name = format!(".{}", name.trim_start_matches('.'));
+		if self.messages.contains_key(&full_name) {
+			return Ok(full_name);
+		}
+		let suffix = format!(".{}", name);
+		let found = self
+			.messages
+			.keys()
+			.filter(|x| x.ends_with(&suffix))
+			.collect::<Vec<_>>();
+		match found.as_slice() {
+			[found] => Ok(found.to_string()),
+			[] => Err(Error::invalid("type", format!("{} not found", name))),
+			_ => Err(Error::invalid("type", format!("{} is ambiguous", name))),
+		}
+	}
+
+	fn is_packed(&self, field: &Field) -> bool {
+		field.repeated && wire_type(&field.kind) != 2 && field.packed.unwrap_or(self.proto3)
+	}
+}
+
+struct Parser {
+	tokens: Vec<String>,
+	pos: usize,
+}
+
+impl Parser {
+	fn next_opt(&mut self) -> Option<String> {
+		let result = self.tokens.get(self.pos).cloned();
+		self.pos += 1;
+		result
+	}
+
+	fn next(&mut self) -> Result<String, Error> {
+		self.next_opt()
+			.ok_or_else(|| Error::invalid("proto", "unexpected end"))
+	}
+
+	fn peek(&self) -> Option<&str> {
+		self.tokens.get(self.pos).map(String::as_str)
+	}
+
+	fn expect(&mut self, expected: &str) -> Result<(), Error> {
+		let token = self.next()?;
+		if token != expected {
+			return Err(Error::invalid(
+				"proto",
+				format!("expected {} but found {}", expected, token),
+			));
+		}
+		Ok(())
+	}
+
+	/// Skip to the end of the statement or the block
+	fn skip_statement(&mut self) -> Result<(), Error> {
+		let mut depth = 0;
+		loop {
+			match self.next()?.as_str() {
+				"{" => depth += 1,
+				"}" => {
+					depth -= 1;
+					if depth == 0 {
+						return Ok(());
+					}
+				}
+				";" if depth == 0 => return Ok(()),
+				_ => (),
+			}
+		}
+	}
+
+	/// Returns the value of the packed option if any
+	fn skip_options(&mut self) -> Result<Option<bool>, Error> {
+		self.expect("[")?;
+		let mut packed = None;
+		loop {
+			match self.next()?.as_str() {
+				"]" => return Ok(packed),
+				"packed" => {
+					self.expect("=")?;
+					packed = Some(self.next()? == "true");
+				}
+				_ => (),
+			}
+		}
+	}
+}
+
+/// `name = number [options];`
+fn parse_field_rest(parser: &mut Parser) -> Result<(String, u64, Option<bool>), Error> {
+	let name = parser.next()?;
+	parser.expect("=")?;
+	let number = parse_int(&parser.next()?)?;
+	if number <= 0 {
+		return Err(Error::invalid(
+			"proto",
+			format!("invalid field number {}", number),
+		));
+	}
+	let packed = if parser.peek() == Some("[") {
+		parser.skip_options()?
+	} else {
+		None
+	};
+	parser.expect(";")?;
+	Ok((name, number as u64, packed))
+}
+
+fn parse_int(input: &str) -> Result<i64, Error> {
+	let (negative, input) = match input.strip_prefix('-') {
+		Some(input) => (true, input),
+		None => (false, input),
+	};
+	let result = if input.starts_with("0x") || input.starts_with("0X") {
+		i64::from_str_radix(&input[2..], 16)
+	} else {
+		input.parse::<i64>()
+	};
+	let result =
+		result.map_err(|_| Error::invalid("proto", format!("invalid number {}", input)))?;
+	Ok(if negative { -result } else { result })
+}
+
+const SCALARS: [&str; 15] = [
+	"double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+	"fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+fn to_kind(type_name: String) -> Kind {
+	if SCALARS.contains(&type_name.as_str()) {
+		Kind::Scalar(type_name)
+	} else {
+		Kind::Named(type_name)
+	}
+}
+
+fn tokenize(input: &str) -> Result<Vec<String>, Error> {
+	let mut tokens = vec![];
+	let mut chars = input.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			c if c.is_whitespace() => (),
+			'/' if chars.peek() == Some(&'/') => {
+				while chars.next().map(|c| c != '\n').unwrap_or(false) {}
+			}
+			'/' if chars.peek() == Some(&'*') => {
+				chars.next();
+				let mut last = ' ';
+				loop {
+					let c = chars
+						.next()
+						.ok_or(Error::invalid("proto", "unterminated comment"))?;
+					if last == '*' && c == '/' {
+						break;
+					}
+					last = c;
+				}
+			}
+			'"' | '\'' => {
+				let mut token = c.to_string();
+				loop {
+					let next = chars
+						.next()
+						.ok_or(Error::invalid("proto", "unterminated string"))?;
+					token.push(next);
+					match next {
+						'\\' => token.push(
+							chars
+								.next()
+								.ok_or(Error::invalid("proto", "unterminated string"))?,
+						),
+						next if next == c => break,
+						_ => (),
+					}
+				}
+				tokens.push(token);
+			}
+			c if c.is_alphanumeric() || "_.-+".contains(c) => {
+				let mut token = c.to_string();
+				while let Some(&c) = chars.peek() {
+					if !(c.is_alphanumeric() || "_.-+".contains(c)) {
+						break;
+					}
+					token.push(c);
+					chars.next();
+				}
+				tokens.push(token);
+			}
+			c => tokens.push(c.to_string()),
+		}
+	}
+	Ok(tokens)
+}
+
+/// 0: varint, 1: 64-bit, 2: length-delimited, 5: 32-bit
+fn wire_type(kind: &Kind) -> u64 {
+	match kind {
+		Kind::Scalar(name) => match name.as_str() {
+			"double" | "fixed64" | "sfixed64" => 1,
+			"float" | "fixed32" | "sfixed32" => 5,
+			"string" | "bytes" => 2,
+			_ => 0,
+		},
+		Kind::Enum(_) => 0,
+		_ => 2,
+	}
+}
+
+enum Wire<'a> {
+	Varint(u64),
+	Fixed64(u64),
+	Bytes(&'a [u8]),
+	Fixed32(u32),
+}
+
+fn read_varint(reader: &mut &[u8]) -> Result<u64, Error> {
+	let mut result = 0u64;
+	for i in 0..10 {
+		let (byte, rest) = reader
+			.split_first()
+			.ok_or(Error::invalid("protobuf", "unexpected end"))?;
+		*reader = rest;
+		result |= u64::from(byte & 0x7f) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Ok(result);
+		}
+	}
+	Err(Error::invalid("protobuf", "varint too long"))
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+	if reader.len() < len {
+		return Err(Error::invalid("protobuf", "unexpected end"));
+	}
+	let (result, rest) = reader.split_at(len);
+	*reader = rest;
+	Ok(result)
+}
+
+fn read_fixed64(reader: &mut &[u8]) -> Result<u64, Error> {
+	let mut buf = [0u8; 8];
+	buf.copy_from_slice(read_bytes(reader, 8)?);
+	Ok(u64::from_le_bytes(buf))
+}
+
+fn read_fixed32(reader: &mut &[u8]) -> Result<u32, Error> {
+	let mut buf = [0u8; 4];
+	buf.copy_from_slice(read_bytes(reader, 4)?);
+	Ok(u32::from_le_bytes(buf))
+}
+
+fn read_fields(input: &[u8]) -> Result<Vec<(u64, Wire<'_>)>, Error> {
+	let mut reader = input;
+	let mut result = vec![];
+	while !reader.is_empty() {
+		let key = read_varint(&mut reader)?;
+		let number = key >> 3;
+		if number == 0 {
+			return Err(Error::invalid("protobuf", "invalid field number 0"));
+		}
+		let wire = match key & 0x7 {
+			0 => Wire::Varint(read_varint(&mut reader)?),
+			1 => Wire::Fixed64(read_fixed64(&mut reader)?),
+			2 => {
+				let len = read_varint(&mut reader)? as usize;
+				Wire::Bytes(read_bytes(&mut reader, len)?)
+			}
+			5 => Wire::Fixed32(read_fixed32(&mut reader)?),
+			wire_type => {
+				return Err(Error::invalid(
+					"protobuf",
+					format!("unsupported wire type {}", wire_type),
+				))
+			}
+		};
+		result.push((number, wire));
+	}
+	Ok(result)
+}
+
+/// Decode without schema, fields are keyed by number
+fn decode_raw(input: &[u8]) -> Result<Value, Error> {
+	let mut map = Map::new();
+	for (number, wire) in read_fields(input)? {
+		let value = match wire {
+			Wire::Varint(v) | Wire::Fixed64(v) => Value::from(v),
+			Wire::Fixed32(v) => Value::from(v),
+			Wire::Bytes(v) => match String::from_utf8(v.to_vec()) {
+				Ok(v) if !v.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+					Value::String(v)
+				}
+				_ => match decode_raw(v) {
+					Ok(v) => v,
+					Err(_) => Value::from(hex_string(v)),
+				},
+			},
+		};
+		insert_repeated(&mut map, number.to_string(), value);
+	}
+	Ok(Value::Object(map))
+}
+
+fn hex_string(input: &[u8]) -> String {
+	base::Hex::from(input.to_vec()).into()
+}
+
+fn insert_repeated(map: &mut Map<String, Value>, key: String, value: Value) {
+	match map.get_mut(&key) {
+		Some(Value::Array(array)) => array.push(value),
+		Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+		None => {
+			map.insert(key, value);
+		}
+	}
+}
+
+/// Proto3 JSON mapping with the original field names
+fn decode(schema: &Schema, name: &str, input: &[u8]) -> Result<Value, Error> {
+	let fields = &schema.messages[name];
+
+	let mut map = Map::new();
+	for (number, wire) in read_fields(input)? {
+		let field = match fields.iter().find(|x| x.number == number) {
+			Some(field) => field,
+			None => {
+				// unknown fields are kept by number
+				let value = match wire {
+					Wire::Varint(v) | Wire::Fixed64(v) => Value::from(v),
+					Wire::Fixed32(v) => Value::from(v),
+					Wire::Bytes(v) => Value::from(hex_string(v)),
+				};
+				insert_repeated(&mut map, number.to_string(), value);
+				continue;
+			}
+		};
+
+		match (&field.kind, field.repeated, wire) {
+			(Kind::Map(key_kind, value_kind), _, Wire::Bytes(v)) => {
+				let (mut key, mut value) = (None, None);
+				for (number, wire) in read_fields(v)? {
+					match number {
+						1 => key = Some(decode_value(schema, key_kind, wire)?),
+						2 => value = Some(decode_value(schema, value_kind, wire)?),
+						_ => (),
+					}
+				}
+				let key = match key {
+					Some(Value::String(key)) => key,
+					Some(key) => key.to_string(),
+					None => default_value(key_kind)
+						.to_string()
+						.trim_matches('"')
+						.to_string(),
+				};
+				let value = value.unwrap_or_else(|| default_value(value_kind));
+				let entry = map
+					.entry(field.name.clone())
+					.or_insert_with(|| Value::Object(Map::new()));
+				if let Value::Object(entry) = entry {
+					entry.insert(key, value);
+				}
+			}
+			(kind, true, Wire::Bytes(mut v)) if wire_type(kind) != 2 => {
+				// packed
+				let mut values = vec![];
+				while !v.is_empty() {
+					let wire = match wire_type(kind) {
+						0 => Wire::Varint(read_varint(&mut v)?),
+						1 => Wire::Fixed64(read_fixed64(&mut v)?),
+						_ => Wire::Fixed32(read_fixed32(&mut v)?),
+					};
+					values.push(decode_value(schema, kind, wire)?);
+				}
+				let entry = map
+					.entry(field.name.clone())
+					.or_insert_with(|| Value::Array(vec![]));
+				if let Value::Array(entry) = entry {
+					entry.extend(values);
+				}
+			}
+			(kind, true, wire) => {
+				let value = decode_value(schema, kind, wire)?;
+				let entry = map
+					.entry(field.name.clone())
+					.or_insert_with(|| Value::Array(vec![]));
+				if let Value::Array(entry) = entry {
+					entry.push(value);
+				}
+			}
+			(kind, false, wire) => {
+				map.insert(field.name.clone(), decode_value(schema, kind, wire)?);
+			}
+		}
+	}
+	Ok(Value::Object(map))
+}
+
+fn decode_value(schema: &Schema, kind: &Kind, wire: Wire) -> Result<Value, Error> {
+	let zigzag = |v: u64| (v >> 1) as i64 ^ -((v & 1) as i64);
+
+	let result = match (kind, wire) {
+		(Kind::Scalar(name), Wire::Varint(v)) => match name.as_str() {
+			"int32" => Value::from(v as i32),
+			"int64" => Value::from((v as i64).to_string()),
+			"uint32" => Value::from(v as u32),
+			"uint64" => Value::from(v.to_string()),
+			"sint32" => Value::from(zigzag(v) as i32),
+			"sint64" => Value::from(zigzag(v).to_string()),
+			"bool" => Value::Bool(v != 0),
+			_ => return Err(wire_type_error(kind)),
+		},
+		(Kind::Scalar(name), Wire::Fixed64(v)) => match name.as_str() {
+			"fixed64" => Value::from(v.to_string()),
+			"sfixed64" => Value::from((v as i64).to_string()),
+			"double" => float_value(f64::from_bits(v), f64::from_bits(v).to_string())?,
+			_ => return Err(wire_type_error(kind)),
+		},
+		(Kind::Scalar(name), Wire::Fixed32(v)) => match name.as_str() {
+			"fixed32" => Value::from(v),
+			"sfixed32" => Value::from(v as i32),
+			"float" => float_value(f64::from(f32::from_bits(v)), f32::from_bits(v).to_string())?,
+			_ => return Err(wire_type_error(kind)),
+		},
+		(Kind::Scalar(name), Wire::Bytes(v)) => match name.as_str() {
+			"string" => Value::from(
+				String::from_utf8(v.to_vec())
+					.map_err(|_| Error::invalid("protobuf", "string not UTF-8"))?,
+			),
+			"bytes" => Value::from(base64::encode(v)),
+			_ => return Err(wire_type_error(kind)),
+		},
+		(Kind::Enum(name), Wire::Varint(v)) => {
+			let v = v as i32;
+			match schema.enums[name]
+				.iter()
+				.find(|(_, number)| *number == i64::from(v))
+			{
+				Some((name, _)) => Value::from(name.clone()),
+				None => Value::from(v),
+			}
+		}
+		(Kind::Message(name), Wire::Bytes(v)) => decode(schema, name, v)?,
+		_ => return Err(wire_type_error(kind)),
+	};
+	Ok(result)
+}
+
+fn wire_type_error(kind: &Kind) -> Error {
+	Error::invalid("protobuf", format!("wire type mismatch for {:?}", kind))
+}
+
+fn float_value(v: f64, display: String) -> Result<Value, Error> {
+	let result = match v {
+		_ if v.is_nan() => Value::from("NaN"),
+		_ if v.is_infinite() && v > 0.0 => Value::from("Infinity"),
+		_ if v.is_infinite() => Value::from("-Infinity"),
+		_ => json::parse(&display)?,
+	};
+	Ok(result)
+}
+
+fn default_value(kind: &Kind) -> Value {
+	match kind {
+		Kind::Scalar(name) => match name.as_str() {
+			"string" | "bytes" => Value::from(""),
+			"bool" => Value::Bool(false),
+			"int64" | "uint64" | "sint64" | "fixed64" | "sfixed64" => Value::from("0"),
+			_ => Value::from(0),
+		},
+		Kind::Message(_) => Value::Object(Map::new()),
+		_ => Value::from(0),
+	}
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut v: u64) {
+	while v >= 0x80 {
+		buf.push((v as u8) | 0x80);
+		v >>= 7;
+	}
+	buf.push(v as u8);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, v: &[u8]) {
+	write_varint(buf, v.len() as u64);
+	buf.extend_from_slice(v);
+}
+
+/// Fields are written in field number order, both the original names and lowerCamelCase names are accepted
+fn encode(schema: &Schema, name: &str, value: &Value) -> Result<Vec<u8>, Error> {
+	let fields = &schema.messages[name];
+
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::input(format!("{} should be object", name))),
+	};
+
+	let mut entries = map
+		.iter()
+		.filter(|(_, v)| !v.is_null())
+		.map(|(k, v)| {
+			let field = fields
+				.iter()
+				.find(|x| &x.name == k || &to_json_name(&x.name) == k)
+				.ok_or_else(|| Error::input(format!("unknown field {}", k)))?;
+			Ok((field, v))
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+	entries.sort_by_key(|(field, _)| field.number);
+
+	let mut buf = vec![];
+	for (field, value) in entries {
+		match (&field.kind, value) {
+			(Kind::Map(key_kind, value_kind), Value::Object(map)) => {
+				for (k, v) in map {
+					let key = match key_kind.as_ref() {
+						Kind::Scalar(name) if name == "bool" => Value::Bool(k == "true"),
+						_ => Value::from(k.clone()),
+					};
+					let mut entry = vec![];
+					encode_field(schema, &mut entry, 1, key_kind, &key)?;
+					encode_field(schema, &mut entry, 2, value_kind, v)?;
+					write_varint(&mut buf, field.number << 3 | 2);
+					write_bytes(&mut buf, &entry);
+				}
+			}
+			(Kind::Map(..), _) => {
+				return Err(Error::input(format!("{} should be object", field.name)))
+			}
+			(kind, Value::Array(array)) if field.repeated => {
+				if schema.is_packed(field) {
+					let mut packed = vec![];
+					for v in array {
+						encode_value(schema, &mut packed, kind, v)?;
+					}
+					write_varint(&mut buf, field.number << 3 | 2);
+					write_bytes(&mut buf, &packed);
+				} else {
+					for v in array {
+						encode_field(schema, &mut buf, field.number, kind, v)?;
+					}
+				}
+			}
+			(_, _) if field.repeated => {
+				return Err(Error::input(format!("{} should be array", field.name)))
+			}
+			(kind, v) => encode_field(schema, &mut buf, field.number, kind, v)?,
+		}
+	}
+	Ok(buf)
+}
+
+fn encode_field(
+	schema: &Schema,
+	buf: &mut Vec<u8>,
+	number: u64,
+	kind: &Kind,
+	value: &Value,
+) -> Result<(), Error> {
+	write_varint(buf, number << 3 | wire_type(kind));
+	encode_value(schema, buf, kind, value)
+}
+
+fn encode_value(
+	schema: &Schema,
+	buf: &mut Vec<u8>,
+	kind: &Kind,
+	value: &Value,
+) -> Result<(), Error> {
+	let invalid = || Error::input(format!("{} for {:?}", value, kind));
+
+	let to_i64 = || match value {
+		Value::Number(v) => v.as_i64(),
+		Value::String(v) => v.parse::<i64>().ok(),
+		_ => None,
+	};
+	let to_u64 = || match value {
+		Value::Number(v) => v.as_u64(),
+		Value::String(v) => v.parse::<u64>().ok(),
+		_ => None,
+	};
+	let to_i32 = || to_i64().and_then(|v| i32::try_from(v).ok());
+	let to_u32 = || to_u64().and_then(|v| u32::try_from(v).ok());
+	let to_f64 = || match value {
+		Value::Number(v) => v.as_f64(),
+		Value::String(v) => match v.as_str() {
+			"NaN" => Some(f64::NAN),
+			"Infinity" => Some(f64::INFINITY),
+			"-Infinity" => Some(f64::NEG_INFINITY),
+			v => v.parse::<f64>().ok(),
+		},
+		_ => None,
+	};
+
+	match kind {
+		Kind::Scalar(name) => match name.as_str() {
+			"int32" => write_varint(buf, to_i32().ok_or_else(invalid)? as i64 as u64),
+			"int64" => write_varint(buf, to_i64().ok_or_else(invalid)? as u64),
+			"uint32" => write_varint(buf, to_u32().ok_or_else(invalid)? as u64),
+			"uint64" => write_varint(buf, to_u64().ok_or_else(invalid)?),
+			"sint32" | "sint64" => {
+				let v = match name.as_str() {
+					"sint32" => to_i32().map(i64::from),
+					_ => to_i64(),
+				}
+				.ok_or_else(invalid)?;
+				write_varint(buf, ((v << 1) ^ (v >> 63)) as u64);
+			}
+			"bool" => match value {
+				Value::Bool(v) => write_varint(buf, *v as u64),
+				_ => return Err(invalid()),
+			},
+			"fixed64" => buf.extend_from_slice(&to_u64().ok_or_else(invalid)?.to_le_bytes()),
+			"sfixed64" => buf.extend_from_slice(&to_i64().ok_or_else(invalid)?.to_le_bytes()),
+			"double" => buf.extend_from_slice(&to_f64().ok_or_else(invalid)?.to_le_bytes()),
+			"fixed32" => buf.extend_from_slice(&to_u32().ok_or_else(invalid)?.to_le_bytes()),
+			"sfixed32" => buf.extend_from_slice(&to_i32().ok_or_else(invalid)?.to_le_bytes()),
+			"float" => buf.extend_from_slice(&(to_f64().ok_or_else(invalid)? as f32).to_le_bytes()),
+			"string" => match value {
+				Value::String(v) => write_bytes(buf, v.as_bytes()),
+				_ => return Err(invalid()),
+			},
+			"bytes" => match value {
+				Value::String(v) => {
+					let v = base64::decode(v)
+						.or_else(|_| base64::decode_config(v, base64::URL_SAFE))
+						.map_err(|_| invalid())?;
+					write_bytes(buf, &v);
+				}
+				_ => return Err(invalid()),
+			},
+			_ => return Err(invalid()),
+		},
+		Kind::Enum(name) => {
+			let number = match value {
+				Value::String(v) => schema.enums[name]
+					.iter()
+					.find(|(name, _)| name == v)
+					.map(|(_, number)| *number),
+				_ => to_i32().map(i64::from),
+			};
+			write_varint(buf, number.ok_or_else(invalid)? as u64);
+		}
+		Kind::Message(name) => write_bytes(buf, &encode(schema, name, value)?),
+		_ => return Err(invalid()),
+	}
+	Ok(())
+}
+
+fn to_json_name(name: &str) -> String {
+	let mut result = String::new();
+	let mut upper = false;
+	for c in name.chars() {
+		match c {
+			'_' => upper = true,
+			c if upper => {
+				result.extend(c.to_uppercase());
+				upper = false;
+			}
+			c => result.push(c),
+		}
+	}
+	result
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"pb2j",
+				vec![
+					Case {
+						desc: "Decode with schema".to_string(),
+						input: vec![
+							"-p",
+							"person.proto",
+							"-t",
+							"Person",
+							"0x0a0564746f6f6c10011a0b612e622e6340642e636f6d",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"{",
+							r#"  "name": "dtool","#,
+							r#"  "id": 1,"#,
+							r#"  "email": "a.b.c@d.com""#,
+							"}",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Decode without schema".to_string(),
+						input: vec!["-m", "0x0a0564746f6f6c1001220408011002"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"1":"dtool","2":1,"4":{"1":1,"2":2}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"j2pb",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec![
+						"-p",
+						"person.proto",
+						"-t",
+						"Person",
+						r#"'{"name":"dtool","id":1,"email":"a.b.c@d.com"}'"#,
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec!["0x0a0564746f6f6c10011a0b612e622e6340642e636f6d"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_schema() {
+		let schema = Schema::parse(
+			r#"
+			syntax = "proto3";
+			package tutorial;
+
+			/* comment */
+			message Person {
+				string name = 1;
+				int32 id = 2; // comment
+				string email = 3;
+				enum PhoneType {
+					MOBILE = 0;
+					HOME = 1;
+				}
+				message PhoneNumber {
+					string number = 1;
+					PhoneType type = 2;
+				}
+				repeated PhoneNumber phones = 4;
+				repeated sint32 scores = 5;
+				map<string, int64> counts = 6;
+				oneof contact {
+					bytes avatar = 7;
+					double weight = 8;
+				}
+			}
+			"#,
+		)
+		.unwrap();
+
+		let name = schema.find_message("Person").unwrap();
+		assert_eq!(name, ".tutorial.Person");
+
+		let input = r#"{"name":"dtool","id":1,"phones":[{"number":"123","type":"HOME"}],"scores":[-1,2],"counts":{"a":"10"},"weight":1.5}"#;
+		let encoded = encode(&schema, &name, &json::parse(input).unwrap()).unwrap();
+		assert_eq!(
+			hex::encode(&encoded),
+			"0a0564746f6f6c100122070a0331323310012a02010432050a0161100a41000000000000f83f"
+		);
+
+		let decoded = decode(&schema, &name, &encoded).unwrap();
+		assert_eq!(
+			json::to_string(&decoded).unwrap(),
+			r#"{"name":"dtool","id":1,"phones":[{"number":"123","type":"HOME"}],"scores":[-1,2],"counts":{"a":"10"},"weight":1.5}"#
+		);
+
+		for input in &[
+			r#"{"id":2147483648}"#,
+			r#"{"id":"-2147483649"}"#,
+			r#"{"scores":[4294967296]}"#,
+		] {
+			assert!(encode(&schema, &name, &json::parse(input).unwrap()).is_err());
+		}
+	}
+}
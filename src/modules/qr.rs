@@ -0,0 +1,279 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use qrcode::render::{svg, unicode};
+use qrcode::{EcLevel, QrCode};
+use rqrr::PreparedImage;
+use std::io::Cursor;
+use std::path::Path;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "QR code encode / decode".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("qr_enc")
+				.about("QR code encode, to the terminal, or to a PNG / SVG file by the extension of -o")
+				.arg(
+					Arg::with_name("EC_LEVEL")
+						.long("ec-level")
+						.short("e")
+						.help("Error correction level\nL: 7% of the code can be restored\nM: 15%\nQ: 25%\nH: 30%")
+						.takes_value(true)
+						.possible_values(&["L", "M", "Q", "H"])
+						.default_value("M")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: run_qr_enc,
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("qr_dec")
+				.about("QR code decode, from a PNG / JPEG image, e.g. a screenshot, one line per code")
+				.args(&base::input_args()),
+			f: |matches| base::convert_bytes(matches, qr_dec),
+			stream: None,
+		},
+	]
+}
+
+/// Options of `qr_enc`
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub ec_level: EcLevel,
+	pub format: Format,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			ec_level: EcLevel::M,
+			format: Format::Terminal,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+	/// Two modules per character
+	Terminal,
+	Png,
+	Svg,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let ec_level = match matches.value_of("EC_LEVEL") {
+			Some("L") => EcLevel::L,
+			Some("Q") => EcLevel::Q,
+			Some("H") => EcLevel::H,
+			_ => EcLevel::M,
+		};
+
+		// by the extension of the output file
+		let extension = matches.value_of("OUTPUT_FILE").map(|x| {
+			Path::new(x)
+				.extension()
+				.and_then(|x| x.to_str())
+				.unwrap_or_default()
+				.to_lowercase()
+		});
+		let format = match extension.as_deref() {
+			None => Format::Terminal,
+			Some("png") => Format::Png,
+			Some("svg") => Format::Svg,
+			Some(extension) => {
+				return Err(Error::invalid(
+					"output",
+					format!(".{}, use .png or .svg", extension),
+				))
+			}
+		};
+
+		Ok(Options { ec_level, format })
+	}
+}
+
+fn run_qr_enc(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	match Options::from_matches(matches)?.format {
+		Format::Terminal => base::convert(matches, qr_enc),
+		_ => base::convert_raw(matches, qr_enc),
+	}
+}
+
+/// QR code encode, to the terminal, PNG or SVG
+pub fn qr_enc(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let code =
+		QrCode::with_error_correction_level(input, options.ec_level).map_err(Error::input)?;
+
+	let output = match options.format {
+		Format::Terminal => terminal(&code).into_bytes(),
+		Format::Png => png(&code)?,
+		Format::Svg => code
+			.render::<svg::Color>()
+			.min_dimensions(200, 200)
+			.build()
+			.into_bytes(),
+	};
+
+	Ok(output)
+}
+
+/// QR code decode, from a PNG / JPEG image, e.g. a screenshot, one line per code
+pub fn qr_dec(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let image = image::load_from_memory(input)
+		.map_err(|e| Error::invalid("image", e))?
+		.into_luma8();
+
+	let result = decode(&image)?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// The content of every code found
+fn decode(image: &GrayImage) -> Result<Vec<String>, Error> {
+	let mut prepared = PreparedImage::prepare_from_greyscale(
+		image.width() as usize,
+		image.height() as usize,
+		|x, y| image.get_pixel(x as u32, y as u32).0[0],
+	);
+	let grids = prepared.detect_grids();
+	if grids.is_empty() {
+		return Err(Error::Failed("No QR code found".to_string()));
+	}
+	grids
+		.iter()
+		.map(|grid| {
+			grid.decode()
+				.map(|(_, content)| content)
+				.map_err(|e| Error::Failed(format!("Decode failed: {}", e)))
+		})
+		.collect()
+}
+
+/// Two modules per character, light on dark so that it scans on dark terminals
+fn terminal(code: &QrCode) -> String {
+	code.render::<unicode::Dense1x2>()
+		.dark_color(unicode::Dense1x2::Light)
+		.light_color(unicode::Dense1x2::Dark)
+		.build()
+}
+
+fn png(code: &QrCode) -> Result<Vec<u8>, Error> {
+	let image = code.render::<Luma<u8>>().module_dimensions(8, 8).build();
+	let mut output = vec![];
+	DynamicImage::ImageLuma8(image)
+		.write_to(&mut Cursor::new(&mut output), ImageFormat::Png)
+		.map_err(|e| Error::Failed(format!("PNG encode failed: {}", e)))?;
+	Ok(output)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"qr_enc",
+				vec![
+					Case {
+						desc: "WiFi credentials to PNG".to_string(),
+						input: vec!["-o", "wifi.png", "'WIFI:T:WPA;S:MyNetwork;P:secret;;'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "OTP URI to SVG, high error correction".to_string(),
+						input: vec![
+							"-e",
+							"H",
+							"-o",
+							"otp.svg",
+							"'otpauth://totp/dtool:alice?secret=JBSWY3DPEHPK3PXP&issuer=dtool'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![],
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"qr_dec",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-f", "wifi.png"].into_iter().map(Into::into).collect(),
+					output: vec!["WIFI:T:WPA;S:MyNetwork;P:secret;;"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: false,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_qr_enc() {
+		// version 1 is 21 modules, with 4 modules of quiet zone on every side
+		let code = QrCode::with_error_correction_level(b"dtool", EcLevel::M).unwrap();
+		let terminal = terminal(&code);
+		let lines = terminal.lines().collect::<Vec<_>>();
+		assert_eq!(lines.len(), 15);
+		assert!(lines.iter().all(|x| x.chars().count() == 29));
+
+		let png = png(&code).unwrap();
+		assert!(png.starts_with(b"\x89PNG"));
+		let image = image::load_from_memory(&png).unwrap();
+		assert_eq!((image.width(), image.height()), (29 * 8, 29 * 8));
+	}
+
+	#[test]
+	fn test_qr_dec() {
+		let code =
+			QrCode::with_error_correction_level(b"WIFI:T:WPA;S:dtool;P:secret;;", EcLevel::Q)
+				.unwrap();
+		let png = png(&code).unwrap();
+		let image = image::load_from_memory(&png).unwrap().into_luma8();
+		assert_eq!(
+			decode(&image).unwrap(),
+			vec!["WIFI:T:WPA;S:dtool;P:secret;;"]
+		);
+
+		let blank = GrayImage::from_pixel(100, 100, Luma([255]));
+		assert!(decode(&blank).is_err());
+	}
+}
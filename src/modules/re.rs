@@ -1,52 +1,648 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
-use regex::Regex;
+use ansi_term::Colour;
+use clap::{Arg, ArgMatches};
+use regex::{Regex, RegexBuilder};
+use regex_syntax::ast::parse::Parser;
+use regex_syntax::ast::{
+	AssertionKind, Ast, Class, ClassBracketed, ClassPerl, ClassPerlKind, ClassSet,
+	ClassSetBinaryOpKind, ClassSetItem, ClassUnicode, ClassUnicodeKind, Flag, Flags, FlagsItemKind,
+	GroupKind, Literal, LiteralKind, Repetition, RepetitionKind, RepetitionRange, Span,
+};
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
-		desc: "Regex match".to_string(),
+		desc: "Regex match, replace, explain, filter".to_string(),
 		commands: commands(),
 		get_cases: cases::cases,
 	}
 }
 
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
-	vec![Command {
-		app: SubCommand::with_name("re")
-			.about("Regex match")
-			.arg(
-				Arg::with_name("PATTERN")
-					.long("pattern")
-					.short("p")
-					.help("Regex pattern")
-					.takes_value(true)
-					.required(true),
-			)
-			.arg(Arg::with_name("INPUT").required(false).index(1)),
-		f: re,
-	}]
+	vec![
+		Command {
+			app: base::sub_command("re")
+				.about("Regex match")
+				.arg(
+					Arg::with_name("PATTERN")
+						.long("pattern")
+						.short("p")
+						.help("Regex pattern")
+						.takes_value(true)
+						.required(true),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, re),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("re_match")
+				.about("Regex match with capture groups and byte offsets")
+				.arg(
+					Arg::with_name("PATTERN")
+						.long("pattern")
+						.short("p")
+						.help("Regex pattern")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("LINE")
+						.long("line")
+						.short("l")
+						.help("Match line by line, offsets are prefixed with the line number")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, re_match),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("re_replace")
+				.about("Regex replace")
+				.arg(
+					Arg::with_name("PATTERN")
+						.long("pattern")
+						.short("p")
+						.help("Regex pattern")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("REPLACEMENT")
+						.long("replacement")
+						.short("r")
+						.help("Replacement, capture groups can be referenced by $1 or ${name}")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("IGNORE_CASE")
+						.long("ignore-case")
+						.short("i")
+						.help("Case insensitive")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MULTI_LINE")
+						.long("multi-line")
+						.short("m")
+						.help("^ and $ match at the beginning and end of each line")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("COUNT")
+						.long("count")
+						.short("c")
+						.help("Maximum number of replacements, 0 for all")
+						.takes_value(true)
+						.default_value("0")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, re_replace),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("re_explain")
+				.about("Regex explain")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, re_explain),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("filter")
+				.about("Keep lines matching regex (grep like)")
+				.arg(
+					Arg::with_name("PATTERN")
+						.long("pattern")
+						.short("p")
+						.help("Regex pattern")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("INVERT")
+						.long("invert")
+						.short("I")
+						.help("Keep lines not matching")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("IGNORE_CASE")
+						.long("ignore-case")
+						.short("i")
+						.help("Case insensitive")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("COLOR")
+						.long("color")
+						.help("Highlight matches")
+						.takes_value(true)
+						.possible_values(&["auto", "always", "never"])
+						.default_value("auto")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, filter),
+			stream: None,
+		},
+	]
 }
 
-fn re(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// Options of the sub commands, the pattern is required except for `re_explain`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub pattern: String,
+	/// Match line by line (`re_match`)
+	pub line: bool,
+	/// Capture groups can be referenced by $1 or ${name} (`re_replace`)
+	pub replacement: String,
+	pub ignore_case: bool,
+	pub multi_line: bool,
+	/// Maximum number of replacements, 0 for all
+	pub count: usize,
+	/// Keep lines not matching (`filter`)
+	pub invert: bool,
+	/// Highlight matches (`filter`)
+	pub color: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let count = match matches.value_of("COUNT") {
+			Some(count) => count
+				.parse::<usize>()
+				.map_err(|_| Error::invalid("count", ""))?,
+			None => 0,
+		};
+		Ok(Options {
+			pattern: matches.value_of("PATTERN").unwrap_or_default().to_string(),
+			line: matches.is_present("LINE"),
+			replacement: matches
+				.value_of("REPLACEMENT")
+				.unwrap_or_default()
+				.to_string(),
+			ignore_case: matches.is_present("IGNORE_CASE"),
+			multi_line: matches.is_present("MULTI_LINE"),
+			count,
+			invert: matches.is_present("INVERT"),
+			color: base::color(matches),
+		})
+	}
+}
 
-	let pattern = matches.value_of("PATTERN").ok_or("Invalid pattern")?;
+/// Regex match
+pub fn re(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
-	let pattern = Regex::new(pattern).map_err(|_| "Invalid pattern")?;
+	let pattern = Regex::new(&options.pattern).map_err(|_| Error::invalid("pattern", ""))?;
 
 	let mut result = vec![];
 
-	for (_i, c) in pattern.captures_iter(&input).enumerate() {
+	for c in pattern.captures_iter(input) {
 		for (j, x) in c.iter().enumerate() {
 			if j == 0 {
-				result.push(format!("{}", x.unwrap().as_str()));
+				result.push(x.unwrap().as_str().to_string());
 			} else {
 				result.push(format!("    group#{}: {}", j, x.unwrap().as_str()));
 			}
 		}
 	}
 
-	Ok(result)
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Regex match with capture groups and byte offsets, fails if nothing matched
+pub fn re_match(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let pattern = Regex::new(&options.pattern).map_err(|e| Error::invalid("pattern", e))?;
+
+	let names = pattern.capture_names().collect::<Vec<_>>();
+
+	let texts = if options.line {
+		input
+			.lines()
+			.enumerate()
+			.map(|(i, line)| (format!("{}:", i + 1), line))
+			.collect()
+	} else {
+		vec![("".to_string(), input)]
+	};
+
+	let mut result = vec![];
+	for (prefix, text) in texts {
+		for c in pattern.captures_iter(text) {
+			for (j, x) in c.iter().enumerate() {
+				let line = match x {
+					Some(x) if j == 0 => {
+						format!("{}{}-{}: {}", prefix, x.start(), x.end(), x.as_str())
+					}
+					Some(x) => format!(
+						"    group#{} {}{}-{}: {}",
+						names[j].unwrap_or(&j.to_string()),
+						prefix,
+						x.start(),
+						x.end(),
+						x.as_str()
+					),
+					None => format!(
+						"    group#{}: (unmatched)",
+						names[j].unwrap_or(&j.to_string())
+					),
+				};
+				result.push(line);
+			}
+		}
+	}
+
+	if result.is_empty() {
+		return Err(Error::failed("No match"));
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Regex replace
+pub fn re_replace(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let pattern = RegexBuilder::new(&options.pattern)
+		.case_insensitive(options.ignore_case)
+		.multi_line(options.multi_line)
+		.build()
+		.map_err(|e| Error::invalid("pattern", e))?;
+
+	let result = pattern.replacen(input, options.count, options.replacement.as_str());
+
+	Ok(result.into_owned().into_bytes())
+}
+
+/// Keep lines matching regex (grep like)
+pub fn filter(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let pattern = RegexBuilder::new(&options.pattern)
+		.case_insensitive(options.ignore_case)
+		.build()
+		.map_err(|e| Error::invalid("pattern", e))?;
+
+	let invert = options.invert;
+
+	let result = input
+		.lines()
+		.filter(|line| pattern.is_match(line) != invert)
+		.map(|line| {
+			// inverted lines have nothing to highlight
+			if options.color && !invert {
+				highlight(&pattern, line)
+			} else {
+				line.to_string()
+			}
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn highlight(pattern: &Regex, line: &str) -> String {
+	let style = Colour::Red.bold();
+	let mut result = String::new();
+	let mut last = 0;
+	for m in pattern.find_iter(line) {
+		result.push_str(&line[last..m.start()]);
+		result.push_str(&style.paint(m.as_str()).to_string());
+		last = m.end();
+	}
+	result.push_str(&line[last..]);
+	result
+}
+
+/// Regex explain, the input is the pattern
+pub fn re_explain(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let ast = Parser::new()
+		.parse(input)
+		.map_err(|e| Error::invalid("pattern", e))?;
+
+	let mut explainer = Explainer {
+		pattern: input,
+		lines: vec![],
+		warnings: vec![],
+	};
+	explainer.explain(&ast, 0);
+
+	if let Ast::Concat(concat) = &ast {
+		if let Some(Ast::Repetition(repetition)) = concat.asts.first() {
+			if repetition.op.kind == RepetitionKind::ZeroOrMore
+				&& matches!(*repetition.ast, Ast::Dot(_))
+			{
+				explainer
+					.warnings
+					.push("leading `.*` is redundant in unanchored search".to_string());
+			}
+		}
+	}
+
+	let mut result = explainer.lines;
+	result.extend(
+		explainer
+			.warnings
+			.into_iter()
+			.map(|x| format!("{}{}", base::WARNING_PREFIX, x)),
+	);
+
+	Ok(result.join("\n").into_bytes())
+}
+
+struct Explainer<'a> {
+	pattern: &'a str,
+	lines: Vec<String>,
+	warnings: Vec<String>,
+}
+
+impl<'a> Explainer<'a> {
+	fn text(&self, span: &Span) -> &'a str {
+		&self.pattern[span.start.offset..span.end.offset]
+	}
+
+	fn push(&mut self, depth: usize, span: &Span, desc: String) {
+		let line = format!("{}`{}` {}", "  ".repeat(depth), self.text(span), desc);
+		self.lines.push(line);
+	}
+
+	fn explain(&mut self, ast: &Ast, depth: usize) {
+		match ast {
+			Ast::Empty(span) => self.push(depth, span, "empty".to_string()),
+			Ast::Flags(flags) => self.push(
+				depth,
+				&flags.span,
+				format!("set flags: {}", describe_flags(&flags.flags)),
+			),
+			Ast::Literal(literal) => self.push(depth, &literal.span, describe_literal(literal)),
+			Ast::Dot(span) => self.push(
+				depth,
+				span,
+				"any character except new line (unless flag s)".to_string(),
+			),
+			Ast::Assertion(assertion) => {
+				let desc = match assertion.kind {
+					AssertionKind::StartLine => "start of text (or line with flag m)",
+					AssertionKind::EndLine => "end of text (or line with flag m)",
+					AssertionKind::StartText => "start of text",
+					AssertionKind::EndText => "end of text",
+					AssertionKind::WordBoundary => "word boundary",
+					AssertionKind::NotWordBoundary => "not word boundary",
+				};
+				self.push(depth, &assertion.span, desc.to_string());
+			}
+			Ast::Class(Class::Perl(class)) => {
+				self.push(depth, &class.span, describe_perl_class(class))
+			}
+			Ast::Class(Class::Unicode(class)) => {
+				self.push(depth, &class.span, describe_unicode_class(class))
+			}
+			Ast::Class(Class::Bracketed(class)) => self.explain_bracketed(class, depth),
+			Ast::Repetition(repetition) => {
+				let times = match &repetition.op.kind {
+					RepetitionKind::ZeroOrOne => "optional".to_string(),
+					RepetitionKind::ZeroOrMore => "zero or more times".to_string(),
+					RepetitionKind::OneOrMore => "one or more times".to_string(),
+					RepetitionKind::Range(RepetitionRange::Exactly(n)) => {
+						format!("exactly {} times", n)
+					}
+					RepetitionKind::Range(RepetitionRange::AtLeast(n)) => {
+						format!("at least {} times", n)
+					}
+					RepetitionKind::Range(RepetitionRange::Bounded(m, n)) => {
+						format!("{} to {} times", m, n)
+					}
+				};
+				let greedy = if repetition.greedy { "greedy" } else { "lazy" };
+				self.push(depth, &repetition.span, format!("{}, {}", times, greedy));
+
+				if is_unbounded(repetition) && contains_unbounded(&repetition.ast) {
+					let warning = format!(
+						"nested quantifiers in `{}`, catastrophic backtracking in backtracking engines (e.g. PCRE, JavaScript)",
+						self.text(&repetition.span)
+					);
+					self.warnings.push(warning);
+				}
+				self.explain(&repetition.ast, depth + 1);
+			}
+			Ast::Group(group) => {
+				let desc = match &group.kind {
+					GroupKind::CaptureIndex(index) => format!("capture group #{}", index),
+					GroupKind::CaptureName(name) => {
+						format!("capture group #{} <{}>", name.index, name.name)
+					}
+					GroupKind::NonCapturing(flags) if flags.items.is_empty() => {
+						"non-capturing group".to_string()
+					}
+					GroupKind::NonCapturing(flags) => {
+						format!("non-capturing group, flags: {}", describe_flags(flags))
+					}
+				};
+				self.push(depth, &group.span, desc);
+				self.explain(&group.ast, depth + 1);
+			}
+			Ast::Alternation(alternation) => {
+				self.push(depth, &alternation.span, "one of".to_string());
+				if alternation.asts.iter().any(|x| matches!(x, Ast::Empty(_))) {
+					let warning = format!(
+						"empty alternative in `{}` matches the empty string",
+						self.text(&alternation.span)
+					);
+					self.warnings.push(warning);
+				}
+				for ast in &alternation.asts {
+					self.explain(ast, depth + 1);
+				}
+			}
+			Ast::Concat(concat) => {
+				self.push(depth, &concat.span, "sequence".to_string());
+				let mut i = 0;
+				while i < concat.asts.len() {
+					// consecutive verbatim literals are shown together
+					let verbatim = |x: &Ast| match x {
+						Ast::Literal(literal) => literal.kind == LiteralKind::Verbatim,
+						_ => false,
+					};
+					let len = concat.asts[i..].iter().take_while(|x| verbatim(x)).count();
+					if len > 1 {
+						let span = Span::new(
+							concat.asts[i].span().start,
+							concat.asts[i + len - 1].span().end,
+						);
+						self.push(depth + 1, &span, "literal".to_string());
+						i += len;
+						continue;
+					}
+
+					let ast = &concat.asts[i];
+					if let Ast::Dot(span) = ast {
+						if i > 0
+							&& verbatim(&concat.asts[i - 1])
+							&& concat.asts.get(i + 1).map(verbatim).unwrap_or(false)
+						{
+							let warning = format!(
+								"`.` at offset {} matches any character, use `\\.` for a literal dot",
+								span.start.offset
+							);
+							self.warnings.push(warning);
+						}
+					}
+					self.explain(ast, depth + 1);
+					i += 1;
+				}
+			}
+		}
+	}
+
+	fn explain_bracketed(&mut self, class: &ClassBracketed, depth: usize) {
+		let desc = if class.negated { "none of" } else { "any of" };
+		self.push(depth, &class.span, desc.to_string());
+		self.explain_class_set(&class.kind, depth + 1);
+	}
+
+	fn explain_class_set(&mut self, set: &ClassSet, depth: usize) {
+		match set {
+			ClassSet::Item(item) => self.explain_class_set_item(item, depth),
+			ClassSet::BinaryOp(op) => {
+				let desc = match op.kind {
+					ClassSetBinaryOpKind::Intersection => "intersection",
+					ClassSetBinaryOpKind::Difference => "difference",
+					ClassSetBinaryOpKind::SymmetricDifference => "symmetric difference",
+				};
+				self.push(depth, &op.span, desc.to_string());
+				self.explain_class_set(&op.lhs, depth + 1);
+				self.explain_class_set(&op.rhs, depth + 1);
+			}
+		}
+	}
+
+	fn explain_class_set_item(&mut self, item: &ClassSetItem, depth: usize) {
+		match item {
+			ClassSetItem::Empty(span) => self.push(depth, span, "empty".to_string()),
+			ClassSetItem::Literal(literal) => {
+				if literal.c == '|' {
+					self.warnings.push(format!(
+						"`|` at offset {} in character class is a literal, not alternation",
+						literal.span.start.offset
+					));
+				}
+				self.push(depth, &literal.span, describe_literal(literal))
+			}
+			ClassSetItem::Range(range) => {
+				let (start, end) = (range.start.c, range.end.c);
+				if start.is_ascii_uppercase() && end.is_ascii_lowercase() {
+					self.warnings.push(format!(
+						"range `{}` also matches [\\]^_` between Z and a",
+						self.text(&range.span)
+					));
+				}
+				self.push(
+					depth,
+					&range.span,
+					format!("range {:?} to {:?}", start, end),
+				)
+			}
+			ClassSetItem::Ascii(class) => {
+				let negated = if class.negated { "not " } else { "" };
+				self.push(
+					depth,
+					&class.span,
+					format!("{}ASCII class {:?}", negated, class.kind).to_lowercase(),
+				)
+			}
+			ClassSetItem::Unicode(class) => {
+				self.push(depth, &class.span, describe_unicode_class(class))
+			}
+			ClassSetItem::Perl(class) => self.push(depth, &class.span, describe_perl_class(class)),
+			ClassSetItem::Bracketed(class) => self.explain_bracketed(class, depth),
+			ClassSetItem::Union(union) => {
+				for item in &union.items {
+					self.explain_class_set_item(item, depth);
+				}
+			}
+		}
+	}
+}
+
+fn describe_literal(literal: &Literal) -> String {
+	match &literal.kind {
+		LiteralKind::Verbatim => "literal".to_string(),
+		LiteralKind::Punctuation => "literal (escaped)".to_string(),
+		LiteralKind::Special(kind) => format!("literal {:?}", kind).to_lowercase(),
+		_ => format!("literal {:?}", literal.c),
+	}
+}
+
+fn describe_perl_class(class: &ClassPerl) -> String {
+	let desc = match (&class.kind, class.negated) {
+		(ClassPerlKind::Digit, false) => "digit",
+		(ClassPerlKind::Digit, true) => "not digit",
+		(ClassPerlKind::Space, false) => "whitespace",
+		(ClassPerlKind::Space, true) => "not whitespace",
+		(ClassPerlKind::Word, false) => "word character",
+		(ClassPerlKind::Word, true) => "not word character",
+	};
+	format!("{} (unicode aware unless flag u is disabled)", desc)
+}
+
+fn describe_unicode_class(class: &ClassUnicode) -> String {
+	let name = match &class.kind {
+		ClassUnicodeKind::OneLetter(c) => c.to_string(),
+		ClassUnicodeKind::Named(name) => name.clone(),
+		ClassUnicodeKind::NamedValue { name, value, .. } => format!("{}={}", name, value),
+	};
+	let negated = if class.negated { "not " } else { "" };
+	format!("{}unicode class {}", negated, name)
+}
+
+fn describe_flags(flags: &Flags) -> String {
+	let mut negated = false;
+	let mut result = vec![];
+	for item in &flags.items {
+		match &item.kind {
+			FlagsItemKind::Negation => negated = true,
+			FlagsItemKind::Flag(flag) => {
+				let desc = match flag {
+					Flag::CaseInsensitive => "case insensitive",
+					Flag::MultiLine => "multi-line",
+					Flag::DotMatchesNewLine => "dot matches new line",
+					Flag::SwapGreed => "swap greed",
+					Flag::Unicode => "unicode",
+					Flag::IgnoreWhitespace => "ignore whitespace",
+				};
+				let prefix = if negated { "no " } else { "" };
+				result.push(format!("{}{}", prefix, desc));
+			}
+		}
+	}
+	result.join(", ")
+}
+
+fn is_unbounded(repetition: &Repetition) -> bool {
+	matches!(
+		repetition.op.kind,
+		RepetitionKind::ZeroOrMore
+			| RepetitionKind::OneOrMore
+			| RepetitionKind::Range(RepetitionRange::AtLeast(_))
+	)
+}
+
+fn contains_unbounded(ast: &Ast) -> bool {
+	match ast {
+		Ast::Repetition(repetition) => is_unbounded(repetition),
+		Ast::Group(group) => contains_unbounded(&group.ast),
+		Ast::Concat(concat) => concat.asts.iter().any(contains_unbounded),
+		_ => false,
+	}
 }
 
 mod cases {
@@ -54,23 +650,224 @@ mod cases {
 	use linked_hash_map::LinkedHashMap;
 
 	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
-		vec![(
-			"re",
-			vec![Case {
-				desc: "".to_string(),
-				input: vec!["-p", "'a(.)c'", "abcadc"]
-					.into_iter()
-					.map(Into::into)
-					.collect(),
-				output: vec!["abc", "    group#1: b", "adc", "    group#1: d"]
-					.into_iter()
-					.map(Into::into)
-					.collect(),
-				is_example: true,
-				is_test: true,
-				since: "0.4.0".to_string(),
-			}],
-		)]
+		vec![
+			(
+				"re",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-p", "'a(.)c'", "abcadc"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["abc", "    group#1: b", "adc", "    group#1: d"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.4.0".to_string(),
+				}],
+			),
+			(
+				"re_match",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-p", "'a(.)c'", "abcadc"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"0-3: abc",
+							"    group#1 1-2: b",
+							"3-6: adc",
+							"    group#1 4-5: d",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Named groups, line by line".to_string(),
+						input: vec![
+							"-l",
+							"-p",
+							r"'(?P<key>\w+)=(?P<value>\d+)?'",
+							"'a=1\nb=\nc'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"1:0-3: a=1",
+							"    group#key 1:0-1: a",
+							"    group#value 1:2-3: 1",
+							"2:0-2: b=",
+							"    group#key 2:0-1: b",
+							"    group#value: (unmatched)",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "No match".to_string(),
+						input: vec!["-p", "'x'", "abc"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["No match"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"re_replace",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-p",
+							r"'(?P<y>\d{4})-(?P<m>\d{2})-(?P<d>\d{2})'",
+							"-r",
+							"'$d/$m/$y'",
+							"'2020-01-02, 2020-03-04'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["02/01/2020, 04/03/2020"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Case insensitive, limited count".to_string(),
+						input: vec!["-i", "-c", "2", "-p", "'a(.)'", "-r", "'${1}x'", "AbAcad"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["bxcxad"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Multi-line".to_string(),
+						input: vec!["-m", "-p", "'^'", "-r", "'# '", "'a\nb'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["# a", "# b"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"re_explain",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r"'^(?P<user>\w+)@[a-z]+\.com$'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							r"`^(?P<user>\w+)@[a-z]+\.com$` sequence",
+							r"  `^` start of text (or line with flag m)",
+							r"  `(?P<user>\w+)` capture group #1 <user>",
+							r"    `\w+` one or more times, greedy",
+							r"      `\w` word character (unicode aware unless flag u is disabled)",
+							r"  `@` literal",
+							r"  `[a-z]+` one or more times, greedy",
+							r"    `[a-z]` any of",
+							r"      `a-z` range 'a' to 'z'",
+							r"  `\.` literal (escaped)",
+							r"  `com` literal",
+							r"  `$` end of text (or line with flag m)",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Warnings".to_string(),
+						input: vec![r"'(a+)+|www.b'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"`(a+)+|www.b` one of",
+							"  `(a+)+` one or more times, greedy",
+							"    `(a+)` capture group #1",
+							"      `a+` one or more times, greedy",
+							"        `a` literal",
+							"  `www.b` sequence",
+							"    `www` literal",
+							"    `.` any character except new line (unless flag s)",
+							"    `b` literal",
+							"warning: nested quantifiers in `(a+)+`, catastrophic backtracking in backtracking engines (e.g. PCRE, JavaScript)",
+							r"warning: `.` at offset 9 matches any character, use `\.` for a literal dot",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"filter",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-p", "'error|warn'", "--color", "never", "'info: a\nwarn: b\nerror: c'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["warn: b", "error: c"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Invert".to_string(),
+						input: vec!["-I", "-i", "-p", "'^INFO'", "'info: a\nwarn: b\nerror: c'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["warn: b", "error: c"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
 		.into_iter()
 		.collect()
 	}
@@ -0,0 +1,221 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use semver::{Version, VersionReq};
+use std::cmp::Ordering;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Semantic version validate / compare / match".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("semver")
+			.about("Semantic version validate, compare with another version, or match a requirement, exit with 1 if invalid or not matched")
+			.arg(
+				Arg::with_name("COMPARE")
+					.long("compare")
+					.short("c")
+					.help("Version to compare with, prints <, = or >, build metadata is ignored")
+					.takes_value(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("SATISFIES")
+					.long("satisfies")
+					.short("s")
+					.help("Requirement the version must satisfy, by the rules of Cargo, e.g. '^1.2, <2.0'\nexit with 1 if not")
+					.takes_value(true)
+					.conflicts_with("COMPARE")
+					.required(false),
+			)
+			.arg(base::input_arg().help("Version, e.g. 1.2.3-beta.1+build.5, a leading v is allowed")).args(&base::file_args()),
+		f: |matches| base::convert(matches, semver),
+		stream: None,
+	}]
+}
+
+/// Options of semver
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Version to compare with, build metadata is ignored
+	pub compare: Option<String>,
+	/// Requirement the version must satisfy, by the rules of Cargo, e.g. `^1.2, <2.0`
+	pub satisfies: Option<String>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			compare: matches.value_of("COMPARE").map(String::from),
+			satisfies: matches.value_of("SATISFIES").map(String::from),
+		})
+	}
+}
+
+/// Semantic version validate, compare with another version, or match a requirement,
+/// `Error::Mismatched` if not matched
+pub fn semver(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let version = parse_version(base::text(input)?)?;
+
+	if let Some(other) = &options.compare {
+		let other = parse_version(other)?;
+		let ordering = match version.cmp_precedence(&other) {
+			Ordering::Less => "<",
+			Ordering::Equal => "=",
+			Ordering::Greater => ">",
+		};
+		return Ok(format!("{} {} {}", version, ordering, other).into_bytes());
+	}
+
+	if let Some(requirement) = &options.satisfies {
+		let requirement = VersionReq::parse(requirement)
+			.map_err(|e| Error::invalid("requirement", format!("{}: {}", requirement, e)))?;
+		return match requirement.matches(&version) {
+			true => Ok(format!("{} satisfies {}", version, requirement).into_bytes()),
+			false => Err(Error::Mismatched(format!(
+				"{} does not satisfy {}",
+				version, requirement
+			))),
+		};
+	}
+
+	let mut result = vec![
+		format!("version: {}", version),
+		format!("major: {}", version.major),
+		format!("minor: {}", version.minor),
+		format!("patch: {}", version.patch),
+	];
+	if !version.pre.is_empty() {
+		result.push(format!("pre: {}", version.pre));
+	}
+	if !version.build.is_empty() {
+		result.push(format!("build: {}", version.build));
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// SemVer 2.0.0, with an optional leading `v` of tags
+fn parse_version(input: &str) -> Result<Version, Error> {
+	let input = input.trim();
+	let version = input.strip_prefix('v').unwrap_or(input);
+	Version::parse(version).map_err(|e| Error::invalid("version", format!("{}: {}", input, e)))
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"semver",
+			vec![
+				Case {
+					desc: "Validate".to_string(),
+					input: vec!["v1.2.3-beta.1+build.5"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"version: 1.2.3-beta.1+build.5",
+						"major: 1",
+						"minor: 2",
+						"patch: 3",
+						"pre: beta.1",
+						"build: build.5",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Compare".to_string(),
+					input: vec!["-c", "1.10.0", "1.2.3"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["1.2.3 < 1.10.0"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Pre-release is lower than the release".to_string(),
+					input: vec!["-c", "1.0.0", "1.0.0-rc.1"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["1.0.0-rc.1 < 1.0.0"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: false,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Release gate, exit with 1 if not satisfied".to_string(),
+					input: vec!["-s", "'^1.2, <2.0'", "1.4.0"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["1.4.0 satisfies ^1.2, <2.0"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_semver() {
+		let run = |input: &str, compare: Option<&str>, satisfies: Option<&str>| {
+			let options = Options {
+				compare: compare.map(String::from),
+				satisfies: satisfies.map(String::from),
+			};
+			semver(input.as_bytes(), &options).and_then(base::lines)
+		};
+
+		assert_eq!(
+			run("1.0.0+a", Some("1.0.0+b"), None).unwrap(),
+			vec!["1.0.0+a = 1.0.0+b"]
+		);
+		assert_eq!(
+			run("2.0.0", None, Some("^1.2, <2.0")),
+			Err(Error::Mismatched(
+				"2.0.0 does not satisfy ^1.2, <2.0".to_string()
+			))
+		);
+		assert!(run("1.3.0-beta", None, Some("^1.2")).is_err());
+		assert!(run("1.0.0", None, Some("^^1")).is_err());
+		assert!(run("1.2", None, None).is_err());
+		assert!(run("01.2.3", None, None).is_err());
+	}
+}
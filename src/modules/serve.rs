@@ -0,0 +1,326 @@
+use crate::error::Error;
+use crate::modules::{base, Command, ModuleManager, SECRET_ARGS};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde_json::{json, Map, Value};
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Args added by dtool for the command line, not available in the API (e.g. files of the server)
+const CLI_ARGS: [&str; 10] = [
+	"INPUT_FILE",
+	"OUTPUT_FILE",
+	"EACH_LINE",
+	"WATCH",
+	"QUIET",
+	"TIMING",
+	"OUTPUT_FORMAT",
+	"RAW_OUTPUT",
+	"NO_COLOR",
+	"VERBOSE",
+];
+
+/// Options of sub commands taking a path of the server, not available in the API
+const PATH_ARGS: [&str; 3] = ["PROTO", "DB", "DICTIONARY"];
+
+/// Sub commands connecting to other hosts, not available in the API (the server is not a proxy)
+const NETWORK_COMMANDS: [&str; 5] = ["http", "ws", "dns", "tls_inspect", "email"];
+
+const MAX_BODY: u64 = 16 * 1024 * 1024;
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+	SubCommand::with_name("serve")
+		.about("Serve sub commands as a REST API, POST /<sub command> with a JSON body, e.g. {\"algo\": \"md5\", \"input\": \"0x616263\"}")
+		.arg(
+			Arg::with_name("HOST")
+				.long("host")
+				.help("Host to listen on")
+				.takes_value(true)
+				.default_value("127.0.0.1")
+				.required(false),
+		)
+		.arg(
+			Arg::with_name("PORT")
+				.long("port")
+				.short("p")
+				.help("Port to listen on")
+				.takes_value(true)
+				.default_value("8080")
+				.required(false),
+		)
+}
+
+/// Runs until killed, every request is handled in order
+pub fn run<'a, 'b>(matches: &ArgMatches, mm: &ModuleManager<'a, 'b>) -> Result<Vec<String>, Error> {
+	let host = matches.value_of("HOST").ok_or(Error::invalid("host", ""))?;
+	let port = matches
+		.value_of("PORT")
+		.ok_or(Error::invalid("port", ""))?
+		.parse::<u16>()
+		.map_err(|_| Error::invalid("port", ""))?;
+
+	let server = Server::http((host, port))
+		.map_err(|e| Error::invalid("address", format!("{}:{}: {}", host, port, e)))?;
+	eprintln!("Listening on http://{}:{}", host, port);
+
+	for mut request in server.incoming_requests() {
+		let (status, content_type, body) = handle(&mut request, mm);
+		let header = Header::from_bytes("Content-Type", content_type).expect("qed");
+		let response = Response::from_data(body)
+			.with_status_code(status)
+			.with_header(header);
+		if let Err(e) = request.respond(response) {
+			eprintln!("Invalid response: {}", e);
+		}
+	}
+
+	Ok(vec![])
+}
+
+/// Returns the status, content type and body
+fn handle<'a, 'b>(
+	request: &mut Request,
+	mm: &ModuleManager<'a, 'b>,
+) -> (u16, &'static str, Vec<u8>) {
+	let name = request.url().trim_start_matches('/').to_string();
+
+	match (request.method(), name.as_str()) {
+		(Method::Get, "") => {
+			let names = mm
+				.commands
+				.keys()
+				.filter(|x| allowed_command(x))
+				.collect::<Vec<_>>();
+			return json_response(200, json!({ "commands": names }));
+		}
+		(Method::Post, _) => (),
+		_ => return error_response(405, Error::invalid("method", "expect POST /<sub command>")),
+	}
+
+	let command = match mm.commands.get(&name).filter(|_| allowed_command(&name)) {
+		Some(command) => command,
+		None => {
+			return error_response(
+				404,
+				Error::invalid(
+					"sub command",
+					format!(
+						"{}{}",
+						name,
+						base::did_you_mean(
+							&name,
+							mm.commands
+								.keys()
+								.map(String::as_str)
+								.filter(|x| allowed_command(x))
+						)
+					),
+				),
+			)
+		}
+	};
+
+	let mut body = vec![];
+	if let Err(e) = request.as_reader().take(MAX_BODY).read_to_end(&mut body) {
+		return error_response(400, Error::invalid("body", e));
+	}
+
+	match run_command(mm, &name, command, &body) {
+		Ok((_, output)) if !output.is_empty() => (200, "application/octet-stream", output),
+		Ok((result, _)) => json_response(200, result),
+		Err(e) => error_response(400, e),
+	}
+}
+
+/// Runs the command with the args of the body, returns the JSON result, or the raw output if written
+fn run_command<'a, 'b>(
+	mm: &ModuleManager<'a, 'b>,
+	name: &str,
+	command: &Command<'a, 'b>,
+	body: &[u8],
+) -> Result<(Value, Vec<u8>), Error> {
+	if !allowed_command(name) {
+		return Err(Error::invalid(
+			"sub command",
+			format!("{}: not available in the API", name),
+		));
+	}
+
+	let body: Map<String, Value> = if body.is_empty() {
+		Map::new()
+	} else {
+		serde_json::from_slice(body).map_err(|e| Error::invalid("body", e))?
+	};
+
+	let input = match body.get("input") {
+		Some(Value::String(input)) => input.as_bytes().to_vec(),
+		Some(_) => return Err(Error::InvalidInput("expect string".to_string())),
+		None => vec![],
+	};
+
+	let mut args = vec![name.to_string()];
+	for (key, value) in body.iter().filter(|(key, _)| *key != "input") {
+		args.extend(to_args(&command.app, key, value)?);
+	}
+
+	// only args given in the body, not the user config or DTOOL_KEY
+	let body_matches = command
+		.app
+		.clone()
+		.get_matches_from_safe(&args)
+		.map_err(|e| Error::failed(e.message))?;
+	if let Some(arg) = CLI_ARGS
+		.iter()
+		.chain(PATH_ARGS.iter())
+		.find(|x| body_matches.occurrences_of(x) > 0)
+	{
+		return Err(Error::invalid(
+			"arg",
+			format!("{}: not available in the API", arg),
+		));
+	}
+
+	let mut matches = mm
+		.get_matches(&command.app, &args, 0)
+		.map_err(|e| Error::failed(e.message))?;
+	// the key of the server (DTOOL_KEY or the config) never signs or derives for a caller
+	for arg in SECRET_ARGS
+		.iter()
+		.filter(|x| body_matches.occurrences_of(x) == 0)
+	{
+		matches.args.remove(arg);
+	}
+
+	let (result, output) = base::with_io(input, || (command.f)(&matches));
+	let result = result?;
+
+	Ok((base::json_output(name, &matches, result), output))
+}
+
+/// `"key": "value"` for options (arrays for multiple values), `"key": true` for flags
+fn to_args(app: &App, key: &str, value: &Value) -> Result<Vec<String>, Error> {
+	let invalid = || Error::invalid("arg", key);
+
+	let flag = !takes_value(app, key);
+	let values = match value {
+		Value::Bool(true) if flag => return Ok(vec![format!("--{}", key)]),
+		Value::Bool(false) if flag => return Ok(vec![]),
+		_ if flag => return Err(invalid()),
+		Value::Array(values) => values.iter().collect::<Vec<_>>(),
+		value => vec![value],
+	};
+	values
+		.into_iter()
+		.map(|value| match value {
+			Value::String(value) => Ok(format!("--{}={}", key, value)),
+			Value::Number(_) | Value::Bool(_) => Ok(format!("--{}={}", key, value)),
+			_ => Err(invalid()),
+		})
+		.collect()
+}
+
+fn takes_value(app: &App, key: &str) -> bool {
+	base::options(app).iter().any(|(_, long)| long == key)
+}
+
+fn allowed_command(name: &str) -> bool {
+	!NETWORK_COMMANDS.contains(&name)
+}
+
+fn json_response(status: u16, value: Value) -> (u16, &'static str, Vec<u8>) {
+	let mut body = serde_json::to_vec_pretty(&value).unwrap_or_default();
+	body.push(b'\n');
+	(status, "application/json", body)
+}
+
+fn error_response(status: u16, error: Error) -> (u16, &'static str, Vec<u8>) {
+	json_response(status, json!({ "error": error.to_json() }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_run_command() {
+		let mm = ModuleManager::new_without_config();
+
+		let (result, output) = run_command(
+			&mm,
+			"hash",
+			&mm.commands["hash"],
+			br#"{"algo": "md5", "input": "0x616263"}"#,
+		)
+		.unwrap();
+		assert_eq!(
+			result,
+			json!({
+				"result": ["0x900150983cd24fb0d6963f7d28e17f72"],
+				"algorithm": "md5",
+				"warnings": [],
+			})
+		);
+		assert!(output.is_empty());
+
+		let (_, output) =
+			run_command(&mm, "h2b", &mm.commands["h2b"], br#"{"input": "0x616263"}"#).unwrap();
+		assert_eq!(output, b"abc".to_vec());
+
+		let (result, _) = run_command(
+			&mm,
+			"hash",
+			&mm.commands["hash"],
+			br#"{"algo": "md5", "binary": true, "input": "abc"}"#,
+		)
+		.unwrap();
+		assert_eq!(result["result"][0], "0x900150983cd24fb0d6963f7d28e17f72");
+
+		// files of the server are not available
+		assert!(run_command(
+			&mm,
+			"hash",
+			&mm.commands["hash"],
+			br#"{"algo": "md5", "file": "/etc/passwd"}"#
+		)
+		.is_err());
+		assert!(run_command(
+			&mm,
+			"hash",
+			&mm.commands["hash"],
+			br#"{"algo": "md5", "binary": "yes"}"#
+		)
+		.is_err());
+		assert!(run_command(&mm, "hash", &mm.commands["hash"], b"[]").is_err());
+
+		// nor paths of the server in options of sub commands, or args of the command line
+		let denied = |name: &str, body: &[u8]| {
+			let error = run_command(&mm, name, &mm.commands[name], body)
+				.unwrap_err()
+				.to_string();
+			assert!(error.contains("not available in the API"), "{}", error);
+		};
+		denied("hash", br#"{"algo": "md5", "file": "/etc/passwd"}"#);
+		denied(
+			"pb2j",
+			br#"{"proto": "/etc/passwd", "type": "A", "input": "0x0801"}"#,
+		);
+		denied("ipinfo", br#"{"db": ["/etc/passwd"], "input": "1.1.1.1"}"#);
+		denied(
+			"hash",
+			br#"{"algo": "md5", "output": "/tmp/a", "input": "abc"}"#,
+		);
+	}
+
+	#[test]
+	fn test_network_commands() {
+		let mm = ModuleManager::new_without_config();
+
+		for name in &NETWORK_COMMANDS {
+			if let Some(command) = mm.commands.get(*name) {
+				let error = run_command(&mm, name, command, br#"{"input": "example.com"}"#)
+					.unwrap_err()
+					.to_string();
+				assert!(error.contains("not available in the API"), "{}", error);
+			}
+		}
+	}
+}
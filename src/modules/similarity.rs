@@ -0,0 +1,286 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use std::collections::HashMap;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "String similarity (Levenshtein, Damerau-Levenshtein, Jaro, Jaro-Winkler)"
+			.to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("similarity")
+			.about("Edit distance and similarity of two strings")
+			.arg(
+				Arg::with_name("TYPE")
+					.long("type")
+					.short("t")
+					.help("Output only the value of the type")
+					.takes_value(true)
+					.possible_values(&[
+						"levenshtein",
+						"damerau_levenshtein",
+						"jaro",
+						"jaro_winkler",
+					])
+					.required(false),
+			)
+			.arg(Arg::with_name("INPUT").required(false).index(1))
+			.args(&base::file_args())
+			.arg(Arg::with_name("OTHER").required(false).index(2)),
+		f: |matches| base::convert(matches, similarity),
+		stream: None,
+	}]
+}
+
+/// Options of `similarity`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// The string compared with the input
+	pub other: String,
+	/// Output only the value of the type
+	pub similarity_type: Option<SimilarityType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimilarityType {
+	Levenshtein,
+	DamerauLevenshtein,
+	Jaro,
+	JaroWinkler,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let other = matches
+			.value_of("OTHER")
+			.ok_or(Error::invalid("other", ""))?
+			.to_string();
+
+		let similarity_type = match matches.value_of("TYPE") {
+			Some("levenshtein") => Some(SimilarityType::Levenshtein),
+			Some("damerau_levenshtein") => Some(SimilarityType::DamerauLevenshtein),
+			Some("jaro") => Some(SimilarityType::Jaro),
+			Some("jaro_winkler") => Some(SimilarityType::JaroWinkler),
+			Some(_) => return Err(Error::invalid("type", "")),
+			None => None,
+		};
+
+		Ok(Options {
+			other,
+			similarity_type,
+		})
+	}
+}
+
+/// Edit distance and similarity of the input and `other`
+pub fn similarity(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let a = base::text(input)?.chars().collect::<Vec<_>>();
+	let b = options.other.chars().collect::<Vec<_>>();
+
+	let values = vec![
+		(
+			SimilarityType::Levenshtein,
+			"levenshtein",
+			levenshtein(&a, &b).to_string(),
+		),
+		(
+			SimilarityType::DamerauLevenshtein,
+			"damerau_levenshtein",
+			damerau_levenshtein(&a, &b).to_string(),
+		),
+		(SimilarityType::Jaro, "jaro", format!("{:.4}", jaro(&a, &b))),
+		(
+			SimilarityType::JaroWinkler,
+			"jaro_winkler",
+			format!("{:.4}", jaro_winkler(&a, &b)),
+		),
+	];
+
+	let result = match options.similarity_type {
+		Some(t) => values
+			.into_iter()
+			.filter(|(similarity_type, _, _)| *similarity_type == t)
+			.map(|(_, _, value)| value)
+			.collect::<Vec<_>>(),
+		None => values
+			.into_iter()
+			.map(|(_, name, value)| format!("{}: {}", name, value))
+			.collect(),
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+pub(crate) fn levenshtein(a: &[char], b: &[char]) -> usize {
+	let mut prev = (0..=b.len()).collect::<Vec<_>>();
+	for (i, ca) in a.iter().enumerate() {
+		let mut current = vec![i + 1; b.len() + 1];
+		for (j, cb) in b.iter().enumerate() {
+			let cost = if ca == cb { 0 } else { 1 };
+			current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+		}
+		prev = current;
+	}
+	prev[b.len()]
+}
+
+/// Unrestricted, transposed characters may be edited again, e.g. ca -> abc is 2
+fn damerau_levenshtein(a: &[char], b: &[char]) -> usize {
+	let max = a.len() + b.len();
+	let mut d = vec![vec![0; b.len() + 2]; a.len() + 2];
+	d[0][0] = max;
+	for i in 0..=a.len() {
+		d[i + 1][0] = max;
+		d[i + 1][1] = i;
+	}
+	for j in 0..=b.len() {
+		d[0][j + 1] = max;
+		d[1][j + 1] = j;
+	}
+
+	// the last row where the char was seen in a
+	let mut last_row = HashMap::new();
+	for i in 1..=a.len() {
+		// the last column where a[i - 1] was matched in b
+		let mut last_col = 0;
+		for j in 1..=b.len() {
+			let i1 = *last_row.get(&b[j - 1]).unwrap_or(&0);
+			let j1 = last_col;
+			let cost = if a[i - 1] == b[j - 1] {
+				last_col = j;
+				0
+			} else {
+				1
+			};
+			d[i + 1][j + 1] = (d[i][j] + cost)
+				.min(d[i + 1][j] + 1)
+				.min(d[i][j + 1] + 1)
+				.min(d[i1][j1] + (i - i1 - 1) + 1 + (j - j1 - 1));
+		}
+		last_row.insert(a[i - 1], i);
+	}
+	d[a.len() + 1][b.len() + 1]
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+	if a.is_empty() && b.is_empty() {
+		return 1.0;
+	}
+	if a.is_empty() || b.is_empty() {
+		return 0.0;
+	}
+
+	let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+	let mut b_matched = vec![false; b.len()];
+	let mut a_matches = vec![];
+	for (i, ca) in a.iter().enumerate() {
+		let start = i.saturating_sub(window);
+		let end = (i + window + 1).min(b.len());
+		if let Some(j) = (start..end).find(|&j| !b_matched[j] && b[j] == *ca) {
+			b_matched[j] = true;
+			a_matches.push(*ca);
+		}
+	}
+
+	let m = a_matches.len();
+	if m == 0 {
+		return 0.0;
+	}
+
+	let b_matches = b
+		.iter()
+		.zip(b_matched)
+		.filter(|(_, matched)| *matched)
+		.map(|(c, _)| *c);
+	let transpositions = a_matches
+		.iter()
+		.zip(b_matches)
+		.filter(|(x, y)| **x != *y)
+		.count()
+		/ 2;
+
+	let m = m as f64;
+	(m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// Common prefix up to 4 chars, scaling factor 0.1
+fn jaro_winkler(a: &[char], b: &[char]) -> f64 {
+	let jaro = jaro(a, b);
+	let prefix = a.iter().zip(b).take(4).take_while(|(x, y)| x == y).count();
+	jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"similarity",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["martha", "marhta"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"levenshtein: 2",
+						"damerau_levenshtein: 1",
+						"jaro: 0.9444",
+						"jaro_winkler: 0.9611",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "".to_string(),
+					input: vec!["-t", "levenshtein", "kitten", "sitting"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["3"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "".to_string(),
+					input: vec!["-t", "damerau_levenshtein", "ca", "abc"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["2"].into_iter().map(Into::into).collect(),
+					is_example: false,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
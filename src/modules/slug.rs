@@ -0,0 +1,164 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use deunicode::deunicode;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Slugify".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("slug")
+			.about("Slugify (transliterate to ASCII, lowercase, join words with separator)")
+			.arg(
+				Arg::with_name("SEPARATOR")
+					.long("separator")
+					.short("s")
+					.help("Separator")
+					.takes_value(true)
+					.default_value("-")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("KEEP_CASE")
+					.long("keep-case")
+					.short("k")
+					.help("Keep case")
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, slug),
+		stream: None,
+	}]
+}
+
+/// Options of `slug`
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub separator: String,
+	pub keep_case: bool,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			separator: "-".to_string(),
+			keep_case: false,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let separator = matches
+			.value_of("SEPARATOR")
+			.ok_or(Error::invalid("separator", ""))?
+			.to_string();
+
+		Ok(Options {
+			separator,
+			keep_case: matches.is_present("KEEP_CASE"),
+		})
+	}
+}
+
+/// Slugify (transliterate to ASCII, lowercase, join words with separator), a slug per line
+pub fn slug(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let result = base::text(input)?
+		.lines()
+		.map(|line| slugify(line, &options.separator, options.keep_case))
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn slugify(input: &str, separator: &str, keep_case: bool) -> String {
+	let mut result = String::new();
+	let mut pending = false;
+	for c in deunicode(input).chars() {
+		match c {
+			c if c.is_ascii_alphanumeric() => {
+				if pending && !result.is_empty() {
+					result.push_str(separator);
+				}
+				pending = false;
+				result.push(if keep_case { c } else { c.to_ascii_lowercase() });
+			}
+			// apostrophes do not split words, e.g. don't
+			'\'' => (),
+			_ => pending = true,
+		}
+	}
+	result
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"slug",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'Crème Brûlée: Don\u{2019}t Panic!'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["creme-brulee-dont-panic"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Separator, keep case".to_string(),
+					input: vec!["-s", "_", "-k", "'Straße in Köln 2020'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["Strasse_in_Koln_2020"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "".to_string(),
+					input: vec!["'北京 Москва'"].into_iter().map(Into::into).collect(),
+					output: vec!["bei-jing-moskva"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: false,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
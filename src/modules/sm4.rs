@@ -1,7 +1,7 @@
-use self::Mode::CTR;
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 use yogcrypt::sm4;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
@@ -15,14 +15,10 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("sm4_enc")
+			app: base::sub_command("sm4_enc")
 				.about("SM4 encrypt")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Plain (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Plain (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("MODE")
 						.long("mode")
@@ -38,6 +34,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("k")
 						.help("Key (Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				)
 				.arg(
@@ -48,17 +46,14 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				),
-			f: sm4_enc,
+			f: |matches| base::convert(matches, sm4_enc),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("sm4_dec")
+			app: base::sub_command("sm4_dec")
 				.about("SM4 decrypt")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Cipher (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Cipher (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("MODE")
 						.long("mode")
@@ -74,6 +69,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("k")
 						.help("Key (Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				)
 				.arg(
@@ -84,80 +81,109 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				),
-			f: sm4_dec,
+			f: |matches| base::convert(matches, sm4_dec),
+			stream: None,
 		},
 	]
 }
 
-enum Mode {
-	CTR { iv: Vec<u8> },
+/// Options of `sm4_enc` and `sm4_dec`, the key is 128 bits
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub key: Vec<u8>,
+	pub mode: Mode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+	Ctr { iv: Vec<u8> },
 }
 
 enum KeySize {
 	KeySize128,
 }
 
-fn sm4_enc(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let (key_size, key, mode, input) = get_common_arg(matches)?;
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let key = matches.value_of("KEY").ok_or(Error::invalid("key", ""))?;
+		let key: Vec<u8> = key
+			.parse::<Hex>()
+			.map_err(|_| Error::invalid("key", ""))?
+			.into();
+
+		let get_iv = || -> Result<Vec<u8>, Error> {
+			let iv = matches.value_of("IV").ok_or(Error::invalid("IV", ""))?;
+			let iv: Vec<u8> = iv
+				.parse::<Hex>()
+				.map_err(|_| Error::invalid("IV", ""))?
+				.into();
+			Ok(iv)
+		};
+
+		let mode = matches.value_of("MODE").ok_or(Error::invalid("mode", ""))?;
+		let mode = match mode {
+			"ctr" => Mode::Ctr { iv: get_iv()? },
+			_ => unreachable!(),
+		};
+
+		Ok(Options { key, mode })
+	}
+}
+
+/// SM4 encrypt, the input is the plain (hex)
+pub fn sm4_enc(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let (key_size, input) = get_common_arg(input, options)?;
+	let key = &options.key;
 
 	// cipher
-	let result = match mode {
-		CTR { iv } => sm4_enc_ctr(key_size, &key, &input, &iv),
+	let result = match &options.mode {
+		Mode::Ctr { iv } => sm4_enc_ctr(key_size, key, &input, iv),
 	}?;
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn sm4_dec(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let (key_size, key, mode, input) = get_common_arg(matches)?;
+/// SM4 decrypt, the input is the cipher (hex)
+pub fn sm4_dec(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let (key_size, input) = get_common_arg(input, options)?;
+	let key = &options.key;
 
 	// plain
-	let result = match mode {
-		CTR { iv } => sm4_dec_ctr(key_size, &key, &input, &iv),
+	let result = match &options.mode {
+		Mode::Ctr { iv } => sm4_dec_ctr(key_size, key, &input, iv),
 	}?;
-	let result = Hex::from(result).into();
+	let result: String = Hex::from(result).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn get_common_arg(matches: &ArgMatches) -> Result<(KeySize, Vec<u8>, Mode, Vec<u8>), String> {
-	let input = base::input_string(matches)?;
-
-	// key and key_size
-	let key = matches.value_of("KEY").ok_or("Invalid key".to_string())?;
-	let key: Vec<u8> = key.parse::<Hex>().map_err(|_| "Invalid key")?.into();
-	let key_size = match key.len() {
+fn get_common_arg(input: &[u8], options: &Options) -> Result<(KeySize, Vec<u8>), Error> {
+	let key_size = match options.key.len() {
 		16 => KeySize::KeySize128,
-		_ => return Err("Invalid key size (should be 128)".to_string()),
+		_ => return Err(Error::invalid("key size (should be 128)", "")),
 	};
 
-	let get_iv = || -> Result<Vec<u8>, String> {
-		let iv = matches.value_of("IV").ok_or("Invalid IV".to_string())?;
-		let iv: Vec<u8> = iv.parse::<Hex>().map_err(|_| "Invalid IV")?.into();
-		Ok(iv)
-	};
-
-	// mode
-	let mode = matches.value_of("MODE").ok_or("Invalid mode".to_string())?;
-	let mode = match mode {
-		"ctr" => CTR { iv: get_iv()? },
-		_ => unreachable!(),
-	};
+	let Mode::Ctr { iv } = &options.mode;
+	if iv.len() != 16 {
+		return Err(Error::invalid("IV size (should be 128)", ""));
+	}
 
-	// input
-	let input = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
+	let input = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::input(""))?
+		.into();
 
-	Ok((key_size, key, mode, input))
+	Ok((key_size, input))
 }
 
 const BLOCK_SIZE: usize = 16;
 
-fn sm4_enc_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn sm4_enc_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	sm4_ctr_process(key_size, key, input, iv)
 }
 
-fn sm4_dec_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+fn sm4_dec_ctr(key_size: KeySize, key: &[u8], input: &[u8], iv: &[u8]) -> Result<Vec<u8>, Error> {
 	sm4_ctr_process(key_size, key, input, iv)
 }
 
@@ -166,7 +192,7 @@ fn sm4_ctr_process(
 	key: &[u8],
 	input: &[u8],
 	iv: &[u8],
-) -> Result<Vec<u8>, String> {
+) -> Result<Vec<u8>, Error> {
 	let mut buff = [0u8; BLOCK_SIZE];
 	buff.copy_from_slice(iv);
 
@@ -191,10 +217,9 @@ fn sm4_ctr_process(
 	}
 
 	let enc = sm4::sm4_enc(&key, &buff);
-	for i in 0..tail_len {
+	for (i, e) in enc.iter().enumerate().take(tail_len) {
 		let ii = block_count * 16 + i;
-		let b = input[ii] ^ enc[i];
-		result[ii] = b;
+		result[ii] = input[ii] ^ e;
 	}
 
 	let result = result.to_vec();
@@ -0,0 +1,295 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::{Arg, ArgMatches};
+
+/// Epoch in milliseconds and the fields below the timestamp, from high to low bits
+const PRESETS: [(&str, i64, &str); 2] = [
+	(
+		"twitter",
+		1288834974657,
+		"datacenter:5,worker:5,sequence:12",
+	),
+	("discord", 1420070400000, "worker:5,process:5,increment:12"),
+];
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Snowflake ID decode".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("snowflake")
+			.about("Snowflake ID decode, the time and the fields below the timestamp")
+			.arg(
+				Arg::with_name("PRESET")
+					.long("preset")
+					.short("p")
+					.help("twitter: epoch 1288834974657, datacenter:5,worker:5,sequence:12\ndiscord: epoch 1420070400000, worker:5,process:5,increment:12")
+					.takes_value(true)
+					.possible_values(&["twitter", "discord"])
+					.default_value("twitter")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("EPOCH")
+					.long("epoch")
+					.short("e")
+					.help("Epoch in Unix milliseconds, overrides the preset")
+					.takes_value(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("LAYOUT")
+					.long("layout")
+					.short("l")
+					.help("Fields below the timestamp as name:bits, from high to low bits, overrides the preset\ne.g. worker:10,sequence:12")
+					.takes_value(true)
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, snowflake),
+		stream: None,
+	}]
+}
+
+/// Options of snowflake, the preset resolved to the epoch and the layout
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Epoch in Unix milliseconds
+	pub epoch: i64,
+	/// Fields below the timestamp as name and bits, from high to low bits
+	pub layout: Vec<(String, u32)>,
+}
+
+/// Twitter
+impl Default for Options {
+	fn default() -> Self {
+		let (_, epoch, layout) = PRESETS[0];
+		Options {
+			epoch,
+			layout: parse_layout(layout).expect("valid preset"),
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let preset = matches.value_of("PRESET").unwrap_or("twitter");
+		let (_, epoch, layout) = PRESETS
+			.iter()
+			.find(|(name, _, _)| *name == preset)
+			.ok_or(Error::invalid("preset", ""))?;
+		let epoch = match matches.value_of("EPOCH") {
+			Some(epoch) => epoch
+				.parse::<i64>()
+				.map_err(|e| Error::invalid("epoch", format!("{}: {}", epoch, e)))?,
+			None => *epoch,
+		};
+		let layout = parse_layout(matches.value_of("LAYOUT").unwrap_or(layout))?;
+		Ok(Options { epoch, layout })
+	}
+}
+
+/// Snowflake ID decode, the time and the fields below the timestamp
+pub fn snowflake(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?.trim();
+
+	let id = input
+		.parse::<u64>()
+		.map_err(|e| Error::invalid("ID", format!("{}: {}", input, e)))?;
+
+	check_layout(&options.layout)?;
+
+	let result = decode(id, options.epoch, &options.layout)?;
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// e.g. `worker:10,sequence:12`
+fn parse_layout(layout: &str) -> Result<Vec<(String, u32)>, Error> {
+	let fields = layout
+		.split(',')
+		.map(|field| {
+			let (name, bits) = field
+				.split_once(':')
+				.ok_or_else(|| Error::invalid("layout", format!("{}: not name:bits", field)))?;
+			let bits = bits
+				.trim()
+				.parse::<u32>()
+				.map_err(|_| Error::invalid("layout", format!("{}: bits not in 1..63", field)))?;
+			Ok((name.trim().to_string(), bits))
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	check_layout(&fields)?;
+
+	Ok(fields)
+}
+
+/// Every field has 1 to 63 bits, and some are left for the timestamp
+fn check_layout(layout: &[(String, u32)]) -> Result<(), Error> {
+	if let Some((name, bits)) = layout.iter().find(|(_, bits)| !(1..64).contains(bits)) {
+		return Err(Error::invalid(
+			"layout",
+			format!("{}:{}: bits not in 1..63", name, bits),
+		));
+	}
+
+	if layout.iter().map(|(_, bits)| bits).sum::<u32>() >= 64 {
+		let layout = layout
+			.iter()
+			.map(|(name, bits)| format!("{}:{}", name, bits))
+			.collect::<Vec<_>>()
+			.join(",");
+		return Err(Error::invalid(
+			"layout",
+			format!("{}: no bits left for the timestamp", layout),
+		));
+	}
+
+	Ok(())
+}
+
+fn decode(id: u64, epoch: i64, layout: &[(String, u32)]) -> Result<Vec<String>, Error> {
+	let shift = layout.iter().map(|(_, bits)| bits).sum::<u32>();
+	let invalid = || Error::invalid("ID", format!("{}: timestamp out of range", id));
+	let timestamp = ((id >> shift) as i64)
+		.checked_add(epoch)
+		.ok_or_else(invalid)?;
+	let time = Utc
+		.timestamp_millis_opt(timestamp)
+		.single()
+		.ok_or_else(invalid)?;
+
+	let mut result = vec![
+		format!("id: {}", id),
+		format!("time: {}", time.format("%Y-%m-%d %H:%M:%S%.3f UTC")),
+		format!("timestamp: {}", timestamp),
+	];
+
+	let mut shift = shift;
+	for (name, bits) in layout {
+		shift -= bits;
+		let value = (id >> shift) & ((1u64 << bits) - 1);
+		result.push(format!("{}: {}", name, value));
+	}
+
+	Ok(result)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"snowflake",
+			vec![
+				Case {
+					desc: "Twitter".to_string(),
+					input: vec!["1212161821003599873"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"id: 1212161821003599873",
+						"time: 2020-01-01 00:01:13.661 UTC",
+						"timestamp: 1577836873661",
+						"datacenter: 26",
+						"worker: 29",
+						"sequence: 1",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Discord".to_string(),
+					input: vec!["-p", "discord", "175928847299117063"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"id: 175928847299117063",
+						"time: 2016-04-30 11:18:25.796 UTC",
+						"timestamp: 1462015105796",
+						"worker: 1",
+						"process: 0",
+						"increment: 7",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Custom epoch and layout".to_string(),
+					input: vec![
+						"-e",
+						"1577836800000",
+						"-l",
+						"machine:10,sequence:12",
+						"4194316293",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						"id: 4194316293",
+						"time: 2020-01-01 00:00:01.000 UTC",
+						"timestamp: 1577836801000",
+						"machine: 3",
+						"sequence: 5",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_parse_layout() {
+		assert_eq!(
+			parse_layout("worker:10, sequence:12").unwrap(),
+			vec![("worker".to_string(), 10), ("sequence".to_string(), 12)]
+		);
+		assert!(parse_layout("worker").is_err());
+		assert!(parse_layout("worker:0").is_err());
+		assert!(parse_layout("worker:32,sequence:32").is_err());
+	}
+
+	#[test]
+	fn test_decode() {
+		let layout = parse_layout("sequence:1").unwrap();
+		assert!(decode(u64::MAX, 1288834974657, &layout).is_err());
+	}
+}
@@ -1,9 +1,11 @@
-use crate::modules::base::Hex;
+use crate::error::Error;
+use crate::modules::base::{FromMatches, Hex};
 use crate::modules::{base, Command, Module};
-use clap::{Arg, ArgMatches, SubCommand};
+use clap::{Arg, ArgMatches};
 
 mod sr25519;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum AltSecretKey {
 	MiniSecretKey(Vec<u8>),
 	SecretKey(Vec<u8>),
@@ -20,25 +22,24 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("sr_gk")
+			app: base::sub_command("sr_gk")
 				.about("sr25519 generate key pair (Mini secret key, Public key)"),
-			f: sr_gk,
+			f: |matches| base::generate(matches, sr_gk),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("sr_sign")
+			app: base::sub_command("sr_sign")
 				.about("sr25519 sign")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Message (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Message (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("MINI_SECRET_KEY")
 						.long("mini-secret-key")
 						.short("m")
 						.help("Mini secret key (Mini private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(false),
 				)
 				.arg(
@@ -47,19 +48,18 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("s")
 						.help("Secret key (Private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(false),
 				),
-			f: sr_sign,
+			f: |matches| base::convert(matches, sr_sign),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("sr_verify")
+			app: base::sub_command("sr_verify")
 				.about("sr25519 verify")
-				.arg(
-					Arg::with_name("INPUT")
-						.help("Message (Hex)")
-						.required(false)
-						.index(1),
-				)
+				.arg(base::input_arg().help("Message (Hex)"))
+				.args(&base::file_args())
 				.arg(
 					Arg::with_name("PUBLIC_KEY")
 						.long("public-key")
@@ -76,10 +76,11 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(true),
 				),
-			f: sr_verify,
+			f: |matches| base::convert(matches, sr_verify),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("sr_sk")
+			app: base::sub_command("sr_sk")
 				.about("sr25519 calculate secret key from mini secret key")
 				.arg(
 					Arg::with_name("MINI_SECRET_KEY")
@@ -87,13 +88,15 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("m")
 						.help("Mini secret key (Mini private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(true),
 				),
-
-			f: sr_sk,
+			f: |matches| base::generate(matches, sr_sk),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("sr_pk")
+			app: base::sub_command("sr_pk")
 				.about("sr25519 calculate public key")
 				.arg(
 					Arg::with_name("MINI_SECRET_KEY")
@@ -101,6 +104,8 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("m")
 						.help("Mini secret key (Mini private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(false),
 				)
 				.arg(
@@ -109,15 +114,55 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.short("s")
 						.help("Secret key (Private key, Hex)")
 						.takes_value(true)
+						.env("DTOOL_KEY")
+						.hide_env_values(true)
 						.required(false),
 				),
 
-			f: sr_pk,
+			f: |matches| base::generate(matches, sr_pk),
+
+			stream: None,
 		},
 	]
 }
 
-fn sr_gk(_matches: &ArgMatches) -> Result<Vec<String>, String> {
+/// Options of the sr25519 commands, the keys and the signature are read where needed
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub secret_key: Option<AltSecretKey>,
+	pub public_key: Vec<u8>,
+	pub signature: Vec<u8>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let hex_arg = |name: &str, what: &str| -> Result<Option<Vec<u8>>, Error> {
+			match matches.value_of(name) {
+				Some(value) => Ok(Some(
+					value
+						.parse::<Hex>()
+						.map_err(|_| Error::invalid(what, ""))?
+						.into(),
+				)),
+				None => Ok(None),
+			}
+		};
+
+		let secret_key = match hex_arg("MINI_SECRET_KEY", "mini secret key")? {
+			Some(mini_secret_key) => Some(AltSecretKey::MiniSecretKey(mini_secret_key)),
+			None => hex_arg("SECRET_KEY", "secret key")?.map(AltSecretKey::SecretKey),
+		};
+
+		Ok(Options {
+			secret_key,
+			public_key: hex_arg("PUBLIC_KEY", "public key")?.unwrap_or_default(),
+			signature: hex_arg("SIGNATURE", "signature")?.unwrap_or_default(),
+		})
+	}
+}
+
+/// sr25519 generate key pair (Mini secret key, Public key), there is no input
+pub fn sr_gk(_: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
 	let (private_key, public_key) = sr25519::sr_gk_sr25519()?;
 
 	let (private_key, public_key): (String, String) =
@@ -125,88 +170,68 @@ fn sr_gk(_matches: &ArgMatches) -> Result<Vec<String>, String> {
 
 	let result = format!("({}, {})", private_key, public_key);
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn sr_sign(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let secret_key = get_alt_secret_key(matches)?;
+/// sr25519 sign, the input is the message (hex)
+pub fn sr_sign(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let secret_key = get_alt_secret_key(options)?;
 
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
+	let input: Vec<u8> = base::text(input)?
+		.parse::<Hex>()
+		.map_err(|_| Error::input(""))?
+		.into();
 
 	let sig = sr25519::sr_sign_sr25519(secret_key, input)?;
 
-	let result = Hex::from(sig).into();
+	let result: String = Hex::from(sig).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn sr_verify(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let public_key = matches.value_of("PUBLIC_KEY").ok_or("Invalid public key")?;
-	let public_key: Vec<u8> = public_key
+/// sr25519 verify, the input is the message (hex)
+pub fn sr_verify(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input: Vec<u8> = base::text(input)?
 		.parse::<Hex>()
-		.map_err(|_| "Invalid secret key")?
+		.map_err(|_| Error::input(""))?
 		.into();
 
-	let sig = matches.value_of("SIGNATURE").ok_or("Invalid signature")?;
-	let sig: Vec<u8> = sig.parse::<Hex>().map_err(|_| "Invalid signature")?.into();
-
-	let input = base::input_string(matches)?;
-	let input: Vec<u8> = input.parse::<Hex>().map_err(|_| "Invalid input")?.into();
-
-	sr25519::sr_verify_sr25519(public_key, sig, input)?;
+	sr25519::sr_verify_sr25519(options.public_key.clone(), options.signature.clone(), input)?;
 
 	let result = "true".to_string();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn sr_sk(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let mini_secret_key = matches
-		.value_of("MINI_SECRET_KEY")
-		.ok_or("Invalid mini secret key")?;
-	let mini_secret_key: Vec<u8> = mini_secret_key
-		.parse::<Hex>()
-		.map_err(|_| "Invalid mini secret key")?
-		.into();
+/// sr25519 calculate secret key from mini secret key, there is no input
+pub fn sr_sk(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let mini_secret_key = match &options.secret_key {
+		Some(AltSecretKey::MiniSecretKey(mini_secret_key)) => mini_secret_key.clone(),
+		_ => return Err(Error::invalid("mini secret key", "")),
+	};
 
 	let secret_key = sr25519::sr_sk_sr25519(mini_secret_key)?;
 
-	let result = Hex::from(secret_key).into();
+	let result: String = Hex::from(secret_key).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn sr_pk(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let secret_key = get_alt_secret_key(matches)?;
+/// sr25519 calculate public key, there is no input
+pub fn sr_pk(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let secret_key = get_alt_secret_key(options)?;
 
 	let public_key = sr25519::sr_pk_sr25519(secret_key)?;
 
-	let result = Hex::from(public_key).into();
+	let result: String = Hex::from(public_key).into();
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn get_alt_secret_key(matches: &ArgMatches) -> Result<AltSecretKey, String> {
-	if matches.is_present("MINI_SECRET_KEY") {
-		let secret_key = matches
-			.value_of("MINI_SECRET_KEY")
-			.ok_or("Invalid mini secret key")?;
-		let secret_key: Vec<u8> = secret_key
-			.parse::<Hex>()
-			.map_err(|_| "Invalid mini secret key")?
-			.into();
-		Ok(AltSecretKey::MiniSecretKey(secret_key))
-	} else if matches.is_present("SECRET_KEY") {
-		let secret_key = matches.value_of("SECRET_KEY").ok_or("Invalid secret key")?;
-		let secret_key: Vec<u8> = secret_key
-			.parse::<Hex>()
-			.map_err(|_| "Invalid secret key")?
-			.into();
-		Ok(AltSecretKey::SecretKey(secret_key))
-	} else {
-		Err("Mini secret key or secret key should be provided".to_string())
-	}
+fn get_alt_secret_key(options: &Options) -> Result<AltSecretKey, Error> {
+	options.secret_key.clone().ok_or(Error::failed(
+		"Mini secret key or secret key should be provided",
+	))
 }
 
 mod cases {
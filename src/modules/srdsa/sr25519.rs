@@ -1,10 +1,11 @@
+use crate::error::Error;
 use crate::modules::srdsa::AltSecretKey;
 use crate::modules::Case;
 use linked_hash_map::LinkedHashMap;
 use schnorrkel::{ExpansionMode, Keypair};
 use secp256k1::rand::thread_rng;
 
-pub fn sr_gk_sr25519() -> Result<(Vec<u8>, Vec<u8>), String> {
+pub fn sr_gk_sr25519() -> Result<(Vec<u8>, Vec<u8>), Error> {
 	let mini_secret_key = schnorrkel::MiniSecretKey::generate_with(&mut thread_rng());
 
 	let secret_key = mini_secret_key.expand(ExpansionMode::Ed25519);
@@ -16,7 +17,7 @@ pub fn sr_gk_sr25519() -> Result<(Vec<u8>, Vec<u8>), String> {
 	Ok((secret_key, public_key))
 }
 
-pub fn sr_sign_sr25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec<u8>, String> {
+pub fn sr_sign_sr25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let key_pair = get_key_pair(secret_key)?;
 
 	let signature = key_pair.sign_simple(&[], &message);
@@ -26,26 +27,23 @@ pub fn sr_sign_sr25519(secret_key: AltSecretKey, message: Vec<u8>) -> Result<Vec
 	Ok(signature)
 }
 
-pub fn sr_verify_sr25519(
-	public_key: Vec<u8>,
-	sig: Vec<u8>,
-	message: Vec<u8>,
-) -> Result<(), String> {
-	let public_key =
-		schnorrkel::PublicKey::from_bytes(&public_key).map_err(|_| "Invalid public key")?;
+pub fn sr_verify_sr25519(public_key: Vec<u8>, sig: Vec<u8>, message: Vec<u8>) -> Result<(), Error> {
+	let public_key = schnorrkel::PublicKey::from_bytes(&public_key)
+		.map_err(|_| Error::invalid("public key", ""))?;
 
-	let signature = schnorrkel::Signature::from_bytes(&sig).map_err(|_| "Invalid signature")?;
+	let signature =
+		schnorrkel::Signature::from_bytes(&sig).map_err(|_| Error::invalid("signature", ""))?;
 
-	let result = public_key
+	public_key
 		.verify_simple(&[], &message, &signature)
-		.map_err(|e| format!("Invalid signature: {}", e))?;
+		.map_err(|e| Error::invalid("signature", e))?;
 
-	Ok(result)
+	Ok(())
 }
 
-pub fn sr_sk_sr25519(mini_secret_key: Vec<u8>) -> Result<Vec<u8>, String> {
+pub fn sr_sk_sr25519(mini_secret_key: Vec<u8>) -> Result<Vec<u8>, Error> {
 	let mini_secret_key = schnorrkel::MiniSecretKey::from_bytes(&mini_secret_key)
-		.map_err(|_| "Invalid mini secret key")?;
+		.map_err(|_| Error::invalid("mini secret key", ""))?;
 	let key_pair = mini_secret_key.expand_to_keypair(ExpansionMode::Ed25519);
 
 	let secret_key = &key_pair.secret;
@@ -54,7 +52,7 @@ pub fn sr_sk_sr25519(mini_secret_key: Vec<u8>) -> Result<Vec<u8>, String> {
 	Ok(secret_key)
 }
 
-pub fn sr_pk_sr25519(secret_key: AltSecretKey) -> Result<Vec<u8>, String> {
+pub fn sr_pk_sr25519(secret_key: AltSecretKey) -> Result<Vec<u8>, Error> {
 	let key_pair = get_key_pair(secret_key)?;
 
 	let public_key = key_pair.public;
@@ -63,19 +61,19 @@ pub fn sr_pk_sr25519(secret_key: AltSecretKey) -> Result<Vec<u8>, String> {
 	Ok(public_key)
 }
 
-fn get_key_pair(secret_key: AltSecretKey) -> Result<Keypair, String> {
+fn get_key_pair(secret_key: AltSecretKey) -> Result<Keypair, Error> {
 	let key_pair = match secret_key {
 		AltSecretKey::MiniSecretKey(key) => {
 			let mini_secret_key = schnorrkel::MiniSecretKey::from_bytes(&key)
-				.map_err(|_| "Invalid mini secret key")?;
-			let key_pair = mini_secret_key.expand_to_keypair(ExpansionMode::Ed25519);
-			key_pair
+				.map_err(|_| Error::invalid("mini secret key", ""))?;
+
+			mini_secret_key.expand_to_keypair(ExpansionMode::Ed25519)
 		}
 		AltSecretKey::SecretKey(key) => {
-			let secret_key =
-				schnorrkel::SecretKey::from_bytes(&key).map_err(|_| "Invalid secret key")?;
-			let key_pair = secret_key.to_keypair();
-			key_pair
+			let secret_key = schnorrkel::SecretKey::from_bytes(&key)
+				.map_err(|_| Error::invalid("secret key", ""))?;
+
+			secret_key.to_keypair()
 		}
 	};
 	Ok(key_pair)
@@ -87,7 +85,7 @@ pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
          vec![
              Case {
                  desc: "".to_string(),
-                 input: Vec::<String>::new().into_iter().map(Into::into).collect(),
+                 input: Vec::<String>::new().into_iter().collect(),
                  output: vec!["(0xc243239f434f7a4b0ab8d4600537001e6479c807c3d3623f99c8ad9f2a588837, 0x6a8ee649b31efe7aabd8d5af58f85c60f12c48f8aa880cb50ae4cd57109e9d6c)"].into_iter().map(Into::into).collect(),
                  is_example: true,
                  is_test: false,
@@ -0,0 +1,168 @@
+use crate::error::Error;
+use crate::modules::base;
+use clap::ArgMatches;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Read, Write};
+use std::mem;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A conversion processing the input chunk by chunk with constant memory
+pub trait Stream {
+	/// Processes the next chunk of the input, returns the output of it
+	fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error>;
+
+	/// Called after the last chunk, returns the rest of the output
+	fn finish(&mut self) -> Result<Vec<u8>, Error>;
+}
+
+/// Whether the command can be run as a stream, i.e. the input is read from stdin or INPUT_FILE
+/// (not DTOOL_INPUT), and the output is written as is (not each line, JSON or raw)
+pub fn streamable(matches: &ArgMatches) -> bool {
+	!matches.is_present("INPUT")
+		&& !base::env_input(matches)
+		&& !matches.is_present("EACH_LINE")
+		&& !matches.is_present("RAW_OUTPUT")
+		&& matches.value_of("OUTPUT_FORMAT") != Some("json")
+}
+
+/// Runs the stream from INPUT_FILE (`-` for stdin) or stdin to OUTPUT_FILE or stdout
+pub fn run(mut stream: Box<dyn Stream>, matches: &ArgMatches) -> Result<(), Error> {
+	let mut input: Box<dyn Read> = match matches.value_of("INPUT_FILE") {
+		Some(file) if file != "-" => Box::new(File::open(file).map_err(|e| Error::io(file, e))?),
+		_ => Box::new(io::stdin()),
+	};
+	let output: Box<dyn Write> = match matches.value_of("OUTPUT_FILE") {
+		Some(file) => Box::new(File::create(file).map_err(|e| Error::io(file, e))?),
+		None if matches.is_present("QUIET") => Box::new(io::sink()),
+		None => Box::new(io::stdout()),
+	};
+	let mut output = BufWriter::new(output);
+	let path = matches.value_of("OUTPUT_FILE").unwrap_or("-");
+
+	let mut chunk = vec![0; CHUNK_SIZE];
+	let (mut read, mut written) = (0, 0);
+	loop {
+		let len = match input.read(&mut chunk) {
+			Ok(0) => break,
+			Ok(len) => len,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(Error::io(matches.value_of("INPUT_FILE").unwrap_or("-"), e)),
+		};
+		read += len;
+		let processed = stream.process(&chunk[..len])?;
+		written += processed.len();
+		output
+			.write_all(&processed)
+			.map_err(|e| Error::io(path, e))?;
+	}
+	let processed = stream.finish()?;
+	written += processed.len();
+	output
+		.write_all(&processed)
+		.map_err(|e| Error::io(path, e))?;
+	output.flush().map_err(|e| Error::io(path, e))?;
+
+	base::count_input(read);
+	base::verbose(matches, 1, || {
+		format!("stream: {} bytes read, {} bytes written", read, written)
+	});
+
+	Ok(())
+}
+
+/// Splits text input (e.g. hex, base64) into whole units of `unit` bytes,
+/// the optional prefix (e.g. `0x`) and a trailing newline are removed like `base::input_string` and `Hex`
+pub struct TextChunks {
+	prefix: &'static [u8],
+	unit: usize,
+	pending: Vec<u8>,
+	started: bool,
+	ended: bool,
+}
+
+impl TextChunks {
+	pub fn new(prefix: &'static [u8], unit: usize) -> Self {
+		Self {
+			prefix,
+			unit,
+			pending: vec![],
+			started: false,
+			ended: false,
+		}
+	}
+
+	/// The whole units of the input so far
+	pub fn next(&mut self, chunk: &[u8]) -> Result<Vec<u8>, Error> {
+		if self.ended {
+			return if chunk.iter().all(is_newline) {
+				Ok(vec![])
+			} else {
+				Err(Error::input(""))
+			};
+		}
+
+		self.pending.extend_from_slice(chunk);
+
+		if !self.started {
+			if self.pending.len() < self.prefix.len() {
+				return Ok(vec![]);
+			}
+			if self.pending.starts_with(self.prefix) {
+				self.pending.drain(..self.prefix.len());
+			}
+			self.started = true;
+		}
+
+		if let Some(end) = self.pending.iter().position(is_newline) {
+			if !self.pending[end..].iter().all(is_newline) {
+				return Err(Error::input(""));
+			}
+			self.pending.truncate(end);
+			self.ended = true;
+		}
+
+		let len = self.pending.len() - self.pending.len() % self.unit;
+		Ok(self.pending.drain(..len).collect())
+	}
+
+	/// The rest of the input, may be a partial unit
+	pub fn rest(&mut self) -> Vec<u8> {
+		if !self.started && self.pending == self.prefix {
+			self.pending.clear();
+		}
+		mem::take(&mut self.pending)
+	}
+}
+
+fn is_newline(x: &u8) -> bool {
+	*x == b'\n' || *x == b'\r'
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_text_chunks() {
+		let mut chunks = TextChunks::new(b"0x", 2);
+		assert_eq!(chunks.next(b"0").unwrap(), b"".to_vec());
+		assert_eq!(chunks.next(b"x616").unwrap(), b"61".to_vec());
+		assert_eq!(chunks.next(b"263\r").unwrap(), b"6263".to_vec());
+		assert_eq!(chunks.next(b"\n").unwrap(), b"".to_vec());
+		assert!(chunks.next(b"61").is_err());
+		assert_eq!(chunks.rest(), b"".to_vec());
+
+		let mut chunks = TextChunks::new(b"0x", 2);
+		assert_eq!(chunks.next(b"616").unwrap(), b"61".to_vec());
+		assert_eq!(chunks.rest(), b"6".to_vec());
+
+		let mut chunks = TextChunks::new(b"0x", 2);
+		assert_eq!(chunks.next(b"0x").unwrap(), b"".to_vec());
+		assert_eq!(chunks.rest(), b"".to_vec());
+
+		let mut chunks = TextChunks::new(b"", 4);
+		assert!(chunks.next(b"YWJj\nYWJj").is_err());
+	}
+}
@@ -0,0 +1,455 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Output limit of str_repeat and str_pad in bytes
+const MAX_LEN: usize = 64 * 1024 * 1024;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "String reverse, repeat, pad, substring".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("str_reverse")
+				.about("Reverse string (by grapheme clusters)")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, str_reverse),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("str_repeat")
+				.about("Repeat string")
+				.arg(
+					Arg::with_name("COUNT")
+						.long("count")
+						.short("n")
+						.help("Count")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("SEPARATOR")
+						.long("separator")
+						.short("s")
+						.help("Separator")
+						.takes_value(true)
+						.default_value("")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, str_repeat),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("str_pad")
+				.about("Pad string to width (display width)")
+				.arg(
+					Arg::with_name("WIDTH")
+						.long("width")
+						.short("w")
+						.help("Width in columns")
+						.takes_value(true)
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("CHAR")
+						.long("char")
+						.short("c")
+						.help("Padding char")
+						.takes_value(true)
+						.default_value(" ")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("SIDE")
+						.long("side")
+						.short("s")
+						.help("Side to pad")
+						.takes_value(true)
+						.possible_values(&["left", "right", "both"])
+						.default_value("left")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, str_pad),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("str_sub")
+				.about(
+					"Substring by char index (or byte index), negative index counts from the end",
+				)
+				.arg(
+					Arg::with_name("START")
+						.long("start")
+						.short("s")
+						.help("Start index (inclusive)")
+						.takes_value(true)
+						.allow_hyphen_values(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("END")
+						.long("end")
+						.short("e")
+						.help("End index (exclusive)")
+						.takes_value(true)
+						.allow_hyphen_values(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("BYTE")
+						.long("byte")
+						.short("b")
+						.help("Index by byte instead of char")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, str_sub),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the string commands
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Count of `str_repeat`
+	pub count: usize,
+	/// Separator of `str_repeat`
+	pub separator: String,
+	/// Width in columns of `str_pad`
+	pub width: usize,
+	/// Padding char of `str_pad`
+	pub pad_char: char,
+	pub side: Side,
+	/// Start index (inclusive) of `str_sub`, negative index counts from the end
+	pub start: Option<isize>,
+	/// End index (exclusive) of `str_sub`, negative index counts from the end
+	pub end: Option<isize>,
+	/// Index by byte instead of char
+	pub byte: bool,
+}
+
+/// Side to pad
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Side {
+	#[default]
+	Left,
+	Right,
+	Both,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			count: 1,
+			separator: String::new(),
+			width: 0,
+			pad_char: ' ',
+			side: Side::default(),
+			start: None,
+			end: None,
+			byte: false,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let default = Options::default();
+
+		let number = |name: &str| -> Result<Option<isize>, Error> {
+			matches
+				.value_of(name)
+				.map(|x| {
+					x.parse::<isize>()
+						.map_err(|_| Error::invalid(&name.to_lowercase(), ""))
+				})
+				.transpose()
+		};
+
+		let count = match matches.value_of("COUNT") {
+			Some(count) => count
+				.parse::<usize>()
+				.map_err(|_| Error::invalid("count", ""))?,
+			None => default.count,
+		};
+
+		let width = match matches.value_of("WIDTH") {
+			Some(width) => width
+				.parse::<usize>()
+				.map_err(|_| Error::invalid("width", ""))?,
+			None => default.width,
+		};
+
+		let pad_char = match matches.value_of("CHAR") {
+			Some(pad_char) => {
+				let mut chars = pad_char.chars();
+				match (chars.next(), chars.next()) {
+					(Some(c), None) => c,
+					_ => return Err(Error::invalid("char", "")),
+				}
+			}
+			None => default.pad_char,
+		};
+
+		let side = match matches.value_of("SIDE") {
+			Some("right") => Side::Right,
+			Some("both") => Side::Both,
+			_ => Side::Left,
+		};
+
+		Ok(Options {
+			count,
+			separator: matches
+				.value_of("SEPARATOR")
+				.map(String::from)
+				.unwrap_or(default.separator),
+			width,
+			pad_char,
+			side,
+			start: number("START")?,
+			end: number("END")?,
+			byte: matches.is_present("BYTE"),
+		})
+	}
+}
+
+/// Reverse string (by grapheme clusters)
+pub fn str_reverse(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = base::text(input)?.graphemes(true).rev().collect::<String>();
+
+	Ok(result.into_bytes())
+}
+
+/// Repeat string
+pub fn str_repeat(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let len = match options.count {
+		0 => Some(0),
+		count => input
+			.len()
+			.checked_mul(count)
+			.zip(options.separator.len().checked_mul(count - 1))
+			.and_then(|(x, y)| x.checked_add(y)),
+	};
+	check_len(len)?;
+
+	let result = vec![input; options.count].join(&options.separator);
+
+	Ok(result.into_bytes())
+}
+
+/// Pad string to width (display width)
+pub fn str_pad(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let len = options.width.saturating_sub(input.width());
+	let (left, right) = match options.side {
+		Side::Right => (0, len),
+		Side::Both => (len / 2, len - len / 2),
+		Side::Left => (len, 0),
+	};
+
+	check_len(
+		options
+			.pad_char
+			.len_utf8()
+			.checked_mul(len)
+			.and_then(|x| x.checked_add(input.len())),
+	)?;
+
+	let pad = |n: usize| options.pad_char.to_string().repeat(n);
+	let result = format!("{}{}{}", pad(left), input, pad(right));
+
+	Ok(result.into_bytes())
+}
+
+/// Substring by char index (or byte index), negative index counts from the end
+pub fn str_sub(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let (start, end) = (options.start, options.end);
+
+	let result = if options.byte {
+		let (start, end) = resolve(start, end, input.len());
+		let bytes = input.as_bytes()[start..end].to_vec();
+		String::from_utf8(bytes).map_err(|_| Error::invalid("index", "not at char boundary"))?
+	} else {
+		let chars = input.chars().collect::<Vec<_>>();
+		let (start, end) = resolve(start, end, chars.len());
+		chars[start..end].iter().collect::<String>()
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// The output length in bytes, none if overflowed
+fn check_len(len: Option<usize>) -> Result<(), Error> {
+	match len {
+		Some(len) if len <= MAX_LEN => Ok(()),
+		_ => Err(Error::invalid(
+			"output",
+			format!("longer than {} bytes", MAX_LEN),
+		)),
+	}
+}
+
+/// Negative index counts from the end, out of range index is clamped
+fn resolve(start: Option<isize>, end: Option<isize>, len: usize) -> (usize, usize) {
+	let resolve = |index: isize| {
+		if index < 0 {
+			len.saturating_sub(index.unsigned_abs())
+		} else {
+			(index as usize).min(len)
+		}
+	};
+	let start = start.map(resolve).unwrap_or(0);
+	let end = end.map(resolve).unwrap_or(len);
+	(start, end.max(start))
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"str_reverse",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'dtool e\u{301}🇨🇳'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["🇨🇳e\u{301} lootd"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"str_repeat",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-n", "3", "-s", ",", "ab"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["ab,ab,ab"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"str_pad",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-w", "5", "-c", "0", "42"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["00042"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-w", "8", "-c", "*", "-s", "both", "'你好'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["**你好**"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"str_sub",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-s", "1", "-e", "-1", "'你好世界'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["好世"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "By byte".to_string(),
+						input: vec!["-b", "-s", "3", "-e", "6", "'你好世界'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["好"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_len_limit() {
+		let repeat = |count: usize| {
+			let options = Options {
+				count,
+				..Options::default()
+			};
+			str_repeat(b"ab", &options)
+		};
+		assert!(repeat(usize::MAX).is_err());
+		assert!(repeat(99999999999).is_err());
+		assert_eq!(repeat(MAX_LEN / 2).unwrap().len(), MAX_LEN);
+		assert!(repeat(MAX_LEN / 2 + 1).is_err());
+
+		let pad = |width: usize| {
+			let options = Options {
+				width,
+				..Options::default()
+			};
+			str_pad(b"a", &options)
+		};
+		assert!(pad(usize::MAX).is_err());
+		assert_eq!(pad(MAX_LEN).unwrap().len(), MAX_LEN);
+		assert!(pad(MAX_LEN + 1).is_err());
+	}
+}
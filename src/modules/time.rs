@@ -1,7 +1,14 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
-use chrono::offset::TimeZone;
-use chrono::{DateTime, FixedOffset, Local, NaiveDateTime};
-use clap::{Arg, ArgMatches, SubCommand};
+use chrono::offset::{Offset, TimeZone};
+use chrono::{
+	DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use clap::{Arg, ArgMatches};
+use regex::Regex;
+use std::fmt::Display;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
@@ -14,12 +21,97 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 enum Time {
 	FixedOffset(DateTime<FixedOffset>),
 	Local(DateTime<Local>),
+	Tz(DateTime<Tz>),
 }
 
+const FORMAT_HELP: &str = "Output format\n\
+	default: 2019-12-23 17:48:54\n\
+	rfc3339: 2019-12-23T17:48:54+08:00\n\
+	rfc2822: Mon, 23 Dec 2019 17:48:54 +0800\n\
+	iso8601: 2019-12-23T17:48:54+08:00\n\
+	iso8601_week: 2019-W52-1T17:48:54+08:00\n\
+	iso8601_ordinal: 2019-357T17:48:54+08:00\n\
+	clf: 23/Dec/2019:17:48:54 +0800\n\
+	unix: 1577094534";
+
+const FORMATS: [&str; 8] = [
+	"default",
+	"rfc3339",
+	"rfc2822",
+	"iso8601",
+	"iso8601_week",
+	"iso8601_ordinal",
+	"clf",
+	"unix",
+];
+
+/// Formats carrying an offset
+const OFFSET_FORMATS: [&str; 7] = [
+	"%Y-%m-%dT%H:%M:%S%.f%#z",
+	"%Y%m%dT%H%M%S%.f%#z",
+	"%G-W%V-%uT%H:%M:%S%.f%#z",
+	"%GW%V%uT%H%M%S%.f%#z",
+	"%Y-%jT%H:%M:%S%.f%#z",
+	"%Y%jT%H%M%S%.f%#z",
+	"%d/%b/%Y:%H:%M:%S %z",
+];
+
+/// Formats without offset, the time zone is given by -z or --tz
+const NAIVE_DATE_TIME_FORMATS: [&str; 4] = [
+	"%Y-%m-%dT%H:%M:%S%.f",
+	"%G-W%V-%uT%H:%M:%S%.f",
+	"%Y-%jT%H:%M:%S%.f",
+	"%d/%b/%Y:%H:%M:%S",
+];
+
+const NAIVE_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%G-W%V-%u", "%Y-%j"];
+
+const DIFF_UNITS: [&str; 6] = [
+	"seconds",
+	"minutes",
+	"hours",
+	"days",
+	"weeks",
+	"business_days",
+];
+
+const EPOCH_HELP: &str = "Epoch\n\
+	filetime: Windows FILETIME, 100ns since 1601-01-01\n\
+	apple: Apple Cocoa / Core Data, seconds since 2001-01-01\n\
+	hfs: Mac HFS+, seconds since 1904-01-01\n\
+	julian: Julian day\n\
+	mjd: Modified Julian day";
+
+const EPOCHS: [&str; 5] = ["filetime", "apple", "hfs", "julian", "mjd"];
+
+/// Seconds from 1601-01-01 to 1970-01-01
+const FILETIME_OFFSET: i64 = 11_644_473_600;
+/// Seconds from 1970-01-01 to 2001-01-01
+const APPLE_OFFSET: i64 = 978_307_200;
+/// Seconds from 1904-01-01 to 1970-01-01
+const HFS_OFFSET: i64 = 2_082_844_800;
+/// Julian day of 1970-01-01 00:00:00
+const JULIAN_DAY_OFFSET: f64 = 2_440_587.5;
+/// Modified Julian day of 1970-01-01 00:00:00
+const MJD_OFFSET: f64 = 40_587.0;
+
+const NOW_FORMATS: [&str; 5] = ["unix", "unix_ms", "rfc3339", "local", "iso8601_week"];
+
+/// Units of relative time, a month is 30 days and a year is 365 days
+const RELATIVE_UNITS: [(&str, i64); 7] = [
+	("year", 365 * 86400),
+	("month", 30 * 86400),
+	("week", 7 * 86400),
+	("day", 86400),
+	("hour", 3600),
+	("minute", 60),
+	("second", 1),
+];
+
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("ts2d")
+			app: base::sub_command("ts2d")
 				.about("Convert timestamp to date")
 				.arg(
 					Arg::with_name("TIMEZONE")
@@ -29,11 +121,30 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				)
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: ts2d,
+				.arg(
+					Arg::with_name("TZ")
+						.long("tz")
+						.help("IANA time zone name\nAsia/Shanghai\nEurope/London\nUTC\netc")
+						.takes_value(true)
+						.conflicts_with("TIMEZONE")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("FORMAT")
+						.long("date-format")
+						.short("F")
+						.help(FORMAT_HELP)
+						.takes_value(true)
+						.possible_values(&FORMATS)
+						.default_value("default")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ts2d),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("d2ts")
+			app: base::sub_command("d2ts")
 				.about("Convert date to timestamp")
 				.arg(
 					Arg::with_name("TIMEZONE")
@@ -43,90 +154,920 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 						.takes_value(true)
 						.required(false),
 				)
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: d2ts,
+				.arg(
+					Arg::with_name("TZ")
+						.long("tz")
+						.help("IANA time zone name\nAsia/Shanghai\nEurope/London\nUTC\netc")
+						.takes_value(true)
+						.conflicts_with("TIMEZONE")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, d2ts),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("d2d")
+				.about("Convert date format")
+				.arg(
+					Arg::with_name("TIMEZONE")
+						.long("timezone")
+						.short("z")
+						.help("Time zone\n8: CN\n0: UK\netc")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("TZ")
+						.long("tz")
+						.help("IANA time zone name\nAsia/Shanghai\nEurope/London\nUTC\netc")
+						.takes_value(true)
+						.conflicts_with("TIMEZONE")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("FORMAT")
+						.long("date-format")
+						.short("F")
+						.help(FORMAT_HELP)
+						.takes_value(true)
+						.possible_values(&FORMATS)
+						.default_value("rfc3339")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, d2d),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ts2r")
+				.about("Convert timestamp to relative time")
+				.arg(
+					Arg::with_name("NOW")
+						.long("now")
+						.short("n")
+						.help("Reference timestamp (default: current time)")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ts2r),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("r2ts")
+				.about("Convert relative time to timestamp")
+				.arg(
+					Arg::with_name("NOW")
+						.long("now")
+						.short("n")
+						.help("Reference timestamp (default: current time)")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, r2ts),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("d2w")
+				.about("Convert date to ISO week date, ordinal date and weekday")
+				.arg(
+					Arg::with_name("TIMEZONE")
+						.long("timezone")
+						.short("z")
+						.help("Time zone\n8: CN\n0: UK\netc")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("TZ")
+						.long("tz")
+						.help("IANA time zone name\nAsia/Shanghai\nEurope/London\nUTC\netc")
+						.takes_value(true)
+						.conflicts_with("TIMEZONE")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("WEEK")
+						.short("w")
+						.help("Output ISO week date")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("ORDINAL")
+						.short("y")
+						.help("Output ordinal date (day of year)")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("WEEKDAY")
+						.short("d")
+						.help("Output weekday")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, d2w),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("w2d")
+				.about("Convert ISO week date or ordinal date to date")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, w2d),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("tdiff")
+				.about("Calculate the difference between two times")
+				.arg(
+					Arg::with_name("TIMEZONE")
+						.long("timezone")
+						.short("z")
+						.help("Time zone\n8: CN\n0: UK\netc")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("TZ")
+						.long("tz")
+						.help("IANA time zone name\nAsia/Shanghai\nEurope/London\nUTC\netc")
+						.takes_value(true)
+						.conflicts_with("TIMEZONE")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("UNIT")
+						.long("unit")
+						.short("u")
+						.help("Output only the given unit")
+						.takes_value(true)
+						.possible_values(&DIFF_UNITS)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("INPUT")
+						.help("Start time")
+						.required(false)
+						.index(1),
+				)
+				.args(&base::file_args())
+				.arg(
+					Arg::with_name("END")
+						.help("End time (default: current time)")
+						.required(false)
+						.index(2),
+				),
+			f: |matches| base::convert(matches, tdiff),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ts2e")
+				.about("Convert timestamp to other epoch")
+				.arg(
+					Arg::with_name("EPOCH")
+						.long("epoch")
+						.short("e")
+						.help(EPOCH_HELP)
+						.takes_value(true)
+						.possible_values(&EPOCHS)
+						.required(true),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ts2e),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("e2ts")
+				.about("Convert other epoch to timestamp")
+				.arg(
+					Arg::with_name("EPOCH")
+						.long("epoch")
+						.short("e")
+						.help(EPOCH_HELP)
+						.takes_value(true)
+						.possible_values(&EPOCHS)
+						.required(true),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, e2ts),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("now").about("Show current time").arg(
+				Arg::with_name("FORMAT")
+					.long("date-format")
+					.short("F")
+					.help("Output only the given format")
+					.takes_value(true)
+					.possible_values(&NOW_FORMATS)
+					.required(false),
+			),
+			f: |matches| base::generate(matches, now),
+			stream: None,
 		},
 	]
 }
 
-fn ts2d(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// Options of the time commands, each reads the ones it needs
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	/// Time zone of the input without offset, and of the output
+	pub zone: Zone,
+	/// Output format of ts2d and d2d
+	pub format: Format,
+	/// Reference timestamp of ts2r and r2ts, the current time if not given
+	pub now: Option<i64>,
+	/// Output of d2w, all if none is set
+	pub week: bool,
+	pub ordinal: bool,
+	pub weekday: bool,
+	/// End time of tdiff, the current time if not given
+	pub end: Option<String>,
+	/// Output only the given unit of tdiff
+	pub unit: Option<DiffUnit>,
+	/// Epoch of ts2e and e2ts
+	pub epoch: Option<Epoch>,
+	/// Output only the given format of now
+	pub now_format: Option<NowFormat>,
+}
 
-	let timestamp: i64 = input.parse().map_err(|_| "Invalid input")?;
+/// Time zone
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Zone {
+	#[default]
+	Local,
+	/// e.g. `+08:00`, whole hours from -12 to 12 on the command line
+	Offset(FixedOffset),
+	/// IANA time zone, e.g. `Asia/Shanghai`
+	Tz(Tz),
+}
 
-	let timezone = matches.value_of("TIMEZONE");
+/// Date format
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	/// `2019-12-23 17:48:54`
+	#[default]
+	Standard,
+	/// `2019-12-23T17:48:54+08:00`
+	Rfc3339,
+	/// `Mon, 23 Dec 2019 17:48:54 +0800`
+	Rfc2822,
+	/// `2019-12-23T17:48:54+08:00`
+	Iso8601,
+	/// `2019-W52-1T17:48:54+08:00`
+	Iso8601Week,
+	/// `2019-357T17:48:54+08:00`
+	Iso8601Ordinal,
+	/// `23/Dec/2019:17:48:54 +0800`
+	Clf,
+	/// `1577094534`
+	Unix,
+}
 
-	let result = match timezone {
-		Some(timezone) => {
-			let timezone: i32 = timezone.parse().map_err(|_| "Invalid input")?;
-			if timezone > 12 || timezone < -12 {
-				return Err("Invalid timezone".to_string());
-			}
-			FixedOffset::east(timezone * 3600)
-				.timestamp(timestamp, 0)
-				.format("%Y-%m-%d %H:%M:%S")
-				.to_string()
+/// Unit of the difference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffUnit {
+	Seconds,
+	Minutes,
+	Hours,
+	Days,
+	Weeks,
+	BusinessDays,
+}
+
+/// Other epoch than the Unix one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Epoch {
+	/// Windows FILETIME, 100ns since 1601-01-01
+	Filetime,
+	/// Apple Cocoa / Core Data, seconds since 2001-01-01
+	Apple,
+	/// Mac HFS+, seconds since 1904-01-01
+	Hfs,
+	/// Julian day
+	Julian,
+	/// Modified Julian day
+	Mjd,
+}
+
+/// Format of the current time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NowFormat {
+	Unix,
+	UnixMs,
+	Rfc3339,
+	Local,
+	Iso8601Week,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let zone = match (matches.value_of("TIMEZONE"), matches.value_of("TZ")) {
+			(_, Some(tz)) => Zone::Tz(parse_tz(tz)?),
+			(Some(timezone), None) => Zone::Offset(parse_timezone(timezone)?),
+			(None, None) => Zone::Local,
+		};
+		// FORMAT of now has its own values
+		let format = match matches.value_of("FORMAT") {
+			Some("rfc3339") => Format::Rfc3339,
+			Some("rfc2822") => Format::Rfc2822,
+			Some("iso8601") => Format::Iso8601,
+			Some("iso8601_week") => Format::Iso8601Week,
+			Some("iso8601_ordinal") => Format::Iso8601Ordinal,
+			Some("clf") => Format::Clf,
+			Some("unix") => Format::Unix,
+			_ => Format::Standard,
+		};
+		let now_format = match matches.value_of("FORMAT") {
+			Some("unix") => Some(NowFormat::Unix),
+			Some("unix_ms") => Some(NowFormat::UnixMs),
+			Some("rfc3339") => Some(NowFormat::Rfc3339),
+			Some("local") => Some(NowFormat::Local),
+			Some("iso8601_week") => Some(NowFormat::Iso8601Week),
+			_ => None,
+		};
+		let now = match matches.value_of("NOW") {
+			Some(now) => Some(now.parse().map_err(|_| Error::invalid("now", ""))?),
+			None => None,
+		};
+		let unit = match matches.value_of("UNIT") {
+			Some("seconds") => Some(DiffUnit::Seconds),
+			Some("minutes") => Some(DiffUnit::Minutes),
+			Some("hours") => Some(DiffUnit::Hours),
+			Some("days") => Some(DiffUnit::Days),
+			Some("weeks") => Some(DiffUnit::Weeks),
+			Some("business_days") => Some(DiffUnit::BusinessDays),
+			_ => None,
+		};
+		let epoch = match matches.value_of("EPOCH") {
+			Some("filetime") => Some(Epoch::Filetime),
+			Some("apple") => Some(Epoch::Apple),
+			Some("hfs") => Some(Epoch::Hfs),
+			Some("julian") => Some(Epoch::Julian),
+			Some("mjd") => Some(Epoch::Mjd),
+			_ => None,
+		};
+		Ok(Options {
+			zone,
+			format,
+			now,
+			week: matches.is_present("WEEK"),
+			ordinal: matches.is_present("ORDINAL"),
+			weekday: matches.is_present("WEEKDAY"),
+			end: matches.value_of("END").map(String::from),
+			unit,
+			epoch,
+			now_format,
+		})
+	}
+}
+
+/// Convert timestamp to date
+pub fn ts2d(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let timestamp: i64 = base::text(input)?.parse().map_err(|_| Error::input(""))?;
+
+	let out_of_range = || Error::invalid("time", format!("{}: out of range", timestamp));
+	let result = match options.zone {
+		Zone::Tz(tz) => Time::Tz(
+			tz.timestamp_opt(timestamp, 0)
+				.single()
+				.ok_or_else(out_of_range)?,
+		),
+		Zone::Offset(offset) => Time::FixedOffset(
+			offset
+				.timestamp_opt(timestamp, 0)
+				.single()
+				.ok_or_else(out_of_range)?,
+		),
+		Zone::Local => Time::Local(
+			Local
+				.timestamp_opt(timestamp, 0)
+				.single()
+				.ok_or_else(out_of_range)?,
+		),
+	};
+
+	let result = format_time(&result, options.format);
+
+	Ok(result.into_bytes())
+}
+
+/// Convert date to timestamp
+pub fn d2ts(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let result = parse_time(base::text(input)?, &options.zone)?;
+
+	let result = format_time(&result, Format::Unix);
+
+	Ok(result.into_bytes())
+}
+
+/// Convert date format
+pub fn d2d(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let result = parse_time(base::text(input)?, &options.zone)?;
+
+	let result = to_zone(result, &options.zone);
+
+	let result = format_time(&result, options.format);
+
+	Ok(result.into_bytes())
+}
+
+/// Convert timestamp to relative time
+pub fn ts2r(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let timestamp: i64 = base::text(input)?.parse().map_err(|_| Error::input(""))?;
+
+	let now = get_now(options);
+
+	let diff = timestamp
+		.checked_sub(now)
+		.ok_or_else(|| Error::invalid("time", format!("{}: out of range", timestamp)))?;
+
+	let result = format_relative(diff);
+
+	Ok(result.into_bytes())
+}
+
+/// Convert relative time to timestamp
+pub fn r2ts(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let now = get_now(options);
+
+	let result = now
+		.checked_add(parse_relative(base::text(input)?)?)
+		.ok_or(Error::invalid("time", "out of range"))?;
+
+	let result = format!("{}", result);
+
+	Ok(result.into_bytes())
+}
+
+/// Convert date to ISO week date, ordinal date and weekday
+pub fn d2w(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let time = parse_time(base::text(input)?, &options.zone)?;
+
+	let date = match to_zone(time, &options.zone) {
+		Time::FixedOffset(time) => time.naive_local().date(),
+		Time::Local(time) => time.naive_local().date(),
+		Time::Tz(time) => time.naive_local().date(),
+	};
+
+	let (week, ordinal, weekday) = (options.week, options.ordinal, options.weekday);
+	let all = !week && !ordinal && !weekday;
+
+	let mut result = vec![];
+	if all || week {
+		result.push(date.format("%G-W%V-%u").to_string());
+	}
+	if all || ordinal {
+		result.push(date.format("%Y-%j").to_string());
+	}
+	if all || weekday {
+		result.push(date.format("%A").to_string());
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert ISO week date or ordinal date to date
+pub fn w2d(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let week_re = Regex::new(r"^(\d{4})-?W(\d{2})(-?([1-7]))?$").expect("qed");
+	let ordinal_re = Regex::new(r"^(\d{4})-?(\d{3})$").expect("qed");
+
+	let date = if let Some(c) = week_re.captures(input.trim()) {
+		let year: i32 = c[1].parse().map_err(|_| Error::invalid("year", ""))?;
+		let week: u32 = c[2].parse().map_err(|_| Error::invalid("week", ""))?;
+		let weekday = match c.get(4).map(|x| x.as_str()) {
+			Some("2") => Weekday::Tue,
+			Some("3") => Weekday::Wed,
+			Some("4") => Weekday::Thu,
+			Some("5") => Weekday::Fri,
+			Some("6") => Weekday::Sat,
+			Some("7") => Weekday::Sun,
+			_ => Weekday::Mon,
+		};
+		NaiveDate::from_isoywd_opt(year, week, weekday).ok_or(Error::invalid("week", ""))?
+	} else if let Some(c) = ordinal_re.captures(input.trim()) {
+		let year: i32 = c[1].parse().map_err(|_| Error::invalid("year", ""))?;
+		let ordinal: u32 = c[2]
+			.parse()
+			.map_err(|_| Error::invalid("day of year", ""))?;
+		NaiveDate::from_yo_opt(year, ordinal).ok_or(Error::invalid("day of year", ""))?
+	} else {
+		return Err(Error::input(""));
+	};
+
+	let result = date.format("%Y-%m-%d").to_string();
+
+	Ok(result.into_bytes())
+}
+
+/// Calculate the difference between two times, the input is the start time
+pub fn tdiff(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let zone = &options.zone;
+
+	let start = to_fixed_offset(parse_time(base::text(input)?, zone)?);
+
+	let end = match &options.end {
+		Some(end) => to_fixed_offset(parse_time(end, zone)?),
+		None => Utc::now().with_timezone(start.offset()),
+	};
+
+	let seconds = end.timestamp() - start.timestamp();
+
+	let business_days = business_days(
+		start.naive_local().date(),
+		end.with_timezone(start.offset()).naive_local().date(),
+	);
+
+	let units = vec![
+		(DiffUnit::Seconds, "seconds", seconds),
+		(DiffUnit::Minutes, "minutes", seconds / 60),
+		(DiffUnit::Hours, "hours", seconds / 3600),
+		(DiffUnit::Days, "days", seconds / 86400),
+		(DiffUnit::Weeks, "weeks", seconds / (7 * 86400)),
+		(DiffUnit::BusinessDays, "business_days", business_days),
+	];
+
+	let result = match options.unit {
+		Some(unit) => units
+			.into_iter()
+			.filter(|(x, _, _)| *x == unit)
+			.map(|(_, _, value)| format!("{}", value))
+			.collect::<Vec<_>>(),
+		None => units
+			.into_iter()
+			.map(|(_, name, value)| format!("{}: {}", name, value))
+			.collect(),
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Count the weekdays (Monday to Friday) in [start, end)
+fn business_days(start: NaiveDate, end: NaiveDate) -> i64 {
+	if end < start {
+		return -business_days(end, start);
+	}
+
+	let days = (end - start).num_days();
+	let mut result = days / 7 * 5;
+	let mut date = start + chrono::Duration::days(days / 7 * 7);
+	while date < end {
+		if date.weekday() != Weekday::Sat && date.weekday() != Weekday::Sun {
+			result += 1;
 		}
-		None => Local
-			.timestamp(timestamp, 0)
-			.format("%Y-%m-%d %H:%M:%S")
+		date = date.succ();
+	}
+	result
+}
+
+/// Convert timestamp to other epoch
+pub fn ts2e(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let timestamp: i64 = base::text(input)?.parse().map_err(|_| Error::input(""))?;
+
+	let epoch = options.epoch.ok_or(Error::invalid("epoch", ""))?;
+
+	let out_of_range = || Error::invalid("time", format!("{}: out of range", timestamp));
+	let result = match epoch {
+		Epoch::Filetime => timestamp
+			.checked_add(FILETIME_OFFSET)
+			.and_then(|x| x.checked_mul(10_000_000))
+			.ok_or_else(out_of_range)?
+			.to_string(),
+		Epoch::Apple => timestamp
+			.checked_sub(APPLE_OFFSET)
+			.ok_or_else(out_of_range)?
 			.to_string(),
+		Epoch::Hfs => timestamp
+			.checked_add(HFS_OFFSET)
+			.ok_or_else(out_of_range)?
+			.to_string(),
+		Epoch::Julian => (timestamp as f64 / 86400.0 + JULIAN_DAY_OFFSET).to_string(),
+		Epoch::Mjd => (timestamp as f64 / 86400.0 + MJD_OFFSET).to_string(),
 	};
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn d2ts(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
-
-	let timezone = matches.value_of("TIMEZONE");
+/// Convert other epoch to timestamp
+pub fn e2ts(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
-	let result = parse_standard(&input, timezone)
-		.or_else(|_| parse_rfc2822(&input))
-		.or_else(|_| parse_rfc3339(&input))?;
+	let epoch = options.epoch.ok_or(Error::invalid("epoch", ""))?;
 
-	let result = match result {
-		Time::FixedOffset(time) => time.timestamp(),
-		Time::Local(time) => time.timestamp(),
+	let out_of_range = || Error::invalid("time", format!("{}: out of range", input.trim()));
+	let result = match epoch {
+		Epoch::Filetime => parse_epoch_integer(input)?
+			.div_euclid(10_000_000)
+			.checked_sub(FILETIME_OFFSET)
+			.ok_or_else(out_of_range)?,
+		Epoch::Apple => parse_epoch_integer(input)?
+			.checked_add(APPLE_OFFSET)
+			.ok_or_else(out_of_range)?,
+		Epoch::Hfs => parse_epoch_integer(input)?
+			.checked_sub(HFS_OFFSET)
+			.ok_or_else(out_of_range)?,
+		Epoch::Julian => parse_epoch_days(input, JULIAN_DAY_OFFSET)?,
+		Epoch::Mjd => parse_epoch_days(input, MJD_OFFSET)?,
 	};
 
 	let result = format!("{}", result);
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn parse_standard(input: &str, timezone: Option<&str>) -> Result<Time, String> {
-	let time =
-		NaiveDateTime::parse_from_str(&input, "%Y-%m-%d %H:%M:%S").map_err(|_| "Invalid input")?;
+/// Parse decimal or hex (0x prefixed) integer
+fn parse_epoch_integer(input: &str) -> Result<i64, Error> {
+	let input = input.trim();
+	let result = if input.starts_with("0x") {
+		i64::from_str_radix(input.trim_start_matches("0x"), 16)
+	} else {
+		input.parse()
+	};
+	result.map_err(|_| Error::input(""))
+}
+
+/// Out of the range of i64 seconds is an error, instead of the saturated cast
+fn parse_epoch_days(input: &str, offset: f64) -> Result<i64, Error> {
+	let days: f64 = input.trim().parse().map_err(|_| Error::input(""))?;
+	let seconds = ((days - offset) * 86400.0).round();
+	if !(seconds >= i64::MIN as f64 && seconds < i64::MAX as f64) {
+		return Err(Error::invalid(
+			"time",
+			format!("{}: out of range", input.trim()),
+		));
+	}
+	Ok(seconds as i64)
+}
+
+/// Show current time, there is no input
+pub fn now(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let now = Utc::now();
+	let local = now.with_timezone(&Local);
+
+	let formats = vec![
+		(NowFormat::Unix, "unix", now.timestamp().to_string()),
+		(
+			NowFormat::UnixMs,
+			"unix_ms",
+			now.timestamp_millis().to_string(),
+		),
+		(
+			NowFormat::Rfc3339,
+			"rfc3339",
+			now.to_rfc3339_opts(SecondsFormat::Secs, true),
+		),
+		(
+			NowFormat::Local,
+			"local",
+			local.format("%Y-%m-%d %H:%M:%S").to_string(),
+		),
+		(
+			NowFormat::Iso8601Week,
+			"iso8601_week",
+			local.format("%G-W%V-%u").to_string(),
+		),
+	];
+
+	let result = match options.now_format {
+		Some(format) => formats
+			.into_iter()
+			.filter(|(x, _, _)| *x == format)
+			.map(|(_, _, value)| value)
+			.collect::<Vec<_>>(),
+		None => formats
+			.into_iter()
+			.map(|(_, name, value)| format!("{}: {}", name, value))
+			.collect(),
+	};
 
-	let result = match timezone {
-		Some(timezone) => {
-			let timezone: i32 = timezone.parse().map_err(|_| "Invalid input")?;
-			if timezone > 12 || timezone < -12 {
-				return Err("Invalid timezone".to_string());
+	Ok(result.join("\n").into_bytes())
+}
+
+fn get_now(options: &Options) -> i64 {
+	options.now.unwrap_or_else(|| Utc::now().timestamp())
+}
+
+fn format_relative(diff: i64) -> String {
+	let abs = diff.unsigned_abs();
+
+	let unit = RELATIVE_UNITS
+		.iter()
+		.find(|(_, seconds)| abs >= *seconds as u64);
+
+	match unit {
+		Some((name, seconds)) => {
+			let n = abs / *seconds as u64;
+			let unit = if n == 1 {
+				name.to_string()
+			} else {
+				format!("{}s", name)
+			};
+			if diff < 0 {
+				format!("{} {} ago", n, unit)
+			} else {
+				format!("in {} {}", n, unit)
 			}
-			Time::FixedOffset(
-				FixedOffset::east(timezone * 3600)
-					.from_local_datetime(&time)
-					.unwrap(),
-			)
 		}
-		None => Time::Local(Local.from_local_datetime(&time).unwrap()),
+		None => "now".to_string(),
+	}
+}
+
+/// Parse phrases like "3 days ago", "in 2 hours", "1h 30m ago", "yesterday"
+fn parse_relative(input: &str) -> Result<i64, Error> {
+	let input = input.trim().to_lowercase();
+
+	match input.as_str() {
+		"now" => return Ok(0),
+		"yesterday" => return Ok(-86400),
+		"tomorrow" => return Ok(86400),
+		_ => (),
+	}
+
+	let (sign, input) = if input.ends_with(" ago") {
+		(-1, input.trim_end_matches(" ago"))
+	} else if input.starts_with("in ") {
+		(1, input.trim_start_matches("in "))
+	} else {
+		return Err(Error::input(""));
 	};
 
-	Ok(result)
+	let phrase_re = Regex::new(r"^(\s*\d+\s*[a-z]+\s*(,|and)?)+$").expect("qed");
+	if !phrase_re.is_match(input) {
+		return Err(Error::input(""));
+	}
+
+	let item_re = Regex::new(r"(\d+)\s*([a-z]+)").expect("qed");
+	let out_of_range = || Error::invalid("time", format!("{}: out of range", input));
+	let mut result = 0i64;
+	for c in item_re.captures_iter(input) {
+		let n: i64 = c[1].parse().map_err(|_| out_of_range())?;
+		let seconds = relative_unit_seconds(&c[2]).ok_or(Error::invalid("unit", ""))?;
+		result = n
+			.checked_mul(seconds)
+			.and_then(|x| result.checked_add(x))
+			.ok_or_else(out_of_range)?;
+	}
+
+	Ok(sign * result)
+}
+
+fn relative_unit_seconds(unit: &str) -> Option<i64> {
+	let name = match unit {
+		"y" | "yr" | "yrs" => "year",
+		"mo" | "mon" | "mons" => "month",
+		"w" | "wk" | "wks" => "week",
+		"d" => "day",
+		"h" | "hr" | "hrs" => "hour",
+		"m" | "min" | "mins" => "minute",
+		"s" | "sec" | "secs" => "second",
+		unit => unit.trim_end_matches('s'),
+	};
+	RELATIVE_UNITS
+		.iter()
+		.find(|(x, _)| *x == name)
+		.map(|(_, seconds)| *seconds)
+}
+
+fn parse_time(input: &str, zone: &Zone) -> Result<Time, Error> {
+	parse_standard(input, zone)
+		.or_else(|_| parse_rfc2822(input))
+		.or_else(|_| parse_rfc3339(input))
+		.or_else(|_| parse_iso8601(input, zone))
+		.or_else(|_| parse_unix(input))
+}
+
+fn parse_standard(input: &str, zone: &Zone) -> Result<Time, Error> {
+	let time =
+		NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S").map_err(|_| Error::input(""))?;
+
+	from_naive(time, zone)
 }
 
-fn parse_rfc2822(input: &str) -> Result<Time, String> {
+fn parse_rfc2822(input: &str) -> Result<Time, Error> {
 	DateTime::parse_from_rfc2822(input)
 		.map(Time::FixedOffset)
-		.map_err(|_| "Invalid input".to_string())
+		.map_err(|_| Error::input(""))
 }
 
-fn parse_rfc3339(input: &str) -> Result<Time, String> {
+fn parse_rfc3339(input: &str) -> Result<Time, Error> {
 	DateTime::parse_from_rfc3339(input)
 		.map(Time::FixedOffset)
-		.map_err(|_| "Invalid input".to_string())
+		.map_err(|_| Error::input(""))
+}
+
+fn parse_unix(input: &str) -> Result<Time, Error> {
+	let timestamp: i64 = input.parse().map_err(|_| Error::input(""))?;
+	let time = Utc
+		.timestamp_opt(timestamp, 0)
+		.single()
+		.ok_or_else(|| Error::invalid("time", format!("{}: out of range", timestamp)))?;
+	Ok(Time::FixedOffset(time.into()))
+}
+
+/// Parse ISO 8601 (calendar, week and ordinal dates) and common log format
+fn parse_iso8601(input: &str, zone: &Zone) -> Result<Time, Error> {
+	if let Some(time) = OFFSET_FORMATS
+		.iter()
+		.find_map(|format| DateTime::parse_from_str(input, format).ok())
+	{
+		return Ok(Time::FixedOffset(time));
+	}
+
+	let time = NAIVE_DATE_TIME_FORMATS
+		.iter()
+		.find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+		.or_else(|| {
+			NAIVE_DATE_FORMATS
+				.iter()
+				.find_map(|format| NaiveDate::parse_from_str(input, format).ok())
+				.map(|date| date.and_hms(0, 0, 0))
+		})
+		.ok_or(Error::input(""))?;
+
+	from_naive(time, zone)
+}
+
+fn from_naive(time: NaiveDateTime, zone: &Zone) -> Result<Time, Error> {
+	let result = match zone {
+		Zone::Tz(tz) => Time::Tz(
+			tz.from_local_datetime(&time)
+				.earliest()
+				.ok_or(Error::invalid("time in time zone", ""))?,
+		),
+		Zone::Offset(offset) => Time::FixedOffset(
+			offset
+				.from_local_datetime(&time)
+				.single()
+				.ok_or(Error::invalid("time in time zone", ""))?,
+		),
+		Zone::Local => Time::Local(
+			Local
+				.from_local_datetime(&time)
+				.earliest()
+				.ok_or(Error::invalid("time in time zone", ""))?,
+		),
+	};
+
+	Ok(result)
+}
+
+/// Convert the time with an offset to the time zone given by -z or --tz
+fn to_zone(time: Time, zone: &Zone) -> Time {
+	match (zone, time) {
+		(Zone::Tz(tz), Time::FixedOffset(time)) => Time::Tz(time.with_timezone(tz)),
+		(Zone::Offset(offset), Time::FixedOffset(time)) => {
+			Time::FixedOffset(time.with_timezone(offset))
+		}
+		(_, time) => time,
+	}
+}
+
+fn parse_timezone(timezone: &str) -> Result<FixedOffset, Error> {
+	let timezone: i32 = timezone.parse().map_err(|_| Error::input(""))?;
+	if !(-12..=12).contains(&timezone) {
+		return Err(Error::invalid("timezone", ""));
+	}
+	Ok(FixedOffset::east(timezone * 3600))
+}
+
+fn parse_tz(tz: &str) -> Result<Tz, Error> {
+	tz.parse::<Tz>()
+		.map_err(|_| Error::invalid("time zone", ""))
+}
+
+fn to_fixed_offset(time: Time) -> DateTime<FixedOffset> {
+	match time {
+		Time::FixedOffset(time) => time,
+		Time::Local(time) => time.with_timezone(&time.offset().fix()),
+		Time::Tz(time) => time.with_timezone(&time.offset().fix()),
+	}
+}
+
+fn format_time(time: &Time, format: Format) -> String {
+	match time {
+		Time::FixedOffset(time) => format_date_time(time, format),
+		Time::Local(time) => format_date_time(time, format),
+		Time::Tz(time) => format_date_time(time, format),
+	}
+}
+
+fn format_date_time<T: TimeZone>(time: &DateTime<T>, format: Format) -> String
+where
+	T::Offset: Display,
+{
+	match format {
+		Format::Standard => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+		Format::Rfc3339 => time.to_rfc3339(),
+		Format::Rfc2822 => time.to_rfc2822(),
+		Format::Iso8601 => time.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+		Format::Iso8601Week => time.format("%G-W%V-%uT%H:%M:%S%:z").to_string(),
+		Format::Iso8601Ordinal => time.format("%Y-%jT%H:%M:%S%:z").to_string(),
+		Format::Clf => time.format("%d/%b/%Y:%H:%M:%S %z").to_string(),
+		Format::Unix => time.timestamp().to_string(),
+	}
 }
 
 mod cases {
@@ -163,6 +1104,90 @@ mod cases {
 						is_test: true,
 						since: "0.1.0".to_string(),
 					},
+					Case {
+						desc: "Use IANA time zone".to_string(),
+						input: vec!["--tz", "Asia/Shanghai", "0"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1970-01-01 08:00:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["--tz", "America/New_York", "1593561600"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2020-06-30 20:00:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Output rfc3339 format".to_string(),
+						input: vec!["-z", "8", "-F", "rfc3339", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2019-12-23T17:48:54+08:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Output ISO 8601 week date format".to_string(),
+						input: vec!["-z", "8", "-F", "iso8601_week", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2019-W52-1T17:48:54+08:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-z", "8", "-F", "rfc2822", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Mon, 23 Dec 2019 17:48:54 +0800"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-z", "8", "-F", "clf", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["23/Dec/2019:17:48:54 +0800"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
 				],
 			),
 			(
@@ -212,6 +1237,542 @@ mod cases {
 						is_test: true,
 						since: "0.1.0".to_string(),
 					},
+					Case {
+						desc: "Use IANA time zone".to_string(),
+						input: vec!["--tz", "America/New_York", "'2020-01-01 00:00:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577854800"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Input ISO 8601 week date format".to_string(),
+						input: vec!["'2019-W52-1T17:48:54+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Input ISO 8601 ordinal date format".to_string(),
+						input: vec!["'2019-357T17:48:54+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Input common log format".to_string(),
+						input: vec!["'23/Dec/2019:17:48:54 +0800'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["'20191223T094854Z'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-z", "0", "2019-12-23"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577059200"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"d2d",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'2019-12-23 17:48:54'", "-z", "8", "-F", "rfc2822"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Mon, 23 Dec 2019 17:48:54 +0800"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Convert to another time zone".to_string(),
+						input: vec!["--tz", "Asia/Tokyo", "'2019-12-23T17:48:54+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2019-12-23T18:48:54+09:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-F", "iso8601_ordinal", "'Mon, 23 Dec 2019 17:48:54 +0800'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2019-357T17:48:54+08:00"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-F", "unix", "'2019-W52-1T17:48:54+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"ts2r",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "1576835334"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["3 days ago"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "1577101734"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["in 2 hours"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "1577094533"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1 second ago"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["now"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"r2ts",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "'3 days ago'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1576835334"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "'in 1h 30m'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577099934"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "'2 weeks and 1 day ago'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1575798534"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-n", "1577094534", "yesterday"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577008134"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"d2w",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["2019-12-23"].into_iter().map(Into::into).collect(),
+						output: vec!["2019-W52-1", "2019-357", "Monday"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Output ISO week date".to_string(),
+						input: vec!["-w", "2020-12-31"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2020-W53-4"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-y", "-z", "8", "'2020-12-31 23:00:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2020-366"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-d", "--tz", "UTC", "'2019-12-23T01:00:00+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Sunday"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"w2d",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["2019-W52-1"].into_iter().map(Into::into).collect(),
+						output: vec!["2019-12-23"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["2020-366"].into_iter().map(Into::into).collect(),
+						output: vec!["2020-12-31"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["2021W01"].into_iter().map(Into::into).collect(),
+						output: vec!["2021-01-04"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"tdiff",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-z", "8", "'2019-12-20 09:00:00'", "'2019-12-23 17:30:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"seconds: 289800",
+							"minutes: 4830",
+							"hours: 80",
+							"days: 3",
+							"weeks: 0",
+							"business_days: 1",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-u",
+							"business_days",
+							"2019-12-01T00:00:00Z",
+							"2019-12-31T00:00:00Z",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["21"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-u", "seconds", "1577094534", "'2019-12-23T17:48:54+08:00'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["0"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-u", "days", "-z", "0", "2020-01-01", "2019-01-01"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["-365"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-u", "business_days", "-z", "0", "2020-01-01", "2019-01-01"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["-261"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"ts2e",
+				vec![
+					Case {
+						desc: "Windows FILETIME".to_string(),
+						input: vec!["-e", "filetime", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["132215681340000000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Apple Cocoa".to_string(),
+						input: vec!["-e", "apple", "1577094534"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["598787334"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Julian day".to_string(),
+						input: vec!["-e", "julian", "0"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["2440587.5"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-e", "hfs", "0"].into_iter().map(Into::into).collect(),
+						output: vec!["2082844800"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-e", "mjd", "86400"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["40588"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"e2ts",
+				vec![
+					Case {
+						desc: "Windows FILETIME".to_string(),
+						input: vec!["-e", "filetime", "132215681340000000"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-e", "filetime", "0x01D5B9763001C700"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-e", "apple", "598787334"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Mac HFS+".to_string(),
+						input: vec!["-e", "hfs", "3659939334"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577094534"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Julian day".to_string(),
+						input: vec!["-e", "julian", "2458840.5"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577059200"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-e", "mjd", "58840"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["1577059200"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"now",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![],
+						output: vec![
+							"unix: 1577094534",
+							"unix_ms: 1577094534123",
+							"rfc3339: 2019-12-23T09:48:54Z",
+							"local: 2019-12-23 17:48:54",
+							"iso8601_week: 2019-W52-1",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-F", "unix_ms"].into_iter().map(Into::into).collect(),
+						output: vec!["1577094534123"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
 				],
 			),
 		]
@@ -224,9 +1785,100 @@ mod cases {
 mod tests {
 	use super::*;
 	use crate::modules::base::test::test_module;
+	use std::iter;
 
 	#[test]
 	fn test_cases() {
 		test_module(module());
 	}
+
+	fn run(name: &str, args: Vec<&str>) -> Result<Vec<String>, Error> {
+		let command = commands()
+			.into_iter()
+			.find(|x| x.app.get_name() == name)
+			.unwrap();
+		let matches = command.app.get_matches_from(iter::once(name).chain(args));
+		(command.f)(&matches)
+	}
+
+	#[test]
+	fn test_tdiff() {
+		assert_eq!(
+			run("tdiff", vec!["-u", "days", "0", "86400"]).unwrap(),
+			vec!["1"]
+		);
+		assert_eq!(
+			run("tdiff", vec!["0", "99999999999999"])
+				.unwrap_err()
+				.to_string(),
+			"Invalid time: 99999999999999: out of range"
+		);
+	}
+
+	#[test]
+	fn test_ts2d() {
+		assert_eq!(
+			run("ts2d", vec!["-z", "0", "0"]).unwrap(),
+			vec!["1970-01-01 00:00:00"]
+		);
+		assert_eq!(
+			run("ts2d", vec!["99999999999999"]).unwrap_err().to_string(),
+			"Invalid time: 99999999999999: out of range"
+		);
+	}
+
+	#[test]
+	fn test_ts2r() {
+		assert_eq!(
+			run("ts2r", vec!["-n", "1", "--", "-9223372036854775808"])
+				.unwrap_err()
+				.to_string(),
+			"Invalid time: -9223372036854775808: out of range"
+		);
+		assert_eq!(format_relative(i64::MIN), "292471208677 years ago");
+	}
+
+	#[test]
+	fn test_d2w() {
+		assert_eq!(
+			run("d2w", vec!["2024-12-30"]).unwrap(),
+			vec!["2025-W01-1", "2024-365", "Monday"]
+		);
+		assert_eq!(
+			run("d2w", vec!["9999999999999"]).unwrap_err().to_string(),
+			"Invalid time: 9999999999999: out of range"
+		);
+	}
+
+	#[test]
+	fn test_epoch() {
+		assert_eq!(
+			run("e2ts", vec!["-e", "julian", "2440588"]).unwrap(),
+			vec!["43200"]
+		);
+		for (command, epoch, input) in &[
+			("e2ts", "julian", "1e300"),
+			("e2ts", "mjd", "-1e300"),
+			("e2ts", "julian", "NaN"),
+			("e2ts", "apple", "9223372036854775807"),
+			("ts2e", "hfs", "9223372036854775807"),
+			("ts2e", "filetime", "-9223372036854775808"),
+		] {
+			assert_eq!(
+				run(command, vec!["-e", epoch, "--", input]).unwrap_err(),
+				Error::invalid("time", format!("{}: out of range", input))
+			);
+		}
+	}
+
+	#[test]
+	fn test_parse_relative() {
+		assert_eq!(parse_relative("1h 30m ago"), Ok(-5400));
+		assert_eq!(
+			parse_relative("in 999999999999 years"),
+			Err(Error::invalid("time", "999999999999 years: out of range"))
+		);
+		assert!(parse_relative("in 99999999999999999999 seconds").is_err());
+		assert!(parse_relative("in 9223372036854775807 seconds, 1 second").is_err());
+	}
 }
@@ -0,0 +1,339 @@
+use crate::error::Error;
+use crate::modules::{base, http, Command, Module};
+use chrono::NaiveDateTime;
+use clap::{Arg, ArgMatches};
+use openssl::asn1::{Asn1Time, Asn1TimeRef};
+use openssl::hash::MessageDigest;
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use openssl::x509::{X509NameRef, X509Ref};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+use url::Url;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "TLS certificate inspect".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("tls_inspect")
+			.about("TLS handshake, then the subject, issuer, validity and fingerprint of every certificate of the chain")
+			.arg(
+				Arg::with_name("SNI")
+					.long("sni")
+					.help("Server name sent in the handshake and verified, the host if not given")
+					.takes_value(true)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("WARN_DAYS")
+					.long("warn-days")
+					.help("Warn about certificates expiring within the days")
+					.takes_value(true)
+					.default_value("30")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("TIMEOUT")
+					.long("timeout")
+					.short("t")
+					.help("Timeout in seconds")
+					.takes_value(true)
+					.default_value("10")
+					.required(false),
+			)
+			.arg(base::input_arg().help("host:port, the port is 443 if not given")).args(&base::file_args()),
+		f: tls_inspect,
+		stream: None,
+	}]
+}
+
+fn tls_inspect(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let input = base::input_string(matches)?;
+	let input = input.trim();
+
+	let url = match input.contains("://") {
+		true => Url::parse(input),
+		false => Url::parse(&format!("https://{}", input)),
+	}
+	.map_err(|e| Error::invalid("host", format!("{}: {}", input, e)))?;
+	let host = url.host_str().ok_or(Error::invalid("host", "no host"))?;
+	let host = host.trim_start_matches('[').trim_end_matches(']');
+	let port = url.port_or_known_default().unwrap_or(443);
+
+	let server_name = matches.value_of("SNI").unwrap_or(host);
+	let warn_days = matches
+		.value_of("WARN_DAYS")
+		.unwrap_or("30")
+		.parse::<i32>()
+		.map_err(|_| Error::invalid("warn days", ""))?;
+	let timeout = matches
+		.value_of("TIMEOUT")
+		.unwrap_or("10")
+		.parse::<f64>()
+		.ok()
+		.filter(|x| *x > 0.0)
+		.map(Duration::from_secs_f64)
+		.ok_or(Error::invalid("timeout", ""))?;
+
+	base::verbose(matches, 1, || format!("server name: {}", server_name));
+	inspect(host, port, server_name, warn_days, timeout)
+}
+
+fn inspect(
+	host: &str,
+	port: u16,
+	server_name: &str,
+	warn_days: i32,
+	timeout: Duration,
+) -> Result<Vec<String>, Error> {
+	let stream = http::tcp_connect(host, port, timeout)
+		.map_err(|e| Error::Failed(format!("Connect failed: {}:{}: {}", host, port, e)))?;
+
+	// the chain is verified but not enforced, so that invalid chains can be inspected too
+	let mut builder =
+		SslConnector::builder(SslMethod::tls()).map_err(|e| Error::invalid("TLS", e))?;
+	builder.set_verify(SslVerifyMode::NONE);
+	let stream = builder
+		.build()
+		.connect(server_name, stream)
+		.map_err(|e| Error::Failed(format!("TLS handshake failed: {}: {}", host, e)))?;
+	let ssl = stream.ssl();
+
+	let mut result = vec![
+		format!("protocol: {}", ssl.version_str()),
+		format!(
+			"cipher: {}",
+			ssl.current_cipher().map(|x| x.name()).unwrap_or("none")
+		),
+	];
+	let mut warnings = vec![];
+
+	let verify = ssl.verify_result();
+	match verify.as_raw() {
+		0 => result.push("verify: ok".to_string()),
+		_ => {
+			result.push(format!("verify: {}", verify.error_string()));
+			warnings.push(format!("verify failed: {}", verify.error_string()));
+		}
+	}
+
+	let chain = ssl
+		.peer_cert_chain()
+		.ok_or_else(|| Error::Failed("No certificate presented".to_string()))?;
+	let now = Asn1Time::days_from_now(0).map_err(|e| Error::invalid("time", e))?;
+	for (i, cert) in chain.iter().enumerate() {
+		result.push("".to_string());
+		result.extend(certificate(i, cert)?);
+
+		let days = now
+			.diff(cert.not_after())
+			.map(|x| x.days)
+			.map_err(|e| Error::invalid("time", e))?;
+		if days < 0 {
+			warnings.push(format!("certificate {} expired {} days ago", i, -days));
+		} else if days < warn_days {
+			warnings.push(format!("certificate {} expires in {} days", i, days));
+		}
+	}
+
+	result.extend(
+		warnings
+			.into_iter()
+			.map(|x| format!("{}{}", base::WARNING_PREFIX, x)),
+	);
+
+	Ok(result)
+}
+
+fn certificate(i: usize, cert: &X509Ref) -> Result<Vec<String>, Error> {
+	let fingerprint = cert
+		.digest(MessageDigest::sha256())
+		.map_err(|e| Error::invalid("certificate", e))?
+		.iter()
+		.map(|x| format!("{:02X}", x))
+		.collect::<Vec<_>>()
+		.join(":");
+	let serial = cert
+		.serial_number()
+		.to_bn()
+		.and_then(|x| x.to_hex_str().map(|x| x.to_string()))
+		.map_err(|e| Error::invalid("certificate", e))?;
+
+	let mut result = vec![
+		format!("certificate: {}", i),
+		format!("subject: {}", name(cert.subject_name())),
+		format!("issuer: {}", name(cert.issuer_name())),
+		format!("serial: {}", serial),
+		format!("not_before: {}", time(cert.not_before())),
+		format!("not_after: {}", time(cert.not_after())),
+	];
+	if let Some(names) = cert.subject_alt_names() {
+		let names = names
+			.iter()
+			.filter_map(|x| {
+				x.dnsname()
+					.map(|x| format!("DNS:{}", x))
+					.or_else(|| x.ipaddress().map(|x| format!("IP:{}", ip(x))))
+			})
+			.collect::<Vec<_>>();
+		if !names.is_empty() {
+			result.push(format!("san: {}", names.join(", ")));
+		}
+	}
+	result.push(format!("sha256: {}", fingerprint));
+	Ok(result)
+}
+
+/// e.g. `C=US, O=Let's Encrypt, CN=R3`
+fn name(name: &X509NameRef) -> String {
+	name.entries()
+		.map(|x| {
+			let key = x.object().nid().short_name().unwrap_or("?");
+			let value = x
+				.data()
+				.as_utf8()
+				.map(|x| x.to_string())
+				.unwrap_or_default();
+			format!("{}={}", key, value)
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// UTC, e.g. `2024-01-30 00:00:00 UTC`
+fn time(time: &Asn1TimeRef) -> String {
+	let text = time.to_string();
+	NaiveDateTime::parse_from_str(&text, "%b %e %H:%M:%S %Y GMT")
+		.map(|x| format!("{} UTC", x.format("%Y-%m-%d %H:%M:%S")))
+		.unwrap_or(text)
+}
+
+fn ip(bytes: &[u8]) -> String {
+	match *bytes {
+		[a, b, c, d] => Ipv4Addr::new(a, b, c, d).to_string(),
+		_ if bytes.len() == 16 => {
+			let mut octets = [0u8; 16];
+			octets.copy_from_slice(bytes);
+			Ipv6Addr::from(octets).to_string()
+		}
+		_ => hex::encode(bytes),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"tls_inspect",
+			vec![Case {
+				desc: "".to_string(),
+				input: vec!["example.com"].into_iter().map(Into::into).collect(),
+				output: vec![
+					"protocol: TLSv1.3",
+					"cipher: TLS_AES_256_GCM_SHA384",
+					"verify: ok",
+					"",
+					"certificate: 0",
+					"subject: C=US, ST=California, L=Los Angeles, O=Internet Corporation for Assigned Names and Numbers, CN=www.example.org",
+					"issuer: C=US, O=DigiCert Inc, CN=DigiCert Global G2 TLS RSA SHA256 2020 CA1",
+					"serial: 075BCEF30689C8ADDF13E51AF4AFE187",
+					"not_before: 2024-01-30 00:00:00 UTC",
+					"not_after: 2025-03-01 23:59:59 UTC",
+					"san: DNS:www.example.org, DNS:example.net, DNS:example.com",
+					"sha256: EF:BA:26:D8:C1:CE:37:79:AC:77:63:0A:90:F8:21:63:A3:D6:89:2E:D6:AF:EE:40:86:E9:3F:A3:12:1F:E1:E2",
+					"",
+					"certificate: 1",
+					"...",
+				]
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+				is_example: true,
+				is_test: false,
+				since: "0.10.0".to_string(),
+			}],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+	use openssl::pkey::PKey;
+	use openssl::rsa::Rsa;
+	use openssl::ssl::SslAcceptor;
+	use openssl::x509::extension::SubjectAlternativeName;
+	use openssl::x509::{X509Name, X509};
+	use std::net::TcpListener;
+	use std::thread;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_inspect() {
+		// self-signed, expiring in 10 days
+		let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+		let mut name = X509Name::builder().unwrap();
+		name.append_entry_by_text("CN", "localhost").unwrap();
+		let name = name.build();
+		let mut cert = X509::builder().unwrap();
+		cert.set_version(2).unwrap();
+		cert.set_subject_name(&name).unwrap();
+		cert.set_issuer_name(&name).unwrap();
+		cert.set_pubkey(&key).unwrap();
+		cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+			.unwrap();
+		cert.set_not_after(&Asn1Time::days_from_now(10).unwrap())
+			.unwrap();
+		let san = SubjectAlternativeName::new()
+			.dns("localhost")
+			.ip("127.0.0.1")
+			.build(&cert.x509v3_context(None, None))
+			.unwrap();
+		cert.append_extension(san).unwrap();
+		cert.sign(&key, MessageDigest::sha256()).unwrap();
+		let cert = cert.build();
+
+		let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+		acceptor.set_private_key(&key).unwrap();
+		acceptor.set_certificate(&cert).unwrap();
+		let acceptor = acceptor.build();
+
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		thread::spawn(move || {
+			let stream = listener.incoming().next().unwrap().unwrap();
+			let _ = acceptor.accept(stream);
+		});
+
+		let result = inspect("127.0.0.1", port, "localhost", 30, Duration::from_secs(10)).unwrap();
+		let has = |x: &str| result.iter().any(|line| line == x);
+		assert!(has("certificate: 0"));
+		assert!(has("subject: CN=localhost"));
+		assert!(has("issuer: CN=localhost"));
+		assert!(has("san: DNS:localhost, IP:127.0.0.1"));
+		assert!(has("verify: self-signed certificate"));
+		assert!(result
+			.iter()
+			.any(|x| x.starts_with("warning: certificate 0 expires in ")));
+	}
+
+	#[test]
+	fn test_time() {
+		let time = Asn1Time::from_str("20250301235959Z").unwrap();
+		assert_eq!(super::time(&time), "2025-03-01 23:59:59 UTC");
+	}
+}
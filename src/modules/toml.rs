@@ -0,0 +1,376 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+use toml::value::Datetime;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "JSON / TOML conversion, TOML format / check".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("j2t")
+				.about("Convert JSON to TOML")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2t),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("t2j")
+				.about("Convert TOML to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, t2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("toml_fmt")
+				.about("TOML format")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, toml_fmt),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("toml_check")
+				.about("TOML check (exit with 1 if invalid)")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, toml_check),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the TOML commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+		})
+	}
+}
+
+/// Convert JSON to TOML
+pub fn j2t(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let value = match from_json(&value)? {
+		value @ toml::Value::Table(_) => value,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	let result = to_string(&value)?;
+
+	Ok(result.into_bytes())
+}
+
+/// Convert TOML to JSON
+pub fn t2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = parse(base::text(input)?)?;
+
+	let value = to_json(value);
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// TOML format, comments are not preserved
+pub fn toml_fmt(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let value = input
+		.parse::<toml::Value>()
+		.map_err(|e| with_position(input, e))?;
+
+	let result = to_string(&value)?;
+
+	Ok(result.into_bytes())
+}
+
+/// TOML check, fails if invalid
+pub fn toml_check(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	input
+		.parse::<toml::Value>()
+		.map_err(|e| with_position(input, e))?;
+
+	Ok(b"Valid".to_vec())
+}
+
+/// Append the error line and a caret at the column
+fn with_position(input: &str, e: toml::de::Error) -> Error {
+	let message = e.to_string();
+	let detail = match e.line_col() {
+		Some((line, col)) => {
+			let content = input.lines().nth(line).unwrap_or_default();
+			let line_no = (line + 1).to_string();
+			[
+				message,
+				format!("{} | {}", line_no, content),
+				format!("{} | {}^", " ".repeat(line_no.len()), " ".repeat(col)),
+			]
+			.join("\n")
+		}
+		None => message,
+	};
+	Error::invalid("TOML", detail)
+}
+
+pub fn parse(input: &str) -> Result<toml::Value, Error> {
+	input
+		.parse::<toml::Value>()
+		.map_err(|e| Error::invalid("TOML", e))
+}
+
+/// Without the trailing newline
+pub fn to_string(value: &toml::Value) -> Result<String, Error> {
+	let result = toml::to_string(value).map_err(|e| Error::invalid("TOML", e))?;
+	Ok(result.strip_suffix('\n').unwrap_or(&result).to_string())
+}
+
+/// Datetimes are converted to RFC 3339 strings
+pub fn to_json(value: toml::Value) -> Value {
+	match value {
+		toml::Value::String(v) => Value::String(v),
+		toml::Value::Integer(v) => Value::from(v),
+		toml::Value::Float(v) => Value::from(v),
+		toml::Value::Boolean(v) => Value::Bool(v),
+		toml::Value::Datetime(v) => Value::String(v.to_string()),
+		toml::Value::Array(v) => Value::Array(v.into_iter().map(to_json).collect()),
+		toml::Value::Table(v) => Value::Object(
+			v.into_iter()
+				.map(|(k, v)| (k, to_json(v)))
+				.collect::<Map<_, _>>(),
+		),
+	}
+}
+
+/// Strings in RFC 3339 format are converted to datetimes,
+/// null is not supported by TOML, so it is an error with the path, e.g. `$.a[0]`
+pub fn from_json(value: &Value) -> Result<toml::Value, Error> {
+	from_json_at(value, "$")
+}
+
+fn from_json_at(value: &Value, path: &str) -> Result<toml::Value, Error> {
+	let result = match value {
+		Value::Null => {
+			return Err(Error::invalid(
+				"JSON",
+				format!("null at {} is not supported by TOML", path),
+			))
+		}
+		Value::Bool(v) => toml::Value::Boolean(*v),
+		Value::Number(v) => match v.as_i64() {
+			Some(v) => toml::Value::Integer(v),
+			None => toml::Value::Float(v.as_f64().ok_or(Error::invalid("number", ""))?),
+		},
+		Value::String(v) => match v.parse::<Datetime>() {
+			Ok(datetime) => toml::Value::Datetime(datetime),
+			Err(_) => toml::Value::String(v.clone()),
+		},
+		Value::Array(v) => toml::Value::Array(
+			v.iter()
+				.enumerate()
+				.map(|(i, x)| from_json_at(x, &format!("{}[{}]", path, i)))
+				.collect::<Result<_, _>>()?,
+		),
+		Value::Object(v) => toml::Value::Table(
+			v.iter()
+				.map(|(k, v)| Ok((k.clone(), from_json_at(v, &format!("{}.{}", path, k))?)))
+				.collect::<Result<_, Error>>()?,
+		),
+	};
+	Ok(result)
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"j2t",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"name":"dtool","deps":{"hex":"0.4"}}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"name = "dtool""#, "", "[deps]", r#"hex = "0.4""#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Array of tables and datetime".to_string(),
+						input: vec![
+							r#"'{"bin":[{"name":"a"},{"name":"b"}],"date":"1979-05-27T07:32:00Z"}'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"date = 1979-05-27T07:32:00Z",
+							"",
+							"[[bin]]",
+							r#"name = "a""#,
+							"",
+							"[[bin]]",
+							r#"name = "b""#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"t2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "'a = 1\n[b]\nc = 1979-05-27T07:32:00Z'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":1,"b":{"c":"1979-05-27T07:32:00Z"}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["'[[bin]]\nname = \"a\"'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"{",
+							r#"  "bin": ["#,
+							"    {",
+							r#"      "name": "a""#,
+							"    }",
+							"  ]",
+							"}",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"toml_fmt",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'b = [ 1,2 ] # comment\n[c]\nd=\"x\"\na  =  true'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["b = [1, 2]", "", "[c]", r#"d = "x""#, "a = true"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"toml_check",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'a = 1\n[b]\nc = 1979-05-27'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["Valid"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Invalid".to_string(),
+						input: vec!["'a = 1\nb c'"].into_iter().map(Into::into).collect(),
+						output: vec![
+							"Invalid TOML: expected an equals, found an identifier at line 2 column 3",
+							"2 | b c",
+							"  |   ^",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_from_json() {
+		let value = serde_json::from_str(r#"{"a":{"b":[1,null]}}"#).unwrap();
+		assert_eq!(
+			from_json(&value),
+			Err(Error::invalid(
+				"JSON",
+				"null at $.a.b[1] is not supported by TOML"
+			))
+		);
+		let value = serde_json::from_str(r#"{"a":null}"#).unwrap();
+		assert!(from_json(&value).is_err());
+	}
+}
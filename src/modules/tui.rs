@@ -0,0 +1,360 @@
+use crate::error::Error;
+use crate::modules::{base, pipe, ModuleManager};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io;
+use std::io::Write;
+use unicode_width::UnicodeWidthChar;
+
+const PALETTE_WIDTH: usize = 24;
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+	SubCommand::with_name("tui")
+		.about(
+			"Interactive mode, pick conversions from the palette and see their output as you type",
+		)
+		.arg(
+			Arg::with_name("COMMANDS")
+				.help("Conversions to start with, sub commands with args, e.g. \"hash -a md5\"")
+				.multiple(true)
+				.required(false)
+				.index(1),
+		)
+}
+
+pub fn run<'a, 'b>(matches: &ArgMatches, mm: &ModuleManager<'a, 'b>) -> Result<Vec<String>, Error> {
+	let mut state = State::new(mm);
+	for line in matches.values_of("COMMANDS").into_iter().flatten() {
+		state.add(line)?;
+	}
+
+	let mut stdout = io::stdout();
+	terminal::enable_raw_mode().map_err(Error::failed)?;
+	execute!(stdout, EnterAlternateScreen, Hide).map_err(Error::failed)?;
+
+	let result = event_loop(&mut state, &mut stdout);
+
+	execute!(stdout, Show, LeaveAlternateScreen).map_err(Error::failed)?;
+	terminal::disable_raw_mode().map_err(Error::failed)?;
+
+	result.map(|_| vec![])
+}
+
+fn event_loop(state: &mut State, stdout: &mut io::Stdout) -> Result<(), Error> {
+	loop {
+		let (width, height) = terminal::size().map_err(Error::failed)?;
+		queue!(stdout, Clear(ClearType::All)).map_err(Error::failed)?;
+		for (i, line) in state
+			.render(width as usize, height as usize)
+			.iter()
+			.enumerate()
+		{
+			queue!(stdout, MoveTo(0, i as u16), crossterm::style::Print(line))
+				.map_err(Error::failed)?;
+		}
+		stdout.flush().map_err(Error::failed)?;
+
+		match event::read().map_err(Error::failed)? {
+			Event::Key(key) if key.kind == KeyEventKind::Press && !state.key(key) => {
+				return Ok(());
+			}
+			_ => (),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq)]
+enum Focus {
+	Palette,
+	Input,
+}
+
+struct State<'c, 'a, 'b> {
+	mm: &'c ModuleManager<'a, 'b>,
+	focus: Focus,
+	/// The sub command to search, args may follow
+	filter: String,
+	selected: usize,
+	input: String,
+	/// Command lines and their output for the input
+	conversions: Vec<(String, Result<Vec<String>, Error>)>,
+	status: String,
+}
+
+impl<'c, 'a, 'b> State<'c, 'a, 'b> {
+	fn new(mm: &'c ModuleManager<'a, 'b>) -> Self {
+		Self {
+			mm,
+			focus: Focus::Palette,
+			filter: String::new(),
+			selected: 0,
+			input: String::new(),
+			conversions: vec![],
+			status: String::new(),
+		}
+	}
+
+	/// Sub commands containing the first word of the filter
+	fn filtered(&self) -> Vec<&'c str> {
+		let keyword = self
+			.filter
+			.split_whitespace()
+			.next()
+			.unwrap_or("")
+			.to_lowercase();
+		self.mm
+			.commands
+			.keys()
+			.map(String::as_str)
+			.filter(|x| x.contains(&keyword))
+			.collect()
+	}
+
+	/// Handles the key, returns false to quit
+	fn key(&mut self, key: KeyEvent) -> bool {
+		let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+		match key.code {
+			KeyCode::Esc => return false,
+			KeyCode::Char('c') if ctrl => return false,
+			KeyCode::Char('x') if ctrl => {
+				self.conversions.pop();
+			}
+			KeyCode::Tab | KeyCode::BackTab => {
+				self.focus = match self.focus {
+					Focus::Palette => Focus::Input,
+					Focus::Input => Focus::Palette,
+				}
+			}
+			_ => match self.focus {
+				Focus::Palette => self.palette_key(key.code),
+				Focus::Input => self.input_key(key.code),
+			},
+		}
+		true
+	}
+
+	fn palette_key(&mut self, code: KeyCode) {
+		match code {
+			KeyCode::Char(c) => {
+				self.filter.push(c);
+				self.selected = 0;
+			}
+			KeyCode::Backspace => {
+				self.filter.pop();
+				self.selected = 0;
+			}
+			KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+			KeyCode::Down if self.selected + 1 < self.filtered().len() => self.selected += 1,
+			KeyCode::Enter => {
+				let name = match self.filtered().get(self.selected) {
+					Some(name) => *name,
+					None => return,
+				};
+				// the args typed after the sub command
+				let args = self
+					.filter
+					.trim_start()
+					.split_once(char::is_whitespace)
+					.map(|(_, args)| args)
+					.unwrap_or("");
+				let line = format!("{} {}", name, args).trim().to_string();
+				self.status = match self.add(&line) {
+					Ok(()) => String::new(),
+					Err(e) => e.to_string(),
+				};
+			}
+			_ => (),
+		}
+	}
+
+	fn input_key(&mut self, code: KeyCode) {
+		match code {
+			KeyCode::Char(c) => self.input.push(c),
+			KeyCode::Enter => self.input.push('\n'),
+			KeyCode::Backspace => {
+				self.input.pop();
+			}
+			_ => return,
+		}
+		self.refresh();
+	}
+
+	/// Adds the conversion, a sub command with args
+	fn add(&mut self, line: &str) -> Result<(), Error> {
+		let stage = match pipe::parse(line)?.as_slice() {
+			[stage] => stage.clone(),
+			_ => return Err(Error::invalid("conversion", line)),
+		};
+		let name = &stage[0];
+		if !self.mm.commands.contains_key(name) {
+			return Err(Error::invalid(
+				"conversion",
+				format!(
+					"unknown sub command: {}{}",
+					name,
+					base::did_you_mean(name, self.mm.commands.keys().map(String::as_str))
+				),
+			));
+		}
+		let output = self.convert(&stage);
+		self.conversions.push((line.to_string(), output));
+		Ok(())
+	}
+
+	fn refresh(&mut self) {
+		for i in 0..self.conversions.len() {
+			let output =
+				pipe::parse(&self.conversions[i].0).and_then(|stages| self.convert(&stages[0]));
+			self.conversions[i].1 = output;
+		}
+	}
+
+	fn convert(&self, stage: &[String]) -> Result<Vec<String>, Error> {
+		let command = self
+			.mm
+			.commands
+			.get(&stage[0])
+			.ok_or(Error::invalid("conversion", ""))?;
+		let matches = self
+			.mm
+			.get_matches(&command.app, stage, 0)
+			.map_err(|e| Error::failed(e.message.lines().next().unwrap_or("")))?;
+		let (result, output) =
+			base::with_io(self.input.as_bytes().to_vec(), || (command.f)(&matches));
+		let result = result?;
+		// raw output written by the command
+		if result.is_empty() && !output.is_empty() {
+			return Ok(String::from_utf8_lossy(&output)
+				.lines()
+				.map(String::from)
+				.collect());
+		}
+		Ok(result)
+	}
+
+	/// The screen lines, the palette on the left, the input and the outputs on the right
+	fn render(&self, width: usize, height: usize) -> Vec<String> {
+		let cursor = |focus| if self.focus == focus { "_" } else { "" };
+
+		let mut left = vec![format!("> {}{}", self.filter, cursor(Focus::Palette))];
+		let filtered = self.filtered();
+		// keep the selected one visible
+		let visible = height.saturating_sub(3).max(1);
+		let skip = (self.selected + 1).saturating_sub(visible);
+		for (i, name) in filtered.iter().enumerate().skip(skip).take(visible) {
+			let marker = if i == self.selected { "> " } else { "  " };
+			left.push(format!("{}{}", marker, name));
+		}
+
+		let mut right = vec!["Input".to_string()];
+		let input = format!("{}{}", self.input, cursor(Focus::Input));
+		right.extend(input.split('\n').map(|x| format!("  {}", x)));
+		for (line, output) in &self.conversions {
+			right.push(String::new());
+			right.push(line.clone());
+			match output {
+				Ok(output) => right.extend(output.iter().map(|x| format!("  {}", x))),
+				Err(e) => right.push(format!("  error: {}", e)),
+			}
+		}
+
+		let right_width = width.saturating_sub(PALETTE_WIDTH + 3);
+		let mut lines = vec![fit(
+			"dtool tui  Tab: palette / input  Enter: add conversion  Ctrl-X: remove last  Esc: quit",
+			width,
+		)];
+		for i in 0..height.saturating_sub(2) {
+			let l = left.get(i).map(String::as_str).unwrap_or("");
+			let r = right.get(i).map(String::as_str).unwrap_or("");
+			lines.push(format!(
+				"{} | {}",
+				pad(&fit(l, PALETTE_WIDTH), PALETTE_WIDTH),
+				fit(r, right_width)
+			));
+		}
+		lines.push(fit(&self.status, width));
+		lines
+	}
+}
+
+/// Truncates to the display width
+fn fit(s: &str, width: usize) -> String {
+	let mut result = String::new();
+	let mut current = 0;
+	for c in s.chars() {
+		// control chars (e.g. tab) are shown as spaces
+		let c = if c.is_control() { ' ' } else { c };
+		let w = c.width().unwrap_or(0);
+		if current + w > width {
+			break;
+		}
+		current += w;
+		result.push(c);
+	}
+	result
+}
+
+fn pad(s: &str, width: usize) -> String {
+	let current = s.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+	format!("{}{}", s, " ".repeat(width.saturating_sub(current)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key(code: KeyCode) -> KeyEvent {
+		KeyEvent::new(code, KeyModifiers::NONE)
+	}
+
+	#[test]
+	fn test_state() {
+		let mm = ModuleManager::new_without_config();
+		let mut state = State::new(&mm);
+
+		for c in "hash -a md5".chars() {
+			assert!(state.key(key(KeyCode::Char(c))));
+		}
+		assert_eq!(state.filtered(), vec!["hash"]);
+		state.key(key(KeyCode::Enter));
+		assert_eq!(state.status, "");
+
+		state.add("s2h").unwrap();
+		assert!(state.add("s2hh").is_err());
+
+		state.key(key(KeyCode::Tab));
+		for c in "abc".chars() {
+			state.key(key(KeyCode::Char(c)));
+		}
+		assert_eq!(
+			state.conversions,
+			vec![
+				(
+					"hash -a md5".to_string(),
+					Err(Error::failed("Convert failed"))
+				),
+				("s2h".to_string(), Ok(vec!["0x616263".to_string()])),
+			]
+		);
+
+		let screen = state.render(80, 10);
+		assert_eq!(screen.len(), 10);
+		assert!(screen[1].starts_with("> hash -a md5  "));
+		assert!(screen.iter().any(|x| x.ends_with("| s2h")));
+		assert!(screen.iter().any(|x| x.ends_with("|   0x616263")));
+
+		state.key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL));
+		assert_eq!(state.conversions.len(), 1);
+		assert!(!state.key(key(KeyCode::Esc)));
+	}
+
+	#[test]
+	fn test_fit() {
+		assert_eq!(fit("abc", 2), "ab");
+		assert_eq!(fit("中文", 3), "中");
+		assert_eq!(pad("中", 3), "中 ");
+	}
+}
@@ -0,0 +1,301 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::{Arg, ArgMatches};
+use ulid::{Generator, Ulid};
+use uuid::Uuid;
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "ULID generate / parse, ULID / UUID convert".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("ulid_gen")
+				.about("ULID generate, 48 bits of Unix time in milliseconds and 80 random bits, sortable")
+				.arg(
+					Arg::with_name("MONOTONIC")
+						.long("monotonic")
+						.short("m")
+						.help("Increment the random part within the same millisecond, so that the ULIDs are strictly sorted"),
+				)
+				.arg(
+					Arg::with_name("COUNT")
+						.long("count")
+						.short("c")
+						.help("Number of ULIDs")
+						.takes_value(true)
+						.default_value("1")
+						.required(false),
+				),
+			f: |matches| base::generate(matches, ulid_gen),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ulid_parse")
+				.about("ULID parse, the time and the random part")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ulid_parse),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ulid2uuid")
+				.about("Convert ULID to UUID, the same 128 bits")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ulid2uuid),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("uuid2ulid")
+				.about("Convert UUID to ULID, the same 128 bits")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, uuid2ulid),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the ULID commands, only ulid_gen has any
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Increment the random part within the same millisecond, so that the ULIDs are strictly sorted
+	pub monotonic: bool,
+	pub count: usize,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			monotonic: false,
+			count: 1,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let count = matches
+			.value_of("COUNT")
+			.unwrap_or("1")
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("count", ""))?;
+		Ok(Options {
+			monotonic: matches.is_present("MONOTONIC"),
+			count,
+		})
+	}
+}
+
+/// ULID generate, 48 bits of Unix time in milliseconds and 80 random bits, sortable, there is no input
+pub fn ulid_gen(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let count = options.count;
+
+	let result = match options.monotonic {
+		true => {
+			let mut generator = Generator::new();
+			(0..count)
+				.map(|_| {
+					generator
+						.generate()
+						.map(|x| x.to_string())
+						.map_err(|e| Error::Failed(format!("Generate failed: {}", e)))
+				})
+				.collect::<Result<Vec<_>, _>>()?
+		}
+		false => (0..count).map(|_| Ulid::new().to_string()).collect(),
+	};
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// ULID parse, the time and the random part
+pub fn ulid_parse(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let ulid = parse_ulid(base::text(input)?)?;
+
+	let timestamp = ulid.timestamp_ms();
+	let time = Utc
+		.timestamp_millis(timestamp as i64)
+		.format("%Y-%m-%d %H:%M:%S%.3f UTC");
+
+	let result = [
+		format!("ulid: {}", ulid),
+		format!("time: {}", time),
+		format!("timestamp: {}", timestamp),
+		format!("random: {:020x}", ulid.random()),
+		format!("uuid: {}", Uuid::from(ulid)),
+	];
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert ULID to UUID, the same 128 bits
+pub fn ulid2uuid(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let ulid = parse_ulid(base::text(input)?)?;
+
+	let result = Uuid::from(ulid).to_string();
+
+	Ok(result.into_bytes())
+}
+
+/// Convert UUID to ULID, the same 128 bits
+pub fn uuid2ulid(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?.trim();
+
+	let uuid =
+		Uuid::parse_str(input).map_err(|e| Error::invalid("UUID", format!("{}: {}", input, e)))?;
+
+	let result = Ulid::from(uuid).to_string();
+
+	Ok(result.into_bytes())
+}
+
+/// Crockford's base32, case insensitive
+fn parse_ulid(input: &str) -> Result<Ulid, Error> {
+	let input = input.trim();
+	// 26 characters hold 130 bits, the first one is at most 7
+	if input.len() == 26 && !matches!(input.as_bytes()[0], b'0'..=b'7') {
+		return Err(Error::invalid("ULID", format!("{}: overflow", input)));
+	}
+	Ulid::from_string(input).map_err(|e| Error::invalid("ULID", format!("{}: {}", input, e)))
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"ulid_gen",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: Vec::<String>::new(),
+						output: vec!["01HZ3V8QHWN4XK9R2B6T7YF0CM"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Monotonic".to_string(),
+						input: vec!["-m", "-c", "2"].into_iter().map(Into::into).collect(),
+						output: vec!["01HZ3V8QHWN4XK9R2B6T7YF0CM", "01HZ3V8QHWN4XK9R2B6T7YF0CN"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"ulid_parse",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"ulid: 01ARZ3NDEKTSV4RRFFQ69G5FAV",
+						"time: 2016-07-30 23:54:10.259 UTC",
+						"timestamp: 1469922850259",
+						"random: d6764c61efb99302bd5b",
+						"uuid: 01563e3a-b5d3-d676-4c61-efb99302bd5b",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"ulid2uuid",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["01563e3a-b5d3-d676-4c61-efb99302bd5b"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"uuid2ulid",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["01563e3a-b5d3-d676-4c61-efb99302bd5b"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["01ARZ3NDEKTSV4RRFFQ69G5FAV"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_ulid_gen() {
+		let options = Options {
+			monotonic: true,
+			count: 100,
+		};
+		let result = base::lines(ulid_gen(&[], &options).unwrap()).unwrap();
+		assert_eq!(result.len(), 100);
+		let mut sorted = result.clone();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(sorted, result);
+
+		let result = base::lines(ulid_gen(&[], &Options::default()).unwrap()).unwrap();
+		let ulid = parse_ulid(&result[0]).unwrap();
+		let now = Utc::now().timestamp_millis() as u64;
+		assert!(now - ulid.timestamp_ms() < 60_000);
+	}
+
+	#[test]
+	fn test_parse_ulid() {
+		let ulid = parse_ulid("01arz3ndektsv4rrffq69g5fav\n").unwrap();
+		assert_eq!(ulid.to_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+		assert!(parse_ulid("01ARZ3NDEKTSV4RRFFQ69G5FA").is_err());
+		assert!(parse_ulid("81ARZ3NDEKTSV4RRFFQ69G5FAV").is_err());
+		assert!(parse_ulid("01ARZ3NDEKTSV4RRFFQ69G5FAU").is_err());
+	}
+}
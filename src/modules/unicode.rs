@@ -1,3 +1,5 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
 use crate::modules::{base, Command, Module};
 use clap::{Arg, ArgMatches, SubCommand};
 use std::char::EscapeUnicode;
@@ -20,81 +22,118 @@ pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
 			app: SubCommand::with_name("s2u")
+				.args(&base::output_args())
+				.arg(base::raw_arg())
 				.about("UTF-8 string to unicode")
 				.arg(
 					Arg::with_name("FORMAT")
 						.long("format")
-						.short("f")
+						.short("F")
 						.help(FORMAT_HELP)
 						.takes_value(true)
 						.required(false),
 				)
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: s2u,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, s2u),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("u2s")
+			app: base::sub_command("u2s")
 				.about("Unicode to UTF-8 string")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: u2s,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, u2s),
+			stream: None,
 		},
 	]
 }
 
-fn s2u(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// The format of `s2u`, `u2s` detects it
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub format: Format,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	/// `\u7c`
+	#[default]
+	Default,
+	/// `&#x7c;`
+	Html,
+	/// `&#124;`
+	HtmlDecimal,
+	/// `\u{7c}`
+	Rust,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let format = match matches.value_of("FORMAT") {
+			Some("html") => Format::Html,
+			Some("html_d") => Format::HtmlDecimal,
+			Some("rust") => Format::Rust,
+			_ => Format::Default,
+		};
+		Ok(Options { format })
+	}
+}
 
-	let format = match matches.value_of("FORMAT") {
-		Some("html") => format_html,
-		Some("html_d") => format_html_d,
-		Some("rust") => format_rust,
-		_ => format_default,
+/// UTF-8 string to unicode
+pub fn s2u(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let format = match options.format {
+		Format::Html => format_html,
+		Format::HtmlDecimal => format_html_d,
+		Format::Rust => format_rust,
+		Format::Default => format_default,
 	};
 
 	let result = input
 		.chars()
 		.map(char::escape_unicode)
 		.map(format)
-		.collect::<Result<Vec<String>, String>>()?;
+		.collect::<Result<Vec<String>, Error>>()?;
 
 	let result = result.join("");
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn u2s(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// Unicode to UTF-8 string
+pub fn u2s(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
 
 	let format = match input {
-		_ if input.starts_with("\\u{") => "rust",
-		_ if input.starts_with("&#x") => "html",
-		_ if input.starts_with("&#") => "html_d",
-		_ => "",
+		_ if input.starts_with("\\u{") => Format::Rust,
+		_ if input.starts_with("&#x") => Format::Html,
+		_ if input.starts_with("&#") => Format::HtmlDecimal,
+		_ => Format::Default,
 	};
 
 	let result = match format {
-		"html" => input
+		Format::Html => input
 			.split(";")
 			.filter_map(from_html)
-			.collect::<Result<String, String>>(),
-		"html_d" => input
+			.collect::<Result<String, Error>>(),
+		Format::HtmlDecimal => input
 			.split(";")
 			.filter_map(from_html_d)
-			.collect::<Result<String, String>>(),
-		"rust" => input
+			.collect::<Result<String, Error>>(),
+		Format::Rust => input
 			.split("}")
 			.filter_map(from_rust)
-			.collect::<Result<String, String>>(),
-		_ => input
+			.collect::<Result<String, Error>>(),
+		Format::Default => input
 			.split("\\u")
 			.filter_map(from_default)
-			.collect::<Result<String, String>>(),
+			.collect::<Result<String, Error>>(),
 	}?;
 
-	Ok(vec![result])
+	Ok(result.into_bytes())
 }
 
-fn format_html(data: EscapeUnicode) -> Result<String, String> {
+fn format_html(data: EscapeUnicode) -> Result<String, Error> {
 	Ok(data
 		.map(|x| match x {
 			'\\' => '&',
@@ -106,64 +145,62 @@ fn format_html(data: EscapeUnicode) -> Result<String, String> {
 		.collect())
 }
 
-fn from_html(data: &str) -> Option<Result<char, String>> {
+fn from_html(data: &str) -> Option<Result<char, Error>> {
 	if data.len() > 3 {
 		let r = u32::from_str_radix(&data[3..], 16)
-			.map_err(|_| "Convert failed".to_string())
-			.and_then(|x| std::char::from_u32(x).ok_or("Convert failed".to_string()));
+			.map_err(|_| Error::failed("Convert failed"))
+			.and_then(|x| std::char::from_u32(x).ok_or(Error::failed("Convert failed")));
 		Some(r)
 	} else {
 		None
 	}
 }
 
-fn format_html_d(data: EscapeUnicode) -> Result<String, String> {
+fn format_html_d(data: EscapeUnicode) -> Result<String, Error> {
 	let number = data
-		.filter_map(|x| match x {
-			'\\' | 'u' | '{' | '}' => None,
-			_ => Some(x),
-		})
+		.filter(|x| !matches!(x, '\\' | 'u' | '{' | '}'))
 		.collect::<String>();
-	let number = u64::from_str_radix(&number, 16).map_err(|_| "Convert failed")?;
+	let number = u64::from_str_radix(&number, 16).map_err(|_| Error::failed("Convert failed"))?;
 
 	Ok(format!("&#{};", number))
 }
 
-fn from_html_d(data: &str) -> Option<Result<char, String>> {
+fn from_html_d(data: &str) -> Option<Result<char, Error>> {
 	if data.len() > 2 {
-		let r = u32::from_str_radix(&data[2..], 10)
-			.map_err(|_| "Convert failed".to_string())
-			.and_then(|x| std::char::from_u32(x).ok_or("Convert failed".to_string()));
+		let r = data[2..]
+			.parse::<u32>()
+			.map_err(|_| Error::failed("Convert failed"))
+			.and_then(|x| std::char::from_u32(x).ok_or(Error::failed("Convert failed")));
 		Some(r)
 	} else {
 		None
 	}
 }
 
-fn format_rust(data: EscapeUnicode) -> Result<String, String> {
+fn format_rust(data: EscapeUnicode) -> Result<String, Error> {
 	Ok(data.collect())
 }
 
-fn from_rust(data: &str) -> Option<Result<char, String>> {
+fn from_rust(data: &str) -> Option<Result<char, Error>> {
 	if data.len() > 3 {
 		let r = u32::from_str_radix(&data[3..], 16)
-			.map_err(|_| "Convert failed".to_string())
-			.and_then(|x| std::char::from_u32(x).ok_or("Convert failed".to_string()));
+			.map_err(|_| Error::failed("Convert failed"))
+			.and_then(|x| std::char::from_u32(x).ok_or(Error::failed("Convert failed")));
 		Some(r)
 	} else {
 		None
 	}
 }
 
-fn format_default(data: EscapeUnicode) -> Result<String, String> {
+fn format_default(data: EscapeUnicode) -> Result<String, Error> {
 	Ok(data.filter(|x| x != &'{' && x != &'}').collect())
 }
 
-fn from_default(data: &str) -> Option<Result<char, String>> {
-	if data.len() > 0 {
-		let r = u32::from_str_radix(&data, 16)
-			.map_err(|_| "Convert failed".to_string())
-			.and_then(|x| std::char::from_u32(x).ok_or("Convert failed".to_string()));
+fn from_default(data: &str) -> Option<Result<char, Error>> {
+	if !data.is_empty() {
+		let r = u32::from_str_radix(data, 16)
+			.map_err(|_| Error::failed("Convert failed"))
+			.and_then(|x| std::char::from_u32(x).ok_or(Error::failed("Convert failed")));
 		Some(r)
 	} else {
 		None
@@ -192,7 +229,7 @@ mod cases {
 					},
 					Case {
 						desc: "HTML format".to_string(),
-						input: vec!["-f", "html", "abc"]
+						input: vec!["-F", "html", "abc"]
 							.into_iter()
 							.map(Into::into)
 							.collect(),
@@ -206,7 +243,7 @@ mod cases {
 					},
 					Case {
 						desc: "HTML decimal format".to_string(),
-						input: vec!["-f", "html_d", "abc"]
+						input: vec!["-F", "html_d", "abc"]
 							.into_iter()
 							.map(Into::into)
 							.collect(),
@@ -220,7 +257,7 @@ mod cases {
 					},
 					Case {
 						desc: "RUST format".to_string(),
-						input: vec!["-f", "rust", "abc"]
+						input: vec!["-F", "rust", "abc"]
 							.into_iter()
 							.map(Into::into)
 							.collect(),
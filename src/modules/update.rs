@@ -0,0 +1,276 @@
+use crate::error::Error;
+use crate::modules::base::Hex;
+use crate::modules::{base, hash};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::process;
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+	SubCommand::with_name("update")
+		.about("Update dtool to the latest GitHub release, the binary is verified by its SHA-256 checksum, curl is required for downloading")
+		.arg(
+			Arg::with_name("CHECK")
+				.long("check")
+				.help("Only check if a newer release is available")
+				.required(false),
+		)
+		.arg(
+			Arg::with_name("TAG")
+				.long("tag")
+				.help("Release tag to install instead of the latest, e.g. v0.10.0")
+				.takes_value(true)
+				.required(false),
+		)
+}
+
+struct Release {
+	tag: String,
+	/// Names and download URLs
+	assets: Vec<(String, String)>,
+}
+
+pub fn run(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let repo = env!("CARGO_PKG_REPOSITORY").trim_start_matches("https://github.com/");
+	let url = match matches.value_of("TAG") {
+		Some(tag) => format!(
+			"https://api.github.com/repos/{}/releases/tags/{}",
+			repo, tag
+		),
+		None => format!("https://api.github.com/repos/{}/releases/latest", repo),
+	};
+	let release = parse_release(&download(matches, &url)?)?;
+	let version = release.tag.trim_start_matches('v');
+
+	if matches.value_of("TAG").is_none() && !is_newer(version, CURRENT_VERSION) {
+		return Ok(vec![format!("Up to date: {}", CURRENT_VERSION)]);
+	}
+	if matches.is_present("CHECK") {
+		return Ok(vec![format!(
+			"Update available: {} -> {}",
+			CURRENT_VERSION, version
+		)]);
+	}
+
+	let (name, url) = select_asset(&release.assets, env::consts::ARCH, env::consts::OS)
+		.ok_or_else(|| {
+			Error::invalid(
+				"release",
+				format!(
+					"{}: no binary for {}-{}",
+					release.tag,
+					env::consts::ARCH,
+					env::consts::OS
+				),
+			)
+		})?;
+	let (checksum_name, checksum_url) =
+		select_checksum(&release.assets, name).ok_or_else(|| {
+			Error::invalid(
+				"release",
+				format!("{}: no checksum for {}", release.tag, name),
+			)
+		})?;
+
+	let binary = download(matches, url)?;
+	let checksums = download(matches, checksum_url)?;
+	let expected = parse_checksum(&String::from_utf8_lossy(&checksums), name)
+		.ok_or_else(|| Error::invalid("checksum", checksum_name))?;
+	let actual: String = Hex::from(hash::digest("sha2_256", binary.clone())?).into();
+	if actual.trim_start_matches("0x") != expected {
+		return Err(Error::Mismatched(actual));
+	}
+	base::verbose(matches, 1, || format!("checksum: {}", actual));
+
+	replace_exe(&binary)?;
+
+	Ok(vec![format!("Updated: {} -> {}", CURRENT_VERSION, version)])
+}
+
+/// Downloads with curl, which follows redirects and fails on HTTP errors
+fn download(matches: &ArgMatches, url: &str) -> Result<Vec<u8>, Error> {
+	base::verbose(matches, 1, || format!("download: {}", url));
+	let output = process::Command::new("curl")
+		.args(["-fsSL", "-H", "User-Agent: dtool", url])
+		.output()
+		.map_err(|e| Error::invalid("download", format!("curl: {}", e)))?;
+	if !output.status.success() {
+		return Err(Error::invalid(
+			"download",
+			format!(
+				"{}: {}",
+				url,
+				String::from_utf8_lossy(&output.stderr).trim()
+			),
+		));
+	}
+	Ok(output.stdout)
+}
+
+fn parse_release(data: &[u8]) -> Result<Release, Error> {
+	let value: Value = serde_json::from_slice(data).map_err(|e| Error::invalid("release", e))?;
+	let tag = value["tag_name"]
+		.as_str()
+		.ok_or(Error::invalid("release", "no tag"))?
+		.to_string();
+	let assets = value["assets"]
+		.as_array()
+		.into_iter()
+		.flatten()
+		.filter_map(|x| {
+			Some((
+				x["name"].as_str()?.to_string(),
+				x["browser_download_url"].as_str()?.to_string(),
+			))
+		})
+		.collect();
+	Ok(Release { tag, assets })
+}
+
+/// Compares dot separated numbers, pre-release suffixes are ignored
+fn is_newer(version: &str, current: &str) -> bool {
+	let numbers = |x: &str| {
+		x.split(['-', '+'])
+			.next()
+			.unwrap_or_default()
+			.split('.')
+			.map(|x| x.parse::<u64>().unwrap_or(0))
+			.collect::<Vec<_>>()
+	};
+	numbers(version) > numbers(current)
+}
+
+/// The binary of the platform, e.g. `dtool-x86_64-unknown-linux-gnu`, archives and checksums are skipped
+fn select_asset<'a>(
+	assets: &'a [(String, String)],
+	arch: &str,
+	os: &str,
+) -> Option<(&'a str, &'a str)> {
+	let os_names: &[&str] = match os {
+		"macos" => &["darwin", "macos"],
+		"windows" => &["windows"],
+		os => &[os],
+	};
+	assets
+		.iter()
+		.find(|(name, _)| {
+			let lower = name.to_lowercase();
+			lower.contains(arch)
+				&& os_names.iter().any(|x| lower.contains(x))
+				&& ![".sha256", ".txt", ".tar.gz", ".zip", ".asc", ".sig"]
+					.iter()
+					.any(|x| lower.ends_with(x))
+		})
+		.map(|(name, url)| (name.as_str(), url.as_str()))
+}
+
+/// `<binary>.sha256`, or a checksum list like `SHA256SUMS`
+fn select_checksum<'a>(assets: &'a [(String, String)], binary: &str) -> Option<(&'a str, &'a str)> {
+	let own = format!("{}.sha256", binary);
+	assets
+		.iter()
+		.find(|(name, _)| *name == own)
+		.or_else(|| {
+			assets.iter().find(|(name, _)| {
+				let lower = name.to_lowercase();
+				lower.starts_with("sha256sums") || lower == "checksums.txt"
+			})
+		})
+		.map(|(name, url)| (name.as_str(), url.as_str()))
+}
+
+/// Lower case hex of the binary, from `<hex>` or `<hex>  <name>` lines
+fn parse_checksum(checksums: &str, binary: &str) -> Option<String> {
+	checksums
+		.lines()
+		.filter_map(|line| {
+			let mut parts = line.split_whitespace();
+			let checksum = parts.next()?;
+			match parts.next() {
+				Some(name) if name.trim_start_matches('*') != binary => None,
+				_ => Some(checksum),
+			}
+		})
+		.find(|x| x.len() == 64 && x.chars().all(|c| c.is_ascii_hexdigit()))
+		.map(|x| x.to_lowercase())
+}
+
+/// Writes next to the current executable and renames over it,
+/// the running executable on Windows is moved aside first
+fn replace_exe(binary: &[u8]) -> Result<(), Error> {
+	let exe = env::current_exe().map_err(|e| Error::invalid("executable", e))?;
+	let exe_name = exe.display().to_string();
+	let new = exe.with_extension("new");
+
+	fs::write(&new, binary).map_err(|e| Error::io(&new.display().to_string(), e))?;
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		fs::set_permissions(&new, fs::Permissions::from_mode(0o755))
+			.map_err(|e| Error::io(&new.display().to_string(), e))?;
+	}
+
+	if cfg!(windows) {
+		fs::rename(&exe, exe.with_extension("old")).map_err(|e| Error::io(&exe_name, e))?;
+	}
+	fs::rename(&new, &exe).map_err(|e| Error::io(&exe_name, e))?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_newer() {
+		assert!(is_newer("0.10.0", "0.9.0"));
+		assert!(is_newer("1.0.0", "0.9.9"));
+		assert!(!is_newer("0.9.0", "0.9.0"));
+		assert!(!is_newer("0.9.0-beta", "0.9.0"));
+	}
+
+	#[test]
+	fn test_select() {
+		let assets = [
+			"dtool-x86_64-unknown-linux-gnu.tar.gz",
+			"dtool-x86_64-unknown-linux-gnu",
+			"dtool-x86_64-apple-darwin",
+			"dtool-x86_64-pc-windows-msvc.exe",
+			"SHA256SUMS",
+		]
+		.iter()
+		.map(|x| (x.to_string(), format!("https://example.com/{}", x)))
+		.collect::<Vec<_>>();
+
+		assert_eq!(
+			select_asset(&assets, "x86_64", "linux").map(|x| x.0),
+			Some("dtool-x86_64-unknown-linux-gnu")
+		);
+		assert_eq!(
+			select_asset(&assets, "x86_64", "macos").map(|x| x.0),
+			Some("dtool-x86_64-apple-darwin")
+		);
+		assert_eq!(select_asset(&assets, "aarch64", "linux"), None);
+		assert_eq!(
+			select_checksum(&assets, "dtool-x86_64-apple-darwin").map(|x| x.0),
+			Some("SHA256SUMS")
+		);
+	}
+
+	#[test]
+	fn test_parse_checksum() {
+		let a = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+		let b = "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824";
+
+		assert_eq!(parse_checksum(a, "dtool"), Some(a.to_string()));
+		let list = format!("{}  dtool-a\n{} *dtool-b\n", a, b);
+		assert_eq!(parse_checksum(&list, "dtool-a"), Some(a.to_string()));
+		assert_eq!(parse_checksum(&list, "dtool-b"), Some(b.to_lowercase()));
+		assert_eq!(parse_checksum(&list, "dtool-c"), None);
+	}
+}
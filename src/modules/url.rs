@@ -1,10 +1,14 @@
-use crate::modules::{base, Command, Module};
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
 use clap::{Arg, ArgMatches, SubCommand};
+use serde_json::{Map, Value};
+use url::Url;
 use urlencoding;
 
 pub fn module<'a, 'b>() -> Module<'a, 'b> {
 	Module {
-		desc: "URL encode / decode".to_string(),
+		desc: "URL encode / decode, query string / JSON conversion, URL parse / build".to_string(),
 		commands: commands(),
 		get_cases: cases::cases,
 	}
@@ -13,34 +17,516 @@ pub fn module<'a, 'b>() -> Module<'a, 'b> {
 pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
 	vec![
 		Command {
-			app: SubCommand::with_name("ue")
+			app: base::sub_command("ue")
 				.about("URL encode")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: ue,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ue),
+			stream: None,
 		},
 		Command {
-			app: SubCommand::with_name("ud")
+			app: base::sub_command("ud")
 				.about("URL decode")
-				.arg(Arg::with_name("INPUT").required(false).index(1)),
-			f: ud,
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ud),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("qs2j")
+				.about("Convert query string to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, qs2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2qs")
+				.about("Convert JSON to query string")
+				.arg(
+					Arg::with_name("ARRAY_FORMAT")
+						.long("array-format")
+						.short("a")
+						.help("Array format\nbrackets: a[]=1&a[]=2\nindices: a[0]=1&a[1]=2\nrepeat: a=1&a=2")
+						.takes_value(true)
+						.possible_values(&["brackets", "indices", "repeat"])
+						.default_value("brackets")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2qs),
+			stream: None,
+		},
+		Command {
+			app: SubCommand::with_name("url_parse")
+				.args(&base::output_args())
+				.arg(base::raw_arg())
+				.about("Parse URL into scheme, host, port, path, query and fragment")
+				.arg(
+					Arg::with_name("FORMAT")
+						.long("format")
+						.short("F")
+						.help("Output format\ntable: one component per line, query parameters as query.<key>\njson: JSON object, repeated query keys are collected to arrays")
+						.takes_value(true)
+						.possible_values(&["table", "json"])
+						.default_value("table")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, url_parse),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("url_build")
+				.about("Build URL from components, encoded as needed")
+				.arg(
+					Arg::with_name("SCHEME")
+						.long("scheme")
+						.help("Scheme, https if not given")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("USERNAME")
+						.long("username")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("PASSWORD")
+						.long("password")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("HOST")
+						.long("host")
+						.help("Host, the components are read from the JSON input if not given")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("PORT")
+						.long("port")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("PATH")
+						.long("path")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("QUERY")
+						.long("query")
+						.help("Query parameter as key=value, appended after those of the JSON input")
+						.takes_value(true)
+						.multiple(true)
+						.number_of_values(1)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("FRAGMENT")
+						.long("fragment")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(base::input_arg().help(
+					"JSON of the components, e.g. the output of url_parse -F json, the options override it",
+				))
+				.args(&base::file_args()),
+			f: run_url_build,
+			stream: None,
 		},
 	]
 }
 
-fn ue(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// `qs2j --minify`, `j2qs --array-format`, `url_parse --format` and the components of `url_build`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub minify: bool,
+	pub array_format: ArrayFormat,
+	pub format: Format,
+	/// https if neither given nor in the JSON input
+	pub scheme: Option<String>,
+	pub username: Option<String>,
+	pub password: Option<String>,
+	/// The JSON input is not required if given
+	pub host: Option<String>,
+	pub port: Option<String>,
+	pub path: Option<String>,
+	/// `key=value`, appended after those of the JSON input
+	pub query: Vec<String>,
+	pub fragment: Option<String>,
+}
+
+/// Keys of array values in query strings
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ArrayFormat {
+	/// `a[]=1&a[]=2`
+	#[default]
+	Brackets,
+	/// `a[0]=1&a[1]=2`
+	Indices,
+	/// `a=1&a=2`
+	Repeat,
+}
+
+/// Output format of `url_parse`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	/// One component per line, query parameters as `query.<key>`
+	#[default]
+	Table,
+	/// JSON object, repeated query keys are collected to arrays
+	Json,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let value = |name: &str| matches.value_of(name).map(String::from);
+		Ok(Options {
+			minify: matches.is_present("MINIFY"),
+			array_format: match matches.value_of("ARRAY_FORMAT") {
+				Some("indices") => ArrayFormat::Indices,
+				Some("repeat") => ArrayFormat::Repeat,
+				_ => ArrayFormat::Brackets,
+			},
+			format: match matches.value_of("FORMAT") {
+				Some("json") => Format::Json,
+				_ => Format::Table,
+			},
+			scheme: value("SCHEME"),
+			username: value("USERNAME"),
+			password: value("PASSWORD"),
+			host: value("HOST"),
+			port: value("PORT"),
+			path: value("PATH"),
+			query: matches
+				.values_of("QUERY")
+				.into_iter()
+				.flatten()
+				.map(String::from)
+				.collect(),
+			fragment: value("FRAGMENT"),
+		})
+	}
+}
+
+/// URL encode
+pub fn ue(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result = urlencoding::encode(base::text(input)?);
+
+	Ok(result.into_bytes())
+}
+
+/// URL decode
+pub fn ud(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let result =
+		urlencoding::decode(base::text(input)?).map_err(|_| Error::failed("Decode failed"))?;
+
+	Ok(result.into_bytes())
+}
+
+/// Convert query string to JSON
+pub fn qs2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	// the query part of the URL if any
+	let input = base::text(input)?.trim();
+	let input = match input.find('?') {
+		Some(index) => &input[index + 1..],
+		None => input,
+	};
+	let input = input.split('#').next().unwrap_or_default();
+
+	let mut value = Value::Object(Map::new());
+	for pair in input.split('&').filter(|x| !x.is_empty()) {
+		let mut pair = pair.splitn(2, '=');
+		let key = decode(pair.next().unwrap_or_default())?;
+		let v = decode(pair.next().unwrap_or_default())?;
+		insert(&mut value, &parse_key(&key), v);
+	}
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to query string
+pub fn j2qs(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let map = match value {
+		Value::Object(map) => map,
+		_ => return Err(Error::failed("Not object")),
+	};
+
+	let mut pairs = vec![];
+	for (k, v) in &map {
+		to_pairs(&urlencoding::encode(k), v, options.array_format, &mut pairs)?;
+	}
+
+	let result = pairs
+		.into_iter()
+		.map(|(k, v)| format!("{}={}", k, v))
+		.collect::<Vec<_>>()
+		.join("&");
+
+	Ok(result.into_bytes())
+}
+
+/// Parse URL into scheme, host, port, path, query and fragment
+pub fn url_parse(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let url = Url::parse(input.trim()).map_err(|e| Error::invalid("URL", e))?;
+
+	// known default ports are shown too, e.g. 443 for https
+	let port = url.port_or_known_default().map(|x| x.to_string());
+	let components = vec![
+		("scheme", Some(url.scheme().to_string())),
+		(
+			"username",
+			Some(url.username().to_string()).filter(|x| !x.is_empty()),
+		),
+		("password", url.password().map(Into::into)),
+		("host", url.host_str().map(Into::into)),
+		("port", port),
+		("path", Some(url.path().to_string())),
+	];
+	let query = url.query_pairs().into_owned().collect::<Vec<_>>();
+	let fragment = url
+		.fragment()
+		.map(|x| decode(x).unwrap_or_else(|_| x.to_string()));
+
+	let result = match options.format {
+		Format::Json => {
+			let mut value = Map::new();
+			for (k, v) in components {
+				value.insert(k.to_string(), v.map(Value::String).unwrap_or(Value::Null));
+			}
+			value.insert("port".to_string(), url.port_or_known_default().into());
+			let mut query_value = Value::Object(Map::new());
+			for (k, v) in query {
+				insert(&mut query_value, &[k], v);
+			}
+			value.insert("query".to_string(), query_value);
+			value.insert(
+				"fragment".to_string(),
+				fragment.map(Value::String).unwrap_or(Value::Null),
+			);
+			json::to_string_pretty(&Value::Object(value), 2)?
+		}
+		Format::Table => components
+			.into_iter()
+			.filter_map(|(k, v)| Some(format!("{}: {}", k, v?)))
+			.chain(
+				query
+					.into_iter()
+					.map(|(k, v)| format!("query.{}: {}", k, v)),
+			)
+			.chain(fragment.map(|x| format!("fragment: {}", x)))
+			.collect::<Vec<_>>()
+			.join("\n"),
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// The JSON input is not read if HOST is given without INPUT
+fn run_url_build(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let options = Options::from_matches(matches)?;
+	let input = if matches.is_present("HOST") && !matches.is_present("INPUT") {
+		String::new()
+	} else {
+		base::input_string(matches)?
+	};
+
+	url_build(input.as_bytes(), &options).and_then(base::lines)
+}
+
+/// Build URL from components, encoded as needed, the components are read from the JSON input
+/// (e.g. the output of `url_parse` with `Format::Json`) unless the input is empty and the host is given,
+/// the options override them
+pub fn url_build(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+	let components = if options.host.is_some() && input.trim().is_empty() {
+		Map::new()
+	} else {
+		match json::parse(input)? {
+			Value::Object(map) => map,
+			_ => return Err(Error::failed("Not object")),
+		}
+	};
+
+	// the option if present, otherwise the component of the JSON input
+	let component = |option: &Option<String>, key: &str| -> Option<String> {
+		option.clone().or_else(|| match components.get(key) {
+			Some(Value::String(x)) => Some(x.clone()),
+			Some(Value::Null) | None => None,
+			Some(x) => Some(x.to_string()),
+		})
+	};
+
+	let scheme = component(&options.scheme, "scheme").unwrap_or_else(|| "https".to_string());
+	let host = component(&options.host, "host").ok_or(Error::invalid("URL", "no host"))?;
+	let mut url =
+		Url::parse(&format!("{}://{}", scheme, host)).map_err(|e| Error::invalid("URL", e))?;
+
+	if let Some(username) = component(&options.username, "username") {
+		url.set_username(&username)
+			.map_err(|_| Error::invalid("URL", "username not allowed"))?;
+	}
+	url.set_password(component(&options.password, "password").as_deref())
+		.map_err(|_| Error::invalid("URL", "password not allowed"))?;
+	if let Some(port) = component(&options.port, "port") {
+		let port = port
+			.parse::<u16>()
+			.map_err(|_| Error::invalid("port", port))?;
+		url.set_port(Some(port))
+			.map_err(|_| Error::invalid("URL", "port not allowed"))?;
+	}
+	if let Some(path) = component(&options.path, "path") {
+		url.set_path(&path);
+	}
+
+	let mut query = vec![];
+	if let Some(Value::Object(map)) = components.get("query") {
+		for (k, v) in map {
+			let values = match v {
+				Value::Array(array) => array.iter().collect(),
+				v => vec![v],
+			};
+			for v in values {
+				let v = match v {
+					Value::String(x) => x.clone(),
+					Value::Null => "".to_string(),
+					x => x.to_string(),
+				};
+				query.push((k.clone(), v));
+			}
+		}
+	}
+	for pair in &options.query {
+		let mut pair = pair.splitn(2, '=');
+		let key = pair.next().unwrap_or_default();
+		query.push((key.to_string(), pair.next().unwrap_or_default().to_string()));
+	}
+	if !query.is_empty() {
+		url.query_pairs_mut().extend_pairs(query);
+	}
+
+	url.set_fragment(component(&options.fragment, "fragment").as_deref());
 
-	let result = urlencoding::encode(&input);
+	Ok(url.to_string().into_bytes())
+}
 
-	Ok(vec![result])
+fn decode(input: &str) -> Result<String, Error> {
+	urlencoding::decode(&input.replace('+', " ")).map_err(|_| Error::failed("Decode failed"))
 }
 
-fn ud(matches: &ArgMatches) -> Result<Vec<String>, String> {
-	let input = base::input_string(matches)?;
+/// `a[b][]` is parsed to ["a", "b", ""]
+fn parse_key(key: &str) -> Vec<String> {
+	match key.find('[') {
+		Some(index) if index > 0 && key.ends_with(']') => {
+			let mut result = vec![key[..index].to_string()];
+			result.extend(key[index + 1..key.len() - 1].split("][").map(Into::into));
+			result
+		}
+		_ => vec![key.to_string()],
+	}
+}
+
+/// Empty or numeric segments create arrays, repeated keys are collected to arrays
+fn insert(target: &mut Value, path: &[String], value: String) {
+	let (segment, rest) = match path.split_first() {
+		Some(x) => x,
+		None => return,
+	};
+
+	let new_container = |rest: &[String]| match rest.first() {
+		Some(x) if x.is_empty() || x.parse::<usize>().is_ok() => Value::Array(vec![]),
+		Some(_) => Value::Object(Map::new()),
+		None => Value::String(value.clone()),
+	};
 
-	let result = urlencoding::decode(&input).map_err(|_| "Decode failed")?;
+	// non-numeric segment on array converts it to object
+	if let Value::Array(array) = target {
+		if !segment.is_empty() && segment.parse::<usize>().is_err() {
+			let map = array
+				.drain(..)
+				.enumerate()
+				.map(|(i, x)| (i.to_string(), x))
+				.collect();
+			*target = Value::Object(map);
+		}
+	}
 
-	Ok(vec![result])
+	match target {
+		Value::Object(map) => match (map.get_mut(segment), rest.is_empty()) {
+			(Some(Value::Array(array)), true) => array.push(Value::String(value)),
+			(Some(existing), true) => {
+				let first = existing.take();
+				*existing = Value::Array(vec![first, Value::String(value)]);
+			}
+			(Some(existing), false) => insert(existing, rest, value),
+			(None, _) => {
+				let mut child = new_container(rest);
+				insert(&mut child, rest, value);
+				map.insert(segment.clone(), child);
+			}
+		},
+		Value::Array(array) => {
+			let index = segment.parse::<usize>().ok();
+			match index.and_then(|i| array.get_mut(i)) {
+				Some(existing) if !rest.is_empty() => insert(existing, rest, value),
+				_ => {
+					let mut child = new_container(rest);
+					insert(&mut child, rest, value);
+					array.push(child);
+				}
+			}
+		}
+		_ => (),
+	}
+}
+
+fn to_pairs(
+	key: &str,
+	value: &Value,
+	array_format: ArrayFormat,
+	pairs: &mut Vec<(String, String)>,
+) -> Result<(), Error> {
+	match value {
+		Value::Object(map) => {
+			for (k, v) in map {
+				let key = format!("{}[{}]", key, urlencoding::encode(k));
+				to_pairs(&key, v, array_format, pairs)?;
+			}
+		}
+		Value::Array(array) => {
+			for (i, v) in array.iter().enumerate() {
+				let key = match array_format {
+					ArrayFormat::Indices => format!("{}[{}]", key, i),
+					ArrayFormat::Repeat => key.to_string(),
+					ArrayFormat::Brackets => format!("{}[]", key),
+				};
+				to_pairs(&key, v, array_format, pairs)?;
+			}
+		}
+		Value::String(v) => pairs.push((key.to_string(), urlencoding::encode(v))),
+		Value::Null => pairs.push((key.to_string(), "".to_string())),
+		v => pairs.push((key.to_string(), json::to_string(v)?)),
+	}
+	Ok(())
 }
 
 mod cases {
@@ -71,6 +557,186 @@ mod cases {
 					since: "0.1.0".to_string(),
 				}],
 			),
+			(
+				"qs2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "'a=1&b=x+y&b=%7Ez&c[d]=2&c[e][]=3&c[e][]=4'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":"1","b":["x y","~z"],"c":{"d":"2","e":["3","4"]}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "URL".to_string(),
+						input: vec!["-m", "'https://example.com/?a[0]=x&a[1]=y&a[x]=z#hash'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":{"0":"x","1":"y","x":"z"}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"j2qs",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"a":1,"b":["x y","~z"],"c":{"d":null}}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a=1&b[]=x%20y&b[]=~z&c[d]="]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-a", "repeat", r#"'{"a":[1,2]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a=1&a=2"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-a", "indices", r#"'{"a":[{"b":1}]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a[0][b]=1"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"url_parse",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'https://user@example.com/a%20b?x=1&y=%7Ez&x=2#top'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"scheme: https",
+							"username: user",
+							"host: example.com",
+							"port: 443",
+							"path: /a%20b",
+							"query.x: 1",
+							"query.y: ~z",
+							"query.x: 2",
+							"fragment: top",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "JSON".to_string(),
+						input: vec!["-F", "json", "'http://example.com:8080/?a=1&a=2&b=x+y'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"{",
+							r#"  "scheme": "http","#,
+							r#"  "username": null,"#,
+							r#"  "password": null,"#,
+							r#"  "host": "example.com","#,
+							r#"  "port": 8080,"#,
+							r#"  "path": "/","#,
+							r#"  "query": {"#,
+							r#"    "a": ["#,
+							r#"      "1","#,
+							r#"      "2""#,
+							r#"    ],"#,
+							r#"    "b": "x y""#,
+							r#"  },"#,
+							r#"  "fragment": null"#,
+							"}",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"url_build",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"--host",
+							"example.com",
+							"--path",
+							"'/a b'",
+							"--query",
+							"'q=x&y'",
+							"--query",
+							"lang=中文",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["https://example.com/a%20b?q=x%26y&lang=%E4%B8%AD%E6%96%87"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "JSON".to_string(),
+						input: vec![
+							"--port",
+							"8080",
+							r#"'{"scheme":"http","host":"example.com","query":{"a":["1","2"],"b":"x y"},"fragment":"top"}'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["http://example.com:8080/?a=1&a=2&b=x+y#top"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
 		]
 		.into_iter()
 		.collect()
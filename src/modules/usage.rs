@@ -1,18 +1,20 @@
+use crate::error::Error;
 use crate::modules::Module;
 use clap::{App, Arg, ArgMatches, SubCommand};
-use escaper;
 use linked_hash_map::LinkedHashMap;
 use madato::mk_table;
 use prettytable::{format, Cell, Row, Table};
 use regex::Captures;
-use std::cmp::min;
+
+/// (module desc, [(sub command, about, case desc, since, example lines)])
+type UsageInfo = Vec<(String, Vec<(String, String, String, String, Vec<String>)>)>;
 
 pub fn app<'a, 'b>() -> App<'a, 'b> {
 	SubCommand::with_name("usage").about("Show usage")
 		.arg(
 			Arg::with_name("FORMAT")
 				.long("format")
-				.short("f").help("Output format\n<default>: term table format\nmarkdown: markdown format\nplain: term plain format")
+				.short("f").help("Output format\n<default>: term table format\nmarkdown: markdown format\nplain: term plain format\ncommands: example command lines, ready to copy")
 				.takes_value(true)
 				.required(false))
 		.arg(
@@ -21,12 +23,20 @@ pub fn app<'a, 'b>() -> App<'a, 'b> {
 				.short("s").help("")
 				.takes_value(true)
 				.required(false))
+		.arg(
+			Arg::with_name("KEYWORD")
+				.help("Search keyword, same as --search")
+				.conflicts_with("SEARCH")
+				.required(false)
+				.index(1))
 }
 
-pub fn run<'a, 'b>(matches: &ArgMatches, modules: &Vec<Module>) -> Result<Vec<String>, String> {
+pub fn run(matches: &ArgMatches, modules: &Vec<Module>) -> Result<Vec<String>, Error> {
 	let usage_info = get_usage_info(modules);
 
-	let search = matches.value_of("SEARCH");
+	let search = matches
+		.value_of("SEARCH")
+		.or_else(|| matches.value_of("KEYWORD"));
 
 	let usage_info = match search {
 		Some(search) => {
@@ -43,7 +53,7 @@ pub fn run<'a, 'b>(matches: &ArgMatches, modules: &Vec<Module>) -> Result<Vec<St
 							row.iter().any(|&cell| cell.to_lowercase().contains(search))
 						})
 						.collect::<Vec<(String, String, String, String, Vec<String>)>>();
-					if commands.len() > 0 {
+					if !commands.is_empty() {
 						Some((title, commands))
 					} else {
 						None
@@ -60,13 +70,12 @@ pub fn run<'a, 'b>(matches: &ArgMatches, modules: &Vec<Module>) -> Result<Vec<St
 	match format {
 		Some("markdown") => markdown_output(usage_info),
 		Some("plain") => term_plain_output(usage_info),
+		Some("commands") => commands_output(usage_info),
 		_ => term_table_output(usage_info),
 	}
 }
 
-fn term_plain_output(
-	usage_info: Vec<(String, Vec<(String, String, String, String, Vec<String>)>)>,
-) -> Result<Vec<String>, String> {
+fn term_plain_output(usage_info: UsageInfo) -> Result<Vec<String>, Error> {
 	const WIDTH: usize = 100;
 
 	let mut result = vec!["Usage".to_string()];
@@ -80,7 +89,7 @@ fn term_plain_output(
 	let body = body.into_iter().fold(Vec::new(), |mut vec, items| {
 		let tmp = vec![format!("# {}", items.0), items.1, items.2, items.3]
 			.into_iter()
-			.filter(|x| x.len() > 0)
+			.filter(|x| !x.is_empty())
 			.map(|x| add_enter(x, WIDTH, false))
 			.chain(items.4.into_iter().map(|x| add_enter(x, WIDTH, true)))
 			.collect::<Vec<String>>();
@@ -94,9 +103,26 @@ fn term_plain_output(
 	Ok(result)
 }
 
-fn term_table_output(
-	usage_info: Vec<(String, Vec<(String, String, String, String, Vec<String>)>)>,
-) -> Result<Vec<String>, String> {
+fn commands_output(usage_info: UsageInfo) -> Result<Vec<String>, Error> {
+	let result = usage_info
+		.into_iter()
+		.flat_map(|(_, commands)| commands)
+		.flat_map(|item| {
+			let comment = vec![item.0, item.1, item.2]
+				.into_iter()
+				.filter(|x| !x.is_empty())
+				.collect::<Vec<String>>()
+				.join(": ");
+			// the first line of the example is the command line
+			let command = item.4[0].trim_start_matches("$ ").to_string();
+			vec![format!("# {}", comment), command]
+		})
+		.collect();
+
+	Ok(result)
+}
+
+fn term_table_output(usage_info: UsageInfo) -> Result<Vec<String>, Error> {
 	const DESC_WIDTH: usize = 40;
 	const EXAMPLE_WIDTH: usize = 60;
 
@@ -114,7 +140,7 @@ fn term_table_output(
 				let sub_command = item.0;
 				let desc = vec![item.1, item.2, item.3]
 					.into_iter()
-					.filter(|x| x.len() > 0)
+					.filter(|x| !x.is_empty())
 					.map(|x| add_enter(x.to_owned(), DESC_WIDTH, false))
 					.collect::<Vec<String>>()
 					.join("\n");
@@ -139,9 +165,7 @@ fn term_table_output(
 	Ok(result)
 }
 
-fn markdown_output(
-	usage_info: Vec<(String, Vec<(String, String, String, String, Vec<String>)>)>,
-) -> Result<Vec<String>, String> {
+fn markdown_output(usage_info: UsageInfo) -> Result<Vec<String>, Error> {
 	const DESC_WIDTH: usize = 40;
 	const EXAMPLE_WIDTH: usize = 60;
 
@@ -169,7 +193,7 @@ fn markdown_output(
 		cell.replace("\n", "<br>")
 	};
 
-	let header = vec!["Sub command", "Desc", "Example"];
+	let header = ["Sub command", "Desc", "Example"];
 
 	for module in usage_info {
 		result.push(format!("## {}", module.0));
@@ -181,7 +205,7 @@ fn markdown_output(
 				let sub_command = item.0;
 				let desc = vec![item.1, item.2, item.3]
 					.into_iter()
-					.filter(|x| x.len() > 0)
+					.filter(|x| !x.is_empty())
 					.map(|x| add_enter(x.to_owned(), DESC_WIDTH, false))
 					.collect::<Vec<String>>()
 					.join("\n");
@@ -216,9 +240,7 @@ fn markdown_output(
 
 /// Get usage info
 /// Sub command, Sub command desc, Case desc, Since, Example
-fn get_usage_info(
-	modules: &Vec<Module>,
-) -> Vec<(String, Vec<(String, String, String, String, Vec<String>)>)> {
+fn get_usage_info(modules: &Vec<Module>) -> UsageInfo {
 	let mut result = vec![];
 	for module in modules {
 		let module_desc = module.desc.clone();
@@ -274,7 +296,12 @@ fn add_enter(data: String, len: usize, add_escape: bool) -> String {
 	let mut v = vec![];
 	let mut cur = data.as_str();
 	while !cur.is_empty() {
-		let (chunk, rest) = cur.split_at(min(len, cur.len()));
+		let mid = cur
+			.char_indices()
+			.nth(len)
+			.map(|(i, _)| i)
+			.unwrap_or(cur.len());
+		let (chunk, rest) = cur.split_at(mid);
 		v.push(chunk);
 		cur = rest;
 	}
@@ -289,7 +316,7 @@ fn anchor(title: &str) -> String {
 			if x.is_whitespace() || x == '-' {
 				Some('-')
 			} else if x.is_alphanumeric() {
-				Some(x.to_lowercase().nth(0).expect("qed"))
+				Some(x.to_lowercase().next().expect("qed"))
 			} else {
 				None
 			}
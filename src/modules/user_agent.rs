@@ -0,0 +1,218 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches, SubCommand};
+use serde_json::{Map, Value};
+use woothee::parser::Parser;
+
+const UNKNOWN: &str = "UNKNOWN";
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "User-Agent parse".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: SubCommand::with_name("ua_parse")
+			.args(&base::output_args())
+			.arg(base::raw_arg())
+			.about("Parse User-Agent into browser, version, OS and device class")
+			.arg(
+				Arg::with_name("FORMAT")
+					.long("format")
+					.short("F")
+					.help("Output format\nplain: one field per line, unknown fields are left out\njson: JSON object, unknown fields are null")
+					.takes_value(true)
+					.possible_values(&["plain", "json"])
+					.default_value("plain")
+					.required(false),
+			)
+			.args(&base::input_args()),
+		f: |matches| base::convert(matches, ua_parse),
+		stream: None,
+	}]
+}
+
+/// Options of `ua_parse`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub format: Format,
+}
+
+/// Output format
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	/// One field per line, unknown fields are left out
+	#[default]
+	Plain,
+	/// JSON object, unknown fields are null
+	Json,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let format = match matches.value_of("FORMAT") {
+			Some("json") => Format::Json,
+			_ => Format::Plain,
+		};
+
+		Ok(Options { format })
+	}
+}
+
+/// Parse User-Agent into browser, version, OS and device class
+pub fn ua_parse(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let fields = parse(base::text(input)?.trim());
+
+	let result = match options.format {
+		Format::Json => {
+			let value = fields
+				.into_iter()
+				.map(|(k, v)| (k.to_string(), v.map(Value::String).unwrap_or(Value::Null)))
+				.collect::<Map<_, _>>();
+			json::to_string_pretty(&Value::Object(value), 2)?
+		}
+		Format::Plain => fields
+			.into_iter()
+			.filter_map(|(k, v)| Some(format!("{}: {}", k, v?)))
+			.collect::<Vec<_>>()
+			.join("\n"),
+	};
+
+	Ok(result.into_bytes())
+}
+
+fn parse(input: &str) -> Vec<(&'static str, Option<String>)> {
+	let known = |x: &str| Some(x.to_string()).filter(|x| !x.is_empty() && x != UNKNOWN);
+
+	let result = Parser::new().parse(input).unwrap_or_default();
+	let device = device(input, result.category);
+
+	vec![
+		("browser", known(result.name)),
+		("version", known(result.version)),
+		("os", known(result.os)),
+		("os_version", known(&result.os_version)),
+		("device", known(device)),
+		("vendor", known(result.vendor)),
+	]
+}
+
+/// desktop, mobile, tablet, feature phone, appliance (e.g. game consoles), bot or other
+fn device(input: &str, category: &str) -> &'static str {
+	// tablets are smartphones for woothee
+	let tablet = input.contains("iPad")
+		|| input.contains("Tablet")
+		|| (input.contains("Android") && !input.contains("Mobile"));
+	match category {
+		"pc" => "desktop",
+		"smartphone" if tablet => "tablet",
+		"smartphone" => "mobile",
+		"mobilephone" => "feature phone",
+		"appliance" => "appliance",
+		"crawler" => "bot",
+		"misc" => "other",
+		_ => UNKNOWN,
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"ua_parse",
+			vec![
+				Case {
+					desc: "".to_string(),
+					input: vec!["'Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec![
+						"browser: Chrome",
+						"version: 120.0.0.0",
+						"os: Windows 10",
+						"os_version: NT 10.0",
+						"device: desktop",
+						"vendor: Google",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "JSON".to_string(),
+					input: vec![
+						"-F",
+						"json",
+						"'Mozilla/5.0 (iPad; CPU OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1'",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					output: vec![
+						"{",
+						r#"  "browser": "Safari","#,
+						r#"  "version": "17.1","#,
+						r#"  "os": "iPad","#,
+						r#"  "os_version": "17.1","#,
+						r#"  "device": "tablet","#,
+						r#"  "vendor": "Apple""#,
+						"}",
+					]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+				Case {
+					desc: "Bot".to_string(),
+					input: vec!["'Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["browser: Googlebot", "device: bot"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				},
+			],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_device() {
+		let android = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+		let android_tablet = "Mozilla/5.0 (Linux; Android 13; SM-X700) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+		assert_eq!(device(android, "smartphone"), "mobile");
+		assert_eq!(device(android_tablet, "smartphone"), "tablet");
+		assert_eq!(device("curl/8.0", "UNKNOWN"), UNKNOWN);
+	}
+}
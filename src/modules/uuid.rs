@@ -0,0 +1,508 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use chrono::{TimeZone, Utc};
+use clap::{Arg, ArgMatches, SubCommand};
+use uuid::{Uuid, Variant, Version as UuidVersion};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "UUID generate / parse".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: SubCommand::with_name("uuid_gen")
+.args(&base::output_args())
+.arg(base::raw_arg())
+				.about("UUID generate")
+				.arg(
+					Arg::with_name("TYPE")
+						.long("type")
+						.short("t")
+						.help("UUID version\n1: time and node (random, multicast bit set)\n4: random\n5: SHA-1 of the namespace and the name (INPUT)\n7: Unix time in milliseconds and random, sortable")
+						.takes_value(true)
+						.possible_values(&["1", "4", "5", "7"])
+						.default_value("4")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("NAMESPACE")
+						.long("namespace")
+						.short("n")
+						.help("Namespace of v5, dns, url, oid, x500 or a UUID")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("FORMAT")
+						.long("format")
+						.short("F")
+						.help("Output format\nhyphenated: 67e55044-10b1-426f-9247-bb680e5fe0c8\nsimple: 67e5504410b1426f9247bb680e5fe0c8\nbraced: {67e55044-10b1-426f-9247-bb680e5fe0c8}\nurn: urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8")
+						.takes_value(true)
+						.possible_values(&["hyphenated", "simple", "braced", "urn"])
+						.default_value("hyphenated")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("UPPERCASE")
+						.long("uppercase")
+						.short("u")
+						.help("Uppercase hex digits"),
+				)
+				.arg(
+					Arg::with_name("COUNT")
+						.long("count")
+						.short("c")
+						.help("Number of UUIDs")
+						.takes_value(true)
+						.default_value("1")
+						.required(false),
+				)
+				.arg(base::input_arg().help("Name of v5")).args(&base::file_args()),
+			f: run_uuid_gen,
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("uuid_parse")
+				.about("UUID parse, the version, the variant, and the timestamp, clock sequence, node or random fields of v1, v6 and v7")
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, uuid_parse),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the UUID commands, uuid_parse has none
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub version: Version,
+	pub format: Format,
+	pub uppercase: bool,
+	pub count: usize,
+}
+
+/// UUID version
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Version {
+	/// Time and node (random, multicast bit set)
+	V1,
+	/// Random
+	#[default]
+	V4,
+	/// SHA-1 of the namespace and the name (the input)
+	V5 { namespace: Uuid },
+	/// Unix time in milliseconds and random, sortable
+	V7,
+}
+
+/// Output format
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	/// `67e55044-10b1-426f-9247-bb680e5fe0c8`
+	#[default]
+	Hyphenated,
+	/// `67e5504410b1426f9247bb680e5fe0c8`
+	Simple,
+	/// `{67e55044-10b1-426f-9247-bb680e5fe0c8}`
+	Braced,
+	/// `urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8`
+	Urn,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			version: Version::default(),
+			format: Format::default(),
+			uppercase: false,
+			count: 1,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let count = matches
+			.value_of("COUNT")
+			.unwrap_or("1")
+			.parse::<usize>()
+			.map_err(|_| Error::invalid("count", ""))?;
+		let version = match matches.value_of("TYPE") {
+			Some("1") => Version::V1,
+			Some("5") => {
+				let namespace = matches.value_of("NAMESPACE").ok_or(Error::invalid(
+					"namespace",
+					"--namespace is required for v5",
+				))?;
+				Version::V5 {
+					namespace: namespace_uuid(namespace)?,
+				}
+			}
+			Some("7") => Version::V7,
+			_ => Version::V4,
+		};
+		let format = match matches.value_of("FORMAT") {
+			Some("simple") => Format::Simple,
+			Some("braced") => Format::Braced,
+			Some("urn") => Format::Urn,
+			_ => Format::Hyphenated,
+		};
+		Ok(Options {
+			version,
+			format,
+			uppercase: matches.is_present("UPPERCASE"),
+			count,
+		})
+	}
+}
+
+/// Only v5 reads the input, the name
+fn run_uuid_gen(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let options = Options::from_matches(matches)?;
+	let input = match options.version {
+		Version::V5 { .. } => base::input_string(matches)?,
+		_ => String::new(),
+	};
+	uuid_gen(input.as_bytes(), &options).and_then(base::lines)
+}
+
+/// UUID generate, the input is the name of v5
+pub fn uuid_gen(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let generate: Box<dyn Fn() -> Uuid> = match options.version {
+		Version::V1 => {
+			// a random node ID has the multicast bit set, RFC 4122 4.5
+			let mut node = [0u8; 6];
+			node.copy_from_slice(&Uuid::new_v4().as_bytes()[..6]);
+			node[0] |= 0x01;
+			Box::new(move || Uuid::now_v1(&node))
+		}
+		Version::V5 { namespace } => {
+			let name = input.to_vec();
+			Box::new(move || Uuid::new_v5(&namespace, &name))
+		}
+		Version::V7 => Box::new(Uuid::now_v7),
+		Version::V4 => Box::new(Uuid::new_v4),
+	};
+
+	let result = (0..options.count)
+		.map(|_| format_uuid(&generate(), options.format, options.uppercase))
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// UUID parse, the version, the variant, and the timestamp, clock sequence, node or random fields of v1, v6 and v7
+pub fn uuid_parse(input: &[u8], _: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?.trim();
+
+	// hyphenated, simple, braced or urn
+	let uuid =
+		Uuid::parse_str(input).map_err(|e| Error::invalid("UUID", format!("{}: {}", input, e)))?;
+
+	let result = parse(&uuid);
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn parse(uuid: &Uuid) -> Vec<String> {
+	let bytes = uuid.as_bytes();
+	let version = match uuid.get_version() {
+		Some(UuidVersion::Nil) => "nil".to_string(),
+		Some(UuidVersion::Max) => "max".to_string(),
+		_ => format!(
+			"{} ({})",
+			uuid.get_version_num(),
+			version_desc(uuid.get_version())
+		),
+	};
+	let variant = match uuid.get_variant() {
+		Variant::NCS => "NCS (reserved)",
+		Variant::RFC4122 => "RFC 4122",
+		Variant::Microsoft => "Microsoft (reserved)",
+		_ => "future (reserved)",
+	};
+
+	let mut result = vec![
+		format!("uuid: {}", uuid.hyphenated()),
+		format!("version: {}", version),
+		format!("variant: {}", variant),
+	];
+
+	match uuid.get_version() {
+		Some(UuidVersion::Mac) | Some(UuidVersion::SortMac) => {
+			if let Some(timestamp) = uuid.get_timestamp() {
+				let (seconds, nanos) = timestamp.to_unix();
+				result.push(format!("time: {}", time(seconds, nanos, 7)));
+			}
+			let clock_seq = (((bytes[8] & 0x3f) as u16) << 8) | bytes[9] as u16;
+			// a random node ID has the multicast bit set, RFC 4122 4.5
+			let node_type = match bytes[10] & 0x01 {
+				0 => "MAC",
+				_ => "random",
+			};
+			let node = bytes[10..16]
+				.iter()
+				.map(|x| format!("{:02x}", x))
+				.collect::<Vec<_>>()
+				.join(":");
+			result.push(format!("clock_seq: {}", clock_seq));
+			result.push(format!("node: {} ({})", node, node_type));
+		}
+		Some(UuidVersion::SortRand) => {
+			if let Some(timestamp) = uuid.get_timestamp() {
+				let (seconds, nanos) = timestamp.to_unix();
+				result.push(format!("time: {}", time(seconds, nanos, 3)));
+			}
+			let rand_a = (((bytes[6] & 0x0f) as u16) << 8) | bytes[7] as u16;
+			let rand_b = bytes[8..16]
+				.iter()
+				.fold(0u64, |acc, x| (acc << 8) | *x as u64)
+				& 0x3fff_ffff_ffff_ffff;
+			result.push(format!("rand_a: {:03x}", rand_a));
+			result.push(format!("rand_b: {:016x}", rand_b));
+		}
+		_ => (),
+	}
+
+	result
+}
+
+fn version_desc(version: Option<UuidVersion>) -> &'static str {
+	match version {
+		Some(UuidVersion::Mac) => "time and node",
+		Some(UuidVersion::Dce) => "DCE security",
+		Some(UuidVersion::Md5) => "MD5 of a namespace and a name",
+		Some(UuidVersion::Random) => "random",
+		Some(UuidVersion::Sha1) => "SHA-1 of a namespace and a name",
+		Some(UuidVersion::SortMac) => "reordered time and node, sortable",
+		Some(UuidVersion::SortRand) => "Unix time in milliseconds and random, sortable",
+		Some(UuidVersion::Custom) => "custom",
+		_ => "unknown",
+	}
+}
+
+/// UTC with the precision of the version, e.g. `2024-06-01 12:00:00.123 UTC`
+fn time(seconds: u64, nanos: u32, digits: u32) -> String {
+	let fraction = nanos / 10u32.pow(9 - digits);
+	format!(
+		"{}.{:0width$} UTC",
+		Utc.timestamp(seconds as i64, 0).format("%Y-%m-%d %H:%M:%S"),
+		fraction,
+		width = digits as usize
+	)
+}
+
+/// Predefined namespaces of RFC 4122 Appendix C, or a UUID
+fn namespace_uuid(namespace: &str) -> Result<Uuid, Error> {
+	match namespace {
+		"dns" => Ok(Uuid::NAMESPACE_DNS),
+		"url" => Ok(Uuid::NAMESPACE_URL),
+		"oid" => Ok(Uuid::NAMESPACE_OID),
+		"x500" => Ok(Uuid::NAMESPACE_X500),
+		_ => Uuid::parse_str(namespace).map_err(|_| Error::invalid("namespace", namespace)),
+	}
+}
+
+fn format_uuid(uuid: &Uuid, format: Format, uppercase: bool) -> String {
+	let mut buffer = Uuid::encode_buffer();
+	let result = match (format, uppercase) {
+		(Format::Simple, false) => uuid.simple().encode_lower(&mut buffer),
+		(Format::Simple, true) => uuid.simple().encode_upper(&mut buffer),
+		(Format::Braced, false) => uuid.braced().encode_lower(&mut buffer),
+		(Format::Braced, true) => uuid.braced().encode_upper(&mut buffer),
+		(Format::Urn, false) => uuid.urn().encode_lower(&mut buffer),
+		(Format::Urn, true) => uuid.urn().encode_upper(&mut buffer),
+		(Format::Hyphenated, false) => uuid.hyphenated().encode_lower(&mut buffer),
+		(Format::Hyphenated, true) => uuid.hyphenated().encode_upper(&mut buffer),
+	};
+	result.to_string()
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"uuid_gen",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: Vec::<String>::new(),
+						output: vec!["67e55044-10b1-426f-9247-bb680e5fe0c8"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Time-ordered v7".to_string(),
+						input: vec!["-t", "7", "-c", "2"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"018fd3a5-0b3c-7a41-9b5e-3f0c2d6e8a11",
+							"018fd3a5-0b3c-7a42-8c1f-7d2a9e4b5c03",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: false,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Name-based v5".to_string(),
+						input: vec!["-t", "5", "-n", "dns", "example.com"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["cfbff0d1-9375-5685-968c-48ce8b15ae17"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Uppercase, braced".to_string(),
+						input: vec![
+							"-t",
+							"5",
+							"-n",
+							"url",
+							"-F",
+							"braced",
+							"-u",
+							"https://example.com",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["{4FD35A71-71EF-5A55-A9D9-AA75C889A6D0}"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"uuid_parse",
+				vec![
+					Case {
+						desc: "Time-ordered v7".to_string(),
+						input: vec!["018fd3a5-0b3c-7a41-9b5e-3f0c2d6e8a11"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"uuid: 018fd3a5-0b3c-7a41-9b5e-3f0c2d6e8a11",
+							"version: 7 (Unix time in milliseconds and random, sortable)",
+							"variant: RFC 4122",
+							"time: 2024-06-01 11:52:39.996 UTC",
+							"rand_a: a41",
+							"rand_b: 1b5e3f0c2d6e8a11",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Time and node v1".to_string(),
+						input: vec!["{C232AB00-9414-11EC-B3C8-9F6BDECED846}"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"uuid: c232ab00-9414-11ec-b3c8-9f6bdeced846",
+							"version: 1 (time and node)",
+							"variant: RFC 4122",
+							"time: 2022-02-22 19:22:22.0000000 UTC",
+							"clock_seq: 13256",
+							"node: 9f:6b:de:ce:d8:46 (random)",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Random v4".to_string(),
+						input: vec!["urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![
+							"uuid: 67e55044-10b1-426f-9247-bb680e5fe0c8",
+							"version: 4 (random)",
+							"variant: RFC 4122",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_uuid_gen() {
+		for (version, num, count) in [
+			(Version::V1, 1, 3),
+			(Version::V4, 4, 2),
+			(Version::V7, 7, 5),
+		] {
+			let options = Options {
+				version,
+				count,
+				..Options::default()
+			};
+			let result = base::lines(uuid_gen(&[], &options).unwrap()).unwrap();
+			assert_eq!(result.len(), count);
+			for uuid in &result {
+				let uuid = Uuid::parse_str(uuid).unwrap();
+				assert_eq!(uuid.get_version_num(), num);
+			}
+			if version == Version::V7 {
+				let mut sorted = result.clone();
+				sorted.sort();
+				assert_eq!(sorted, result);
+			}
+		}
+	}
+}
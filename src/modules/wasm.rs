@@ -0,0 +1,199 @@
+use crate::config;
+use crate::error::Error;
+use crate::modules::base;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use lazy_static::lazy_static;
+use std::fs;
+use std::path::PathBuf;
+use wasmi::{Config, Engine, Linker, Module, Store};
+
+lazy_static! {
+	/// `<name>.wasm` in `$XDG_CONFIG_HOME/dtool/plugins` (default `~/.config/dtool/plugins`)
+	static ref WASM_PLUGINS: Vec<(String, PathBuf)> = discover();
+	static ref WASM_PLUGIN_ABOUTS: Vec<String> = WASM_PLUGINS
+		.iter()
+		.map(|(_, path)| format!("WASM plugin {}", path.display()))
+		.collect();
+}
+
+pub fn apps<'a, 'b>() -> Vec<App<'a, 'b>> {
+	WASM_PLUGINS
+		.iter()
+		.zip(WASM_PLUGIN_ABOUTS.iter())
+		.map(|((name, _), about)| {
+			SubCommand::with_name(name)
+				.about(about.as_str())
+				.arg(
+					Arg::with_name("OUTPUT_FILE")
+						.long("output")
+						.short("o")
+						.value_name("FILE")
+						.help("Write output to file")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(Arg::with_name("INPUT").required(false).index(1))
+		})
+		.collect()
+}
+
+pub fn contains(name: &str) -> bool {
+	WASM_PLUGINS.iter().any(|(plugin, _)| plugin == name)
+}
+
+/// Converts the input (raw bytes) by the WASM plugin, the output is written as raw bytes
+pub fn run(name: &str, matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let path = WASM_PLUGINS
+		.iter()
+		.find(|(plugin, _)| plugin == name)
+		.map(|(_, path)| path)
+		.ok_or(Error::invalid("plugin", ""))?;
+
+	let input = base::input_bytes(matches)?;
+
+	let wasm = fs::read(path)
+		.map_err(|e| Error::invalid("plugin", format!("{}: {}", path.display(), e)))?;
+
+	let output = convert(&wasm, &input, FUEL)
+		.map_err(|e| Error::invalid("plugin", format!("{}: {}", path.display(), e)))?;
+
+	base::output_bytes(matches, &output)?;
+
+	Ok(vec![])
+}
+
+/// Fuel of a plugin run, about one per instruction, so a plugin looping forever fails
+const FUEL: u64 = 1_000_000_000;
+
+/// The module should export
+/// - `memory`
+/// - `alloc(len: i32) -> i32`, returns the pointer to write the input to
+/// - `convert(ptr: i32, len: i32) -> i64`, returns the pointer and length of the output as `ptr << 32 | len`
+fn convert(wasm: &[u8], input: &[u8], fuel: u64) -> Result<Vec<u8>, Error> {
+	let mut config = Config::default();
+	config.consume_fuel(true);
+	let engine = Engine::new(&config);
+	let module = Module::new(&engine, wasm).map_err(Error::failed)?;
+	let mut store = Store::new(&engine, ());
+	store.add_fuel(fuel).map_err(Error::failed)?;
+	let linker = Linker::<()>::new(&engine);
+	let instance = linker
+		.instantiate(&mut store, &module)
+		.and_then(|x| x.start(&mut store))
+		.map_err(Error::failed)?;
+
+	let memory = instance
+		.get_memory(&store, "memory")
+		.ok_or(Error::failed("Expect export: memory"))?;
+	let alloc = instance
+		.get_typed_func::<i32, i32>(&store, "alloc")
+		.map_err(|e| Error::failed(format!("Expect export: alloc: {}", e)))?;
+	let convert = instance
+		.get_typed_func::<(i32, i32), i64>(&store, "convert")
+		.map_err(|e| Error::failed(format!("Expect export: convert: {}", e)))?;
+
+	let len = input.len() as i32;
+	let ptr = alloc.call(&mut store, len).map_err(Error::failed)?;
+	memory
+		.write(&mut store, ptr as u32 as usize, input)
+		.map_err(Error::failed)?;
+
+	let result = convert
+		.call(&mut store, (ptr, len))
+		.map_err(Error::failed)? as u64;
+
+	// the output is within the memory, not allocated from the length returned
+	let (ptr, len) = ((result >> 32) as usize, (result & 0xffff_ffff) as usize);
+	let output = ptr
+		.checked_add(len)
+		.and_then(|end| memory.data(&store).get(ptr..end))
+		.ok_or_else(|| Error::failed(format!("Output out of memory: {} bytes at {}", len, ptr)))?;
+
+	Ok(output.to_vec())
+}
+
+fn discover() -> Vec<(String, PathBuf)> {
+	let entries = match config::dir().map(|x| fs::read_dir(x.join("plugins"))) {
+		Some(Ok(entries)) => entries,
+		_ => return vec![],
+	};
+
+	let mut result = entries
+		.filter_map(|entry| entry.ok().map(|x| x.path()))
+		.filter(|path| path.is_file() && path.extension().map(|x| x == "wasm").unwrap_or(false))
+		.filter_map(|path| {
+			let name = path.file_stem()?.to_str()?.to_string();
+			Some((name, path))
+		})
+		.collect::<Vec<_>>();
+	result.sort();
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_convert() {
+		// toggles the case of ASCII letters in place
+		let wasm = wat::parse_str(
+			r#"
+			(module
+				(memory (export "memory") 1)
+				(func (export "alloc") (param i32) (result i32)
+					i32.const 1024)
+				(func (export "convert") (param $ptr i32) (param $len i32) (result i64)
+					(local $i i32)
+					(block $done
+						(loop $next
+							(br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+							(i32.store8
+								(i32.add (local.get $ptr) (local.get $i))
+								(i32.xor
+									(i32.load8_u (i32.add (local.get $ptr) (local.get $i)))
+									(i32.const 32)))
+							(local.set $i (i32.add (local.get $i) (i32.const 1)))
+							(br $next)))
+					(i64.or
+						(i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+						(i64.extend_i32_u (local.get $len)))))
+			"#,
+		)
+		.unwrap();
+		assert_eq!(convert(&wasm, b"dTool", FUEL).unwrap(), b"DtOOL".to_vec());
+		assert_eq!(convert(&wasm, b"", FUEL).unwrap(), b"".to_vec());
+
+		let wasm = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+		assert!(convert(&wasm, b"a", FUEL).is_err());
+
+		// output length beyond the memory
+		let wasm = wat::parse_str(
+			r#"
+			(module
+				(memory (export "memory") 1)
+				(func (export "alloc") (param i32) (result i32)
+					i32.const 0)
+				(func (export "convert") (param i32 i32) (result i64)
+					i64.const 0xffffffff))
+			"#,
+		)
+		.unwrap();
+		assert!(convert(&wasm, b"a", FUEL).is_err());
+
+		// out of fuel, a small one not to loop for long in the test
+		let wasm = wat::parse_str(
+			r#"
+			(module
+				(memory (export "memory") 1)
+				(func (export "alloc") (param i32) (result i32)
+					i32.const 0)
+				(func (export "convert") (param i32 i32) (result i64)
+					(loop $forever (br $forever))
+					i64.const 0))
+			"#,
+		)
+		.unwrap();
+		assert!(convert(&wasm, b"a", 1_000_000).is_err());
+	}
+}
@@ -0,0 +1,269 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Whitespace clean, tab / space conversion".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("ws_clean")
+				.about(
+					"Strip trailing whitespace and collapse blank lines (both if none specified)",
+				)
+				.arg(
+					Arg::with_name("TRAILING")
+						.long("trailing")
+						.short("t")
+						.help("Strip trailing whitespace")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("BLANK")
+						.long("blank")
+						.short("b")
+						.help("Collapse multiple blank lines into one")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ws_clean),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ws_expand")
+				.about("Convert tabs to spaces")
+				.arg(
+					Arg::with_name("TAB_WIDTH")
+						.long("tab-width")
+						.short("w")
+						.help("Tab width")
+						.takes_value(true)
+						.default_value("4")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ws_expand),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("ws_unexpand")
+				.about("Convert leading spaces to tabs")
+				.arg(
+					Arg::with_name("TAB_WIDTH")
+						.long("tab-width")
+						.short("w")
+						.help("Tab width")
+						.takes_value(true)
+						.default_value("4")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, ws_unexpand),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the whitespace commands
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Strip trailing whitespace
+	pub trailing: bool,
+	/// Collapse multiple blank lines into one
+	pub blank: bool,
+	pub tab_width: usize,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			trailing: false,
+			blank: false,
+			tab_width: 4,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let tab_width = match matches.value_of("TAB_WIDTH") {
+			Some(width) => width.parse().map_err(|_| Error::invalid("tab width", ""))?,
+			None => Options::default().tab_width,
+		};
+
+		Ok(Options {
+			trailing: matches.is_present("TRAILING"),
+			blank: matches.is_present("BLANK"),
+			tab_width,
+		})
+	}
+}
+
+/// Strip trailing whitespace and collapse blank lines (both if none specified)
+pub fn ws_clean(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let (trailing, blank) = match (options.trailing, options.blank) {
+		(false, false) => (true, true),
+		x => x,
+	};
+
+	let mut result: Vec<String> = vec![];
+	for line in base::text(input)?.lines() {
+		let line = if trailing { line.trim_end() } else { line };
+		let is_blank = line.trim().is_empty();
+		if blank && is_blank && result.last().map(|x| x.trim().is_empty()).unwrap_or(false) {
+			continue;
+		}
+		result.push(line.to_string());
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert tabs to spaces
+pub fn ws_expand(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let tab_width = tab_width(options)?;
+
+	let result = base::text(input)?
+		.lines()
+		.map(|line| {
+			let mut result = String::new();
+			// tab stops are by column
+			let mut column = 0;
+			for c in line.chars() {
+				if c == '\t' {
+					let len = tab_width - column % tab_width;
+					result.push_str(&" ".repeat(len));
+					column += len;
+				} else {
+					result.push(c);
+					column += 1;
+				}
+			}
+			result
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Convert leading spaces to tabs
+pub fn ws_unexpand(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let tab_width = tab_width(options)?;
+
+	let result = base::text(input)?
+		.lines()
+		.map(|line| {
+			let content = line.trim_start_matches(&[' ', '\t'][..]);
+			let indent = &line[..line.len() - content.len()];
+
+			let mut column = 0;
+			for c in indent.chars() {
+				column = match c {
+					'\t' => column + tab_width - column % tab_width,
+					_ => column + 1,
+				};
+			}
+
+			format!(
+				"{}{}{}",
+				"\t".repeat(column / tab_width),
+				" ".repeat(column % tab_width),
+				content
+			)
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+fn tab_width(options: &Options) -> Result<usize, Error> {
+	match options.tab_width {
+		0 => Err(Error::invalid("tab width", "")),
+		width => Ok(width),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"ws_clean",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["'a  \n\n \n\nb\t'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a", "", "b"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Trailing whitespace only".to_string(),
+						input: vec!["-t", "'a  \n\n\nb\t'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["a", "", "", "b"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"ws_expand",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-w", "4", "'\tab\tc'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["    ab  c"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+			(
+				"ws_unexpand",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["-w", "4", "'      a  b'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["\t  a  b"].into_iter().map(Into::into).collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
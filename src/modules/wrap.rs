@@ -0,0 +1,261 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, Command, Module};
+use clap::{Arg, ArgMatches};
+use textwrap::Wrapper;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "Text wrap, truncate".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("wrap")
+				.about("Wrap lines to width (display width, e.g. CJK chars take 2 columns)")
+				.arg(
+					Arg::with_name("WIDTH")
+						.long("width")
+						.short("w")
+						.help("Width in columns")
+						.takes_value(true)
+						.default_value("80")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("KEEP_WORDS")
+						.long("keep-words")
+						.short("k")
+						.help("Do not break words longer than the width")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, wrap),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("truncate")
+				.about("Truncate lines to width (display width) with ellipsis")
+				.arg(
+					Arg::with_name("WIDTH")
+						.long("width")
+						.short("w")
+						.help("Width in columns, including the ellipsis")
+						.takes_value(true)
+						.default_value("80")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("ELLIPSIS")
+						.long("ellipsis")
+						.short("e")
+						.help("Ellipsis")
+						.takes_value(true)
+						.default_value("…")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, truncate),
+			stream: None,
+		},
+	]
+}
+
+/// Options of `wrap` and `truncate`
+#[derive(Debug, Clone)]
+pub struct Options {
+	/// Width in columns, including the ellipsis if truncated
+	pub width: usize,
+	/// Do not break words longer than the width
+	pub keep_words: bool,
+	pub ellipsis: String,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			width: 80,
+			keep_words: false,
+			ellipsis: "…".to_string(),
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let default = Options::default();
+
+		let width = match matches.value_of("WIDTH") {
+			Some(width) => width.parse().map_err(|_| Error::invalid("width", ""))?,
+			None => default.width,
+		};
+
+		Ok(Options {
+			width,
+			keep_words: matches.is_present("KEEP_WORDS"),
+			ellipsis: matches
+				.value_of("ELLIPSIS")
+				.map(String::from)
+				.unwrap_or(default.ellipsis),
+		})
+	}
+}
+
+/// Wrap lines to width (display width, e.g. CJK chars take 2 columns)
+pub fn wrap(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let width = width(options)?;
+
+	let wrapper = Wrapper::new(width).break_words(!options.keep_words);
+
+	let mut result = vec![];
+	for line in base::text(input)?.lines() {
+		if line.trim().is_empty() {
+			result.push("".to_string());
+		} else {
+			result.extend(wrapper.wrap(line).into_iter().map(|x| x.to_string()));
+		}
+	}
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// Truncate lines to width (display width) with ellipsis
+pub fn truncate(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let width = width(options)?;
+
+	// an ellipsis wider than the width is clipped too
+	let ellipsis = clip(&options.ellipsis, width);
+
+	let result = base::text(input)?
+		.lines()
+		.map(|line| {
+			if line.width() <= width {
+				return line.to_string();
+			}
+			let mut result = clip(line, width - ellipsis.width()).to_string();
+			result.push_str(ellipsis);
+			result
+		})
+		.collect::<Vec<_>>();
+
+	Ok(result.join("\n").into_bytes())
+}
+
+/// The leading chars of the text within the width
+fn clip(text: &str, width: usize) -> &str {
+	let mut used = 0;
+	for (i, c) in text.char_indices() {
+		let w = c.width().unwrap_or(0);
+		if used + w > width {
+			return &text[..i];
+		}
+		used += w;
+	}
+	text
+}
+
+fn width(options: &Options) -> Result<usize, Error> {
+	match options.width {
+		0 => Err(Error::invalid("width", "")),
+		width => Ok(width),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"wrap",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-w", "10", "'The quick brown fox jumps over the lazy dog'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["The quick", "brown fox", "jumps over", "the lazy", "dog"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Display width".to_string(),
+						input: vec!["-w", "6", "'你好 世界'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["你好", "世界"].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"truncate",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-w", "10", "'The quick brown fox\nshort'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["The quick…", "short"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec!["-w", "7", "-e", "...", "'你好世界'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["你好..."].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Ellipsis wider than the width".to_string(),
+						input: vec!["-w", "1", "-e", "...", "'abc'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["."].into_iter().map(Into::into).collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -0,0 +1,298 @@
+use crate::error::Error;
+use crate::modules::{base, http, Command, Module};
+use chrono::Local;
+use clap::{Arg, ArgMatches};
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::{Message, WebSocket};
+use url::Url;
+
+/// How often the input is checked while waiting for frames
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "WebSocket client".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![Command {
+		app: base::sub_command("ws")
+			.about("WebSocket client, sends every line of stdin as a text message, prints the received frames with timestamps")
+			.arg(Arg::with_name("URL").help("ws:// or wss:// URL").required(true).index(1))
+			.arg(
+				Arg::with_name("HEADER")
+					.long("header")
+					.short("H")
+					.help("Handshake header as name: value, repeat for several")
+					.takes_value(true)
+					.multiple(true)
+					.number_of_values(1)
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("WAIT")
+					.long("wait")
+					.short("w")
+					.help("Seconds to wait for frames after the end of stdin, then close")
+					.takes_value(true)
+					.default_value("1")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("TIMEOUT")
+					.long("timeout")
+					.short("t")
+					.help("Connect timeout in seconds")
+					.takes_value(true)
+					.default_value("10")
+					.required(false),
+			)
+			.arg(
+				Arg::with_name("INSECURE")
+					.long("insecure")
+					.short("k")
+					.help("Skip TLS certificate verification")
+					.required(false),
+			),
+		f: ws,
+		stream: None,
+	}]
+}
+
+fn ws(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+	let url = matches.value_of("URL").ok_or(Error::invalid("URL", ""))?;
+	let url = Url::parse(url).map_err(|e| Error::invalid("URL", format!("{}: {}", url, e)))?;
+	if !matches!(url.scheme(), "ws" | "wss") {
+		return Err(Error::invalid(
+			"URL",
+			format!("{}: not ws:// or wss://", url),
+		));
+	}
+
+	let seconds = |name: &str, default: &str| {
+		matches
+			.value_of(name)
+			.unwrap_or(default)
+			.parse::<f64>()
+			.ok()
+			.filter(|x| *x >= 0.0)
+			.map(Duration::from_secs_f64)
+	};
+	let timeout = seconds("TIMEOUT", "10")
+		.filter(|x| !x.is_zero())
+		.ok_or(Error::invalid("timeout", ""))?;
+	let wait = seconds("WAIT", "1").ok_or(Error::invalid("wait", ""))?;
+
+	let mut request = url
+		.as_str()
+		.into_client_request()
+		.map_err(|e| Error::invalid("URL", format!("{}: {}", url, e)))?;
+	for (name, value) in http::headers(matches)? {
+		let header = (
+			HeaderName::from_bytes(name.as_bytes()),
+			HeaderValue::from_str(&value),
+		);
+		match header {
+			(Ok(name), Ok(value)) => request.headers_mut().append(name, value),
+			_ => return Err(Error::invalid("header", format!("{}: {}", name, value))),
+		};
+	}
+
+	let connection = http::connect(&url, timeout, matches.is_present("INSECURE"))?;
+	let (socket, response) = tungstenite::client(request, connection)
+		.map_err(|e| Error::Failed(format!("Handshake failed: {}: {}", url, e)))?;
+	base::verbose(matches, 1, || format!("connected: {}", response.status()));
+
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		for line in io::stdin().lock().lines() {
+			if sender.send(line).is_err() {
+				break;
+			}
+		}
+	});
+
+	let stdout = io::stdout();
+	let mut output: Box<dyn Write> = match matches.is_present("QUIET") {
+		true => Box::new(io::sink()),
+		false => Box::new(stdout.lock()),
+	};
+	session(socket, receiver, wait, &mut output)?;
+
+	Ok(vec![])
+}
+
+/// Sends the lines and prints the frames until the server closes, or the wait after the last line
+fn session<S: http::Connection>(
+	mut socket: WebSocket<S>,
+	lines: Receiver<io::Result<String>>,
+	wait: Duration,
+	output: &mut dyn Write,
+) -> Result<(), Error> {
+	let failed = |e: tungstenite::Error| Error::Failed(format!("WebSocket failed: {}", e));
+	let write = |output: &mut dyn Write, line: String| {
+		let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+		writeln!(output, "[{}] {}", time, line)
+			.and_then(|_| output.flush())
+			.map_err(|e| Error::io("-", e))
+	};
+
+	socket
+		.get_ref()
+		.set_read_timeout(Some(POLL_INTERVAL))
+		.map_err(|e| Error::Failed(format!("WebSocket failed: {}", e)))?;
+
+	let mut input_end = None;
+	let mut closing = false;
+	loop {
+		while input_end.is_none() {
+			match lines.try_recv() {
+				Ok(line) => {
+					let line = line.map_err(|e| Error::io("-", e))?;
+					socket.send(Message::Text(line)).map_err(failed)?;
+				}
+				Err(TryRecvError::Empty) => break,
+				Err(TryRecvError::Disconnected) => input_end = Some(Instant::now()),
+			}
+		}
+
+		if !closing && input_end.map(|x| x.elapsed() >= wait).unwrap_or(false) {
+			// the server answers with a close frame, then the read fails with ConnectionClosed
+			socket.close(None).map_err(failed)?;
+			closing = true;
+		}
+
+		match socket.read() {
+			Ok(message) => {
+				if let Some(line) = frame(message) {
+					write(output, line)?;
+				}
+			}
+			Err(tungstenite::Error::Io(e))
+				if matches!(
+					e.kind(),
+					io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+				) =>
+			{
+				// a closing handshake the server never answers
+				if closing && input_end.map(|x| x.elapsed() >= wait * 2).unwrap_or(false) {
+					return Ok(());
+				}
+			}
+			Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => {
+				return Ok(())
+			}
+			Err(e) => return Err(failed(e)),
+		}
+	}
+}
+
+/// Text as is, binary as hex, pings and pongs are answered by tungstenite and not printed
+fn frame(message: Message) -> Option<String> {
+	match message {
+		Message::Text(text) => Some(format!("< {}", text)),
+		Message::Binary(data) => Some(format!("< binary: {}", hex::encode(data))),
+		Message::Close(Some(frame)) => Some(format!(
+			"< close: {} {}",
+			u16::from(frame.code),
+			frame.reason
+		))
+		.map(|x| x.trim_end().to_string()),
+		Message::Close(None) => Some("< close".to_string()),
+		Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => None,
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![(
+			"ws",
+			vec![Case {
+				desc: "Lines of stdin as messages, e.g. echo hello | dtool ws ...".to_string(),
+				input: vec!["wss://echo.websocket.org"]
+					.into_iter()
+					.map(Into::into)
+					.collect(),
+				output: vec![
+					"[2024-06-01 12:00:00.120] < Request served by 1781505b56ee58",
+					"[2024-06-01 12:00:00.180] < hello",
+					"[2024-06-01 12:00:01.190] < close",
+				]
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+				is_example: true,
+				is_test: false,
+				since: "0.10.0".to_string(),
+			}],
+		)]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+	use std::net::{TcpListener, TcpStream};
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+
+	#[test]
+	fn test_session() {
+		// echoes every message
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		thread::spawn(move || {
+			let stream = listener.incoming().next().unwrap().unwrap();
+			let mut socket = tungstenite::accept(stream).unwrap();
+			while let Ok(message) = socket.read() {
+				if message.is_text() || message.is_binary() {
+					socket.send(message).unwrap();
+				}
+			}
+		});
+
+		let url = format!("ws://127.0.0.1:{}/", port);
+		let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+		let (socket, _) = tungstenite::client(url, stream).unwrap();
+
+		let (sender, receiver) = mpsc::channel();
+		sender.send(Ok("hello".to_string())).unwrap();
+		sender.send(Ok("world".to_string())).unwrap();
+		drop(sender);
+
+		let mut output = vec![];
+		session(socket, receiver, Duration::from_millis(200), &mut output).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		let frames = output
+			.lines()
+			.map(|x| x.split_once("] ").unwrap().1)
+			.collect::<Vec<_>>();
+		assert_eq!(frames, vec!["< hello", "< world", "< close"]);
+	}
+
+	#[test]
+	fn test_frame() {
+		assert_eq!(
+			frame(Message::Binary(vec![0, 255])),
+			Some("< binary: 00ff".to_string())
+		);
+		assert_eq!(frame(Message::Ping(vec![])), None);
+	}
+}
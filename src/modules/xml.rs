@@ -0,0 +1,561 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use serde_json::{Map, Value};
+use xml::name::OwnedName;
+use xml::namespace::Namespace;
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent as WriterEvent};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "XML format, JSON / XML conversion".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("xml_fmt")
+				.about("XML format (pretty print or minify)")
+				.arg(
+					Arg::with_name("INDENT")
+						.long("indent")
+						.short("i")
+						.help("Indent width")
+						.takes_value(true)
+						.default_value("2")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, xml_fmt),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("x2j")
+				.about("Convert XML to JSON")
+				.arg(
+					Arg::with_name("ATTR_PREFIX")
+						.long("attr-prefix")
+						.short("a")
+						.help("Prefix of attribute keys")
+						.takes_value(true)
+						.default_value("@")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("IGNORE_ATTRS")
+						.long("ignore-attrs")
+						.short("A")
+						.help("Ignore attributes")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, x2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("j2x")
+				.about("Convert JSON to XML")
+				.arg(
+					Arg::with_name("ATTR_PREFIX")
+						.long("attr-prefix")
+						.short("a")
+						.help("Prefix of attribute keys")
+						.takes_value(true)
+						.default_value("@")
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("ROOT")
+						.long("root")
+						.short("r")
+						.help("Root element name, required if the object has more than one key")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2x),
+			stream: None,
+		},
+	]
+}
+
+const TEXT_KEY: &str = "#text";
+
+/// Options of the XML commands
+#[derive(Debug, Clone)]
+pub struct Options {
+	pub indent: usize,
+	pub minify: bool,
+	/// Prefix of attribute keys
+	pub attr_prefix: String,
+	pub ignore_attrs: bool,
+	/// Root element name, required if the object has more than one key
+	pub root: Option<String>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			indent: 2,
+			minify: false,
+			attr_prefix: "@".to_string(),
+			ignore_attrs: false,
+			root: None,
+		}
+	}
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let default = Options::default();
+
+		let indent = match matches.value_of("INDENT") {
+			Some(indent) => indent.parse().map_err(|_| Error::invalid("indent", ""))?,
+			None => default.indent,
+		};
+
+		Ok(Options {
+			indent,
+			minify: matches.is_present("MINIFY"),
+			attr_prefix: matches
+				.value_of("ATTR_PREFIX")
+				.map(String::from)
+				.unwrap_or(default.attr_prefix),
+			ignore_attrs: matches.is_present("IGNORE_ATTRS"),
+			root: matches.value_of("ROOT").map(String::from),
+		})
+	}
+}
+
+/// XML format (pretty print or minify)
+pub fn xml_fmt(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let input = base::text(input)?;
+
+	let has_declaration = input.trim_start().starts_with("<?xml");
+
+	let mut writer = writer(get_indent(options), has_declaration);
+
+	let reader = parser_config().create_reader(input.as_bytes());
+
+	for event in reader {
+		let event = event.map_err(|e| Error::invalid("XML", e))?;
+		if let XmlEvent::StartDocument { .. } = event {
+			if !has_declaration {
+				continue;
+			}
+		}
+		if let Some(event) = event.as_writer_event() {
+			writer.write(event).map_err(|e| Error::invalid("XML", e))?;
+		}
+	}
+
+	Ok(writer.into_inner())
+}
+
+/// Convert XML to JSON
+pub fn x2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let reader = parser_config()
+		.ignore_comments(true)
+		.create_reader(base::text(input)?.as_bytes());
+
+	let value = to_json(reader, &options.attr_prefix, options.ignore_attrs)?;
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert JSON to XML
+pub fn j2x(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let (name, value) = match (&options.root, value) {
+		(Some(root), value) => (root.clone(), value),
+		(None, Value::Object(map)) if map.len() == 1 => {
+			map.into_iter().next().ok_or(Error::input(""))?
+		}
+		_ => return Err(Error::failed("Root element name required")),
+	};
+
+	let mut writer = writer(get_indent(options), false);
+
+	from_json(&mut writer, &name, &value, &options.attr_prefix)?;
+
+	Ok(writer.into_inner())
+}
+
+fn get_indent(options: &Options) -> Option<usize> {
+	if options.minify {
+		None
+	} else {
+		Some(options.indent)
+	}
+}
+
+fn parser_config() -> ParserConfig {
+	ParserConfig::new()
+		.trim_whitespace(true)
+		.ignore_comments(false)
+}
+
+fn writer(indent: Option<usize>, has_declaration: bool) -> EventWriter<Vec<u8>> {
+	let config = EmitterConfig::new().write_document_declaration(has_declaration);
+	let config = match indent {
+		Some(indent) => config
+			.perform_indent(true)
+			.indent_string(" ".repeat(indent)),
+		None => config.perform_indent(false),
+	};
+	config.create_writer(vec![])
+}
+
+fn to_name(name: &OwnedName) -> String {
+	match &name.prefix {
+		Some(prefix) => format!("{}:{}", prefix, name.local_name),
+		None => name.local_name.clone(),
+	}
+}
+
+struct Element {
+	name: String,
+	map: Map<String, Value>,
+	text: String,
+	namespace: Namespace,
+}
+
+/// Attributes are converted to prefixed keys, text of element with attributes or children to "#text",
+/// repeated children to array, element with only text to string, empty element to null
+fn to_json<R: std::io::Read>(
+	reader: EventReader<R>,
+	attr_prefix: &str,
+	ignore_attrs: bool,
+) -> Result<Value, Error> {
+	let mut stack: Vec<Element> = vec![];
+	let mut root = Map::new();
+
+	for event in reader {
+		match event.map_err(|e| Error::invalid("XML", e))? {
+			XmlEvent::StartElement {
+				name,
+				attributes,
+				namespace,
+			} => {
+				let mut map = Map::new();
+				if !ignore_attrs {
+					// namespaces declared by this element
+					let parent = stack.last().map(|x| &x.namespace);
+					for (prefix, uri) in &namespace {
+						let declared = match parent {
+							Some(parent) => parent.get(prefix) == Some(uri),
+							None => {
+								matches!(prefix, "xml" | "xmlns")
+									|| (prefix.is_empty() && uri.is_empty())
+							}
+						};
+						if !declared {
+							let key = match prefix {
+								"" => "xmlns".to_string(),
+								prefix => format!("xmlns:{}", prefix),
+							};
+							map.insert(format!("{}{}", attr_prefix, key), Value::from(uri));
+						}
+					}
+					for attribute in attributes {
+						map.insert(
+							format!("{}{}", attr_prefix, to_name(&attribute.name)),
+							Value::from(attribute.value),
+						);
+					}
+				}
+				stack.push(Element {
+					name: to_name(&name),
+					map,
+					text: String::new(),
+					namespace,
+				});
+			}
+			XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+				if let Some(element) = stack.last_mut() {
+					element.text.push_str(&text);
+				}
+			}
+			XmlEvent::EndElement { .. } => {
+				let element = stack.pop().ok_or(Error::invalid("XML", ""))?;
+				let mut map = element.map;
+				let value = match (map.is_empty(), element.text.is_empty()) {
+					(true, true) => Value::Null,
+					(true, false) => Value::String(element.text),
+					(false, true) => Value::Object(map),
+					(false, false) => {
+						map.insert(TEXT_KEY.to_string(), Value::String(element.text));
+						Value::Object(map)
+					}
+				};
+				let parent = match stack.last_mut() {
+					Some(parent) => &mut parent.map,
+					None => &mut root,
+				};
+				match parent.get_mut(&element.name) {
+					Some(Value::Array(array)) => array.push(value),
+					Some(existing) => {
+						let first = existing.take();
+						*existing = Value::Array(vec![first, value]);
+					}
+					None => {
+						parent.insert(element.name, value);
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	Ok(Value::Object(root))
+}
+
+fn from_json(
+	writer: &mut EventWriter<Vec<u8>>,
+	name: &str,
+	value: &Value,
+	attr_prefix: &str,
+) -> Result<(), Error> {
+	// array is converted to repeated elements
+	if let Value::Array(array) = value {
+		for item in array {
+			from_json(writer, name, item, attr_prefix)?;
+		}
+		return Ok(());
+	}
+
+	let write = |writer: &mut EventWriter<Vec<u8>>, event: WriterEvent| {
+		writer.write(event).map_err(|e| Error::invalid("XML", e))
+	};
+
+	let scalar = |value: &Value| -> Result<String, Error> {
+		match value {
+			Value::String(v) => Ok(v.clone()),
+			Value::Null => Ok("".to_string()),
+			v => json::to_string(v),
+		}
+	};
+
+	match value {
+		Value::Object(map) => {
+			let is_attr = |k: &str| !attr_prefix.is_empty() && k.starts_with(attr_prefix);
+			let attrs = map
+				.iter()
+				.filter(|(k, _)| is_attr(k))
+				.map(|(k, v)| Ok((k[attr_prefix.len()..].to_string(), scalar(v)?)))
+				.collect::<Result<Vec<_>, Error>>()?;
+			let mut event = WriterEvent::start_element(name);
+			for (k, v) in &attrs {
+				event = event.attr(k.as_str(), v.as_str());
+			}
+			write(writer, event.into())?;
+			for (k, v) in map {
+				if k == TEXT_KEY {
+					write(writer, WriterEvent::characters(&scalar(v)?))?;
+				} else if !is_attr(k) {
+					from_json(writer, k, v, attr_prefix)?;
+				}
+			}
+		}
+		value => {
+			write(writer, WriterEvent::start_element(name).into())?;
+			let text = scalar(value)?;
+			if !text.is_empty() {
+				write(writer, WriterEvent::characters(&text))?;
+			}
+		}
+	}
+	write(writer, WriterEvent::end_element().into())?;
+
+	Ok(())
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"xml_fmt",
+				vec![
+					Case {
+						desc: "Pretty print".to_string(),
+						input: vec![r#"'<a x="1"><b>text</b><c/></a>'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"<a x="1">"#, "  <b>text</b>", "  <c />", "</a>"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Minify".to_string(),
+						input: vec!["-m", "'<a>\n  <b>text</b>\n  <!-- comment -->\n</a>'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["<a><b>text</b><!-- comment --></a>"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"x2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-m",
+							r#"'<rss version="2.0"><item><title>a</title></item><item><title lang="en">b</title></item></rss>'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							r##"{"rss":{"@version":"2.0","item":[{"title":"a"},{"title":{"@lang":"en","#text":"b"}}]}}"##,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Ignore attributes".to_string(),
+						input: vec!["-A", "-m", r#"'<a x="1"><b y="2">c</b><d/></a>'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":{"b":"c","d":null}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-a",
+							"'_'",
+							"-m",
+							r#"'<s:Envelope xmlns:s="urn:s"><s:Body><![CDATA[<x>]]></s:Body></s:Envelope>'"#,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![r#"{"s:Envelope":{"_xmlns:s":"urn:s","s:Body":"<x>"}}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"j2x",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							r##"'{"rss":{"@version":"2.0","item":[{"title":"a"},{"title":{"@lang":"en","#text":"b"}}]}}'"##,
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							r#"<rss version="2.0">"#,
+							"  <item>",
+							"    <title>a</title>",
+							"  </item>",
+							"  <item>",
+							r#"    <title lang="en">b</title>"#,
+							"  </item>",
+							"</rss>",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Root element name".to_string(),
+						input: vec!["-r", "root", "-m", r#"'{"a":1,"b":[true,null]}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["<root><a>1</a><b>true</b><b /></root>"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}
@@ -0,0 +1,486 @@
+use crate::error::Error;
+use crate::modules::base::FromMatches;
+use crate::modules::{base, json, Command, Module};
+use clap::{Arg, ArgMatches};
+use linked_hash_map::LinkedHashMap;
+use serde_json::{Map, Value};
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+pub fn module<'a, 'b>() -> Module<'a, 'b> {
+	Module {
+		desc: "JSON / YAML conversion, YAML split / merge".to_string(),
+		commands: commands(),
+		get_cases: cases::cases,
+	}
+}
+
+pub fn commands<'a, 'b>() -> Vec<Command<'a, 'b>> {
+	vec![
+		Command {
+			app: base::sub_command("j2y")
+				.about("Convert JSON to YAML")
+				.arg(
+					Arg::with_name("SPLIT")
+						.long("split")
+						.short("s")
+						.help("Output each item of the top-level array as a document")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, j2y),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("y2j")
+				.about("Convert YAML to JSON")
+				.arg(
+					Arg::with_name("MINIFY")
+						.long("minify")
+						.short("m")
+						.help("Minify")
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, y2j),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("yaml_split")
+				.about("YAML select documents from multi-document stream")
+				.arg(
+					Arg::with_name("INDEX")
+						.long("index")
+						.short("i")
+						.help("Document indexes (0-based), e.g. 0,2")
+						.takes_value(true)
+						.required(false),
+				)
+				.arg(
+					Arg::with_name("SELECTOR")
+						.long("selector")
+						.short("s")
+						.help("Field selector, e.g. kind=Deployment,metadata.name=web")
+						.takes_value(true)
+						.required(false),
+				)
+				.args(&base::input_args()),
+			f: |matches| base::convert(matches, yaml_split),
+			stream: None,
+		},
+		Command {
+			app: base::sub_command("yaml_merge")
+				.about("YAML concatenate documents to multi-document stream")
+				.arg(
+					Arg::with_name("INPUTS")
+						.value_name("INPUT")
+						.required(true)
+						.multiple(true)
+						.index(1),
+				),
+			f: |matches| base::generate(matches, yaml_merge),
+			stream: None,
+		},
+	]
+}
+
+/// Options of the YAML commands
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+	pub split: bool,
+	pub minify: bool,
+	/// Document indexes (0-based) to select, all if none
+	pub index: Option<Vec<usize>>,
+	/// Field selectors (dotted path, value) to select
+	pub selector: Vec<(String, String)>,
+	/// YAML streams to merge
+	pub inputs: Vec<String>,
+}
+
+impl FromMatches for Options {
+	fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
+		let index = match matches.value_of("INDEX") {
+			Some(index) => Some(
+				index
+					.split(',')
+					.map(|x| {
+						x.trim()
+							.parse::<usize>()
+							.map_err(|_| Error::invalid("index", ""))
+					})
+					.collect::<Result<Vec<_>, _>>()?,
+			),
+			None => None,
+		};
+
+		let selector = match matches.value_of("SELECTOR") {
+			Some(selector) => selector
+				.split(',')
+				.map(|x| {
+					let mut kv = x.splitn(2, '=');
+					match (kv.next(), kv.next()) {
+						(Some(k), Some(v)) => Ok((k.trim().to_string(), v.trim().to_string())),
+						_ => Err(Error::invalid("selector", x)),
+					}
+				})
+				.collect::<Result<Vec<_>, _>>()?,
+			None => vec![],
+		};
+
+		let inputs = matches
+			.values_of("INPUTS")
+			.map(|x| x.map(String::from).collect())
+			.unwrap_or_default();
+
+		Ok(Options {
+			split: matches.is_present("SPLIT"),
+			minify: matches.is_present("MINIFY"),
+			index,
+			selector,
+			inputs,
+		})
+	}
+}
+
+/// Convert JSON to YAML
+pub fn j2y(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let value = json::parse(base::text(input)?)?;
+
+	let result = match (options.split, value) {
+		(true, Value::Array(values)) => {
+			let documents = values.iter().map(from_json).collect::<Vec<_>>();
+			to_string(&documents)?
+		}
+		(true, _) => return Err(Error::failed("Not array")),
+		(false, value) => to_string(&[from_json(&value)])?,
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// Convert YAML to JSON
+pub fn y2j(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let documents = parse(base::text(input)?)?;
+
+	let mut values = documents
+		.into_iter()
+		.map(to_json)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	// multi-document stream is converted to array
+	let value = match values.len() {
+		0 => Value::Null,
+		1 => values.remove(0),
+		_ => Value::Array(values),
+	};
+
+	let result = if options.minify {
+		json::to_string(&value)?
+	} else {
+		json::to_string_pretty(&value, 2)?
+	};
+
+	Ok(result.into_bytes())
+}
+
+/// YAML select documents from multi-document stream
+pub fn yaml_split(input: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	let documents = parse(base::text(input)?)?;
+
+	let documents = documents
+		.into_iter()
+		.enumerate()
+		.filter(|(i, _)| {
+			options
+				.index
+				.as_ref()
+				.map(|x| x.contains(i))
+				.unwrap_or(true)
+		})
+		.filter(|(_, document)| {
+			options
+				.selector
+				.iter()
+				.all(|(k, v)| select(document, k).as_deref() == Some(v.as_str()))
+		})
+		.map(|(_, document)| document)
+		.collect::<Vec<_>>();
+
+	let result = to_string(&documents)?;
+
+	Ok(result.into_bytes())
+}
+
+/// YAML concatenate documents to multi-document stream, the streams are `inputs`
+pub fn yaml_merge(_: &[u8], options: &Options) -> Result<Vec<u8>, Error> {
+	if options.inputs.is_empty() {
+		return Err(Error::input(""));
+	}
+
+	let mut documents = vec![];
+	for input in &options.inputs {
+		documents.extend(parse(input)?);
+	}
+
+	let result = to_string(&documents)?;
+
+	Ok(result.into_bytes())
+}
+
+/// Returns the scalar at the dotted path as string
+fn select(document: &Yaml, path: &str) -> Option<String> {
+	let mut current = document;
+	for key in path.split('.') {
+		current = match current {
+			Yaml::Hash(_) => &current[key],
+			Yaml::Array(array) => array.get(key.parse::<usize>().ok()?)?,
+			_ => return None,
+		};
+	}
+	match current {
+		Yaml::String(v) | Yaml::Real(v) => Some(v.clone()),
+		Yaml::Integer(v) => Some(v.to_string()),
+		Yaml::Boolean(v) => Some(v.to_string()),
+		Yaml::Null => Some("null".to_string()),
+		_ => None,
+	}
+}
+
+/// Parse YAML stream, anchors and aliases are resolved
+pub fn parse(input: &str) -> Result<Vec<Yaml>, Error> {
+	YamlLoader::load_from_str(input).map_err(|e| Error::invalid("YAML", e))
+}
+
+/// Emit YAML documents, the document start marker is omitted if there is only one document
+pub fn to_string(documents: &[Yaml]) -> Result<String, Error> {
+	let mut result = String::new();
+	for document in documents {
+		if !result.is_empty() {
+			result.push('\n');
+		}
+		let mut emitter = YamlEmitter::new(&mut result);
+		emitter
+			.dump(document)
+			.map_err(|_| Error::invalid("YAML", ""))?;
+	}
+	if documents.len() == 1 {
+		result = result.trim_start_matches("---\n").to_string();
+	}
+	Ok(result)
+}
+
+pub fn to_json(yaml: Yaml) -> Result<Value, Error> {
+	let result = match yaml {
+		Yaml::Null => Value::Null,
+		Yaml::Boolean(v) => Value::Bool(v),
+		Yaml::Integer(v) => Value::from(v),
+		Yaml::Real(v) => match serde_json::from_str::<Value>(&v) {
+			Ok(Value::Number(n)) => Value::Number(n),
+			// .inf, .nan, etc
+			_ => Value::from(Yaml::Real(v).as_f64().ok_or(Error::invalid("number", ""))?),
+		},
+		Yaml::String(v) => Value::String(v),
+		Yaml::Array(v) => Value::Array(v.into_iter().map(to_json).collect::<Result<_, _>>()?),
+		Yaml::Hash(v) => Value::Object(
+			v.into_iter()
+				.map(|(k, v)| Ok((to_key(k)?, to_json(v)?)))
+				.collect::<Result<Map<_, _>, Error>>()?,
+		),
+		Yaml::Alias(_) | Yaml::BadValue => return Err(Error::invalid("YAML", "")),
+	};
+	Ok(result)
+}
+
+fn to_key(yaml: Yaml) -> Result<String, Error> {
+	match yaml {
+		Yaml::String(v) | Yaml::Real(v) => Ok(v),
+		Yaml::Integer(v) => Ok(v.to_string()),
+		Yaml::Boolean(v) => Ok(v.to_string()),
+		Yaml::Null => Ok("null".to_string()),
+		_ => Err(Error::invalid("key", "")),
+	}
+}
+
+pub fn from_json(value: &Value) -> Yaml {
+	match value {
+		Value::Null => Yaml::Null,
+		Value::Bool(v) => Yaml::Boolean(*v),
+		Value::Number(v) => match v.as_i64() {
+			Some(v) => Yaml::Integer(v),
+			None => Yaml::Real(v.to_string()),
+		},
+		Value::String(v) => Yaml::String(v.clone()),
+		Value::Array(v) => Yaml::Array(v.iter().map(from_json).collect()),
+		Value::Object(v) => Yaml::Hash(
+			v.iter()
+				.map(|(k, v)| (Yaml::String(k.clone()), from_json(v)))
+				.collect::<LinkedHashMap<_, _>>(),
+		),
+	}
+}
+
+mod cases {
+	use crate::modules::Case;
+	use linked_hash_map::LinkedHashMap;
+
+	pub fn cases() -> LinkedHashMap<&'static str, Vec<Case>> {
+		vec![
+			(
+				"j2y",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec![r#"'{"name":"dtool","tags":["a","b"],"n":1.50}'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["name: dtool", "tags:", "  - a", "  - b", "n: 1.50"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Split to multiple documents".to_string(),
+						input: vec!["-s", r#"'[{"a":1},{"b":"x\ny"}]'"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["---", "a: 1", "---", r#"b: "x\ny""#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"y2j",
+				vec![
+					Case {
+						desc: "".to_string(),
+						input: vec!["-m", "'a: 1\nb: [x, y]'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"{"a":1,"b":["x","y"]}"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "Anchors and multiple documents".to_string(),
+						input: vec!["-m", "'a: &x 1\nb: *x\n---\nc: |\n  line1\n  line2\n'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec![r#"[{"a":1,"b":1},{"c":"line1\nline2\n"}]"#]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"yaml_split",
+				vec![
+					Case {
+						desc: "By index".to_string(),
+						input: vec!["-i", "1", "'a: 1\n---\nb: 2\n---\nc: 3'"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						output: vec!["b: 2"].into_iter().map(Into::into).collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "By field selector".to_string(),
+						input: vec![
+							"-s",
+							"kind=Service",
+							"'kind: Deployment\nmetadata:\n  name: web\n---\nkind: Service\nmetadata:\n  name: web\n---\nkind: Service\nmetadata:\n  name: db'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec![
+							"---",
+							"kind: Service",
+							"metadata:",
+							"  name: web",
+							"---",
+							"kind: Service",
+							"metadata:",
+							"  name: db",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						is_example: true,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+					Case {
+						desc: "".to_string(),
+						input: vec![
+							"-s",
+							"kind=Service,metadata.name=db",
+							"'kind: Service\nmetadata:\n  name: web\n---\nkind: Service\nmetadata:\n  name: db'",
+						]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+						output: vec!["kind: Service", "metadata:", "  name: db"]
+							.into_iter()
+							.map(Into::into)
+							.collect(),
+						is_example: false,
+						is_test: true,
+						since: "0.10.0".to_string(),
+					},
+				],
+			),
+			(
+				"yaml_merge",
+				vec![Case {
+					desc: "".to_string(),
+					input: vec!["'a: 1'", "'b: 2\n---\nc: 3'"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					output: vec!["---", "a: 1", "---", "b: 2", "---", "c: 3"]
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+					is_example: true,
+					is_test: true,
+					since: "0.10.0".to_string(),
+				}],
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::base::test::test_module;
+
+	#[test]
+	fn test_cases() {
+		test_module(module());
+	}
+}